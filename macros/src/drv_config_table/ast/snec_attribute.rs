@@ -1,7 +1,10 @@
 use std::convert::TryFrom;
 use syn::{
     Ident,
+    LitStr,
+    Member,
     Path,
+    Expr,
     Type,
     Visibility,
     Attribute,
@@ -103,7 +106,7 @@ impl Parse for SnecAttributeBodyInParentheses {
 pub struct SnecAttributeBody {
     pub commands: Punctuated<AttributeCommand, Token![,]>,
 }
-#[allow(clippy::clippy::eval_order_dependence)] // it's all clear and according to Syn docs
+#[allow(clippy::mixed_read_write_in_expression)] // it's all clear and according to Syn docs
 impl Parse for SnecAttributeBody {
     #[inline]
     fn parse(input: ParseStream) -> syn::Result<Self> {
@@ -141,16 +144,18 @@ pub enum AttributeCommand {
         /// The path to the marker type.
         value: Path,
     },
-    /// Set the name for the module containing entries generated by the `Entry` command.
+    /// Set the path of the module containing entries generated by the `Entry` command, relative to the struct. Nested modules are generated for paths with several segments, and `self` places the entries next to the struct without generating a module.
     ///
     /// Usage:
     /// ```rust
     /// #[snec(entry_module(my_own_module_name))]
+    /// #[snec(entry_module(config::entries))]
+    /// #[snec(entry_module(self))]
     /// ```
     EntryModule {
         name: custom_token::EntryModule,
         parentheses: token::Paren,
-        value: Ident,
+        value: Path,
     },
     /// Set the visibility for the module containing entries generated by the `Entry` command.
     ///
@@ -163,6 +168,17 @@ pub enum AttributeCommand {
         parentheses: token::Paren,
         value: Visibility,
     },
+    /// Set the visibility of the entry markers generated by the `Entry` command, either for a single field or the default for the whole struct. The visibility is relative to the module containing the struct, not the module containing the entries.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(entry_visibility(pub(crate)))]
+    /// ```
+    EntryVisibility {
+        name: custom_token::EntryVisibility,
+        parentheses: token::Paren,
+        value: Visibility,
+    },
     /// Add attributes to the module containing entries generated by the `Entry` command. Must be outer, not inner.
     ///
     /// Usage:
@@ -193,6 +209,233 @@ pub enum AttributeCommand {
         /// The type of the expression
         ty: Type,
     },
+    /// Set a receiver for the whole struct which is notified about changes of every entry, in addition to the receivers of the entries themselves.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(table_receiver({MyTableReceiver::new()}: MyTableReceiver))]
+    /// ```
+    TableReceiver {
+        name: custom_token::TableReceiver,
+        parentheses: token::Paren,
+        braces: token::Brace,
+        /// Expression fetching a table receiver to be pasted in the `get_handle` implementation.
+        expression: TokenStream,
+        colon: Token![:],
+        /// The type of the expression
+        ty: Type,
+    },
+    /// Use a field of the struct as the receiver, either for the whole struct or for a single field's generated `Entry` marker. The handle borrows the field for as long as it exists, instead of constructing a new receiver every time. Incompatible with `Receiver` for the same field.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(receiver_field = notifier)]
+    /// ```
+    ReceiverField {
+        name: custom_token::ReceiverField,
+        eq: Token![=],
+        /// The name of the field, or its index for tuple structs.
+        value: Member,
+    },
+    /// Defer the notifications of all entries of the struct, recording the changes in the specified field, which must be a `DeferredQueue`, until `DeferredTable::dispatch_pending` is called.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(deferred = pending)]
+    /// ```
+    Deferred {
+        name: custom_token::Deferred,
+        eq: Token![=],
+        /// The name of the field, or its index for tuple structs.
+        value: Member,
+    },
+    /// Make the handle to a field's entry also notify the receivers of the entries for the specified other fields, passing them their unchanged values, for entries which depend on the value of this one.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(invalidates(layout_cache, line_height))]
+    /// ```
+    Invalidates {
+        name: custom_token::Invalidates,
+        parentheses: token::Paren,
+        /// The names of the fields, or their indices for tuple structs.
+        value: Punctuated<Member, Token![,]>,
+    },
+    /// Implement `ValidateTable` for the struct by calling the specified function with a reference to the struct. The error type defaults to `&'static str`.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(validate_table = check_invariants)]
+    /// #[snec(validate_table = check_invariants: MyError)]
+    /// ```
+    ValidateTable {
+        name: custom_token::ValidateTable,
+        eq: Token![=],
+        /// The path to the validation function.
+        value: Path,
+        /// The error type of the validation function, if it isn't `&'static str`.
+        error_type: Option<(Token![:], Type)>,
+    },
+    /// Generate a companion struct with an `Option` of every field which has an entry, for partial updates of the struct.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(patch)] // Named after the struct with `Patch` appended
+    /// #[snec(patch(MyPatchType))] // Custom name
+    /// ```
+    Patch {
+        name: custom_token::Patch,
+        parentheses: Option<token::Paren>,
+        /// The name of the patch struct.
+        value: Option<Ident>,
+    },
+    /// Generate a builder for the struct, with a setter for every field and a `build` method which applies defaults and runs validators.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(builder)] // Named after the struct with `Builder` appended
+    /// #[snec(builder(MyBuilderType))] // Custom name
+    /// ```
+    Builder {
+        name: custom_token::Builder,
+        parentheses: Option<token::Paren>,
+        /// The name of the builder struct.
+        value: Option<Ident>,
+    },
+    /// Add attributes to the patch struct generated by the `Patch` command, or to the field of the patch struct generated for a field. Must be outer, not inner.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(patch_attributes(#[derive(Debug, Clone, serde::Deserialize)]))]
+    /// ```
+    PatchAttributes {
+        name: custom_token::PatchAttributes,
+        parentheses: token::Paren,
+        value: Vec<Attribute>,
+    },
+    /// Set the validator of a field's generated `Entry` marker, a function which takes a reference to the value and returns `Result<(), &'static str>`.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(validate = check_port)]
+    /// ```
+    Validate {
+        name: custom_token::Validate,
+        eq: Token![=],
+        /// The path to the validation function.
+        value: Path,
+    },
+    /// Override the `NAME` of a field's generated `Entry` marker, which defaults to the name of the field.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(name = "max-connections")]
+    /// ```
+    Name {
+        name: custom_token::Name,
+        eq: Token![=],
+        value: LitStr,
+    },
+    /// Set the `UNIT` of a field's generated `Entry` marker, the unit of measurement of its value.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(unit = "ms")]
+    /// ```
+    Unit {
+        name: custom_token::Unit,
+        eq: Token![=],
+        value: LitStr,
+    },
+    /// Set the `UI_RANGE` of a field's generated `Entry` marker, the range of values offered by user interfaces, independent from validation.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(ui(min = 0.0, max = 1.0, step = 0.05))]
+    /// ```
+    Ui {
+        name: custom_token::Ui,
+        parentheses: token::Paren,
+        value: Punctuated<UiArgument, Token![,]>,
+    },
+    /// Re-export the contents of the module containing entries generated by the `Entry` command next to the struct, using the visibility of the module.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(reexport_entries)]
+    /// ```
+    ReexportEntries {
+        name: custom_token::ReexportEntries,
+    },
+    /// Write the code generated for the struct to a file, for inspection.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(debug_expansion)]
+    /// ```
+    DebugExpansion {
+        name: custom_token::DebugExpansion,
+    },
+    /// Make every field of the struct an entry, as if it had a `#[snec]` attribute, unless it has a `#[snec(skip)]` attribute. Fields which only have other commands, such as `receiver`, get an entry as well.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(all_fields)]
+    /// ```
+    AllFields {
+        name: custom_token::AllFields,
+    },
+    /// Implement `Default` for the struct, using the default values of the entries of the fields which have them, and the `Default` implementations of the types of the other fields.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(derive_default)]
+    /// ```
+    DeriveDefault {
+        name: custom_token::DeriveDefault,
+    },
+    /// Mark a field's generated `Entry` marker as secret, redacting its value in debug output and withholding it from dynamic receivers. With `zeroize`, also erase old values with `Zeroize` when they are overwritten, and the field when the struct is dropped.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(secret)]
+    /// #[snec(secret(zeroize))]
+    /// ```
+    Secret {
+        name: custom_token::Secret,
+        parentheses: Option<token::Paren>,
+        zeroize: Option<custom_token::Zeroize>,
+    },
+    /// Mirror a field's entry in shared memory, making the struct implement `ShmTable` with the entries of all fields marked like this.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(shm)]
+    /// ```
+    Shm {
+        name: custom_token::Shm,
+    },
+    /// Exclude a field from the config table when `AllFields` is used. Cannot be combined with other commands.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(skip)]
+    /// ```
+    Skip {
+        name: custom_token::Skip,
+    },
+    /// Set the default value for a field's generated `Entry` marker.
+    ///
+    /// Usage:
+    /// ```rust
+    /// #[snec(default = 42)]
+    /// ```
+    Default {
+        name: custom_token::Default,
+        eq: Token![=],
+        /// Expression producing the default value, pasted into the `default_value` implementation.
+        value: Expr,
+    },
 }
 /// Expands `#[snec]` to `#[snec(entry)]`.
 impl Default for AttributeCommand {
@@ -206,7 +449,7 @@ impl Default for AttributeCommand {
         }
     }
 }
-#[allow(clippy::clippy::eval_order_dependence)] // same here
+#[allow(clippy::mixed_read_write_in_expression)] // same here
 impl Parse for AttributeCommand {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let ident = input.parse::<Ident>()?;
@@ -230,6 +473,53 @@ impl Parse for AttributeCommand {
                 parentheses,
                 value: inside_parentheses.map(|input| input.parse()).transpose()?,
             }
+        } else if ident == "patch" {
+            let (
+                parentheses,
+                inside_parentheses,
+            ) = if let Some((parentheses, inside_parentheses)) = parentheses {
+                (Some(parentheses), Some(inside_parentheses))
+            } else {
+                (None, None)
+            };
+            Self::Patch {
+                name: custom_token::Patch(ident.span()),
+                parentheses,
+                value: inside_parentheses.map(|input| input.parse()).transpose()?,
+            }
+        } else if ident == "builder" {
+            let (
+                parentheses,
+                inside_parentheses,
+            ) = if let Some((parentheses, inside_parentheses)) = parentheses {
+                (Some(parentheses), Some(inside_parentheses))
+            } else {
+                (None, None)
+            };
+            Self::Builder {
+                name: custom_token::Builder(ident.span()),
+                parentheses,
+                value: inside_parentheses.map(|input| input.parse()).transpose()?,
+            }
+        } else if ident == "patch_attributes" {
+            let (parentheses, inside_parentheses) = if let Some((
+                parentheses,
+                inside_parentheses,
+            )) = parentheses {
+                (parentheses, inside_parentheses)
+            } else {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(patch_attributes(...))]` attributes cannot be empty",
+                    )
+                )
+            };
+            Self::PatchAttributes {
+                name: custom_token::PatchAttributes(ident.span()),
+                parentheses,
+                value: inside_parentheses.call(Attribute::parse_outer)?,
+            }
         } else if ident == "receiver" {
             let (parentheses, inside_parentheses) = if let Some((
                 parentheses,
@@ -254,6 +544,30 @@ impl Parse for AttributeCommand {
                 colon: inside_parentheses.parse()?,
                 ty: inside_parentheses.parse()?,
             }
+        } else if ident == "table_receiver" {
+            let (parentheses, inside_parentheses) = if let Some((
+                parentheses,
+                inside_parentheses,
+            )) = parentheses {
+                (parentheses, inside_parentheses)
+            } else {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(table_receiver(...))]` attributes cannot be empty",
+                    )
+                )
+            };
+            let inside_braces;
+            let braces = braced!(inside_braces in inside_parentheses);
+            Self::TableReceiver {
+                name: custom_token::TableReceiver(ident.span()),
+                parentheses,
+                braces,
+                expression: inside_braces.parse()?,
+                colon: inside_parentheses.parse()?,
+                ty: inside_parentheses.parse()?,
+            }
         } else if ident == "use_entry" {
             let (parentheses, inside_parentheses) = if let Some((
                 parentheses,
@@ -290,7 +604,7 @@ impl Parse for AttributeCommand {
             Self::EntryModule {
                 name: custom_token::EntryModule(ident.span()),
                 parentheses,
-                value: inside_parentheses.parse()?,
+                value: inside_parentheses.call(Path::parse_mod_style)?,
             }
         } else if ident == "entry_module_visibility" {
             let (parentheses, inside_parentheses) = if let Some((
@@ -311,6 +625,25 @@ impl Parse for AttributeCommand {
                 parentheses,
                 value: inside_parentheses.parse()?,
             }
+        } else if ident == "entry_visibility" {
+            let (parentheses, inside_parentheses) = if let Some((
+                parentheses,
+                inside_parentheses,
+            )) = parentheses {
+                (parentheses, inside_parentheses)
+            } else {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(entry_visibility(...))]` attributes cannot be empty",
+                    )
+                )
+            };
+            Self::EntryVisibility {
+                name: custom_token::EntryVisibility(ident.span()),
+                parentheses,
+                value: inside_parentheses.parse()?,
+            }
         } else if ident == "entry_module_attributes" {
             let (parentheses, inside_parentheses) = if let Some((
                 parentheses,
@@ -330,17 +663,230 @@ impl Parse for AttributeCommand {
                 parentheses,
                 value: inside_parentheses.call(Attribute::parse_outer)?,
             }
+        } else if ident == "invalidates" {
+            let (parentheses, inside_parentheses) = if let Some((
+                parentheses,
+                inside_parentheses,
+            )) = parentheses {
+                (parentheses, inside_parentheses)
+            } else {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(invalidates(...))]` attributes cannot be empty",
+                    )
+                )
+            };
+            Self::Invalidates {
+                name: custom_token::Invalidates(ident.span()),
+                parentheses,
+                value: inside_parentheses.call(Punctuated::parse_terminated)?,
+            }
+        } else if ident == "all_fields"
+            || ident == "skip"
+            || ident == "shm"
+            || ident == "reexport_entries"
+            || ident == "debug_expansion"
+            || ident == "derive_default" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        format!("`#[snec({})]` attributes do not take any arguments", ident),
+                    )
+                )
+            }
+            if ident == "all_fields" {
+                Self::AllFields {name: custom_token::AllFields(ident.span())}
+            } else if ident == "reexport_entries" {
+                Self::ReexportEntries {name: custom_token::ReexportEntries(ident.span())}
+            } else if ident == "debug_expansion" {
+                Self::DebugExpansion {name: custom_token::DebugExpansion(ident.span())}
+            } else if ident == "derive_default" {
+                Self::DeriveDefault {name: custom_token::DeriveDefault(ident.span())}
+            } else if ident == "shm" {
+                Self::Shm {name: custom_token::Shm(ident.span())}
+            } else {
+                Self::Skip {name: custom_token::Skip(ident.span())}
+            }
+        } else if ident == "secret" {
+            let (
+                parentheses,
+                inside_parentheses,
+            ) = if let Some((parentheses, inside_parentheses)) = parentheses {
+                (Some(parentheses), Some(inside_parentheses))
+            } else {
+                (None, None)
+            };
+            Self::Secret {
+                name: custom_token::Secret(ident.span()),
+                parentheses,
+                zeroize: inside_parentheses.map(|input| input.parse()).transpose()?,
+            }
+        } else if ident == "receiver_field" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(receiver_field = ...)]` attributes use `=` instead of parentheses",
+                    )
+                )
+            }
+            Self::ReceiverField {
+                name: custom_token::ReceiverField(ident.span()),
+                eq: input.parse()?,
+                value: input.parse()?,
+            }
+        } else if ident == "deferred" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(deferred = ...)]` attributes use `=` instead of parentheses",
+                    )
+                )
+            }
+            Self::Deferred {
+                name: custom_token::Deferred(ident.span()),
+                eq: input.parse()?,
+                value: input.parse()?,
+            }
+        } else if ident == "validate_table" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(validate_table = ...)]` attributes use `=` instead of parentheses",
+                    )
+                )
+            }
+            Self::ValidateTable {
+                name: custom_token::ValidateTable(ident.span()),
+                eq: input.parse()?,
+                value: input.parse()?,
+                error_type: if input.peek(Token![:]) {
+                    Some((input.parse()?, input.parse()?))
+                } else {
+                    None
+                },
+            }
+        } else if ident == "validate" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(validate = ...)]` attributes use `=` instead of parentheses",
+                    )
+                )
+            }
+            Self::Validate {
+                name: custom_token::Validate(ident.span()),
+                eq: input.parse()?,
+                value: input.parse()?,
+            }
+        } else if ident == "name" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(name = \"...\")]` attributes use `=` instead of parentheses",
+                    )
+                )
+            }
+            Self::Name {
+                name: custom_token::Name(ident.span()),
+                eq: input.parse()?,
+                value: input.parse()?,
+            }
+        } else if ident == "unit" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(unit = \"...\")]` attributes use `=` instead of parentheses",
+                    )
+                )
+            }
+            Self::Unit {
+                name: custom_token::Unit(ident.span()),
+                eq: input.parse()?,
+                value: input.parse()?,
+            }
+        } else if ident == "ui" {
+            let (parentheses, inside_parentheses) = if let Some((
+                parentheses,
+                inside_parentheses,
+            )) = parentheses {
+                (parentheses, inside_parentheses)
+            } else {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(ui(...))]` attributes cannot be empty",
+                    )
+                )
+            };
+            Self::Ui {
+                name: custom_token::Ui(ident.span()),
+                parentheses,
+                value: inside_parentheses.call(Punctuated::parse_terminated)?,
+            }
+        } else if ident == "default" {
+            if parentheses.is_some() {
+                return Err(
+                    syn::Error::new(
+                        ident.span(),
+                        "`#[snec(default = ...)]` attributes use `=` instead of parentheses",
+                    )
+                )
+            }
+            Self::Default {
+                name: custom_token::Default(ident.span()),
+                eq: input.parse()?,
+                value: input.parse()?,
+            }
         } else {
             return Err(
                 syn::Error::new(
                     ident.span(),
-                    "expected `entry`, `receiver`, `use_entry` or `entry_module` command name"),
+                    "\
+expected `entry`, `receiver`, `receiver_field`, `table_receiver`, `deferred`, `use_entry`, `entry_module`, \
+`entry_module_visibility`, `entry_visibility`, `entry_module_attributes`, `reexport_entries`, \
+`debug_expansion`, `all_fields`, `skip`, `invalidates`, `validate`, `validate_table`, `patch`, \
+`patch_attributes`, `builder`, `derive_default`, `secret`, `shm`, `name`, `unit`, `ui` or \
+`default` command name",
+                ),
             )
         };
         Ok(result)
     }
 }
 
+/// One of the bounds or the step in `#[snec(ui(...))]`, e.g. `min = 0.0`.
+pub struct UiArgument {
+    pub name: Ident,
+    pub eq: Token![=],
+    pub value: Expr,
+}
+impl Parse for UiArgument {
+    #[inline]
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse::<Ident>()?;
+        if name != "min" && name != "max" && name != "step" {
+            return Err(
+                syn::Error::new(name.span(), "expected `min`, `max` or `step`")
+            )
+        }
+        Ok(
+            Self {
+                name,
+                eq: input.parse()?,
+                value: input.parse()?,
+            }
+        )
+    }
+}
+
 pub enum AttributeCommandIter<I: Iterator<Item = AttributeCommand>> {
     Iterator(I),
     Single(Option<AttributeCommand>),
@@ -405,9 +951,33 @@ pub mod custom_token {
         // Command names
         (Entry, "entry"),
         (Receiver, "receiver"),
+        (ReceiverField, "receiver_field"),
+        (TableReceiver, "table_receiver"),
+        (Deferred, "deferred"),
         (UseEntry, "use_entry"),
         (EntryModule, "entry_module"),
         (EntryModuleVisibility, "entry_module_visibility"),
+        (EntryVisibility, "entry_visibility"),
         (EntryModuleAttributes, "entry_module_attributes"),
+        (ReexportEntries, "reexport_entries"),
+        (DebugExpansion, "debug_expansion"),
+        (AllFields, "all_fields"),
+        (Skip, "skip"),
+        (Invalidates, "invalidates"),
+        (ValidateTable, "validate_table"),
+        (Patch, "patch"),
+        (PatchAttributes, "patch_attributes"),
+        (Builder, "builder"),
+        (Validate, "validate"),
+        (DeriveDefault, "derive_default"),
+        (Secret, "secret"),
+        (Shm, "shm"),
+        (Name, "name"),
+        (Unit, "unit"),
+        (Ui, "ui"),
+        (Default, "default"),
+
+        // Command arguments
+        (Zeroize, "zeroize"),
     }
 }
\ No newline at end of file
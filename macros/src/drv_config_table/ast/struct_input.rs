@@ -3,6 +3,7 @@ use syn::{
     token,
     Token,
     braced,
+    parenthesized,
     Attribute,
     Visibility,
     Generics,
@@ -17,8 +18,16 @@ pub struct ConfigTableStruct {
     pub struct_token: Token![struct],
     pub ident: Ident,
     pub generics: Generics,
-    pub braces: token::Brace,
+    pub delimiter: StructDelimiter,
+    /// The fields of the struct, which are unnamed if it's a tuple struct.
     pub fields: Punctuated<Field, Token![,]>,
+    /// The semicolon terminating a tuple struct.
+    pub semicolon: Option<Token![;]>,
+}
+/// The delimiter around the fields of a struct, which tells apart normal and tuple structs.
+pub enum StructDelimiter {
+    Braces(token::Brace),
+    Parentheses(token::Paren),
 }
 impl Parse for ConfigTableStruct {
     #[inline]
@@ -28,13 +37,22 @@ impl Parse for ConfigTableStruct {
         let struct_token = input.parse()?;
         let ident = input.parse()?;
         let generics = input.parse()?;
-        let inside_braces;
-        let braces = braced!(inside_braces in input);
-        let fields = inside_braces.call(
-            |input| Punctuated::parse_terminated_with(input, Field::parse_named),
-        )?;
+        let inside_delimiter;
+        let (delimiter, fields, semicolon) = if input.peek(token::Paren) {
+            let parentheses = parenthesized!(inside_delimiter in input);
+            let fields = inside_delimiter.call(
+                |input| Punctuated::parse_terminated_with(input, Field::parse_unnamed),
+            )?;
+            (StructDelimiter::Parentheses(parentheses), fields, Some(input.parse()?))
+        } else {
+            let braces = braced!(inside_delimiter in input);
+            let fields = inside_delimiter.call(
+                |input| Punctuated::parse_terminated_with(input, Field::parse_named),
+            )?;
+            (StructDelimiter::Braces(braces), fields, None)
+        };
         Ok (
-            Self {attrs, visibility, struct_token, ident, generics, braces, fields}
+            Self {attrs, visibility, struct_token, ident, generics, delimiter, fields, semicolon}
         )
     }
 }
\ No newline at end of file
@@ -1,32 +1,52 @@
 use super::*;
 use quote::ToTokens;
+use std::{env, fs, path::PathBuf};
+use syn::{ExprLit, ExprUnary, LitFloat, UnOp};
 
-/// Converts an iterator over normal Rust attributes to an iterator of `SnecAttribute`s by filtering out all attributes which were not for Snec.
+/// Converts an iterator over normal Rust attributes to a list of `SnecAttribute`s by filtering out all attributes which were not for Snec. Fails if any of the Snec attributes is malformed, so that a typo doesn't silently turn into a missing entry.
 #[inline]
 pub fn filter_to_snec_attributes(
     attributes: impl IntoIterator<Item = Attribute>,
-) -> impl Iterator<Item = SnecAttribute> {
-    attributes.into_iter().filter_map(|attr| {
-        SnecAttribute::try_from(attr).ok()
-    })
+) -> syn::Result<Vec<SnecAttribute>> {
+    attributes.into_iter()
+        // Attributes of other derives, like `#[serde(skip)]`, can look exactly like ours.
+        .filter(|attr| attr.path.is_ident("snec"))
+        .map(SnecAttribute::try_from)
+        .collect()
 }
 
-pub fn concat_to_path(x: Ident, y: Ident) -> Path {
-    let x = PathSegment {
-        ident: x,
-        arguments: PathArguments::None,
-    };
+/// Collects the contents of `///` comments and `#[doc = "..."]` attributes into one string, removing the space which conventionally follows the `///` and the whitespace around the whole text.
+pub fn collect_doc_comments(attributes: &[Attribute]) -> String {
+    let lines = attributes.iter().filter_map(|attr| {
+        match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {path, lit: Lit::Str(lit), ..})) if path.is_ident("doc") => {
+                Some(lit.value())
+            },
+            _ => None,
+        }
+    });
+    let mut result = String::new();
+    for line in lines {
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(line.strip_prefix(' ').unwrap_or(&line));
+    }
+    result.trim().to_string()
+}
+
+/// Appends an identifier to a module path. Appending to `self` produces just the identifier, which, unlike `self::y`, also works inside of function bodies.
+pub fn concat_to_path(x: Path, y: Ident) -> Path {
+    if x.is_ident("self") {
+        return y.into();
+    }
     let y = PathSegment {
         ident: y,
         arguments: PathArguments::None,
     };
-    let segments = {
-        let mut segments = Punctuated::new();
-        segments.push(x);
-        segments.push(y);
-        segments
-    };
-    Path {leading_colon: None, segments}
+    let mut path = x;
+    path.segments.push(y);
+    path
 }
 
 /// Constructs an expression which points to the `::snec::EmptyReceiver` unit constructor with call-site hygeine.
@@ -69,10 +89,116 @@ fn default_receiver_path() -> Path {
     };
     Path {leading_colon, segments}
 }
-/// Constructs an identifier pointing to `entries` with call-site hygeine.
+/// Constructs a path pointing to `entries` with call-site hygeine.
+#[inline]
+pub fn default_entry_module() -> Path {
+    Ident::new("entries", Span::call_site()).into()
+}
+
+/// Returns the number of modules between the struct and its entries: 0 for `self`, which places the entries next to the struct, or the number of segments of the path otherwise.
+pub fn entry_module_depth(entry_module: &Path) -> Result<usize, syn::Error> {
+    let is_keyword = |segment: &PathSegment| {
+        segment.ident == "self" || segment.ident == "super" || segment.ident == "crate"
+    };
+    if entry_module.is_ident("self") {
+        Ok(0)
+    } else if entry_module.leading_colon.is_some() || entry_module.segments.iter().any(is_keyword) {
+        Err(
+            syn::Error::new(
+                entry_module.span(),
+                "the entry module must be `self` or a path of module names relative to the struct",
+            )
+        )
+    } else {
+        Ok(entry_module.segments.len())
+    }
+}
+
+/// Creates the expression used to access a field: its name for named fields or its index for the fields of tuple structs.
+#[inline]
+pub fn field_member(ident: Option<Ident>, index: usize, span: Span) -> Member {
+    match ident {
+        Some(ident) => Member::Named(ident),
+        None => Member::Unnamed(
+            Index {index: index as u32, span}
+        ),
+    }
+}
+/// Returns the name of a field, which is the index for the fields of tuple structs.
 #[inline]
-pub fn default_entry_module() -> Ident {
-    Ident::new("entries", Span::call_site())
+pub fn member_to_string(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+/// Returns the default name of the entry marker type for a field: the name of the field converted to `CamelCase`, or `Field` followed by the index for the fields of tuple structs.
+#[inline]
+pub fn default_marker_name(member: &Member) -> Ident {
+    match member {
+        Member::Named(ident) => snake_to_camel(ident.clone()),
+        Member::Unnamed(index) => Ident::new(&format!("Field{}", index.index), index.span),
+    }
+}
+
+/// Checks whether the tokens contain the `self` keyword anywhere, including inside of groups.
+pub fn mentions_self(tokens: &TokenStream) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => ident == "self",
+        TokenTree::Group(group) => mentions_self(&group.stream()),
+        _ => false,
+    })
+}
+
+/// Renders a type the way it would usually be written in source code, e.g. `Vec<&'static str>` rather than `Vec < & 'static str >`, which is what `to_string` produces.
+pub fn type_to_string(ty: &Type) -> String {
+    let mut result = String::new();
+    write_tokens(ty.to_token_stream(), &mut result);
+    result
+}
+fn write_tokens(tokens: TokenStream, result: &mut String) {
+    let mut previous_was_word = false;
+    let mut previous_was_joint_minus = false;
+    for token in tokens {
+        let is_word = matches!(token, TokenTree::Ident(..) | TokenTree::Literal(..));
+        let is_joint_minus = matches!(
+            &token,
+            TokenTree::Punct(punct) if punct.as_char() == '-' && punct.spacing() == Spacing::Joint
+        );
+        if is_word && previous_was_word {
+            result.push(' ');
+        }
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                result.push_str(open);
+                write_tokens(group.stream(), result);
+                result.push_str(close);
+            },
+            TokenTree::Punct(punct) => match punct.as_char() {
+                ',' | ';' => {
+                    result.push(punct.as_char());
+                    result.push(' ');
+                },
+                '+' | '=' => {
+                    result.push(' ');
+                    result.push(punct.as_char());
+                    result.push(' ');
+                },
+                '-' if is_joint_minus => result.push_str(" -"),
+                '>' if previous_was_joint_minus => result.push_str("> "),
+                c => result.push(c),
+            },
+            token => result.push_str(&token.to_string()),
+        }
+        previous_was_joint_minus = is_joint_minus;
+        previous_was_word = is_word;
+    }
 }
 
 /// Converts a `snake_case` identifier to a `CamelCase` one, preserving its exact span.
@@ -94,4 +220,72 @@ pub fn snake_to_camel(ident: Ident) -> Ident {
         }
     });
     Ident::new(&result, span)
-}
\ No newline at end of file
+}
+/// Converts a visibility written relative to the module containing the struct into one which means the same thing inside of the module containing the entries, which is `depth` levels deeper.
+pub fn visibility_in_entry_module(visibility: Visibility, depth: usize) -> TokenStream {
+    if depth == 0 {
+        return visibility.into_token_stream();
+    }
+    let supers = vec![quote! {super}; depth];
+    match visibility {
+        Visibility::Inherited => quote! {pub(in #(#supers)::*)},
+        Visibility::Restricted(VisRestricted {path, ..}) => {
+            let first_segment = path.segments.first().map(|segment| segment.ident.to_string());
+            match first_segment.as_deref() {
+                Some("self") if path.leading_colon.is_none() => {
+                    let rest = path.segments.iter().skip(1);
+                    quote! {pub(in #(#supers)::* #(::#rest)*)}
+                },
+                Some("super") if path.leading_colon.is_none() => quote! {pub(in #(#supers::)* #path)},
+                _ => quote! {pub(in #path)},
+            }
+        },
+        visibility => visibility.into_token_stream(),
+    }
+}
+
+/// Combines the conditions of `#[cfg(...)]` attributes into one, or returns `None` if there are no such attributes.
+pub fn cfg_condition(cfg_attributes: &[Attribute]) -> Option<TokenStream> {
+    let mut conditions = cfg_attributes.iter().map(|attr| {
+        match attr.tokens.clone().into_iter().next() {
+            Some(TokenTree::Group(group)) => group.stream(),
+            _ => attr.tokens.clone(),
+        }
+    }).collect::<Vec<_>>();
+    match conditions.len() {
+        0 => None,
+        1 => conditions.pop(),
+        _ => Some(quote! {all(#(#conditions),*)}),
+    }
+}
+
+/// Writes the code generated for a struct to `{directory}/{struct name}.rs`, where the directory is the `OUT_DIR` of the crate if it has a build script, or `target/snec-expansion` inside of the crate otherwise. Returns the error message if writing fails.
+pub fn write_expansion(struct_name: &Ident, expansion: &TokenStream) -> Result<(), String> {
+    let directory = match env::var_os("OUT_DIR") {
+        Some(out_dir) => PathBuf::from(out_dir),
+        None => {
+            let manifest_dir = env::var_os("CARGO_MANIFEST_DIR")
+                .ok_or("cannot find the crate directory to write the expansion to")?;
+            PathBuf::from(manifest_dir).join("target").join("snec-expansion")
+        },
+    };
+    let path = directory.join(format!("{}.rs", struct_name));
+    fs::create_dir_all(&directory)
+        .and_then(|_| fs::write(&path, expansion.to_string()))
+        .map_err(|error| format!("failed to write the expansion to `{}`: {}", path.display(), error))
+}
+
+/// Turns integer literals, including negated ones, into floating-point literals, so that `#[snec(ui(min = 0))]` can be used for entries with integer values without a cast. Other expressions are left as they are.
+pub fn integer_literal_to_float(expr: Expr) -> Expr {
+    match expr {
+        Expr::Lit(ExprLit {attrs, lit: Lit::Int(int)}) => {
+            let float = LitFloat::new(&format!("{}.0", int.base10_digits()), int.span());
+            Expr::Lit(ExprLit {attrs, lit: Lit::Float(float)})
+        },
+        Expr::Unary(ExprUnary {attrs, op: UnOp::Neg(minus), expr}) => {
+            let expr = Box::new(integer_literal_to_float(*expr));
+            Expr::Unary(ExprUnary {attrs, op: UnOp::Neg(minus), expr})
+        },
+        other => other,
+    }
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)] // syntax tree nodes keep all of their tokens, even the ones we never read
 mod ast {
     mod snec_attribute;
     pub use snec_attribute::*;
@@ -20,29 +21,59 @@ use syn::{
     Type,
     TypePath,
     Lit,
+    Member,
+    Index,
+    Meta,
+    MetaNameValue,
     LitStr,
     Visibility,
+    VisRestricted,
     punctuated::Punctuated,
+    spanned::Spanned,
     token,
 };
-use proc_macro2::{TokenStream, Span, Ident};
-use quote::quote;
+use proc_macro2::{TokenStream, TokenTree, Delimiter, Spacing, Span, Ident};
+use quote::{quote, format_ident, ToTokens};
 
 pub fn derive_config_table_expand(input: TokenStream) -> Result<TokenStream, syn::Error> {
     let struct_input = syn::parse2::<ConfigTableStruct>(input)?;
     let (
         default_receiver_expr,
         default_receiver_type,
+        default_receiver_field,
+        table_receiver,
+        deferred,
         entry_module,
         entry_module_visibility,
         entry_module_attributes,
+        default_entry_visibility,
+        reexport_entries,
+        debug_expansion,
+        all_fields,
+        validate_table,
+        patch,
+        patch_attributes,
+        builder,
+        derive_default,
     ) = {
         let mut receiver_expr = None;
         let mut receiver_type = None;
+        let mut receiver_field = None;
+        let mut table_receiver = None;
+        let mut deferred = None;
         let mut entry_module = None;
         let mut entry_module_visibility = None;
         let mut entry_module_attributes = Vec::new();
-        for attr in filter_to_snec_attributes(struct_input.attrs) {
+        let mut entry_visibility = None;
+        let mut reexport_entries = None;
+        let mut debug_expansion = None;
+        let mut all_fields = false;
+        let mut validate_table = None;
+        let mut patch = None;
+        let mut patch_attributes = Vec::new();
+        let mut builder = None;
+        let mut derive_default = false;
+        for attr in filter_to_snec_attributes(struct_input.attrs)? {
             let body = if let Some(body) = attr.body {
                 body
             } else {
@@ -64,10 +95,64 @@ pub fn derive_config_table_expand(input: TokenStream) -> Result<TokenStream, syn
                     AttributeCommand::EntryModuleAttributes { value, .. } => {
                         entry_module_attributes.extend(value);
                     },
+                    AttributeCommand::EntryVisibility { value, .. } => {
+                        entry_visibility = Some(value);
+                    },
                     AttributeCommand::Receiver { expression, ty, .. } => {
                         receiver_expr = Some(expression);
                         receiver_type = Some(ty);
                     },
+                    AttributeCommand::ReceiverField { name, value, .. } => {
+                        receiver_field = Some((name, value));
+                    },
+                    AttributeCommand::TableReceiver { expression, ty, .. } => {
+                        table_receiver = Some((expression, ty));
+                    },
+                    AttributeCommand::Deferred { value, .. } => {
+                        deferred = Some(value);
+                    },
+                    AttributeCommand::AllFields { .. } => {
+                        all_fields = true;
+                    },
+                    AttributeCommand::ReexportEntries { name } => {
+                        reexport_entries = Some(name.0);
+                    },
+                    AttributeCommand::DebugExpansion { name } => {
+                        debug_expansion = Some(name.0);
+                    },
+                    AttributeCommand::ValidateTable { value, error_type, .. } => {
+                        validate_table = Some((value, error_type.map(|(_, ty)| ty)));
+                    },
+                    AttributeCommand::Patch { value, .. } => {
+                        patch = Some(value);
+                    },
+                    AttributeCommand::PatchAttributes { value, .. } => {
+                        patch_attributes.extend(value);
+                    },
+                    AttributeCommand::Builder { value, .. } => {
+                        builder = Some(value);
+                    },
+                    AttributeCommand::DeriveDefault { .. } => {
+                        derive_default = true;
+                    },
+                    AttributeCommand::Skip { name } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(skip)]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Invalidates { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(invalidates(...))]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
                     AttributeCommand::Entry { name, .. } => {
                         return Err(
                             syn::Error::new(
@@ -86,24 +171,191 @@ pub fn derive_config_table_expand(input: TokenStream) -> Result<TokenStream, syn
                             )
                         )
                     },
+                    AttributeCommand::Default { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(default = ...)]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Name { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(name = \"...\")]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Unit { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(unit = \"...\")]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Ui { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(ui(...))]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Validate { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(validate = ...)]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Secret { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(secret)]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Shm { name } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+`#[snec(shm)]` attribute cannot be applied to whole struct",
+                            )
+                        )
+                    },
                 }
             }
         }
+        if let (None, Some(attribute)) = (&patch, patch_attributes.first()) {
+            return Err(
+                syn::Error::new(
+                    attribute.span(),
+                    "the `#[snec(patch_attributes(...))]` attribute requires `#[snec(patch)]`",
+                )
+            )
+        }
+        if let (Some(_), Some((name, _))) = (&receiver_expr, &receiver_field) {
+            return Err(
+                syn::Error::new(
+                    name.0,
+                    "the `#[snec(receiver_field = ...)]` attribute cannot be combined with `#[snec(receiver(...))]`",
+                )
+            )
+        }
         (
             receiver_expr.unwrap_or_else(default_receiver_expr),
             receiver_type.unwrap_or_else(default_receiver_type),
+            receiver_field.map(|(_, field)| field),
+            table_receiver,
+            deferred,
             entry_module.unwrap_or_else(default_entry_module),
             entry_module_visibility.unwrap_or(Visibility::Inherited),
             entry_module_attributes,
+            entry_visibility,
+            reexport_entries,
+            debug_expansion,
+            all_fields,
+            validate_table,
+            patch,
+            patch_attributes,
+            builder,
+            derive_default,
         )
     };
+    let depth = entry_module_depth(&entry_module)?;
+    let default_entry_visibility = if depth == 0 {
+        if let Some(attribute) = entry_module_attributes.first() {
+            return Err(
+                syn::Error::new(
+                    attribute.span(),
+                    "\
+`#[snec(entry_module_attributes(...))]` cannot be used with `#[snec(entry_module(self))]`, \
+since no module is generated",
+                )
+            )
+        }
+        if let Some(span) = reexport_entries {
+            return Err(
+                syn::Error::new(
+                    span,
+                    "\
+`#[snec(reexport_entries)]` cannot be used with `#[snec(entry_module(self))]`, \
+since the entries are already next to the struct",
+                )
+            )
+        }
+        // Without a module, the visibility of the module is the visibility of the entries.
+        default_entry_visibility.or_else(|| Some(entry_module_visibility.clone()))
+    } else {
+        default_entry_visibility
+    };
+    // Looked up by `#[snec(receiver_field = ...)]`, which can refer to any field.
+    let field_types = struct_input.fields.iter().enumerate().map(|(index, field)| {
+        (field_member(field.ident.clone(), index, field.ty.span()), field.ty.clone())
+    }).collect::<Vec<_>>();
+    if let Some(queue) = &deferred {
+        if field_types.iter().all(|(member, _)| member != queue) {
+            return Err(
+                syn::Error::new(
+                    queue.span(),
+                    format!("the struct has no field named `{}`", member_to_string(queue)),
+                )
+            )
+        }
+    }
     let mut requested_get_impls = Vec::with_capacity(struct_input.fields.len());
     let mut requested_generated_entries = Vec::with_capacity(struct_input.fields.len());
-    for field in struct_input.fields {
-        let field_ident = field.ident.unwrap();
-        for attr in filter_to_snec_attributes(field.attrs) {
+    let mut patch_fields = Vec::with_capacity(struct_input.fields.len());
+    let mut table_fields = Vec::with_capacity(struct_input.fields.len());
+    for (index, field) in struct_input.fields.into_iter().enumerate() {
+        let field_ident = field_member(field.ident.clone(), index, field.ty.span());
+        let description = collect_doc_comments(&field.attrs);
+        let cfg_attributes = field.attrs.iter()
+            .filter(|attr| attr.path.is_ident("cfg"))
+            .cloned()
+            .collect::<Vec<_>>();
+        let doc_attributes = field.attrs.iter()
+            .filter(|attr| attr.path.is_ident("doc"))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut field_patch_attributes = Vec::new();
+        let mut field_patch_attributes_span = None;
+        table_fields.push(
+            TableField {
+                field_name: field_ident.clone(),
+                field_type: field.ty.clone(),
+                cfg_attributes: cfg_attributes.clone(),
+                doc_attributes: doc_attributes.clone(),
+                setter_name: match &field.ident {
+                    Some(ident) => ident.clone(),
+                    None => format_ident!("field_{}", index),
+                },
+            }
+        );
+        let mut bodies = filter_to_snec_attributes(field.attrs)?
+            .into_iter()
+            .map(|attr| attr.body)
+            .collect::<Vec<_>>();
+        if all_fields && bodies.is_empty() {
+            // Same as a bare `#[snec]`.
+            bodies.push(None);
+        }
+        let mut skip = None;
+        let mut used = false;
+        for body in bodies {
             let commands = {
-                if let Some(body) = attr.body {
+                if let Some(body) = body {
                     body.commands.into_iter().into()
                 } else {
                     AttributeCommandIter::from(AttributeCommand::default())
@@ -115,6 +367,18 @@ pub fn derive_config_table_expand(input: TokenStream) -> Result<TokenStream, syn
             let mut custom_marker_name = None;
             let mut custom_receiver_expr = None;
             let mut custom_receiver_type = None;
+            let mut custom_receiver_field = None;
+            let mut invalidates = Vec::new();
+            let mut default_value = None;
+            let mut validator = None;
+            let mut secret = None;
+            let mut zeroize_secret = false;
+            let mut shm = None;
+            let mut entry_name = None;
+            let mut unit = None;
+            let mut ui_range = None;
+            let mut entry_visibility = None;
+            let mut skipped = None;
             for command in commands {
                 match command {
                     AttributeCommand::Entry { value, .. } => {
@@ -135,6 +399,125 @@ pub fn derive_config_table_expand(input: TokenStream) -> Result<TokenStream, syn
                         custom_receiver_expr = Some(expression);
                         custom_receiver_type = Some(ty);
                     },
+                    AttributeCommand::ReceiverField { name, value, .. } => {
+                        custom_receiver_field = Some((name, value));
+                    },
+                    AttributeCommand::Invalidates { value, .. } => {
+                        invalidates.extend(value);
+                    },
+                    AttributeCommand::Default { name, value, .. } => {
+                        default_value = Some((name, value));
+                    },
+                    AttributeCommand::Validate { name, value, .. } => {
+                        validator = Some((name, value));
+                    },
+                    AttributeCommand::Secret { name, zeroize, .. } => {
+                        secret = Some(name.0);
+                        zeroize_secret |= zeroize.is_some();
+                    },
+                    AttributeCommand::Name { name, value, .. } => {
+                        entry_name = Some((name, value));
+                    },
+                    AttributeCommand::Unit { name, value, .. } => {
+                        unit = Some((name, value));
+                    },
+                    AttributeCommand::Ui { name, value, .. } => {
+                        ui_range = Some((name, UiRangeData::try_from(value)?));
+                    },
+                    AttributeCommand::Shm { name } => {
+                        shm = Some(name.0);
+                    },
+                    AttributeCommand::EntryVisibility { name, value, .. } => {
+                        entry_visibility = Some((name, value));
+                    },
+                    AttributeCommand::PatchAttributes { name, value, .. } => {
+                        field_patch_attributes_span.get_or_insert(name.0);
+                        field_patch_attributes.extend(value);
+                    },
+                    AttributeCommand::Skip { name } => {
+                        skipped = Some(name.0);
+                    },
+                    AttributeCommand::AllFields { name } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(all_fields)]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::ReexportEntries { name } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(reexport_entries)]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::DebugExpansion { name } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(debug_expansion)]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Patch { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(patch(...))]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Builder { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(builder(...))]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::DeriveDefault { name } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(derive_default)]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::ValidateTable { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(validate_table = ...)]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::TableReceiver { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(table_receiver(...))]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
+                    AttributeCommand::Deferred { name, .. } => {
+                        return Err(
+                            syn::Error::new(
+                                name.0,
+                                "\
+the `#[snec(deferred = ...)]` attribute can only be applied to the whole struct",
+                            )
+                        )
+                    },
                     AttributeCommand::EntryModule { name, .. } => {
                         return Err(
                             syn::Error::new(
@@ -164,31 +547,232 @@ the `#[snec(entry_module_attributes(...))]` attribute can only be applied to the
                     },
                 }
             }
+            if let Some(span) = skipped {
+                if generate_get_impl
+                    || custom_receiver_expr.is_some()
+                    || custom_receiver_field.is_some()
+                    || !invalidates.is_empty()
+                    || default_value.is_some()
+                    || validator.is_some()
+                    || secret.is_some()
+                    || shm.is_some()
+                    || entry_name.is_some()
+                    || unit.is_some()
+                    || ui_range.is_some()
+                    || entry_visibility.is_some() {
+                    return Err(
+                        syn::Error::new(
+                            span,
+                            "the `#[snec(skip)]` attribute cannot be combined with other commands",
+                        )
+                    )
+                }
+                skip = Some(span);
+                continue;
+            }
+            if all_fields && !generate_get_impl {
+                generate_get_impl = true;
+                generate_entry = true;
+            }
+            used |= generate_get_impl;
+            if let (Some(member), false) = (invalidates.first(), generate_get_impl) {
+                return Err(
+                    syn::Error::new(
+                        member.span(),
+                        "\
+the `#[snec(invalidates(...))]` attribute can only be used together with `#[snec(entry)]` or \
+`#[snec(use_entry(...))]`",
+                    )
+                )
+            }
+            let default_value = match default_value {
+                Some((name, _)) if !generate_entry => {
+                    return Err(
+                        syn::Error::new(
+                            name.0,
+                            "\
+the `#[snec(default = ...)]` attribute can only be used together with `#[snec(entry)]`",
+                        )
+                    )
+                },
+                Some((_, value)) => Some(value),
+                None => None,
+            };
+            let validator = match validator {
+                Some((name, _)) if !generate_entry => {
+                    return Err(
+                        syn::Error::new(
+                            name.0,
+                            "\
+the `#[snec(validate = ...)]` attribute can only be used together with `#[snec(entry)]`",
+                        )
+                    )
+                },
+                Some((_, value)) => Some(value),
+                None => None,
+            };
+            if let (Some(span), false) = (secret, generate_entry) {
+                return Err(
+                    syn::Error::new(
+                        span,
+                        "the `#[snec(secret)]` attribute can only be used together with `#[snec(entry)]`",
+                    )
+                )
+            }
+            if let (Some(span), false) = (shm, generate_get_impl) {
+                return Err(
+                    syn::Error::new(
+                        span,
+                        "\
+the `#[snec(shm)]` attribute can only be used together with `#[snec(entry)]` or \
+`#[snec(use_entry(...))]`",
+                    )
+                )
+            }
+            let entry_name = match entry_name {
+                Some((name, _)) if !generate_entry => {
+                    return Err(
+                        syn::Error::new(
+                            name.0,
+                            "\
+the `#[snec(name = \"...\")]` attribute can only be used together with `#[snec(entry)]`",
+                        )
+                    )
+                },
+                Some((_, value)) => Some(value),
+                None => None,
+            };
+            let unit = match unit {
+                Some((name, _)) if !generate_entry => {
+                    return Err(
+                        syn::Error::new(
+                            name.0,
+                            "\
+the `#[snec(unit = \"...\")]` attribute can only be used together with `#[snec(entry)]`",
+                        )
+                    )
+                },
+                Some((_, value)) => Some(value),
+                None => None,
+            };
+            let ui_range = match ui_range {
+                Some((name, _)) if !generate_entry => {
+                    return Err(
+                        syn::Error::new(
+                            name.0,
+                            "\
+the `#[snec(ui(...))]` attribute can only be used together with `#[snec(entry)]`",
+                        )
+                    )
+                },
+                Some((_, value)) => Some(value),
+                None => None,
+            };
+            let entry_visibility = match entry_visibility {
+                Some((name, _)) if !generate_entry => {
+                    return Err(
+                        syn::Error::new(
+                            name.0,
+                            "\
+the `#[snec(entry_visibility(...))]` attribute can only be used together with `#[snec(entry)]`",
+                        )
+                    )
+                },
+                Some((_, value)) => Some(value),
+                None => default_entry_visibility.clone(),
+            };
             if generate_entry {
                 requested_generated_entries.push(
                     RequestedGeneratedEntry {
                         field_name: field_ident.clone(),
+                        cfg_attributes: cfg_attributes.clone(),
                         field_type: field.ty.clone(),
+                        description: description.clone(),
+                        default_value,
+                        validator,
+                        secret: secret.is_some(),
+                        zeroize: zeroize_secret,
+                        entry_name,
+                        unit,
+                        ui_range,
+                        visibility: entry_visibility,
                         marker_name: custom_marker_name.unwrap_or_else(
-                            || snake_to_camel(field_ident.clone())
+                            || default_marker_name(&field_ident)
                         ),
                     }
                 )
             }
             if generate_get_impl {
+                if let (Some(_), Some((name, _))) = (&custom_receiver_expr, &custom_receiver_field) {
+                    return Err(
+                        syn::Error::new(
+                            name.0,
+                            "the `#[snec(receiver_field = ...)]` attribute cannot be combined with `#[snec(receiver(...))]`",
+                        )
+                    )
+                }
+                if deferred.as_ref() == Some(&field_ident) {
+                    return Err(
+                        syn::Error::new(
+                            field_ident.span(),
+                            "the field storing the queue of `#[snec(deferred = ...)]` cannot have an entry",
+                        )
+                    )
+                }
+                // A receiver specified for the field takes precedence over any kind of receiver specified for the struct.
+                let receiver_field = match custom_receiver_field {
+                    Some((_, field)) => Some(field),
+                    None if custom_receiver_expr.is_some() => None,
+                    None => default_receiver_field.clone(),
+                };
+                let mut receiver_expr = custom_receiver_expr.unwrap_or_else(
+                    || default_receiver_expr.clone()
+                );
+                let mut receiver_type = custom_receiver_type.unwrap_or_else(
+                    || default_receiver_type.clone()
+                );
+                if let Some(receiver_field) = &receiver_field {
+                    let field_type = field_types.iter().find_map(|(member, ty)| {
+                        if member == receiver_field {Some(ty)} else {None}
+                    }).ok_or_else(|| {
+                        syn::Error::new(
+                            receiver_field.span(),
+                            format!("the struct has no field named `{}`", member_to_string(receiver_field)),
+                        )
+                    })?;
+                    if *receiver_field == field_ident {
+                        return Err(
+                            syn::Error::new(
+                                receiver_field.span(),
+                                "a field cannot be the receiver of its own entry",
+                            )
+                        )
+                    }
+                    receiver_type = syn::parse_quote! {::snec::BorrowedReceiver<#field_type>};
+                }
+                if let Some((table_receiver_expr, table_receiver_type)) = &table_receiver {
+                    receiver_expr = quote! {
+                        ::snec::WithTableReceiver::new({#receiver_expr}, {#table_receiver_expr})
+                    };
+                    receiver_type = syn::parse_quote! {
+                        ::snec::WithTableReceiver<#receiver_type, #table_receiver_type>
+                    };
+                }
                 requested_get_impls.push(
                     RequestedGetImpl {
                         field_name: field_ident.clone(),
-                        receiver_expr: custom_receiver_expr.unwrap_or_else(
-                            || default_receiver_expr.clone()
-                        ),
-                        receiver_type: custom_receiver_type.unwrap_or_else(
-                            || default_receiver_type.clone()
-                        ),
+                        field_type: field.ty.clone(),
+                        cfg_attributes: cfg_attributes.clone(),
+                        condition: cfg_condition(&cfg_attributes),
+                        receiver_expr,
+                        receiver_type,
+                        receiver_field,
+                        invalidates: invalidates.clone(),
+                        shm: shm.is_some(),
                         marker_path: custom_marker_path.unwrap_or_else(
                             || concat_to_path(
                                 entry_module.clone(),
-                                snake_to_camel(field_ident.clone()),
+                                default_marker_name(&field_ident),
                             )
                         ),
                         
@@ -196,85 +780,1060 @@ the `#[snec(entry_module_attributes(...))]` attribute can only be applied to the
                 )
             }
         }
+        if let (Some(span), true) = (skip, used) {
+            return Err(
+                syn::Error::new(
+                    span,
+                    "the `#[snec(skip)]` attribute cannot be combined with other commands",
+                )
+            )
+        }
+        if let Some(span) = field_patch_attributes_span {
+            if patch.is_none() {
+                return Err(
+                    syn::Error::new(
+                        span,
+                        "the `#[snec(patch_attributes(...))]` attribute requires `#[snec(patch)]` on the struct",
+                    )
+                )
+            }
+            if !used {
+                return Err(
+                    syn::Error::new(
+                        span,
+                        "\
+the `#[snec(patch_attributes(...))]` attribute can only be used on fields which have entries",
+                    )
+                )
+            }
+        }
+        if used {
+            patch_fields.push(
+                PatchField {
+                    field_name: field_ident,
+                    field_type: field.ty,
+                    cfg_attributes,
+                    attributes: doc_attributes.into_iter().chain(field_patch_attributes).collect(),
+                }
+            );
+        }
     }
+    check_marker_name_collisions(&requested_generated_entries)?;
+    let dependents = resolve_dependents(&requested_get_impls)?;
     let mut impls = Vec::with_capacity(
         requested_get_impls.len() + requested_generated_entries.len()
     );
     let mut generated_entries = Vec::with_capacity(requested_generated_entries.len());
-    for get_impl_data in requested_get_impls {
-        let entry_path = get_impl_data.marker_path;
-        let field_ident = get_impl_data.field_name;
-        let receiver_expr = get_impl_data.receiver_expr;
-        let receiver_type = get_impl_data.receiver_type;
-        let struct_name = &struct_input.ident;
+    // The handles produced by SplitHandles are only disjoint if no field is used for several entries and the receivers don't borrow the table.
+    let splittable = deferred.is_none() && requested_get_impls.iter().enumerate().all(|(i, get_impl_data)| {
+        get_impl_data.receiver_field.is_none()
+            && get_impl_data.invalidates.is_empty()
+            && !mentions_self(&get_impl_data.receiver_expr)
+            && requested_get_impls[..i].iter().all(|x| x.field_name != get_impl_data.field_name)
+    }) && (
+        // The associated type of SplitHandles would leak entries with restricted visibility from a public struct.
+        matches!(struct_input.visibility, Visibility::Inherited)
+            || requested_generated_entries.iter().all(|entry_data| {
+                matches!(entry_data.visibility, None | Some(Visibility::Public(..)))
+            })
+    );
+    let struct_name = &struct_input.ident;
+    let mut deferred_variants = Vec::new();
+    let mut deferred_arms = Vec::new();
+    let mut deferred_impls = Vec::new();
+    for (get_impl_data, dependents) in requested_get_impls.iter().zip(&dependents) {
+        let entry_path = &get_impl_data.marker_path;
+        let field_ident = &get_impl_data.field_name;
+        let receiver_expr = &get_impl_data.receiver_expr;
+        let own_receiver_type = &get_impl_data.receiver_type;
+        let mut receiver_type = own_receiver_type.to_token_stream();
+        let cfg_attributes = &get_impl_data.cfg_attributes;
+        let mut dependent_receivers = Vec::with_capacity(dependents.len());
+        let mut dependent_bindings = Vec::with_capacity(dependents.len());
+        let mut invalidating_calls = Vec::with_capacity(dependents.len());
+        for (index, dependent) in dependents.iter().enumerate() {
+            let dependent = &requested_get_impls[*dependent];
+            let dependent_entry_path = &dependent.marker_path;
+            let dependent_field = &dependent.field_name;
+            let dependent_receiver_type = &dependent.receiver_type;
+            let binding = format_ident!("__snec_dependent_{}", index);
+            dependent_receivers.push(&dependent.receiver_expr);
+            invalidating_calls.push(quote! {
+                .invalidating::<#dependent_entry_path, _>(&self.#dependent_field, #binding)
+            });
+            dependent_bindings.push(binding);
+            receiver_type = quote! {
+                (#receiver_type, ::snec::DependentReceiver<#dependent_entry_path, #dependent_receiver_type>)
+            };
+        }
+        // The receivers of the dependent entries are created first, since they may borrow the table.
+        let dependent_lets = quote! {
+            #(
+                let #dependent_bindings = {
+                    #dependent_receivers
+                };
+            )*
+        };
+        // Builds the handle which notifies the receivers of the entry, pointing to the specified place.
+        let handle_expr = |target: TokenStream| {
+            let handle_expr = match (&get_impl_data.receiver_field, &table_receiver) {
+                (Some(receiver_field), Some((table_receiver_expr, _))) => quote! {
+                    let table_receiver = {
+                        #table_receiver_expr
+                    };
+                    ::snec::Handle::with_borrowed_receiver_and_table_receiver(
+                        #target,
+                        &mut self.#receiver_field,
+                        table_receiver,
+                    )
+                },
+                (Some(receiver_field), None) => quote! {
+                    ::snec::Handle::with_borrowed_receiver(
+                        #target,
+                        &mut self.#receiver_field,
+                    )
+                },
+                (None, _) => quote! {
+                    let receiver = {
+                        #receiver_expr
+                    };
+                    ::snec::Handle::new(#target, receiver)
+                },
+            };
+            let handle_expr = if dependents.is_empty() {
+                handle_expr
+            } else {
+                // The type of the handle has to be spelled out for the method calls to resolve.
+                quote! {
+                    #dependent_lets
+                    let handle: ::snec::Handle<'_, #entry_path, #own_receiver_type> = {
+                        #handle_expr
+                    };
+                    handle #(#invalidating_calls)*
+                }
+            };
+            handle_expr
+        };
+        let (get_receiver_type, get_handle_expr) = match &deferred {
+            // The queue is the receiver of every entry, and the actual receivers are notified by `dispatch_pending`.
+            Some(queue) => {
+                let variant = format_ident!("Entry{}", deferred_arms.len());
+                let field_type = &get_impl_data.field_type;
+                let dispatch_handle_expr = handle_expr(quote! {&mut __snec_value});
+                deferred_variants.push(quote! {
+                    #(#cfg_attributes)*
+                    #variant(#field_type),
+                });
+                deferred_arms.push(quote! {
+                    #(#cfg_attributes)*
+                    __SnecRecord::#variant(mut __snec_value) => {
+                        {
+                            let mut handle: ::snec::Handle<'_, #entry_path, #receiver_type> = {
+                                #dispatch_handle_expr
+                            };
+                            handle.modify_with(|_| {});
+                        }
+                        <#entry_path as ::snec::Entry>::erase(&mut __snec_value);
+                    },
+                });
+                deferred_impls.push(quote! {
+                    #(#cfg_attributes)*
+                    impl ::snec::RecordChange<#entry_path> for #struct_name {
+                        #[inline]
+                        fn record(new_value: &<#entry_path as ::snec::Entry>::Data) -> __SnecRecord {
+                            __SnecRecord::#variant(::core::clone::Clone::clone(new_value))
+                        }
+                    }
+                });
+                (
+                    quote! {::snec::BorrowedReceiver<::snec::DeferredQueue<#struct_name>>},
+                    quote! {::snec::Handle::with_borrowed_receiver(&mut self.#field_ident, &mut self.#queue)},
+                )
+            },
+            None => (receiver_type, handle_expr(quote! {&mut self.#field_ident})),
+        };
         let token_stream = quote! {
+            #(#cfg_attributes)*
             impl ::snec::Get<#entry_path> for #struct_name {
-                type Receiver = #receiver_type;
+                type Receiver = #get_receiver_type;
                 #[inline(always)]
                 fn get_ref(&self) -> &<#entry_path as ::snec::Entry>::Data {
                     &self.#field_ident
                 }
                 #[inline]
-                fn get_handle(&mut self) -> ::snec::Handle<'_, #entry_path, #receiver_type> {
-                    let receiver = {
-                        #receiver_expr
-                    };
-                    ::snec::Handle::new(&mut self.#field_ident, receiver)
+                fn get_handle(&mut self) -> ::snec::Handle<'_, #entry_path, #get_receiver_type> {
+                    #get_handle_expr
+                }
+                #[inline(always)]
+                fn get_mut(
+                    &mut self,
+                    _: ::snec::__private::Token,
+                ) -> &mut <#entry_path as ::snec::Entry>::Data {
+                    &mut self.#field_ident
                 }
             }
         };
         impls.push(token_stream);
     }
+    if let Some(queue) = &deferred {
+        // The records are only reachable through `DeferredTable::Record`.
+        impls.push(quote! {
+            const _: () = {
+                #[doc(hidden)]
+                pub enum __SnecRecord {
+                    #(#deferred_variants)*
+                }
+                impl ::snec::DeferredTable for #struct_name {
+                    type Record = __SnecRecord;
+                    fn dispatch_pending(&mut self) -> usize {
+                        let records = ::snec::DeferredQueue::take(&mut self.#queue);
+                        let count = records.len();
+                        for record in records {
+                            match record {
+                                #(#deferred_arms)*
+                            }
+                        }
+                        count
+                    }
+                }
+                #(#deferred_impls)*
+            };
+        });
+    }
+    // Used by the builder to fill in the fields which were not set.
+    let default_entries = requested_generated_entries.iter()
+        .filter(|entry_data| entry_data.default_value.is_some())
+        .map(|entry_data| {
+            (
+                entry_data.field_name.clone(),
+                concat_to_path(entry_module.clone(), entry_data.marker_name.clone()),
+            )
+        })
+        .collect::<Vec<_>>();
+    // Erased by the `Drop` implementation.
+    let zeroized_entries = requested_generated_entries.iter()
+        .filter(|entry_data| entry_data.zeroize)
+        .map(|entry_data| {
+            (
+                entry_data.field_name.clone(),
+                entry_data.cfg_attributes.clone(),
+                concat_to_path(entry_module.clone(), entry_data.marker_name.clone()),
+            )
+        })
+        .collect::<Vec<_>>();
     for entry_data in requested_generated_entries {
         let entry_name = entry_data.marker_name;
         let field_ident = entry_data.field_name;
         let data_type = entry_data.field_type;
+        let field_name = member_to_string(&field_ident);
         let documentation = format!(
             "The entry identifier type for the `{}` field in the `{}` config table.",
-            &field_ident,
+            &field_name,
             &struct_input.ident,
         );
         let documentation = Lit::Str(
             LitStr::new(&documentation, Span::call_site()),
         );
         let field_name_literal = Lit::Str(
-            LitStr::new(&field_ident.to_string(), Span::call_site()),
+            entry_data.entry_name.unwrap_or_else(|| LitStr::new(&field_name, Span::call_site())),
+        );
+        let description_literal = Lit::Str(
+            LitStr::new(&entry_data.description, Span::call_site()),
+        );
+        let field_documentation = if entry_data.description.is_empty() {
+            None
+        } else {
+            Some(quote! {
+                #[doc = ""]
+                #[doc = #description_literal]
+            })
+        };
+        let default_value = entry_data.default_value.map(|value| quote! {
+            #[inline]
+            fn default_value() -> ::core::option::Option<Self::Data> {
+                ::core::option::Option::Some(#value)
+            }
+        });
+        let validator = entry_data.validator.map(|validator| quote! {
+            #[inline]
+            fn validate(value: &Self::Data) -> ::core::result::Result<(), &'static str> {
+                #validator(value)
+            }
+        });
+        let secret = if entry_data.secret {
+            Some(quote! {const SECRET: bool = true;})
+        } else {
+            None
+        };
+        let unit = entry_data.unit.map(|unit| quote! {const UNIT: &'static str = #unit;});
+        let ui_range = entry_data.ui_range.map(|UiRangeData {min, max, step}| {
+            let [min, max, step] = [min, max, step].map(|value| match value {
+                Some(value) => quote! {::core::option::Option::Some(#value)},
+                None => quote! {::core::option::Option::None},
+            });
+            quote! {
+                const UI_RANGE: ::snec::UiRange = ::snec::UiRange {min: #min, max: #max, step: #step};
+            }
+        });
+        let erase = if entry_data.zeroize {
+            Some(quote! {
+                #[inline]
+                fn erase(value: &mut Self::Data) {
+                    ::snec::zeroize::Zeroize::zeroize(value)
+                }
+            })
+        } else {
+            None
+        };
+        let visibility = entry_data.visibility.map_or_else(
+            || quote! {pub},
+            |visibility| visibility_in_entry_module(visibility, depth),
         );
+        let type_name_literal = LitStr::new(&type_to_string(&data_type), Span::call_site());
+        let cfg_attributes = entry_data.cfg_attributes;
         let entry = quote! {
+            #(#cfg_attributes)*
             #[doc = #documentation]
-            pub enum #entry_name {}
+            #field_documentation
+            #visibility enum #entry_name {}
         };
+        let entry_path = concat_to_path(entry_module.clone(), entry_name.clone());
         let entry_impl = quote! {
-            impl ::snec::Entry for #entry_module::#entry_name {
+            #(#cfg_attributes)*
+            impl ::snec::Entry for #entry_path {
                 type Data = #data_type;
                 const NAME: &'static str = #field_name_literal;
+                const DESCRIPTION: &'static str = #description_literal;
+                const TYPE_NAME: &'static str = #type_name_literal;
+                #unit
+                #ui_range
+                #secret
+                #erase
+                #default_value
+                #validator
             }
         };
         generated_entries.push(entry);
         impls.push(entry_impl);
     }
-    let result = quote! {
-        #(#entry_module_attributes)*
-        #entry_module_visibility mod #entry_module {
-            #(#generated_entries)*
+    // `#[cfg(...)]` can't be applied to the bounds of a where clause or to the elements of a type-level list, so the lists of entries and handles and the positions of the handles are built out of type aliases, with two of them generated for every gated entry, one for either outcome of the condition.
+    let mut aliases = Vec::new();
+    let mut gated_alias = |alias: Ident, generics: TokenStream, condition: &TokenStream, present: TokenStream, absent: TokenStream| {
+        aliases.push(quote! {
+            #[cfg(#condition)]
+            type #alias #generics = #present;
+            #[cfg(not(#condition))]
+            type #alias #generics = #absent;
+        });
+        quote! {#alias #generics}
+    };
+    let mut entry_list = quote! {::snec::split::Nil};
+    let mut handles_type = quote! {::snec::split::Nil};
+    for (index, get_impl_data) in requested_get_impls.iter().enumerate().rev() {
+        let entry_path = &get_impl_data.marker_path;
+        let receiver_type = &get_impl_data.receiver_type;
+        let present_entries = quote! {::snec::split::Cons<#entry_path, #entry_list>};
+        let present_handles = quote! {
+            ::snec::split::Cons<::snec::Handle<'a, #entry_path, #receiver_type>, #handles_type>
+        };
+        match &get_impl_data.condition {
+            Some(condition) => {
+                entry_list = gated_alias(
+                    format_ident!("__SnecEntries{}", index),
+                    quote! {},
+                    condition,
+                    present_entries,
+                    entry_list,
+                );
+                if splittable {
+                    handles_type = gated_alias(
+                        format_ident!("__SnecHandles{}", index),
+                        quote! {<'a>},
+                        condition,
+                        present_handles,
+                        handles_type,
+                    );
+                }
+            },
+            None => {
+                entry_list = present_entries;
+                handles_type = present_handles;
+            },
+        }
+    }
+    let mut split_indices = Vec::with_capacity(requested_get_impls.len());
+    let mut split_index = quote! {::snec::split::Here};
+    for (index, get_impl_data) in requested_get_impls.iter().enumerate() {
+        split_indices.push(split_index.clone());
+        let next_index = quote! {::snec::split::There<#split_index>};
+        split_index = match &get_impl_data.condition {
+            Some(condition) if splittable => gated_alias(
+                format_ident!("__SnecIndex{}", index + 1),
+                quote! {},
+                condition,
+                next_index,
+                split_index,
+            ),
+            _ => next_index,
+        };
+    }
+    let registered_entries = requested_get_impls.iter()
+        .map(|get_impl_data| &get_impl_data.marker_path)
+        .collect::<Vec<_>>();
+    let entry_cfg_attributes = requested_get_impls.iter()
+        .map(|get_impl_data| &get_impl_data.cfg_attributes)
+        .collect::<Vec<_>>();
+    let mut list_impls = vec![quote! {
+        impl<V: ?::core::marker::Sized> ::snec::VisitEntries<V> for #struct_name
+        where #entry_list: ::snec::__private::VisitList<Self, V> {
+            #[inline]
+            fn visit_entries(&mut self, visitor: &mut V) {
+                <#entry_list as ::snec::__private::VisitList<Self, V>>::visit_entries(self, visitor)
+            }
+        }
+        impl<V: ?::core::marker::Sized> ::snec::VisitEntryTypes<V> for #struct_name
+        where #entry_list: ::snec::__private::VisitTypeList<V> {
+            #[inline]
+            fn visit_entry_types(visitor: &mut V) {
+                <#entry_list as ::snec::__private::VisitTypeList<V>>::visit_entry_types(visitor)
+            }
+        }
+    }];
+    if splittable {
+        let split_members = requested_get_impls.iter()
+            .map(|get_impl_data| &get_impl_data.field_name)
+            .collect::<Vec<_>>();
+        let split_bindings = (0..requested_get_impls.len())
+            .map(|index| format_ident!("__snec_field_{}", index))
+            .collect::<Vec<_>>();
+        let receiver_exprs = requested_get_impls.iter()
+            .map(|get_impl_data| &get_impl_data.receiver_expr)
+            .collect::<Vec<_>>();
+        let reversed_cfg_attributes = entry_cfg_attributes.iter().rev();
+        let reversed_bindings = split_bindings.iter().rev();
+        let reversed_receiver_exprs = receiver_exprs.iter().rev();
+        list_impls.push(quote! {
+            impl<'a> ::snec::split::SplitHandles<'a> for #struct_name {
+                type Handles = #handles_type;
+                #[inline]
+                fn split_handles(&'a mut self) -> Self::Handles {
+                    let Self {#(#(#entry_cfg_attributes)* #split_members: #split_bindings,)* ..} = self;
+                    let __snec_handles = ::snec::split::Nil;
+                    #(
+                        #(#reversed_cfg_attributes)*
+                        let __snec_handles = ::snec::split::Cons(
+                            ::snec::Handle::new(#reversed_bindings, {#reversed_receiver_exprs}),
+                            __snec_handles,
+                        );
+                    )*
+                    __snec_handles
+                }
+            }
+            #(
+                #(#entry_cfg_attributes)*
+                impl ::snec::split::EntryIndex<#registered_entries> for #struct_name {
+                    type Index = #split_indices;
+                }
+            )*
+        });
+    }
+    impls.push(quote! {
+        impl ::snec::ConfigTable for #struct_name {
+            const ENTRIES: &'static [::snec::EntryInfo] = &[
+                #(#(#entry_cfg_attributes)* ::snec::EntryInfo::of::<#registered_entries>()),*
+            ];
         }
+    });
+    if aliases.is_empty() {
+        impls.extend(list_impls);
+    } else {
+        impls.push(quote! {
+            const _: () = {
+                #(#aliases)*
+                #(#list_impls)*
+            };
+        });
+    }
+    if requested_get_impls.iter().any(|get_impl_data| get_impl_data.shm) {
+        let shm_entries = requested_get_impls.iter()
+            .filter(|get_impl_data| get_impl_data.shm)
+            .collect::<Vec<_>>();
+        let shm_cfg_attributes = shm_entries.iter().map(|get_impl_data| &get_impl_data.cfg_attributes);
+        let shm_entries = shm_entries.iter().map(|get_impl_data| &get_impl_data.marker_path);
+        impls.push(quote! {
+            impl ::snec::shm::ShmTable for #struct_name {
+                #[inline]
+                fn visit_shm_entries<V: ::snec::shm::ShmVisitor<Self>>(visitor: &mut V) {
+                    #(
+                        #(#shm_cfg_attributes)*
+                        visitor.visit::<#shm_entries>();
+                    )*
+                }
+            }
+        });
+    }
+    // Also used by the builder, which reports the errors of the table-level validator.
+    let table_error_type = match &validate_table {
+        Some((_, Some(error_type))) => error_type.to_token_stream(),
+        _ => quote! {&'static str},
+    };
+    // Tables without a validator are always valid, so that the loaders can validate every table.
+    let validate_table_impl = match &validate_table {
+        Some((validator, _)) => quote! {
+            type Error = #table_error_type;
+            #[inline]
+            fn validate_table(&self) -> ::core::result::Result<(), Self::Error> {
+                #validator(self)
+            }
+        },
+        None => quote! {
+            type Error = ::core::convert::Infallible;
+            #[inline(always)]
+            fn validate_table(&self) -> ::core::result::Result<(), Self::Error> {
+                ::core::result::Result::Ok(())
+            }
+        },
+    };
+    impls.push(quote! {
+        impl ::snec::ValidateTable for #struct_name {
+            #validate_table_impl
+        }
+    });
+    if let Some(patch_name) = patch {
+        let patch_name = patch_name.unwrap_or_else(|| format_ident!("{}Patch", struct_name));
+        let visibility = &struct_input.visibility;
+        let struct_doc = format!(
+            "A partial update of [`{0}`], with a field for every field of `{0}` which has an entry, set to `None` for entries which are left unchanged.",
+            struct_name,
+        );
+        let cfg_attributes = patch_fields.iter().map(|patch_field| &patch_field.cfg_attributes).collect::<Vec<_>>();
+        let attributes = patch_fields.iter().map(|patch_field| &patch_field.attributes).collect::<Vec<_>>();
+        let types = patch_fields.iter().map(|patch_field| &patch_field.field_type).collect::<Vec<_>>();
+        let members = patch_fields.iter().enumerate().map(|(index, patch_field)| {
+            match &patch_field.field_name {
+                Member::Named(ident) => Member::Named(ident.clone()),
+                // The fields without entries are left out, which shifts the indices of the rest.
+                Member::Unnamed(original) => Member::Unnamed(Index {index: index as u32, span: original.span}),
+            }
+        }).collect::<Vec<_>>();
+        // The fields are written without notifying anyone, so that the table can be validated before the receivers learn about the changes. The first entry of a field is the one whose value is erased, and the receivers of all of its entries are notified.
+        let written_fields = patch_fields.iter().zip(&members).enumerate().filter_map(|(index, (patch_field, member))| {
+            let mut entries = requested_get_impls.iter()
+                .filter(|get_impl_data| get_impl_data.field_name == patch_field.field_name)
+                .map(|get_impl_data| &get_impl_data.marker_path)
+                .peekable();
+            let first_entry = *entries.peek()?;
+            let notifications = entries.map(|entry_path| quote! {
+                <#struct_name as ::snec::Get<#entry_path>>::get_handle(table).modify_with(|_| {});
+                changed({
+                    const INFO: ::snec::EntryInfo = ::snec::EntryInfo::of::<#entry_path>();
+                    &INFO
+                });
+            }).collect::<Vec<_>>();
+            Some(
+                WrittenPatchField {
+                    cfg_attributes: &patch_field.cfg_attributes,
+                    table_member: &patch_field.field_name,
+                    patch_member: member,
+                    // Bound to locals which can't collide with the parameters.
+                    previous: format_ident!("__snec_previous_{}", index),
+                    first_entry,
+                    notifications,
+                }
+            )
+        }).collect::<Vec<_>>();
+        let finish_application = {
+            let cfg_attributes = written_fields.iter().map(|field| field.cfg_attributes).collect::<Vec<_>>();
+            let table_members = written_fields.iter().map(|field| field.table_member).collect::<Vec<_>>();
+            let previous = written_fields.iter().map(|field| &field.previous).collect::<Vec<_>>();
+            let first_entries = written_fields.iter().map(|field| &field.first_entry).collect::<Vec<_>>();
+            let notifications = written_fields.iter().map(|field| &field.notifications).collect::<Vec<_>>();
+            quote! {
+                if let ::core::result::Result::Err(error) = ::snec::ValidateTable::validate_table(&*table) {
+                    #(
+                        #(#cfg_attributes)*
+                        if let ::core::option::Option::Some(previous) = #previous {
+                            let mut rejected = ::core::mem::replace(&mut table.#table_members, previous);
+                            <#first_entries as ::snec::Entry>::erase(&mut rejected);
+                        }
+                    )*
+                    return ::core::result::Result::Err(error);
+                }
+                #(
+                    #(#cfg_attributes)*
+                    if let ::core::option::Option::Some(mut previous) = #previous {
+                        <#first_entries as ::snec::Entry>::erase(&mut previous);
+                        #(#notifications)*
+                    }
+                )*
+                ::core::result::Result::Ok(())
+            }
+        };
+        let writes = written_fields.iter().map(|field| {
+            let WrittenPatchField {cfg_attributes, table_member, patch_member, previous, ..} = field;
+            quote! {
+                #(#cfg_attributes)*
+                let #previous = match self.#patch_member {
+                    ::core::option::Option::Some(value) => ::core::option::Option::Some(
+                        ::core::mem::replace(&mut table.#table_member, value)
+                    ),
+                    ::core::option::Option::None => ::core::option::Option::None,
+                };
+            }
+        }).collect::<Vec<_>>();
+        // Same as above, except that the values equal to the current ones are not written.
+        let changed_writes = written_fields.iter().map(|field| {
+            let WrittenPatchField {cfg_attributes, table_member, patch_member, previous, ..} = field;
+            quote! {
+                #(#cfg_attributes)*
+                let #previous = match self.#patch_member {
+                    ::core::option::Option::Some(value) if table.#table_member != value => ::core::option::Option::Some(
+                        ::core::mem::replace(&mut table.#table_member, value)
+                    ),
+                    _ => ::core::option::Option::None,
+                };
+            }
+        }).collect::<Vec<_>>();
+        let patch_struct = if let StructDelimiter::Parentheses(..) = struct_input.delimiter {
+            quote! {
+                #visibility struct #patch_name (
+                    #(#(#cfg_attributes)* #(#attributes)* pub ::core::option::Option<#types>,)*
+                );
+            }
+        } else {
+            quote! {
+                #visibility struct #patch_name {
+                    #(#(#cfg_attributes)* #(#attributes)* pub #members: ::core::option::Option<#types>,)*
+                }
+            }
+        };
+        impls.push(quote! {
+            #[doc = #struct_doc]
+            #(#patch_attributes)*
+            #patch_struct
+            #[allow(dead_code)]
+            impl #patch_name {
+                /// Creates a patch which doesn't change any entries.
+                #[inline]
+                pub const fn new() -> Self {
+                    Self {#(#(#cfg_attributes)* #members: ::core::option::Option::None,)*}
+                }
+            }
+            impl ::core::default::Default for #patch_name {
+                #[inline(always)]
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+            impl ::snec::patch::Patch for #patch_name {
+                type Table = #struct_name;
+                #[inline]
+                fn is_empty(&self) -> bool {
+                    #(
+                        #(#cfg_attributes)*
+                        if self.#members.is_some() {
+                            return false;
+                        }
+                    )*
+                    true
+                }
+                #[inline]
+                fn apply_with<F>(
+                    self,
+                    table: &mut #struct_name,
+                    mut changed: F,
+                ) -> ::core::result::Result<(), <#struct_name as ::snec::ValidateTable>::Error>
+                where F: ::core::ops::FnMut(&'static ::snec::EntryInfo) {
+                    #(#writes)*
+                    #finish_application
+                }
+            }
+            // The bounds only hold if every field can be compared, and are higher-ranked so that the impl is left out instead of failing to compile otherwise.
+            impl ::snec::patch::ComparingPatch for #patch_name
+            where #(for<'snec> #types: ::core::cmp::PartialEq,)* {
+                #[inline]
+                fn apply_changed_with<F>(
+                    self,
+                    table: &mut #struct_name,
+                    mut changed: F,
+                ) -> ::core::result::Result<(), <#struct_name as ::snec::ValidateTable>::Error>
+                where F: ::core::ops::FnMut(&'static ::snec::EntryInfo) {
+                    #(#changed_writes)*
+                    #finish_application
+                }
+            }
+        });
+    }
+    if derive_default {
+        // Fields without a default value for their entry must implement `Default`, which is checked by the compiler.
+        let values = table_fields.iter().map(|table_field| {
+            match default_entries.iter().find(|(member, _)| *member == table_field.field_name) {
+                Some((_, entry_path)) => quote! {
+                    ::core::option::Option::unwrap(<#entry_path as ::snec::Entry>::default_value())
+                },
+                None => quote! {::core::default::Default::default()},
+            }
+        });
+        let cfg_attributes = table_fields.iter().map(|table_field| &table_field.cfg_attributes);
+        let members = table_fields.iter().map(|table_field| &table_field.field_name);
+        impls.push(quote! {
+            impl ::core::default::Default for #struct_name {
+                #[inline]
+                fn default() -> Self {
+                    Self {
+                        #(#(#cfg_attributes)* #members: #values,)*
+                    }
+                }
+            }
+        });
+    }
+    if !zeroized_entries.is_empty() {
+        let erasures = zeroized_entries.iter().map(|(member, cfg_attributes, entry_path)| quote! {
+            #(#cfg_attributes)*
+            <#entry_path as ::snec::Entry>::erase(&mut self.#member);
+        });
+        impls.push(quote! {
+            impl ::core::ops::Drop for #struct_name {
+                #[inline]
+                fn drop(&mut self) {
+                    #(#erasures)*
+                }
+            }
+        });
+    }
+    if let Some(builder_name) = builder {
+        let builder_name = builder_name.unwrap_or_else(|| format_ident!("{}Builder", struct_name));
+        let visibility = &struct_input.visibility;
+        let struct_doc = format!(
+            "A builder for [`{}`], which applies the default values of the entries and runs their validators along with the table-level validator, if there is one.",
+            struct_name,
+        );
+        let cfg_attributes = table_fields.iter().map(|table_field| &table_field.cfg_attributes).collect::<Vec<_>>();
+        let types = table_fields.iter().map(|table_field| &table_field.field_type).collect::<Vec<_>>();
+        let members = table_fields.iter().map(|table_field| &table_field.field_name).collect::<Vec<_>>();
+        let setters = table_fields.iter().map(|table_field| &table_field.setter_name).collect::<Vec<_>>();
+        let setter_docs = table_fields.iter().map(|table_field| {
+            let summary = format!("Sets the value of the `{}` field.", member_to_string(&table_field.field_name));
+            let doc_attributes = &table_field.doc_attributes;
+            if doc_attributes.is_empty() {
+                quote! {#[doc = #summary]}
+            } else {
+                quote! {
+                    #[doc = #summary]
+                    #[doc = ""]
+                    #(#doc_attributes)*
+                }
+            }
+        });
+        // The values are bound to locals which can't collide with the ones used by `build` itself.
+        let bindings = (0..table_fields.len())
+            .map(|index| format_ident!("__snec_field_{}", index))
+            .collect::<Vec<_>>();
+        let checks = table_fields.iter().zip(&bindings).map(|(table_field, binding)| {
+            let setter = &table_field.setter_name;
+            let cfg_attributes = &table_field.cfg_attributes;
+            let value = match default_entries.iter().find(|(member, _)| *member == table_field.field_name) {
+                Some((_, entry_path)) => quote! {
+                    self.#setter.or_else(<#entry_path as ::snec::Entry>::default_value)
+                },
+                None => quote! {self.#setter},
+            };
+            let validations = requested_get_impls.iter()
+                .filter(|get_impl_data| get_impl_data.field_name == table_field.field_name)
+                .map(|get_impl_data| {
+                    let entry_path = &get_impl_data.marker_path;
+                    quote! {
+                        if let ::core::result::Result::Err(error) = <#entry_path as ::snec::Entry>::validate(value) {
+                            errors.push(
+                                ::snec::builder::BuildError::InvalidEntry {
+                                    entry: <#entry_path as ::snec::Entry>::NAME,
+                                    error,
+                                }
+                            );
+                        }
+                    }
+                });
+            let field_name_literal = LitStr::new(&member_to_string(&table_field.field_name), Span::call_site());
+            quote! {
+                #(#cfg_attributes)*
+                let #binding = #value;
+                #(#cfg_attributes)*
+                match &#binding {
+                    ::core::option::Option::Some(value) => {
+                        #(#validations)*
+                    },
+                    ::core::option::Option::None => {
+                        errors.push(::snec::builder::BuildError::Missing(#field_name_literal));
+                    },
+                }
+            }
+        });
+        let table_validation = validate_table.as_ref().map(|_| quote! {
+            if let ::core::result::Result::Err(error) = ::snec::ValidateTable::validate_table(&table) {
+                errors.push(::snec::builder::BuildError::InvalidTable(error));
+                return ::core::result::Result::Err(errors);
+            }
+        });
+        impls.push(quote! {
+            #[doc = #struct_doc]
+            #visibility struct #builder_name {
+                #(#(#cfg_attributes)* #setters: ::core::option::Option<#types>,)*
+            }
+            #[allow(dead_code)]
+            impl #builder_name {
+                /// Creates a builder without any of the fields set.
+                #[inline]
+                pub const fn new() -> Self {
+                    Self {#(#(#cfg_attributes)* #setters: ::core::option::Option::None,)*}
+                }
+                #(
+                    #(#cfg_attributes)*
+                    #setter_docs
+                    #[inline]
+                    pub fn #setters(mut self, value: #types) -> Self {
+                        self.#setters = ::core::option::Option::Some(value);
+                        self
+                    }
+                )*
+                /// Builds the table, using the default values of the entries for the fields which were not set. Returns all errors found in the values of the fields, or the error of the table-level validator if the values are valid but the table isn't.
+                pub fn build(self) -> ::core::result::Result<#struct_name, ::snec::builder::BuildErrors<#table_error_type>> {
+                    let mut errors = ::snec::builder::BuildErrors::new();
+                    #(#checks)*
+                    if !errors.is_empty() {
+                        return ::core::result::Result::Err(errors);
+                    }
+                    // All of the fields are present if no errors were found.
+                    let table = #struct_name {
+                        #(#(#cfg_attributes)* #members: #bindings.unwrap(),)*
+                    };
+                    #table_validation
+                    ::core::result::Result::Ok(table)
+                }
+            }
+            impl ::core::default::Default for #builder_name {
+                #[inline(always)]
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        });
+    }
+    let entries = if depth == 0 {
+        quote! {#(#generated_entries)*}
+    } else {
+        let mut modules = entry_module.segments.iter().rev();
+        let innermost = &modules.next().unwrap().ident;
+        let visibility = if depth == 1 {
+            entry_module_visibility.to_token_stream()
+        } else {
+            quote! {pub}
+        };
+        let mut entries = quote! {
+            #(#entry_module_attributes)*
+            #visibility mod #innermost {
+                #(#generated_entries)*
+            }
+        };
+        for (i, module) in modules.enumerate() {
+            let module = &module.ident;
+            // Only the outermost module restricts the visibility, since the others are only reachable through it.
+            let visibility = if i + 2 == depth {
+                entry_module_visibility.to_token_stream()
+            } else {
+                quote! {pub}
+            };
+            entries = quote! {
+                #visibility mod #module {
+                    #entries
+                }
+            };
+        }
+        if reexport_entries.is_some() {
+            entries.extend(quote! {
+                #[allow(unused_imports)]
+                #entry_module_visibility use #entry_module::*;
+            });
+        }
+        entries
+    };
+    let result = quote! {
+        #entries
         #(#impls)*
     };
-    println!("{}", &result);
+    if let Some(span) = debug_expansion {
+        write_expansion(&struct_input.ident, &result)
+            .map_err(|error| syn::Error::new(span, error))?;
+    }
     Ok(result)
 }
 
+/// Finds the `Get` implementations for the fields listed in `#[snec(invalidates(...))]` of every `Get` implementation, returning their indices.
+fn resolve_dependents(get_impls: &[RequestedGetImpl]) -> Result<Vec<Vec<usize>>, syn::Error> {
+    get_impls.iter().map(|get_impl_data| {
+        get_impl_data.invalidates.iter().map(|member| {
+            let mut candidates = get_impls.iter().enumerate()
+                .filter(|(_, dependent)| dependent.field_name == *member)
+                .map(|(index, _)| index);
+            let error = |message: &str| Err(syn::Error::new(member.span(), message));
+            let index = match (candidates.next(), candidates.next()) {
+                (Some(index), None) => index,
+                (None, _) => return error("the field has no entry in this config table"),
+                (Some(_), Some(_)) => {
+                    return error("the field is used for several entries, which makes the dependency ambiguous")
+                },
+            };
+            let dependent = &get_impls[index];
+            if dependent.field_name == get_impl_data.field_name {
+                error("an entry cannot invalidate itself")
+            } else if dependent.receiver_field.is_some() {
+                error("entries whose receiver is borrowed from a field cannot be invalidated by other entries")
+            } else if dependent.condition.as_ref().map(|x| x.to_string())
+                != get_impl_data.condition.as_ref().map(|x| x.to_string()) {
+                error("the field has different `#[cfg(...)]` conditions than the entry which invalidates it")
+            } else {
+                Ok(index)
+            }
+        }).collect()
+    }).collect()
+}
+
+/// Reports an error if two fields generate marker types with the same name, which would otherwise be reported by the compiler as a confusing duplicate definition inside of the generated module. Fields with different `#[cfg(...)]` conditions are allowed to collide, since they might never exist at the same time.
+fn check_marker_name_collisions(entries: &[RequestedGeneratedEntry]) -> Result<(), syn::Error> {
+    for (i, entry_data) in entries.iter().enumerate() {
+        let condition = cfg_condition(&entry_data.cfg_attributes).map(|x| x.to_string());
+        let colliding = entries[..i].iter().find(|other| {
+            other.marker_name == entry_data.marker_name
+                && cfg_condition(&other.cfg_attributes).map(|x| x.to_string()) == condition
+        });
+        if let Some(other) = colliding {
+            let mut error = syn::Error::new(
+                entry_data.marker_name.span(),
+                format!(
+                    "the entry type `{}` generated for the field `{}` has the same name as the one generated for the field `{}`",
+                    entry_data.marker_name,
+                    member_to_string(&entry_data.field_name),
+                    member_to_string(&other.field_name),
+                ),
+            );
+            error.combine(
+                syn::Error::new(
+                    other.marker_name.span(),
+                    format!(
+                        "`{}` is first generated here; use `#[snec(entry(...))]` to rename one of them",
+                        other.marker_name,
+                    ),
+                )
+            );
+            return Err(error)
+        }
+    }
+    Ok(())
+}
+
 /// Data needed to collect from attributes to generate one `Get` implementation for one field.
 struct RequestedGetImpl {
-    field_name: Ident,
+    field_name: Member,
+    field_type: Type,
+    /// The `#[cfg(...)]` attributes on the field.
+    cfg_attributes: Vec<Attribute>,
+    /// The condition of the `#[cfg(...)]` attributes on the field, if there are any.
+    condition: Option<TokenStream>,
     receiver_type: Type,
     receiver_expr: TokenStream,
+    /// The field which is borrowed as the receiver, in which case `receiver_expr` is not used.
+    receiver_field: Option<Member>,
+    /// The fields whose entries are notified together with this one.
+    invalidates: Vec<Member>,
+    /// Whether the entry is mirrored in shared memory.
+    shm: bool,
     marker_path: Path,
 }
+/// Data needed to generate the parts of the builder and the `Default` implementation which concern one field.
+struct TableField {
+    field_name: Member,
+    field_type: Type,
+    /// The `#[cfg(...)]` attributes on the field.
+    cfg_attributes: Vec<Attribute>,
+    /// The documentation attributes on the field, which are copied to the setter.
+    doc_attributes: Vec<Attribute>,
+    /// The name of the field of the builder and of the setter: the name of the field, or `field_` followed by the index for the fields of tuple structs.
+    setter_name: Ident,
+}
+/// Data needed to generate one field of the patch struct.
+struct PatchField {
+    field_name: Member,
+    field_type: Type,
+    /// The `#[cfg(...)]` attributes on the field.
+    cfg_attributes: Vec<Attribute>,
+    /// The documentation attributes on the field, followed by the ones specified with `#[snec(patch_attributes(...))]`.
+    attributes: Vec<Attribute>,
+}
+/// Data needed to write one field of the patch struct into the config table.
+struct WrittenPatchField<'a> {
+    cfg_attributes: &'a [Attribute],
+    table_member: &'a Member,
+    patch_member: &'a Member,
+    /// The local holding the previous value of the field if it was written.
+    previous: Ident,
+    first_entry: &'a Path,
+    /// The notifications of the receivers of the entries of the field, each followed by reporting the entry as changed.
+    notifications: Vec<TokenStream>,
+}
+/// The bounds and the step specified with `#[snec(ui(...))]`, as expressions of type `f64`.
+struct UiRangeData {
+    min: Option<Expr>,
+    max: Option<Expr>,
+    step: Option<Expr>,
+}
+impl TryFrom<Punctuated<UiArgument, token::Comma>> for UiRangeData {
+    type Error = syn::Error;
+    fn try_from(arguments: Punctuated<UiArgument, token::Comma>) -> Result<Self, Self::Error> {
+        let mut result = Self {min: None, max: None, step: None};
+        for UiArgument {name, value, ..} in arguments {
+            let slot = if name == "min" {
+                &mut result.min
+            } else if name == "max" {
+                &mut result.max
+            } else {
+                &mut result.step
+            };
+            if slot.is_some() {
+                return Err(
+                    syn::Error::new(
+                        name.span(),
+                        format!("`{}` is specified more than once", name),
+                    )
+                )
+            }
+            *slot = Some(integer_literal_to_float(value));
+        }
+        Ok(result)
+    }
+}
 /// Data needed to collect from attributes to generate one marker type implementing `Entry` for one field.
 struct RequestedGeneratedEntry {
-    field_name: Ident,
+    field_name: Member,
+    /// The `#[cfg(...)]` attributes on the field.
+    cfg_attributes: Vec<Attribute>,
     field_type: Type,
+    /// The documentation comments on the field, used as the description of the entry.
+    description: String,
+    /// Expression producing the default value of the entry, if one was specified.
+    default_value: Option<Expr>,
+    /// The path to the validation function of the entry, if one was specified.
+    validator: Option<Path>,
+    /// Whether the value of the entry is secret.
+    secret: bool,
+    /// Whether old values of the entry are erased with `Zeroize`, which also requires erasing the field when the struct is dropped.
+    zeroize: bool,
+    /// The `NAME` of the entry, if it was overridden instead of using the name of the field.
+    entry_name: Option<LitStr>,
+    /// The `UNIT` of the entry, if one was specified.
+    unit: Option<LitStr>,
+    /// The range of values offered by user interfaces, if one was specified.
+    ui_range: Option<UiRangeData>,
+    /// The visibility of the marker type relative to the module containing the struct, if one was specified.
+    visibility: Option<Visibility>,
     marker_name: Ident,
 }
 
@@ -285,20 +1844,18 @@ mod tests {
     fn basic() {
         let input = quote! {
             struct MyConfigTable {
+                /// The one and only field.
                 #[snec]
                 field: String,
             }
         };
         let expected_output = quote! {
             mod entries {
-                use super::*;
                 #[doc = "The entry identifier type for the `field` field in the `MyConfigTable` config table."]
+                #[doc = ""]
+                #[doc = "The one and only field."]
                 pub enum Field {}
-                impl ::snec::Entry for Field {
-                    type Data = String;
-                    const NAME: &'static str = "field";
-                }
-            };
+            }
             impl ::snec::Get<entries::Field> for MyConfigTable {
                 type Receiver = ::snec::EmptyReceiver;
                 #[inline(always)]
@@ -314,9 +1871,85 @@ mod tests {
                     };
                     ::snec::Handle::new(&mut self.field, receiver)
                 }
+                #[inline(always)]
+                fn get_mut(
+                    &mut self,
+                    _: ::snec::__private::Token,
+                ) -> &mut <entries::Field as ::snec::Entry>::Data {
+                    &mut self.field
+                }
+            }
+            impl ::snec::Entry for entries::Field {
+                type Data = String;
+                const NAME: &'static str = "field";
+                const DESCRIPTION: &'static str = "The one and only field.";
+                const TYPE_NAME: &'static str = "String";
+            }
+            impl ::snec::ConfigTable for MyConfigTable {
+                const ENTRIES: &'static [::snec::EntryInfo] = &[
+                    ::snec::EntryInfo::of::<entries::Field>()
+                ];
+            }
+            impl<V: ?::core::marker::Sized> ::snec::VisitEntries<V> for MyConfigTable
+            where ::snec::split::Cons<entries::Field, ::snec::split::Nil>: ::snec::__private::VisitList<Self, V> {
+                #[inline]
+                fn visit_entries(&mut self, visitor: &mut V) {
+                    <::snec::split::Cons<entries::Field, ::snec::split::Nil> as ::snec::__private::VisitList<Self, V>>::visit_entries(
+                        self, visitor
+                    )
+                }
+            }
+            impl<V: ?::core::marker::Sized> ::snec::VisitEntryTypes<V> for MyConfigTable
+            where ::snec::split::Cons<entries::Field, ::snec::split::Nil>: ::snec::__private::VisitTypeList<V> {
+                #[inline]
+                fn visit_entry_types(visitor: &mut V) {
+                    <::snec::split::Cons<entries::Field, ::snec::split::Nil> as ::snec::__private::VisitTypeList<V>>::visit_entry_types(
+                        visitor
+                    )
+                }
+            }
+            impl<'a> ::snec::split::SplitHandles<'a> for MyConfigTable {
+                type Handles = ::snec::split::Cons<
+                    ::snec::Handle<'a, entries::Field, ::snec::EmptyReceiver>,
+                    ::snec::split::Nil
+                >;
+                #[inline]
+                fn split_handles(&'a mut self) -> Self::Handles {
+                    let Self {field: __snec_field_0, ..} = self;
+                    let __snec_handles = ::snec::split::Nil;
+                    let __snec_handles = ::snec::split::Cons(
+                        ::snec::Handle::new(__snec_field_0, {::snec::EmptyReceiver}),
+                        __snec_handles,
+                    );
+                    __snec_handles
+                }
+            }
+            impl ::snec::split::EntryIndex<entries::Field> for MyConfigTable {
+                type Index = ::snec::split::Here;
+            }
+            impl ::snec::ValidateTable for MyConfigTable {
+                type Error = ::core::convert::Infallible;
+                #[inline(always)]
+                fn validate_table(&self) -> ::core::result::Result<(), Self::Error> {
+                    ::core::result::Result::Ok(())
+                }
             }
         };
         let output = derive_config_table_expand(input).unwrap();
         assert_eq!(output.to_string(), expected_output.to_string());
     }
+
+    #[test]
+    fn type_names() {
+        let cases: [(Type, &str); 5] = [
+            (syn::parse_quote! {Vec<String>}, "Vec<String>"),
+            (syn::parse_quote! {&'static str}, "&'static str"),
+            (syn::parse_quote! {[u8; 4]}, "[u8; 4]"),
+            (syn::parse_quote! {Box<dyn Fn(u32) -> u32 + Send>}, "Box<dyn Fn(u32) -> u32 + Send>"),
+            (syn::parse_quote! {std::collections::HashMap<String, (i32, bool)>}, "std::collections::HashMap<String, (i32, bool)>"),
+        ];
+        for (ty, expected) in &cases {
+            assert_eq!(type_to_string(ty), *expected);
+        }
+    }
 }
\ No newline at end of file
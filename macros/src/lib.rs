@@ -1,4 +1,4 @@
-#![allow(clippy::clippy::large_enum_variant)] // nope
+#![allow(clippy::large_enum_variant)] // nope
 
 use proc_macro::TokenStream;
 
@@ -8,14 +8,42 @@ use drv_config_table::derive_config_table_expand;
 /// Generates necessary trait implementations to use a struct as a configuration table.
 ///
 /// By itself, `#[derive(ConfigTable)]` won't do anything, as it requires additional markers to describe how exactly the struct will serve as a configuration table. Those markers are expressed using `#[snec(...)]` attributes. The following "Snec-commands" are supported:
-/// - `#[snec(entry(`*`EntryMarker`*`))]` (one per struct field) — generates an uninhabited type which implements `Entry` and a `Get` implementation for it. `EntryMarker` is the optional name for the marker type which defaults to the field's name converted to camel case.
+/// - `#[snec(entry(`*`EntryMarker`*`))]` (one per struct field) — generates an uninhabited type which implements `Entry` and a `Get` implementation for it. `EntryMarker` is the optional name for the marker type which defaults to the field's name converted to camel case. If two fields end up with the same marker type name, such as `foo_bar` and `foo__bar`, an error pointing to both of them is reported.
 /// - `#[snec]` (one per struct field) — alias of `#[snec(entry)]`.
 /// - `#[snec(use_entry(`*`entry_marker`*`))]` (one per struct field) — only adds a `Get` implementation for the specified entry identifier, without generating the type itself. `entry_marker` is given as an absolute or relative path to the entry type, i.e. it's not necessary for it to be in scope.
-/// - `#[snec(receiver({`*`receiver_expression`*`}: `*`ReceiverType`*`))]` (can be one per struct field and also one on whole struct) — sets the receiver used in `get_handle` implementations for one struct field or the default for the whole struct to be used with `#[snec(entry)]`. *`receiver_expression`* is any valid Rust expression used to create the receiver, executed in the context of the `Get` implementation on the config struct. The type, *`ReceiverType`* must be annotated explicitly. If this attribute is not present, the receiver defaults to `EmptyReceiver`, which does nothing when notified.
-/// - `#[snec(entry_module(`*`module_name`*`))]` (one on whole struct) — sets the module name in which the entry types generated by `#[snec(entry(...))]` will be placed to *`module_name`*. The default value is `entries`.
-/// - `#[snec(entry_module_visibility(`*`visibility`*`))]` (one on whole struct) — visibility specifier the generated module for entry marker types. Uses private visibility by default.
+/// - `#[snec(receiver({`*`receiver_expression`*`}: `*`ReceiverType`*`))]` (can be one per struct field and also one on whole struct) — sets the receiver used in `get_handle` implementations for one struct field or the default for the whole struct to be used with `#[snec(entry)]`. *`receiver_expression`* is any valid Rust expression used to create the receiver, executed in the context of the `Get` implementation on the config struct. The type, *`ReceiverType`* must be annotated explicitly. If this attribute is not present, the receiver defaults to `EmptyReceiver`, which does nothing when notified. To notify several receivers, a tuple of them can be used, e.g. `{(MyReceiver, MyOtherReceiver)}: (MyReceiver, MyOtherReceiver)`.
+/// - `#[snec(receiver_field = `*`field_name`*`)]` (can be one per struct field and also one on whole struct) — uses a field of the struct as the receiver instead of a receiver expression, which allows stateful receivers to be stored inside of the config table. The handle borrows the field for as long as it exists, wrapping it in `BorrowedReceiver`. Cannot be combined with `#[snec(receiver(...))]` on the same field or on the whole struct, but a receiver specified for a field overrides either kind of receiver specified for the whole struct. With `#[snec(all_fields)]`, the field used as the receiver needs `#[snec(skip)]`.
+/// - `#[snec(invalidates(`*`field_name`*`, ...))]` (can be one per struct field) — makes the handle to the entry of the field also notify the receivers of the entries of the listed fields whenever it notifies its own receiver, passing them their current, unchanged values. This is meant for entries whose receivers depend on the value of this one, such as a layout cache depending on the font size. The listed fields must have exactly one entry each, and their receivers cannot be borrowed with `receiver_field`. Only the receivers of the listed entries are notified, not the ones of the entries which they invalidate in turn.
+/// - `#[snec(table_receiver({`*`receiver_expression`*`}: `*`ReceiverType`*`))]` (one on whole struct) — sets a table receiver, implementing `TableReceiver`, which is notified with the name of the entry whenever any entry of the struct changes, in addition to the receiver of the entry. The syntax is the same as for `#[snec(receiver(...))]`. The receivers of all `Get` implementations are wrapped in `WithTableReceiver`.
+/// - `#[snec(deferred = `*`field_name`*`)]` (one on whole struct, requires the `alloc` feature of Snec) — defers the notifications of all entries until `DeferredTable::dispatch_pending` is called on the struct, which is implemented along with it. The field, whose type must be `DeferredQueue<Self>`, records every change together with a clone of the new value, so the types of the fields with entries must implement `Clone`. When the queue is drained, the receivers specified by the other commands are created and notified with the recorded values, in the order in which the changes were made. With `#[snec(all_fields)]`, the field storing the queue needs `#[snec(skip)]`.
+/// - `#[snec(validate_table = `*`path::to::function`*`)]` or `#[snec(validate_table = `*`path::to::function`*`: `*`ErrorType`*`)]` (one on whole struct) — implements `ValidateTable` for the struct by calling the specified function with a reference to it, which checks invariants spanning several fields, such as a minimum which must not exceed a maximum. The function returns `Result<(), &'static str>` unless another error type is specified. Changes made with `GetExt::set_validated`, `GetExt::modify_together_validated`, patches and loaders are then undone if the function returns an error. Without this attribute, `ValidateTable` is implemented with `Infallible` as the error type, accepting every state of the table.
+/// - `#[snec(patch(`*`PatchType`*`))]` (one on whole struct) — generates a struct named *`PatchType`*, which defaults to the name of the config table with `Patch` appended, with a public field of type `Option<T>` for every field which has an entry, for partial updates of the config table. The patch struct has the same visibility as the config table and implements `Patch` and `Default`, and is applied with `ApplyPatch::apply_patch`, which notifies only the receivers of the entries whose fields are `Some`. If the types of all of those fields implement `PartialEq`, the patch struct also implements `ComparingPatch`, and `ApplyPatch::apply_patch_changed` skips the fields which are equal to the current values. See the `patch` module for details.
+/// - `#[snec(patch_attributes(...))]` (can be one per struct field and also one on whole struct, requires `#[snec(patch)]`) — any outer Rust attributes applied to the patch struct, or to the field of the patch struct generated for a field, such as `#[derive(serde::Deserialize)]` on the struct or `#[serde(rename = "max-connections")]` on a field.
+/// - `#[snec(builder(`*`BuilderType`*`))]` (one on whole struct, requires the `alloc` feature of Snec) — generates a builder named *`BuilderType`*, which defaults to the name of the config table with `Builder` appended, with a setter for every field and a `build` method which fills in the fields which were not set using the default values of their entries and runs the validators of all entries along with the table-level validator, returning the table or the list of errors. The builder has the same visibility as the config table. See the `builder` module for details.
+/// - `#[snec(derive_default)]` (one on whole struct) — implements `Default` for the struct, using the default values specified with `#[snec(default = ...)]` for the fields which have them and the `Default` implementations of the types of the other fields, which therefore have to implement `Default`.
+/// - `#[snec(default = `*`expression`*`)]` (one per struct field, requires `#[snec(entry)]`) — sets the default value of the generated entry, which is used by `Handle::reset`, `ConfigTable::reset_all`, builders generated by `#[snec(builder)]` and the `Default` implementation generated by `#[snec(derive_default)]`. *`expression`* is any valid Rust expression evaluating to the type of the field.
+/// - `#[snec(validate = `*`path::to::function`*`)]` (one per struct field, requires `#[snec(entry)]`) — sets the validator of the generated entry, which is used by builders generated by `#[snec(builder)]`. The function takes a reference to the value and returns `Result<(), &'static str>`.
+/// - `#[snec(secret)]` (one per struct field, requires `#[snec(entry)]`) — marks the generated entry as secret by setting `Entry::SECRET`, which redacts its value in the `Debug` output of handles, in dumps of the config table and in exports of its registry, and withholds it from dynamic receivers attached with `DynAdapter::new`. Meant for passwords, API keys and other values which must not end up in logs.
+/// - `#[snec(secret(zeroize))]` (one per struct field, requires `#[snec(entry)]` and the `zeroize` feature of Snec) — same as `#[snec(secret)]`, but additionally implements `Entry::erase` using `Zeroize`, so that handles zero the memory of old values when overwriting them, and implements `Drop` for the struct to zero the field when the config table is dropped. The type of the field must implement `Zeroize`, and the struct can't have its own `Drop` implementation.
+/// - `#[snec(shm)]` (one per struct field, requires `#[snec(entry)]` or `#[snec(use_entry(...))]` and the `shm` feature of Snec) — mirrors the entry in shared memory, by implementing `shm::ShmTable` for the struct with the entries of all fields marked like this, in the order of the fields. The type of the field must implement `shm::ShmData`.
+/// - `#[snec(name = "`*`entry_name`*`")]` (one per struct field, requires `#[snec(entry)]`) — overrides the `NAME` of the generated entry, which defaults to the name of the field. This allows the names used by loaders, schemas and other external tooling to stay the same when the field is renamed, or to use characters which can't appear in Rust identifiers, e.g. `"max-connections"`.
+/// - `#[snec(unit = "`*`unit`*`")]` (one per struct field, requires `#[snec(entry)]`) — sets the `UNIT` of the generated entry, such as `"ms"`, `"dB"` or `"px"`, which is listed in the runtime registry of the config table so that user interfaces and exporters can label the value. Entries have no unit by default.
+/// - `#[snec(ui(min = `*`expression`*`, max = `*`expression`*`, step = `*`expression`*`))]` (one per struct field, requires `#[snec(entry)]`) — sets the `UI_RANGE` of the generated entry, the range of values which user interfaces should offer, such as the bounds and the step of a slider. Any of the three can be omitted. Each *`expression`* is a constant of type `f64`, but integer literals such as `0` or `-5` are accepted as well. This is independent from `#[snec(validate = ...)]`: values outside of the range can still be set.
+/// - `#[snec(debug_expansion)]` (one on whole struct) — writes the code generated for the struct to a file named after the struct, e.g. `MyConfigTable.rs`, for inspection. The file is placed into `OUT_DIR` if the crate has a build script, or into `target/snec-expansion` inside of the crate directory otherwise.
+/// - `#[snec(all_fields)]` (one on whole struct) — makes every field an entry, as if it had a `#[snec]` attribute, which prevents fields from being left out of the config table by accident. Fields with other commands but without `entry` or `use_entry`, such as `#[snec(receiver(...))]` or `#[snec(default = ...)]`, get a generated entry as well.
+/// - `#[snec(skip)]` (one per struct field) — excludes the field from the config table when `#[snec(all_fields)]` is used. Cannot be combined with other commands.
+/// - `#[snec(entry_module(`*`module_path`*`))]` (one on whole struct) — sets the module in which the entry types generated by `#[snec(entry(...))]` will be placed to *`module_path`*, relative to the struct. The default value is `entries`. A path with several segments, such as `config::entries`, generates nested modules, all of which must not exist yet. `self` places the entry types next to the struct without generating a module, which allows them to be put into an existing module by declaring the struct there; in that case, their default visibility is the one set by `#[snec(entry_module_visibility(...))]`.
+/// - `#[snec(entry_module_visibility(`*`visibility`*`))]` (one on whole struct) — visibility specifier the generated module for entry marker types. For nested modules, this is the visibility of the outermost one. Uses private visibility by default.
+/// - `#[snec(entry_visibility(`*`visibility`*`))]` (can be one per struct field, requires `#[snec(entry)]`, and also one on whole struct) — sets the visibility of the entry marker type generated for one struct field, or the default for the whole struct. *`visibility`* is written relative to the module containing the struct, so `entry_visibility()` makes the marker type private to that module, as if it was declared next to the struct. The default is `pub`, which makes the marker types as visible as the module for entry marker types.
+/// - `#[snec(reexport_entries)]` (one on whole struct) — re-exports the contents of the module for entry marker types next to the struct, using the visibility set by `#[snec(entry_module_visibility(...))]`, so that the entries can be used both as `entries::MyEntry` and `MyEntry`.
 /// - `#[snec(entry_module_attributes(...))]` (one on whole struct) — any Rust attributes applied to the generated module for entry marker types. Those attributes can be any valid Rust attributes, which include `///`-style and `/** */`-style documentation, but are *restricted to outer attributes*, i.e. `#[...]` and not `#![...]`.
 ///
+/// Fields gated by `#[cfg(...)]` attributes are supported: the attributes are copied to the entry types and the `Get` implementations generated for the fields, and to the elements of the lists of entries in the implementations of the traits which list all entries, such as `ConfigTable`, so any number of fields can be gated by any number of different conditions.
+///
+/// Tuple structs are supported as well. For their fields, the marker type generated by `#[snec(entry)]` defaults to `Field` followed by the index of the field, e.g. `Field0`, and the `NAME` of the entry is the index itself, e.g. `"0"`.
+///
+/// Documentation comments on struct fields are used as the `DESCRIPTION` of the entry types generated for them. Additionally, the `ConfigTable` trait is implemented for the struct, providing a runtime registry of all entries which received a `Get` implementation, along with `VisitEntries` and `VisitEntryTypes` for passing all of those entries to visitors. Unless the notifications are deferred, a receiver expression refers to `self`, a receiver is borrowed from a field, an entry invalidates other entries, a field is used for several entries or an entry marker type has a visibility other than `pub`, `SplitHandles` and `EntryIndex` are implemented as well, allowing handles to several entries to be held at once.
+///
 /// # Example
 /// ```no_run
 /// # /*
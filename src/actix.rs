@@ -0,0 +1,119 @@
+//! Delivering changes of entries to [Actix] actors through their mailboxes.
+//!
+//! `Recipient<ConfigChanged<E>>` is a [receiver] for the entry `E`: every change sends a [`ConfigChanged`] message with a clone of the new value to the actor, which handles it like any other message, in order with the rest of its mailbox. Since recipients are cheap to clone, the receiver expression usually clones one stored in the config table. Plain notifications are delivered even if the mailbox is full, while [`Handle::try_set`] reports a full or closed mailbox as an error.
+//!
+//! # Example
+//! ```
+//! use actix::prelude::*;
+//! use snec::{ConfigTable, GetExt as _, actix::ConfigChanged};
+//! #[derive(ConfigTable)]
+//! struct Config {
+//!     #[snec(entry, receiver({self.mixer.clone()}: Recipient<ConfigChanged<entries::Volume>>))]
+//!     volume: u8,
+//!     mixer: Recipient<ConfigChanged<entries::Volume>>,
+//! }
+//! struct Mixer {
+//!     volume: u8,
+//! }
+//! impl Actor for Mixer {
+//!     type Context = Context<Self>;
+//! }
+//! impl Handler<ConfigChanged<entries::Volume>> for Mixer {
+//!     type Result = ();
+//!     fn handle(&mut self, change: ConfigChanged<entries::Volume>, _: &mut Self::Context) {
+//!         self.volume = change.new_value;
+//!     }
+//! }
+//! struct GetVolume;
+//! impl Message for GetVolume {
+//!     type Result = u8;
+//! }
+//! impl Handler<GetVolume> for Mixer {
+//!     type Result = u8;
+//!     fn handle(&mut self, _: GetVolume, _: &mut Self::Context) -> u8 {
+//!         self.volume
+//!     }
+//! }
+//! System::new().block_on(async {
+//!     let mixer = Mixer {volume: 50}.start();
+//!     let mut config = Config {volume: 50, mixer: mixer.clone().recipient()};
+//!     config.get_handle_to::<entries::Volume>().set(80);
+//!     // Messages are handled in order, so the change is applied before the query.
+//!     assert_eq!(mixer.send(GetVolume).await.unwrap(), 80);
+//! });
+//! ```
+//!
+//! [Actix]: https://actix.rs " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`ConfigChanged`]: struct.ConfigChanged.html " "
+//! [`Handle::try_set`]: ../struct.Handle.html#method.try_set " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    marker::PhantomData,
+};
+use actix::{dev::SendError, Message, Recipient};
+use super::{Entry, Receiver, TryReceiver};
+
+/// The message sent to an actor when the value of the entry `E` changes.
+pub struct ConfigChanged<E: Entry> {
+    /// The new value of the entry.
+    pub new_value: E::Data,
+    entry: PhantomData<fn() -> E>,
+}
+impl<E: Entry> ConfigChanged<E> {
+    /// Creates the message for the specified new value of the entry.
+    #[inline(always)]
+    pub fn new(new_value: E::Data) -> Self {
+        Self {new_value, entry: PhantomData}
+    }
+}
+impl<E> Message for ConfigChanged<E>
+where
+    E: Entry + 'static,
+    E::Data: 'static {
+    type Result = ();
+}
+impl<E> Clone for ConfigChanged<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.new_value.clone())
+    }
+}
+impl<E> Debug for ConfigChanged<E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigChanged")
+            .field("entry", &E::NAME)
+            .field("new_value", &self.new_value)
+            .finish()
+    }
+}
+
+/// Sends the message even if the mailbox is full, and drops it if the actor has stopped.
+impl<E> Receiver<E> for Recipient<ConfigChanged<E>>
+where
+    E: Entry + 'static,
+    E::Data: Clone + Send + 'static {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.do_send(ConfigChanged::new(new_value.clone()));
+    }
+}
+/// Fails if the mailbox is full or the actor has stopped, giving the message back.
+impl<E> TryReceiver<E> for Recipient<ConfigChanged<E>>
+where
+    E: Entry + 'static,
+    E::Data: Clone + Send + 'static {
+    type Error = SendError<ConfigChanged<E>>;
+    #[inline]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.try_send(ConfigChanged::new(new_value.clone()))
+    }
+}
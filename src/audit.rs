@@ -0,0 +1,257 @@
+//! Recording who changed which entries, when, and from which values to which.
+//!
+//! [`AuditLog`] is a [receiver] which appends an [`AuditRecord`] for every change it's notified about. The actor responsible for the change, such as the component or the user making it, is specified by tagging the handle used for the change with [`Handle::with_audit`], which also lets the log record the value the entry had before the change. Changes made through untagged handles are recorded without an actor and without the old value.
+//!
+//! Values are recorded as their `Debug` representations, except for the values of [secret] entries, which are recorded as `<redacted>`. The tag is carried by the receiver of the tagged handle, which makes the actor and the old value available to the logs it notifies, including ones nested in other receivers such as tuples, only for the duration of the notification and only for the value of the entry the handle points to. Changes made through other handles in the meantime, even to entries with the same name in other tables, are thus never attributed to the actor. Logs which are notified on another thread, such as through a [`SpawnReceiver`], don't see the tag.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, audit::AuditLog};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.audit.clone()}: AuditLog))]
+//! struct Config {
+//!     #[snec]
+//!     port: u16,
+//!     #[snec(entry, secret)]
+//!     api_key: String,
+//!     audit: AuditLog,
+//! }
+//! let mut config = Config {port: 8080, api_key: "hunter2".to_string(), audit: AuditLog::new()};
+//! config.get_handle_to::<entries::Port>().with_audit("admin-ui").set(8081);
+//! config.get_handle_to::<entries::ApiKey>().set("correct horse".to_string());
+//! let records = config.audit.records();
+//! assert_eq!(records[0].to_string(), "admin-ui changed port from 8080 to 8081");
+//! assert_eq!(records[1].to_string(), "<unknown> changed api_key to <redacted>");
+//! ```
+//! A tagged handle doesn't affect changes made through other handles while it exists:
+//! ```
+//! # use snec::{ConfigTable, GetExt as _, audit::AuditLog};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.audit.clone()}: AuditLog))]
+//! struct Server {
+//!     #[snec]
+//!     port: u16,
+//!     audit: AuditLog,
+//! }
+//! let audit = AuditLog::new();
+//! let mut public = Server {port: 80, audit: audit.clone()};
+//! let mut admin = Server {port: 8080, audit: audit.clone()};
+//! let mut tagged = public.get_handle_to::<entries::Port>().with_audit("deploy-script");
+//! admin.get_handle_to::<entries::Port>().set(8443);
+//! tagged.set(443);
+//! drop(tagged);
+//! let records = audit.records();
+//! assert_eq!(records[0].to_string(), "<unknown> changed port to 8443");
+//! assert_eq!(records[1].to_string(), "deploy-script changed port from 80 to 443");
+//! ```
+//!
+//! [`AuditLog`]: struct.AuditLog.html " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`AuditRecord`]: struct.AuditRecord.html " "
+//! [`Handle::with_audit`]: ../struct.Handle.html#method.with_audit " "
+//! [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [`SpawnReceiver`]: ../struct.SpawnReceiver.html " "
+
+use core::{
+    cell::RefCell,
+    fmt::{self, Formatter, Debug, Display},
+};
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::SystemTime,
+};
+use super::{Entry, Handle, Receiver, Redacted, lock_ignoring_poison};
+
+/// A change of an entry, as recorded by an [`AuditLog`].
+///
+/// Displayed as a sentence describing the change, such as `admin-ui changed port from 8080 to 8081`, without the time.
+///
+/// [`AuditLog`]: struct.AuditLog.html " "
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditRecord {
+    /// The [name] of the entry which was changed.
+    ///
+    /// [name]: ../trait.Entry.html#associatedconstant.NAME " "
+    pub entry: &'static str,
+    /// The actor which made the change, or `None` if the handle wasn't tagged.
+    pub actor: Option<String>,
+    /// When the change was recorded.
+    pub time: SystemTime,
+    /// The value before the change, or `None` if the handle wasn't tagged.
+    pub old_value: Option<String>,
+    /// The value after the change.
+    pub new_value: String,
+}
+impl Display for AuditRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.actor.as_deref().unwrap_or("<unknown>"))?;
+        write!(f, " changed {}", self.entry)?;
+        if let Some(old_value) = &self.old_value {
+            write!(f, " from {}", old_value)?;
+        }
+        write!(f, " to {}", self.new_value)
+    }
+}
+
+/// A [receiver] which records every change it's notified about. Clones share the records. See the [module-level documentation] for more.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [module-level documentation]: index.html " "
+#[derive(Clone, Default)]
+pub struct AuditLog {
+    records: Arc<Mutex<Vec<AuditRecord>>>,
+}
+impl AuditLog {
+    /// Creates a log without any records.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns clones of the records, in the order in which the changes were made.
+    #[inline]
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.lock().clone()
+    }
+    /// Takes the records out of the log, leaving it empty, for moving them to persistent storage.
+    #[inline]
+    pub fn take(&self) -> Vec<AuditRecord> {
+        core::mem::take(&mut *self.lock())
+    }
+    /// Returns the number of records.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+    /// Returns `true` if there are no records.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Vec<AuditRecord>> {
+        lock_ignoring_poison(&self.records)
+    }
+}
+impl<E> Receiver<E> for AuditLog
+where
+    E: Entry,
+    E::Data: Debug {
+    fn receive(&mut self, new_value: &E::Data) {
+        let address = address(new_value);
+        let (actor, old_value) = CONTEXT.with(|contexts| {
+            let contexts = contexts.borrow();
+            let context = contexts.iter().rev().find(|context| context.entry == E::NAME && context.address == address);
+            match context {
+                Some(context) => (Some(context.actor.clone()), Some(context.old_value.clone())),
+                None => (None, None),
+            }
+        });
+        let new_value = format_value::<E>(new_value);
+        self.lock().push(AuditRecord {entry: E::NAME, actor, time: SystemTime::now(), old_value, new_value});
+    }
+}
+impl Debug for AuditLog {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("records", &self.len())
+            .finish()
+    }
+}
+
+/// The actor and the old value of a change which a tagged handle is notifying its receiver about on this thread.
+struct Context {
+    entry: &'static str,
+    /// The address of the value of the entry, which tells it apart from the values of entries with the same name in other tables.
+    address: usize,
+    actor: String,
+    old_value: String,
+}
+std::thread_local! {
+    /// The changes being notified about on this thread, innermost last.
+    static CONTEXT: RefCell<Vec<Context>> = const { RefCell::new(Vec::new()) };
+}
+/// Removes the innermost context when the notification ends, even if a receiver panics.
+struct ContextGuard;
+impl Drop for ContextGuard {
+    #[inline]
+    fn drop(&mut self) {
+        // Fails only if the thread-local was already destroyed, in which case there's nothing to remove.
+        let _ = CONTEXT.try_with(|contexts| contexts.borrow_mut().pop());
+    }
+}
+
+impl<'a, E, R> Handle<'a, E, R>
+where
+    E: Entry,
+    E::Data: Debug,
+    R: Receiver<E> {
+    /// Tags the handle with the specified actor, which is recorded by [audit logs] for all changes made through the returned handle, along with the old values of the entry. See the [`audit`] module for more.
+    ///
+    /// [audit logs]: audit/struct.AuditLog.html " "
+    /// [`audit`]: audit/index.html " "
+    pub fn with_audit(self, actor: impl Into<String>) -> Handle<'a, E, Audited<R>> {
+        let last_value = format_value::<E>(self.get());
+        let actor = actor.into();
+        self.map_receiver(|receiver| Audited {receiver, actor, last_value})
+    }
+}
+
+/// The receiver of a [handle] tagged with an actor by [`Handle::with_audit`], which notifies the wrapped receiver and makes the actor and the old value available to the [audit logs] it notifies.
+///
+/// [handle]: ../struct.Handle.html " "
+/// [`Handle::with_audit`]: ../struct.Handle.html#method.with_audit " "
+/// [audit logs]: struct.AuditLog.html " "
+pub struct Audited<R> {
+    /// The wrapped receiver.
+    pub receiver: R,
+    actor: String,
+    /// The formatted value before the next change.
+    last_value: String,
+}
+impl<R> Audited<R> {
+    /// Returns the actor the handle is tagged with.
+    #[inline(always)]
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+}
+impl<E, R> Receiver<E> for Audited<R>
+where
+    E: Entry,
+    E::Data: Debug,
+    R: Receiver<E> {
+    fn receive(&mut self, new_value: &E::Data) {
+        let old_value = core::mem::replace(&mut self.last_value, format_value::<E>(new_value));
+        let context = Context {entry: E::NAME, address: address(new_value), actor: self.actor.clone(), old_value};
+        CONTEXT.with(|contexts| contexts.borrow_mut().push(context));
+        let _guard = ContextGuard;
+        self.receiver.receive(new_value);
+    }
+}
+impl<R: Debug> Debug for Audited<R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Audited")
+            .field("receiver", &self.receiver)
+            .field("actor", &self.actor)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Formats the value of the entry for a record.
+#[inline]
+fn format_value<E>(value: &E::Data) -> String
+where
+    E: Entry,
+    E::Data: Debug {
+    if E::SECRET {
+        format!("{:?}", Redacted)
+    } else {
+        format!("{:?}", value)
+    }
+}
+#[inline(always)]
+fn address<T>(value: &T) -> usize {
+    value as *const T as usize
+}
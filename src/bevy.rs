@@ -0,0 +1,209 @@
+//! Using config tables as resources in [Bevy] apps.
+//!
+//! [`ConfigPlugin`] inserts a config table into the app as a resource, so that systems can read it with `Res` and change its entries through handles with `ResMut`, using `into_inner` to get a mutable reference to the table. [`BevyReceiver`], attached to the entries of the table as their receiver, turns every change into a [`ConfigChanged`] event for the entry, which systems can react to with an ordinary `EventReader`. The plugin registers the events for every entry listed in the [registry] of the table.
+//!
+//! Since the receiver can't access the world while the table is being changed, the events are queued and sent at the end of the frame, in the [`Last`] schedule, in the order in which the changes were made. Readers running in the [`Update`] schedule thus see them in the next frame.
+//!
+//! # Example
+//! ```
+//! use bevy_app::{App, Update};
+//! use bevy_ecs::prelude::*;
+//! use snec::{ConfigTable, GetExt as _, bevy::{BevyReceiver, ConfigChanged, ConfigPlugin}};
+//! #[derive(ConfigTable, Resource)]
+//! #[snec(receiver({self.events.clone()}: BevyReceiver))]
+//! struct Settings {
+//!     #[snec]
+//!     volume: f32,
+//!     #[snec]
+//!     vsync: bool,
+//!     events: BevyReceiver,
+//! }
+//! #[derive(Default, Resource)]
+//! struct Mixer {
+//!     volume: f32,
+//! }
+//! fn update_mixer(mut changes: EventReader<ConfigChanged<entries::Volume>>, mut mixer: ResMut<Mixer>) {
+//!     for change in changes.read() {
+//!         mixer.volume = change.new_value;
+//!     }
+//! }
+//! let events = BevyReceiver::new();
+//! let settings = Settings {volume: 1.0, vsync: true, events: events.clone()};
+//! let mut app = App::new();
+//! app.add_plugins(ConfigPlugin::new(settings, events))
+//!     .init_resource::<Mixer>()
+//!     .add_systems(Update, update_mixer);
+//! app.world_mut().resource_mut::<Settings>().into_inner().get_handle_to::<entries::Volume>().set(0.5);
+//! // The event is sent at the end of the first frame and read in the second one.
+//! app.update();
+//! app.update();
+//! assert_eq!(app.world().resource::<Mixer>().volume, 0.5);
+//! ```
+//!
+//! [Bevy]: https://bevyengine.org " "
+//! [`ConfigPlugin`]: struct.ConfigPlugin.html " "
+//! [`BevyReceiver`]: struct.BevyReceiver.html " "
+//! [`ConfigChanged`]: struct.ConfigChanged.html " "
+//! [registry]: ../trait.ConfigTable.html " "
+//! [`Last`]: https://docs.rs/bevy_app/0.14/bevy_app/struct.Last.html " "
+//! [`Update`]: https://docs.rs/bevy_app/0.14/bevy_app/struct.Update.html " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    marker::PhantomData,
+    mem,
+};
+use std::sync::{Arc, Mutex};
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::{event::Event, system::Resource, world::World};
+use super::{Entry, EntryTypeVisitor, Receiver, VisitEntryTypes, lock_ignoring_poison};
+
+/// The event sent when the value of the entry `E` changes.
+#[derive(Event)]
+pub struct ConfigChanged<E: Entry> {
+    /// The new value of the entry.
+    pub new_value: E::Data,
+    entry: PhantomData<fn() -> E>,
+}
+impl<E: Entry> ConfigChanged<E> {
+    /// Creates the event for the specified new value of the entry.
+    #[inline(always)]
+    pub fn new(new_value: E::Data) -> Self {
+        Self {new_value, entry: PhantomData}
+    }
+}
+impl<E> Clone for ConfigChanged<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.new_value.clone())
+    }
+}
+impl<E> Debug for ConfigChanged<E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigChanged")
+            .field("entry", &E::NAME)
+            .field("new_value", &self.new_value)
+            .finish()
+    }
+}
+
+/// An event waiting to be sent to the world.
+type PendingEvent = Box<dyn FnOnce(&mut World) + Send>;
+
+/// A [receiver] which queues a [`ConfigChanged`] event for every change, to be sent to the world with [`flush`]. Clones of the receiver share the queue. See the [module-level documentation] for more.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [`ConfigChanged`]: struct.ConfigChanged.html " "
+/// [`flush`]: #method.flush " "
+/// [module-level documentation]: index.html " "
+#[derive(Clone, Default)]
+pub struct BevyReceiver {
+    queue: Arc<Mutex<Vec<PendingEvent>>>,
+}
+impl BevyReceiver {
+    /// Creates a receiver with an empty queue.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sends the queued events to the world, in the order in which the changes were made. [`ConfigPlugin`] does this at the end of every frame.
+    ///
+    /// [`ConfigPlugin`]: struct.ConfigPlugin.html " "
+    pub fn flush(&self, world: &mut World) {
+        // Taken out first, so that the queue isn't locked while the events are being sent.
+        let events = mem::take(&mut *lock_ignoring_poison(&self.queue));
+        for event in events {
+            event(world);
+        }
+    }
+    /// Returns the number of events waiting to be sent.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        lock_ignoring_poison(&self.queue).len()
+    }
+}
+impl<E> Receiver<E> for BevyReceiver
+where
+    E: Entry + 'static,
+    E::Data: Clone + Send + Sync + 'static {
+    fn receive(&mut self, new_value: &E::Data) {
+        let event = ConfigChanged::<E>::new(new_value.clone());
+        lock_ignoring_poison(&self.queue)
+            .push(Box::new(move |world: &mut World| {
+                world.send_event(event);
+            }));
+    }
+}
+impl Debug for BevyReceiver {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BevyReceiver")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+/// A [plugin] which inserts a config table into the app as a resource, registers the [`ConfigChanged`] events for its entries and sends the events queued by a [`BevyReceiver`] at the end of every frame. See the [module-level documentation] for more.
+///
+/// [plugin]: https://docs.rs/bevy_app/0.14/bevy_app/trait.Plugin.html " "
+/// [`ConfigChanged`]: struct.ConfigChanged.html " "
+/// [`BevyReceiver`]: struct.BevyReceiver.html " "
+/// [module-level documentation]: index.html " "
+pub struct ConfigPlugin<T> {
+    /// Taken out when the plugin is built, since plugins are built by reference.
+    table: Mutex<Option<T>>,
+    receiver: BevyReceiver,
+}
+impl<T> ConfigPlugin<T> {
+    /// Creates a plugin inserting the specified table and sending the events queued by the specified receiver, which should be the one attached to the entries of the table.
+    #[inline]
+    pub fn new(table: T, receiver: BevyReceiver) -> Self {
+        Self {table: Mutex::new(Some(table)), receiver}
+    }
+}
+impl<T> Plugin for ConfigPlugin<T>
+where
+    T: Resource + for<'a> VisitEntryTypes<EventRegistrar<'a>> {
+    fn build(&self, app: &mut App) {
+        T::visit_entry_types(&mut EventRegistrar {app});
+        if let Some(table) = lock_ignoring_poison(&self.table).take() {
+            app.insert_resource(table);
+        }
+        let receiver = self.receiver.clone();
+        app.add_systems(Last, move |world: &mut World| receiver.flush(world));
+    }
+}
+impl<T> Debug for ConfigPlugin<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigPlugin")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [type visitor] which registers the [`ConfigChanged`] events of the entries it visits, used by [`ConfigPlugin`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`ConfigChanged`]: struct.ConfigChanged.html " "
+/// [`ConfigPlugin`]: struct.ConfigPlugin.html " "
+#[derive(Debug)]
+pub struct EventRegistrar<'a> {
+    app: &'a mut App,
+}
+impl<E> EntryTypeVisitor<E> for EventRegistrar<'_>
+where
+    E: Entry + 'static,
+    E::Data: Send + Sync + 'static {
+    #[inline]
+    fn visit_type(&mut self) {
+        self.app.add_event::<ConfigChanged<E>>();
+    }
+}
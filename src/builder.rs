@@ -0,0 +1,90 @@
+//! Constructing config tables with validation, one field at a time.
+//!
+//! `#[snec(builder)]` generates a builder for a config table, named after it with `Builder` appended unless another name is specified with `#[snec(builder(MyBuilderType))]`. The builder has a setter for every field of the table, named after the field or `field_` followed by the index for the fields of tuple structs, and a `build` method, which:
+//! - uses the [default values] of the entries whose fields were not set, reporting the fields which have neither a value nor a default as [`BuildError::Missing`]
+//! - runs the [validators] of all entries, reporting every rejected value as [`BuildError::InvalidEntry`]
+//! - constructs the table and runs the table-level validator specified with `#[snec(validate_table = ...)]`, if there is one, reporting its error as [`BuildError::InvalidTable`]
+//!
+//! All errors found before the table is constructed are collected into a list instead of stopping at the first one. No receivers are notified, since the table doesn't exist before it's built. Since the setters are named after the fields, fields named `new` or `build` can't be used with builders.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, builder::BuildError};
+//! #[derive(ConfigTable)]
+//! #[snec(builder, validate_table = check_range)]
+//! struct MyConfigTable {
+//!     #[snec(entry, default = 1)]
+//!     min_threads: u32,
+//!     #[snec(entry, validate = nonzero)]
+//!     max_threads: u32,
+//!     #[snec]
+//!     name: String,
+//! }
+//! fn nonzero(value: &u32) -> Result<(), &'static str> {
+//!     if *value == 0 {Err("must not be zero")} else {Ok(())}
+//! }
+//! fn check_range(table: &MyConfigTable) -> Result<(), &'static str> {
+//!     if table.min_threads <= table.max_threads {Ok(())} else {Err("`min_threads` exceeds `max_threads`")}
+//! }
+//! let table = MyConfigTableBuilder::new()
+//!     .max_threads(8)
+//!     .name("worker".to_string())
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(table.min_threads, 1);
+//! let errors = MyConfigTableBuilder::new().max_threads(0).build().err().unwrap();
+//! assert_eq!(errors, [
+//!     BuildError::InvalidEntry {entry: "max_threads", error: "must not be zero"},
+//!     BuildError::Missing("name"),
+//! ]);
+//! ```
+//!
+//! [default values]: ../trait.Entry.html#method.default_value " "
+//! [validators]: ../trait.Entry.html#method.validate " "
+//! [`BuildError::Missing`]: enum.BuildError.html#variant.Missing " "
+//! [`BuildError::InvalidEntry`]: enum.BuildError.html#variant.InvalidEntry " "
+//! [`BuildError::InvalidTable`]: enum.BuildError.html#variant.InvalidTable " "
+
+use core::fmt::{self, Formatter, Display};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// The list of errors collected by the `build` methods of generated builders. Requires the `alloc` feature.
+#[cfg(feature = "alloc")]
+pub type BuildErrors<E = &'static str> = Vec<BuildError<E>>;
+
+/// An error which prevented a builder from constructing a config table.
+///
+/// `E` is the error type of the table-level validator, which is `&'static str` unless specified otherwise in `#[snec(validate_table = ...)]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BuildError<E = &'static str> {
+    /// The field with the specified name was not set, and it doesn't have an entry with a default value.
+    Missing(&'static str),
+    /// The value of an entry was rejected by its [validator].
+    ///
+    /// [validator]: ../trait.Entry.html#method.validate " "
+    InvalidEntry {
+        /// The [name] of the entry.
+        ///
+        /// [name]: ../trait.Entry.html#associatedconstant.NAME " "
+        entry: &'static str,
+        /// The error message produced by the validator.
+        error: &'static str,
+    },
+    /// The table was rejected by its [table-level validator].
+    ///
+    /// [table-level validator]: ../trait.ValidateTable.html " "
+    InvalidTable(E),
+}
+impl<E: Display> Display for BuildError<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(field) => write!(f, "no value for field `{}`", field),
+            Self::InvalidEntry {entry, error} => write!(f, "invalid value for entry `{}`: {}", entry, error),
+            Self::InvalidTable(error) => write!(f, "invalid config table: {}", error),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + Display> std::error::Error for BuildError<E> {}
@@ -0,0 +1,210 @@
+//! Generating command line arguments for config tables using [Clap].
+//!
+//! [`TableArgs`] implements Clap's [`Args`] trait, adding one optional argument per entry of a config table, which allows it to be flattened into any Clap command. Each argument takes the form `--entry-name <VALUE>`, where the name is the [name] of the entry with underscores replaced by hyphens. The values are validated using [`FromStr`] while parsing the command line, and the [description] of the entry is used as the help message. After parsing, [`apply_to`] writes the provided values into the table through handles, notifying the receivers.
+//!
+//! The IDs of the arguments are the names of the entries themselves, which means that they must not collide with the IDs of other arguments of the command.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, clap::TableArgs};
+//! use clap::{Args, FromArgMatches};
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     /// The port to listen on.
+//!     #[snec]
+//!     port: u16,
+//!     #[snec]
+//!     log_level: String,
+//! }
+//! let command = TableArgs::<MyConfigTable>::augment_args(clap::Command::new("myserver"));
+//! let matches = command.get_matches_from(["myserver", "--log-level", "debug"]);
+//! let args = TableArgs::<MyConfigTable>::from_arg_matches(&matches).unwrap();
+//!
+//! let mut table = MyConfigTable {port: 8080, log_level: "info".to_string()};
+//! assert_eq!(args.apply_to(&mut table).unwrap(), 1);
+//! assert_eq!(table.log_level, "debug");
+//! assert_eq!(table.port, 8080);
+//! ```
+//!
+//! [Clap]: https://docs.rs/clap " "
+//! [`TableArgs`]: struct.TableArgs.html " "
+//! [`Args`]: https://docs.rs/clap/4/clap/trait.Args.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [description]: ../trait.Entry.html#associatedconstant.DESCRIPTION " "
+//! [`FromStr`]: https://doc.rust-lang.org/std/str/trait.FromStr.html " "
+//! [`apply_to`]: struct.TableArgs.html#method.apply_to " "
+
+use core::{
+    fmt::{self, Formatter, Debug, Display},
+    marker::PhantomData,
+    str::FromStr,
+};
+use ::clap::{Arg, ArgMatches, Args, Command, FromArgMatches};
+use super::{
+    Entry, Receiver, Handle,
+    ConfigTable, EntryVisitor, VisitEntries, EntryTypeVisitor, VisitEntryTypes,
+    layers::{Layer, LayerError, ArgsLayer},
+};
+
+/// Command line arguments for the entries of a config table. See the [module-level documentation] for more.
+///
+/// Also implements [`Layer`], which allows using the parsed arguments as the topmost layer of a [`Layers`] stack instead of applying them directly.
+///
+/// [module-level documentation]: index.html " "
+/// [`Layer`]: ../layers/trait.Layer.html " "
+/// [`Layers`]: ../layers/struct.Layers.html " "
+pub struct TableArgs<T> {
+    values: ArgsLayer,
+    _phantom: PhantomData<fn() -> T>,
+}
+impl<T> TableArgs<T> {
+    /// Returns the value provided for the entry with the specified name, if there is one.
+    #[inline(always)]
+    pub fn get(&self, entry_name: &str) -> Option<&str> {
+        self.values.get(entry_name)
+    }
+    /// Writes the provided values into the table, notifying the receivers of the affected entries. Returns the number of entries which were set.
+    ///
+    /// Since the values were validated while parsing the command line, this can only fail if the arguments were obtained from a different command than the one produced by [`augment_args`].
+    ///
+    /// [`augment_args`]: #method.augment_args " "
+    #[inline]
+    pub fn apply_to(&self, table: &mut T) -> Result<usize, ::clap::Error>
+    where T: for<'a> VisitEntries<ArgsApplier<'a>> {
+        let mut applier = ArgsApplier {values: &self.values, applied: 0, error: None};
+        table.visit_entries(&mut applier);
+        match applier.error {
+            Some(error) => Err(error),
+            None => Ok(applier.applied),
+        }
+    }
+    /// Returns the provided values as a [layer].
+    ///
+    /// [layer]: ../layers/trait.Layer.html " "
+    #[inline(always)]
+    pub fn into_layer(self) -> ArgsLayer {
+        self.values
+    }
+}
+impl<T> Clone for TableArgs<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {values: self.values.clone(), _phantom: PhantomData}
+    }
+}
+impl<T> Debug for TableArgs<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TableArgs")
+            .field("values", &self.values)
+            .finish()
+    }
+}
+impl<T> FromArgMatches for TableArgs<T>
+where T: ConfigTable {
+    #[inline]
+    fn from_arg_matches(matches: &ArgMatches) -> Result<Self, ::clap::Error> {
+        let mut result = Self {values: ArgsLayer::new(), _phantom: PhantomData};
+        result.update_from_arg_matches(matches)?;
+        Ok(result)
+    }
+    fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), ::clap::Error> {
+        for info in T::ENTRIES {
+            // Entries which don't have an argument are simply skipped.
+            if let Ok(Some(value)) = matches.try_get_one::<String>(info.name) {
+                self.values.insert(info.name, value.as_str());
+            }
+        }
+        Ok(())
+    }
+}
+impl<T> Args for TableArgs<T>
+where T: ConfigTable + VisitEntryTypes<ArgsBuilder> {
+    #[inline]
+    fn augment_args(command: Command) -> Command {
+        let mut builder = ArgsBuilder {command: Some(command)};
+        T::visit_entry_types(&mut builder);
+        builder.command.unwrap()
+    }
+    #[inline(always)]
+    fn augment_args_for_update(command: Command) -> Command {
+        Self::augment_args(command)
+    }
+}
+impl<T, E> Layer<E> for TableArgs<T>
+where
+    E: Entry,
+    E::Data: FromStr,
+    <E::Data as FromStr>::Err: Display {
+    #[inline(always)]
+    fn value(&self) -> Option<Result<E::Data, LayerError>> {
+        <ArgsLayer as Layer<E>>::value(&self.values)
+    }
+}
+
+/// A [type visitor] which adds an argument for every entry to a Clap command, used by [`TableArgs`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`TableArgs`]: struct.TableArgs.html " "
+#[derive(Debug)]
+pub struct ArgsBuilder {
+    // Clap's builder methods take the command by value.
+    command: Option<Command>,
+}
+impl<E> EntryTypeVisitor<E> for ArgsBuilder
+where
+    E: Entry,
+    E::Data: FromStr,
+    <E::Data as FromStr>::Err: Display {
+    fn visit_type(&mut self) {
+        let mut arg = Arg::new(E::NAME)
+            .long(E::NAME.replace('_', "-"))
+            .required(false)
+            .value_parser(|value: &str| {
+                value.parse::<E::Data>()
+                    .map(|_| value.to_string())
+                    .map_err(|error| error.to_string())
+            });
+        if !E::DESCRIPTION.is_empty() {
+            arg = arg.help(E::DESCRIPTION);
+        }
+        self.command = self.command.take().map(|command| command.arg(arg));
+    }
+}
+
+/// A [visitor] which writes parsed arguments into a config table, used by [`TableArgs::apply_to`].
+///
+/// [visitor]: ../trait.EntryVisitor.html " "
+/// [`TableArgs::apply_to`]: struct.TableArgs.html#method.apply_to " "
+#[derive(Debug)]
+pub struct ArgsApplier<'a> {
+    values: &'a ArgsLayer,
+    applied: usize,
+    error: Option<::clap::Error>,
+}
+impl<E> EntryVisitor<E> for ArgsApplier<'_>
+where
+    E: Entry,
+    E::Data: FromStr,
+    <E::Data as FromStr>::Err: Display {
+    fn visit<R: Receiver<E>>(&mut self, mut handle: Handle<'_, E, R>) {
+        if self.error.is_some() {
+            return;
+        }
+        match <ArgsLayer as Layer<E>>::value(self.values) {
+            Some(Ok(value)) => {
+                handle.set(value);
+                self.applied += 1;
+            },
+            Some(Err(error)) => {
+                self.error = Some(
+                    ::clap::Error::raw(
+                        ::clap::error::ErrorKind::ValueValidation,
+                        format!("invalid value for `--{}`: {}\n", E::NAME.replace('_', "-"), error),
+                    )
+                );
+            },
+            None => {},
+        }
+    }
+}
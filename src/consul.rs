@@ -0,0 +1,216 @@
+//! Synchronizing config tables with the key-value store of [Consul].
+//!
+//! Every entry is stored under a key made of a common prefix followed by the [name] of the entry, with the value serialized as JSON. [`ConsulSource`] and [`ConsulSink`] are the [remote backends] for Consul: the source reads all keys under the prefix and waits for them to change using [blocking queries], which return once the index of the prefix advances past the one which was last read, and the sink writes the values of entries with `PUT` requests. See the documentation of the [`remote`] module for how the synchronization works.
+//!
+//! Consul can only block on prefixes which contain at least one key. Waiting for changes under an empty prefix returns right away with no changes, which is why the initial values should be written to Consul before watching it.
+//!
+//! The client is asynchronous and requires a Tokio runtime.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, remote::RemoteReceiver};
+//! use consulrs::client::{ConsulClient, ConsulClientSettingsBuilder};
+//! use std::sync::Arc;
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.remote.clone()}: RemoteReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     max_connections: u32,
+//!     remote: RemoteReceiver,
+//! }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let settings = ConsulClientSettingsBuilder::default().address("http://localhost:8500").build()?;
+//! let client = Arc::new(ConsulClient::new(settings)?);
+//! let (mut sync, writer) = snec::consul::sync(client, "services/my-service/");
+//! let mut table = MyConfigTable {max_connections: 100, remote: sync.receiver()};
+//! sync.load_into(&mut table).await?;
+//! // Usually spawned as a separate task.
+//! let writer = writer.run();
+//! while let Some(changed) = sync.apply_next(&mut table).await? {
+//!     println!("{} entries changed remotely", changed);
+//! }
+//! # drop(writer);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [Consul]: https://www.consul.io " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`ConsulSource`]: struct.ConsulSource.html " "
+//! [`ConsulSink`]: struct.ConsulSink.html " "
+//! [remote backends]: ../remote/trait.Source.html " "
+//! [blocking queries]: https://developer.hashicorp.com/consul/api-docs/features/blocking " "
+//! [`remote`]: ../remote/index.html " "
+
+use core::{
+    convert::TryInto,
+    fmt::{self, Formatter, Debug},
+};
+use std::sync::Arc;
+use consulrs::{
+    api::{
+        self as consul_api,
+        features::{Blocking, Features},
+        kv::{common::KVPair, requests::{ReadKeyRequest, SetKeyRequest}},
+    },
+    client::ConsulClient,
+    error::ClientError,
+    kv,
+};
+use super::remote::{self, BoxFuture, Values, RemoteSync, RemoteWriter, Source, Sink};
+
+/// Synchronizes config tables with Consul.
+pub type ConsulSync = RemoteSync<ConsulSource>;
+/// Writes local changes to Consul.
+pub type ConsulWriter = RemoteWriter<ConsulSink>;
+/// An error which occurred while synchronizing with Consul.
+pub type Error = remote::Error<ClientError>;
+
+/// Creates a synchronizer for the entries stored under the specified prefix, along with the writer which writes local changes to Consul.
+#[inline]
+pub fn sync(client: Arc<ConsulClient>, prefix: impl Into<String>) -> (ConsulSync, ConsulWriter) {
+    let prefix = prefix.into();
+    let sink = ConsulSink {client: Arc::clone(&client), prefix: prefix.clone()};
+    RemoteSync::new(ConsulSource::new(client, prefix), sink)
+}
+
+/// A [source] which reads the values of entries from the keys under a prefix in Consul and waits for them to change using blocking queries.
+///
+/// [source]: ../remote/trait.Source.html " "
+pub struct ConsulSource {
+    client: Arc<ConsulClient>,
+    prefix: String,
+    index: u64,
+    wait: Option<String>,
+}
+impl ConsulSource {
+    /// Creates a source for the entries stored under the specified prefix.
+    #[inline]
+    pub fn new(client: Arc<ConsulClient>, prefix: impl Into<String>) -> Self {
+        Self {client, prefix: prefix.into(), index: 0, wait: None}
+    }
+    /// Sets the maximum duration of a single blocking query, such as `"5m"`, after which the query is repeated. Consul uses 5 minutes if none is specified.
+    #[inline]
+    pub fn with_wait(mut self, wait: impl Into<String>) -> Self {
+        self.wait = Some(wait.into());
+        self
+    }
+    /// Returns the prefix of the keys of the entries.
+    #[inline(always)]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    /// Returns the index of the prefix which was last read, or 0 if it wasn't read yet.
+    #[inline(always)]
+    pub fn index(&self) -> u64 {
+        self.index
+    }
+    /// Reads all keys under the prefix, blocking until the index advances past the specified one if it's not `None`. Returns the pairs and the new index.
+    async fn read(&self, blocking_index: Option<u64>) -> Result<(Vec<KVPair>, u64), ClientError> {
+        let mut request = ReadKeyRequest::builder();
+        request.recurse(true);
+        if let Some(index) = blocking_index {
+            request.features(
+                Features {
+                    blocking: Some(Blocking {index, wait: self.wait.clone()}),
+                    ..Features::default()
+                }
+            );
+        }
+        match kv::read(&*self.client, &self.prefix, Some(&mut request)).await {
+            Ok(response) => {
+                let index = response.index.and_then(|index| index.parse().ok()).unwrap_or(0);
+                Ok((response.response, index))
+            },
+            // Returned when there are no keys under the prefix.
+            Err(ClientError::APIError {code: 404, ..}) => Ok((Vec::new(), self.index)),
+            Err(error) => Err(error),
+        }
+    }
+    /// Collects the values of the pairs which were modified after the specified index.
+    fn values(&self, pairs: Vec<KVPair>, after_index: u64) -> Result<Values, ClientError> {
+        let mut values = Values::new();
+        for pair in pairs {
+            if pair.modify_index <= after_index {
+                continue;
+            }
+            if let (Some(name), Some(value)) = (pair.key.strip_prefix(&self.prefix), pair.value) {
+                values.insert(name.to_string(), value.try_into()?);
+            }
+        }
+        Ok(values)
+    }
+}
+impl Source for ConsulSource {
+    type Error = ClientError;
+    fn load(&mut self) -> BoxFuture<'_, Result<Values, Self::Error>> {
+        Box::pin(async move {
+            let (pairs, index) = self.read(None).await?;
+            self.index = index;
+            self.values(pairs, 0)
+        })
+    }
+    fn next_changes(&mut self) -> BoxFuture<'_, Result<Option<Values>, Self::Error>> {
+        Box::pin(async move {
+            loop {
+                let (pairs, index) = self.read(Some(self.index)).await?;
+                if index == self.index && !pairs.is_empty() {
+                    // The query timed out without any changes.
+                    continue;
+                }
+                // Consul may reset the index, in which case all values are treated as changed.
+                let after_index = if index < self.index {0} else {self.index};
+                self.index = index;
+                return self.values(pairs, after_index).map(Some);
+            }
+        })
+    }
+}
+impl Debug for ConsulSource {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsulSource")
+            .field("prefix", &self.prefix)
+            .field("index", &self.index)
+            .field("wait", &self.wait)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [sink] which writes the values of entries to the keys under a prefix in Consul.
+///
+/// [sink]: ../remote/trait.Sink.html " "
+#[derive(Clone)]
+pub struct ConsulSink {
+    client: Arc<ConsulClient>,
+    prefix: String,
+}
+impl ConsulSink {
+    /// Creates a sink for the entries stored under the specified prefix.
+    #[inline]
+    pub fn new(client: Arc<ConsulClient>, prefix: impl Into<String>) -> Self {
+        Self {client, prefix: prefix.into()}
+    }
+}
+impl Sink for ConsulSink {
+    type Error = ClientError;
+    fn store(&mut self, entry_name: &'static str, value: Vec<u8>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let request = SetKeyRequest {
+                key: format!("{}{}", self.prefix, entry_name),
+                value,
+                ..SetKeyRequest::default()
+            };
+            consul_api::exec_with_result(&*self.client, request).await?;
+            Ok(())
+        })
+    }
+}
+impl Debug for ConsulSink {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConsulSink")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
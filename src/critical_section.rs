@@ -0,0 +1,120 @@
+//! Interrupt-safe receivers using the [`critical-section`] crate.
+//!
+//! On embedded targets, configuration is usually changed in thread context while being read from interrupt handlers, which rules out the locks from the standard library. With the `critical-section` feature enabled, the `Mutex` from the `critical-section` crate can be used to share state between the two:
+//! - A shared reference to a `Mutex<Cell<T>>` stores the new value of an entry whose data type is `T`, which is how interrupt handlers usually observe configuration
+//! - A shared reference to a `Mutex<RefCell<R>>` forwards notifications to the receiver `R` inside a critical section; this also works for [table receivers] and [dynamic receivers]
+//! - [`CriticalSectionReceiver`] notifies any receiver inside a critical section, so that all of its side effects are observed by interrupt handlers at once
+//!
+//! The crate requires an implementation of critical sections for the target to be linked into the final binary, which is usually provided by the HAL or the runtime crate of the target.
+//!
+//! # Example
+//! ```
+//! # use snec::{Entry, Handle};
+//! use core::cell::Cell;
+//! use critical_section::Mutex;
+//! enum Brightness {}
+//! impl Entry for Brightness {
+//!     type Data = u8;
+//!     const NAME: &'static str = "brightness";
+//! }
+//! static BRIGHTNESS: Mutex<Cell<u8>> = Mutex::new(Cell::new(0));
+//! fn timer_interrupt() -> u8 {
+//!     critical_section::with(|cs| BRIGHTNESS.borrow(cs).get())
+//! }
+//! let mut brightness = 0;
+//! Handle::<Brightness, _>::new(&mut brightness, &BRIGHTNESS).set(200);
+//! assert_eq!(timer_interrupt(), 200);
+//! ```
+//!
+//! [`critical-section`]: https://docs.rs/critical-section " "
+//! [table receivers]: ../trait.TableReceiver.html " "
+//! [dynamic receivers]: ../trait.DynReceiver.html " "
+//! [`CriticalSectionReceiver`]: struct.CriticalSectionReceiver.html " "
+
+use core::{
+    any::Any,
+    cell::{Cell, RefCell},
+};
+use ::critical_section::Mutex;
+use super::{Entry, Receiver, TableReceiver, DynReceiver};
+
+/// A [receiver] which notifies the inner receiver inside a critical section.
+///
+/// This is useful for receivers which update several pieces of state shared with interrupt handlers, which would otherwise be able to observe some of them updated and some not.
+///
+/// [receiver]: ../trait.Receiver.html " "
+#[allow(clippy::module_name_repetitions)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CriticalSectionReceiver<R> {
+    /// The receiver which is notified inside a critical section.
+    pub inner: R,
+}
+impl<R> CriticalSectionReceiver<R> {
+    /// Wraps the specified receiver.
+    #[inline(always)]
+    pub const fn new(inner: R) -> Self {
+        Self {inner}
+    }
+}
+impl<E, R> Receiver<E> for CriticalSectionReceiver<R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        let inner = &mut self.inner;
+        ::critical_section::with(|_| inner.receive(new_value));
+    }
+}
+
+/// Stores the new value, making it available to interrupt handlers.
+impl<E> Receiver<E> for &Mutex<Cell<E::Data>>
+where
+    E: Entry,
+    E::Data: Copy {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        ::critical_section::with(|cs| self.borrow(cs).set(*new_value));
+    }
+}
+impl<E, R> Receiver<E> for Mutex<RefCell<R>>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.get_mut().get_mut().receive(new_value);
+    }
+}
+impl<E, R> Receiver<E> for &Mutex<RefCell<R>>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        ::critical_section::with(|cs| self.borrow_ref_mut(cs).receive(new_value));
+    }
+}
+
+impl<T> TableReceiver for &Mutex<RefCell<T>>
+where T: TableReceiver {
+    #[inline]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        ::critical_section::with(|cs| self.borrow_ref_mut(cs).receive_change(entry_name));
+    }
+}
+/// Sets the flag to `true` when notified, marking the table as changed.
+impl TableReceiver for &Mutex<Cell<bool>> {
+    #[inline]
+    fn receive_change(&mut self, _: &'static str) {
+        ::critical_section::with(|cs| self.borrow(cs).set(true));
+    }
+}
+
+impl<R> DynReceiver for &Mutex<RefCell<R>>
+where R: DynReceiver {
+    #[inline]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        ::critical_section::with(|cs| self.borrow_ref_mut(cs).receive_dyn(entry_name, new_value));
+    }
+}
@@ -0,0 +1,124 @@
+//! Sending changes of entries over [`crossbeam-channel`] channels.
+//!
+//! With the `crossbeam-channel` feature enabled, `crossbeam_channel::Sender<E::Data>` is a [receiver] for the entry `E` which sends a clone of every new value over the channel. Since several channels can be waited on at once with `select!`, a worker thread can handle configuration changes alongside its regular jobs. Plain notifications block while a bounded channel is full, and [`Handle::try_set`] fails instead. Values sent after the receiving end is dropped are discarded.
+//!
+//! A slow consumer of a bounded channel either blocks changes or makes them fail. [`conflating`] creates a channel which never does either: its [`Conflating`] sender replaces the value which hasn't been picked up yet with the new one, so that the consumer lags behind by at most one value and always sees the latest one.
+//!
+//! # Example
+//! ```
+//! # use snec::{Entry, Handle};
+//! use crossbeam_channel::TrySendError;
+//! enum Quality {}
+//! impl Entry for Quality {
+//!     type Data = u8;
+//!     const NAME: &'static str = "quality";
+//! }
+//! let (sender, receiver) = crossbeam_channel::bounded(1);
+//! let mut quality = 80;
+//! let mut handle = Handle::<Quality, _>::new(&mut quality, sender);
+//! handle.set(90);
+//! // The encoder hasn't picked up the previous change yet.
+//! assert_eq!(handle.try_set(95), Err(TrySendError::Full(95)));
+//! assert_eq!(receiver.try_recv(), Ok(90));
+//! assert_eq!(handle.try_set(100), Ok(()));
+//! assert_eq!(receiver.try_recv(), Ok(100));
+//! ```
+//!
+//! [`crossbeam-channel`]: https://docs.rs/crossbeam-channel " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`conflating`]: fn.conflating.html " "
+//! [`Conflating`]: struct.Conflating.html " "
+//! [`Handle::try_set`]: ../struct.Handle.html#method.try_set " "
+
+use core::fmt::{self, Formatter, Debug};
+use ::crossbeam_channel::{Sender, TrySendError};
+use super::{Entry, Receiver, TryReceiver};
+
+/// Blocks while the channel is full.
+impl<E> Receiver<E> for Sender<E::Data>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        // Sending fails only if the receiving side was dropped, in which case nobody is interested in the change.
+        let _ = self.send(new_value.clone());
+    }
+}
+/// Fails if the channel is full or the receiving side was dropped, giving the value back.
+impl<E> TryReceiver<E> for Sender<E::Data>
+where
+    E: Entry,
+    E::Data: Clone {
+    type Error = TrySendError<E::Data>;
+    #[inline]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.try_send(new_value.clone())
+    }
+}
+
+/// Creates a channel for the values of the entry `E` which only keeps the latest value, returning its [`Conflating`] sender, to be used as the receiver of the entry, and its receiving side.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle};
+/// enum Brightness {}
+/// impl Entry for Brightness {
+///     type Data = u8;
+///     const NAME: &'static str = "brightness";
+/// }
+/// let (sender, receiver) = snec::crossbeam_channel::conflating::<Brightness>();
+/// let mut brightness = 50;
+/// let mut handle = Handle::<Brightness, _>::new(&mut brightness, sender);
+/// handle.set(60);
+/// handle.set(70);
+/// // Neither change blocked, and the display only has to apply the latest one.
+/// handle.set(80);
+/// assert_eq!(receiver.try_recv(), Ok(80));
+/// assert!(receiver.try_recv().is_err());
+/// ```
+///
+/// [`Conflating`]: struct.Conflating.html " "
+pub fn conflating<E: Entry>() -> (Conflating<E>, ::crossbeam_channel::Receiver<E::Data>) {
+    let (sender, receiver) = ::crossbeam_channel::bounded(1);
+    (Conflating {sender, stale: receiver.clone()}, receiver)
+}
+
+/// The sending side of a channel created by [`conflating`], which replaces the value the consumer hasn't picked up yet instead of blocking or failing. See the [module-level documentation] for more.
+///
+/// The sender keeps a receiving side of its own to take the stale value out, so the channel stays connected for as long as the sender exists, even after the consumer drops its side.
+///
+/// [`conflating`]: fn.conflating.html " "
+/// [module-level documentation]: index.html " "
+pub struct Conflating<E: Entry> {
+    sender: Sender<E::Data>,
+    stale: ::crossbeam_channel::Receiver<E::Data>,
+}
+impl<E> Receiver<E> for Conflating<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    fn receive(&mut self, new_value: &E::Data) {
+        let mut value = new_value.clone();
+        // Loops only if another clone of the sender fills the channel between taking out the stale value and retrying.
+        while let Err(TrySendError::Full(rejected)) = self.sender.try_send(value) {
+            // Fails if the consumer picked the value up in the meantime, which frees up the room just as well.
+            let _ = self.stale.try_recv();
+            value = rejected;
+        }
+    }
+}
+impl<E: Entry> Clone for Conflating<E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {sender: self.sender.clone(), stale: self.stale.clone()}
+    }
+}
+impl<E: Entry> Debug for Conflating<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Conflating")
+            .field("entry", &E::NAME)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,386 @@
+//! Exposing config tables as [D-Bus] objects.
+//!
+//! A [`DbusObject`] serves a config table shared behind a mutex as a D-Bus interface, with one read-write property per entry, named after the [name] of the entry, so that the table can be inspected and modified with standard tooling such as `busctl` and `gdbus`. Writes made over D-Bus go through handles, and thus notify the receivers of the entries just like local changes; values which fail the [validation] of their entry are rejected with an `InvalidArgs` error. [Secret] entries are write-only.
+//!
+//! [`DbusReceiver`], attached to the entries of the table as their receiver, forwards all changes, whether made locally or over D-Bus, to [`DbusSignals`], which emits the `PropertiesChanged` signals for them as a separate task, batching the changes made since the last signal. Secret entries are announced as invalidated, without their values.
+//!
+//! The name of the interface is specified by implementing [`DbusInterface`] for the table. The entries need to be convertible to and from D-Bus values, which is the case for integers, floating-point numbers, booleans, strings, as well as vectors and maps of them.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, dbus::{DbusInterface, DbusObject, DbusReceiver}};
+//! use std::sync::{Arc, Mutex};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.dbus.clone()}: DbusReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     font_size: u32,
+//!     #[snec]
+//!     theme: String,
+//!     dbus: DbusReceiver,
+//! }
+//! impl DbusInterface for MyConfigTable {
+//!     const INTERFACE_NAME: &'static str = "org.example.MyApp.Config";
+//! }
+//! # async fn example() -> zbus::Result<()> {
+//! let (receiver, signals) = snec::dbus::channel::<MyConfigTable>();
+//! let table = MyConfigTable {font_size: 11, theme: "dark".to_string(), dbus: receiver};
+//! let table = Arc::new(Mutex::new(table));
+//! let connection = zbus::connection::Builder::session()?
+//!     .name("org.example.MyApp")?
+//!     .serve_at("/org/example/MyApp/Config", DbusObject::new(Arc::clone(&table)))?
+//!     .build()
+//!     .await?;
+//! let context = zbus::SignalContext::new(&connection, "/org/example/MyApp/Config")?;
+//! // Usually spawned as a separate task.
+//! signals.run(&context).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [D-Bus]: https://www.freedesktop.org/wiki/Software/dbus/ " "
+//! [`DbusObject`]: struct.DbusObject.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [validation]: ../trait.Entry.html#method.validate " "
+//! [Secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [`DbusReceiver`]: struct.DbusReceiver.html " "
+//! [`DbusSignals`]: struct.DbusSignals.html " "
+//! [`DbusInterface`]: trait.DbusInterface.html " "
+
+use core::{
+    convert::TryFrom,
+    fmt::{self, Formatter, Debug, Write},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use async_trait::async_trait;
+use tokio::sync::mpsc;
+use zbus::{
+    fdo,
+    names::{InterfaceName, MemberName},
+    zvariant::{OwnedValue, Type, Value},
+    Connection,
+    DispatchResult,
+    Interface,
+    Message,
+    ObjectServer,
+    SignalContext,
+};
+use super::{Entry, Get, Receiver, Handle, EntryVisitor, VisitEntries, EntryTypeVisitor, VisitEntryTypes};
+
+static POISONING_MSG: &str = "attempt to serve a config table behind a poisoned lock";
+
+/// Specifies the name of the D-Bus interface under which a config table is exposed.
+pub trait DbusInterface {
+    /// The name of the interface, such as `org.example.MyApp.Config`. Must be a valid D-Bus interface name, or serving the table will panic.
+    const INTERFACE_NAME: &'static str;
+}
+
+/// Creates a receiver which forwards changes to the returned signal emitter, which emits the signals on behalf of the interface of the config table `T`.
+///
+/// # Panics
+/// If the [name of the interface] of `T` isn't a valid D-Bus interface name.
+///
+/// [name of the interface]: trait.DbusInterface.html#associatedconstant.INTERFACE_NAME " "
+pub fn channel<T: DbusInterface>() -> (DbusReceiver, DbusSignals) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    (
+        DbusReceiver {changes: sender},
+        DbusSignals {interface: interface_name::<T>(), changes: receiver},
+    )
+}
+
+/// A D-Bus interface serving a config table, with one property per entry. See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+pub struct DbusObject<T> {
+    table: Arc<Mutex<T>>,
+}
+impl<T> DbusObject<T> {
+    /// Creates an interface serving the specified table.
+    #[inline(always)]
+    pub fn new(table: Arc<Mutex<T>>) -> Self {
+        Self {table}
+    }
+    /// Returns the table served by the interface.
+    #[inline(always)]
+    pub fn table(&self) -> &Arc<Mutex<T>> {
+        &self.table
+    }
+}
+#[async_trait]
+impl<T> Interface for DbusObject<T>
+where
+    T: DbusInterface + Send + 'static,
+    T: for<'a> VisitEntryTypes<PropertyReader<'a, T>>,
+    T: for<'a> VisitEntries<PropertyWriter<'a>>,
+    T: for<'a> VisitEntryTypes<Introspector<'a>> {
+    #[inline]
+    fn name() -> InterfaceName<'static> {
+        interface_name::<T>()
+    }
+    async fn get(&self, property_name: &str) -> Option<fdo::Result<OwnedValue>> {
+        let table = self.table.lock().expect(POISONING_MSG);
+        let mut reader = PropertyReader::new(&*table, Some(property_name));
+        T::visit_entry_types(&mut reader);
+        if reader.write_only {
+            return Some(
+                Err(fdo::Error::AccessDenied(format!("property `{}` is write-only", property_name)))
+            );
+        }
+        reader.values.remove(property_name).map(Ok)
+    }
+    async fn get_all(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let table = self.table.lock().expect(POISONING_MSG);
+        let mut reader = PropertyReader::new(&*table, None);
+        T::visit_entry_types(&mut reader);
+        Ok(reader.values)
+    }
+    async fn set_mut(
+        &mut self,
+        property_name: &str,
+        value: &Value<'_>,
+        _ctxt: &SignalContext<'_>,
+    ) -> Option<fdo::Result<()>> {
+        let value = match value.try_to_owned() {
+            Ok(value) => value,
+            Err(error) => return Some(Err(fdo::Error::InvalidArgs(error.to_string()))),
+        };
+        let mut table = self.table.lock().expect(POISONING_MSG);
+        let mut writer = PropertyWriter {name: property_name, value: Some(value), result: None};
+        table.visit_entries(&mut writer);
+        writer.result
+    }
+    #[inline(always)]
+    fn call<'call>(
+        &'call self,
+        _server: &'call ObjectServer,
+        _connection: &'call Connection,
+        _msg: &'call Message,
+        _name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        DispatchResult::NotFound
+    }
+    #[inline(always)]
+    fn call_mut<'call>(
+        &'call mut self,
+        _server: &'call ObjectServer,
+        _connection: &'call Connection,
+        _msg: &'call Message,
+        _name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        DispatchResult::NotFound
+    }
+    fn introspect_to_writer(&self, writer: &mut dyn Write, level: usize) {
+        let _ = writeln!(writer, "{:indent$}<interface name=\"{}\">", "", T::INTERFACE_NAME, indent = level);
+        T::visit_entry_types(&mut Introspector {writer, level: level + 2});
+        let _ = writeln!(writer, "{:indent$}</interface>", "", indent = level);
+    }
+}
+impl<T> Debug for DbusObject<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DbusObject").finish_non_exhaustive()
+    }
+}
+
+/// A property which changed, with its new value, or `None` if the value is secret.
+type Change = (&'static str, Option<OwnedValue>);
+
+/// A [receiver] which sends the new values of entries to [`DbusSignals`], created by [`channel`].
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [`DbusSignals`]: struct.DbusSignals.html " "
+/// [`channel`]: fn.channel.html " "
+#[derive(Clone)]
+pub struct DbusReceiver {
+    changes: mpsc::UnboundedSender<Change>,
+}
+impl<E> Receiver<E> for DbusReceiver
+where
+    E: Entry,
+    E::Data: Clone + Into<Value<'static>> {
+    fn receive(&mut self, new_value: &E::Data) {
+        let value = if E::SECRET {
+            None
+        } else {
+            OwnedValue::try_from(new_value.clone().into()).ok()
+        };
+        // Sending fails only if the signal emitter was dropped, in which case signals aren't wanted.
+        let _ = self.changes.send((E::NAME, value));
+    }
+}
+impl Debug for DbusReceiver {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DbusReceiver").finish_non_exhaustive()
+    }
+}
+
+/// Emits `PropertiesChanged` signals for the changes sent by [`DbusReceiver`]s.
+///
+/// [`DbusReceiver`]: struct.DbusReceiver.html " "
+pub struct DbusSignals {
+    interface: InterfaceName<'static>,
+    changes: mpsc::UnboundedReceiver<Change>,
+}
+impl DbusSignals {
+    /// Emits signals from the object at the path of the specified context until all receivers are dropped, or until emitting a signal fails.
+    pub async fn run(mut self, context: &SignalContext<'_>) -> zbus::Result<()> {
+        while let Some(change) = self.changes.recv().await {
+            let mut batch = vec![change];
+            while let Ok(change) = self.changes.try_recv() {
+                batch.push(change);
+            }
+            let mut changed = HashMap::new();
+            let mut invalidated = Vec::new();
+            // Later changes of an entry replace the earlier ones.
+            for (name, value) in &batch {
+                match value {
+                    Some(value) => {
+                        changed.insert(*name, &**value);
+                    },
+                    None if !invalidated.contains(name) => invalidated.push(*name),
+                    None => {},
+                }
+            }
+            fdo::Properties::properties_changed(context, self.interface.clone(), &changed, &invalidated)
+                .await?;
+        }
+        Ok(())
+    }
+}
+impl Debug for DbusSignals {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DbusSignals")
+            .field("interface", &self.interface)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [type visitor] which reads the values of properties, used by [`DbusObject`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`DbusObject`]: struct.DbusObject.html " "
+pub struct PropertyReader<'a, T> {
+    table: &'a T,
+    name: Option<&'a str>,
+    values: HashMap<String, OwnedValue>,
+    write_only: bool,
+}
+impl<'a, T> PropertyReader<'a, T> {
+    #[inline]
+    fn new(table: &'a T, name: Option<&'a str>) -> Self {
+        Self {table, name, values: HashMap::new(), write_only: false}
+    }
+}
+impl<'a, T, E> EntryTypeVisitor<E> for PropertyReader<'a, T>
+where
+    T: Get<E>,
+    E: Entry,
+    E::Data: Clone + Into<Value<'static>> {
+    fn visit_type(&mut self) {
+        if matches!(self.name, Some(name) if name != E::NAME) {
+            return;
+        }
+        if E::SECRET {
+            self.write_only = self.name.is_some();
+            return;
+        }
+        if let Ok(value) = OwnedValue::try_from(self.table.get_ref().clone().into()) {
+            self.values.insert(E::NAME.to_string(), value);
+        }
+    }
+}
+impl<T> Debug for PropertyReader<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PropertyReader")
+            .field("name", &self.name)
+            .field("values", &self.values)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [visitor] which writes the value of a property into its entry, used by [`DbusObject`].
+///
+/// [visitor]: ../trait.EntryVisitor.html " "
+/// [`DbusObject`]: struct.DbusObject.html " "
+#[derive(Debug)]
+pub struct PropertyWriter<'a> {
+    name: &'a str,
+    value: Option<OwnedValue>,
+    result: Option<fdo::Result<()>>,
+}
+impl<E> EntryVisitor<E> for PropertyWriter<'_>
+where
+    E: Entry,
+    E::Data: TryFrom<OwnedValue> {
+    fn visit<R: Receiver<E>>(&mut self, mut handle: Handle<'_, E, R>) {
+        if self.name != E::NAME {
+            return;
+        }
+        let value = match self.value.take() {
+            Some(value) => value,
+            None => return,
+        };
+        let result = match E::Data::try_from(value) {
+            Ok(value) => match E::validate(&value) {
+                Ok(()) => {
+                    handle.set(value);
+                    Ok(())
+                },
+                Err(error) => Err(format!("invalid value for property `{}`: {}", E::NAME, error)),
+            },
+            Err(..) => Err(format!("value of the wrong type for property `{}`", E::NAME)),
+        };
+        self.result = Some(result.map_err(fdo::Error::InvalidArgs));
+    }
+}
+
+/// A [type visitor] which writes the introspection data of properties, used by [`DbusObject`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`DbusObject`]: struct.DbusObject.html " "
+pub struct Introspector<'a> {
+    writer: &'a mut dyn Write,
+    level: usize,
+}
+impl<E> EntryTypeVisitor<E> for Introspector<'_>
+where
+    E: Entry,
+    E::Data: Type {
+    fn visit_type(&mut self) {
+        let (name, signature, level) = (E::NAME, <E::Data as Type>::signature(), self.level);
+        let _ = if E::SECRET {
+            writeln!(
+                self.writer,
+                "{:indent$}<property name=\"{}\" type=\"{}\" access=\"write\">\n\
+{:indent$}  <annotation name=\"org.freedesktop.DBus.Property.EmitsChangedSignal\" value=\"invalidates\"/>\n\
+{:indent$}</property>",
+                "", name, signature, "", "", indent = level,
+            )
+        } else {
+            writeln!(
+                self.writer,
+                "{:indent$}<property name=\"{}\" type=\"{}\" access=\"readwrite\"/>",
+                "", name, signature, indent = level,
+            )
+        };
+    }
+}
+impl Debug for Introspector<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Introspector")
+            .field("level", &self.level)
+            .finish_non_exhaustive()
+    }
+}
+
+fn interface_name<T: DbusInterface>() -> InterfaceName<'static> {
+    InterfaceName::from_static_str(T::INTERFACE_NAME).expect("invalid D-Bus interface name")
+}
@@ -0,0 +1,55 @@
+//! Logging changes of configuration on embedded targets using [`defmt`].
+//!
+//! [`DefmtReceiver`] logs the [name] of the entry and its new value with `defmt::info!` whenever it's notified. It can also be used as a [table receiver], in which case only the name of the entry is logged, since the value can be of any type.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, GetExt as _, defmt::DefmtReceiver};
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec(entry, receiver({DefmtReceiver}: DefmtReceiver))]
+//!     brightness: u8,
+//! }
+//! let mut table = MyConfigTable {brightness: 100};
+//! // Logs "brightness changed to 50".
+//! table.get_handle_to::<entries::Brightness>().set(50);
+//! ```
+//!
+//! [`defmt`]: https://docs.rs/defmt " "
+//! [`DefmtReceiver`]: struct.DefmtReceiver.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [table receiver]: ../trait.TableReceiver.html " "
+
+use ::defmt::Format;
+use super::{Entry, Receiver, TableReceiver};
+
+/// A [receiver] which logs the name of the entry and the new value using `defmt::info!`.
+///
+/// [receiver]: ../trait.Receiver.html " "
+#[allow(clippy::module_name_repetitions)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DefmtReceiver;
+impl<E> Receiver<E> for DefmtReceiver
+where
+    E: Entry,
+    E::Data: Format {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        ::defmt::info!("{=str} changed to {}", E::NAME, new_value);
+    }
+}
+impl<E> Receiver<E> for &DefmtReceiver
+where
+    E: Entry,
+    E::Data: Format {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        ::defmt::info!("{=str} changed to {}", E::NAME, new_value);
+    }
+}
+impl TableReceiver for DefmtReceiver {
+    #[inline]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        ::defmt::info!("{=str} changed", entry_name);
+    }
+}
@@ -0,0 +1,464 @@
+//! Subscribing to entries by their names, including whole groups of them at once using wildcards.
+//!
+//! A [`Pattern`] matches the [names] of entries, with `*` standing for any sequence of characters and `?` standing for any single character, so that `"render.*"` matches every entry whose name starts with `render.`. Patterns are used by:
+//! - [`PatternReceiver`], which forwards notifications to a [dynamic receiver] or a [table receiver] only if the name of the entry matches the pattern
+//! - [`DynDispatcher`], which holds any number of subscriptions, each made of a pattern and a dynamic receiver, and notifies all subscribers whose pattern matches; it requires the `alloc` feature, which is enabled by `std`
+//! - [`ConfigBus`], a thread-safe dispatcher which is shared between the config table and the subscribers, so that subscriptions can be made and cancelled from anywhere in the program, also by entry type; it requires the `std` feature
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, DynAdapter, GetExt as _, dispatch::DynDispatcher};
+//! use std::{any::Any, rc::Rc, cell::RefCell};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({DynAdapter::new(Rc::clone(&self.dispatcher))}: DynAdapter<Rc<RefCell<DynDispatcher>>>))]
+//! struct MyConfigTable {
+//!     #[snec(entry, name = "render.vsync")]
+//!     vsync: bool,
+//!     #[snec(entry, name = "render.fps_limit")]
+//!     fps_limit: u32,
+//!     #[snec(entry, name = "audio.volume")]
+//!     volume: f32,
+//!     dispatcher: Rc<RefCell<DynDispatcher>>,
+//! }
+//! let changed = Rc::new(RefCell::new(Vec::new()));
+//! let mut dispatcher = DynDispatcher::new();
+//! let changed_in_renderer = Rc::clone(&changed);
+//! dispatcher.subscribe_fn("render.*", move |entry_name: &'static str, _: &dyn Any| {
+//!     changed_in_renderer.borrow_mut().push(entry_name);
+//! });
+//! let mut table = MyConfigTable {
+//!     vsync: true,
+//!     fps_limit: 60,
+//!     volume: 1.0,
+//!     dispatcher: Rc::new(RefCell::new(dispatcher)),
+//! };
+//! table.get_handle_to::<entries::Vsync>().set(false);
+//! table.get_handle_to::<entries::Volume>().set(0.5);
+//! table.get_handle_to::<entries::FpsLimit>().set(144);
+//! assert_eq!(*changed.borrow(), ["render.vsync", "render.fps_limit"]);
+//! ```
+//!
+//! [`Pattern`]: struct.Pattern.html " "
+//! [names]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`PatternReceiver`]: struct.PatternReceiver.html " "
+//! [`DynDispatcher`]: struct.DynDispatcher.html " "
+//! [`ConfigBus`]: struct.ConfigBus.html " "
+//! [dynamic receiver]: ../trait.DynReceiver.html " "
+//! [table receiver]: ../trait.TableReceiver.html " "
+
+use core::{
+    any::Any,
+    fmt::{self, Formatter},
+};
+#[cfg(feature = "std")]
+use core::any::TypeId;
+#[cfg(feature = "alloc")]
+use alloc::{
+    boxed::Box,
+    string::String,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use super::{DynReceiver, TableReceiver};
+#[cfg(feature = "std")]
+use super::{Entry, Receiver, Redacted, lock_ignoring_poison, reentrancy::Notifying, receiver::POISONING_MSG};
+
+/// A pattern matching the names of entries, in which `*` matches any sequence of characters, including an empty one, and `?` matches exactly one character. All other characters match themselves.
+///
+/// # Example
+/// ```
+/// # use snec::dispatch::Pattern;
+/// let pattern = Pattern::new("render.*");
+/// assert!(pattern.matches("render.vsync"));
+/// assert!(!pattern.matches("audio.volume"));
+/// assert!(Pattern::new("*.volume").matches("audio.volume"));
+/// assert!(Pattern::new("window.?").matches("window.x"));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Pattern<S = &'static str> {
+    pattern: S,
+}
+impl<S: AsRef<str>> Pattern<S> {
+    /// Creates a pattern from its textual form.
+    #[inline(always)]
+    pub const fn new(pattern: S) -> Self {
+        Self {pattern}
+    }
+    /// Returns the textual form of the pattern.
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        self.pattern.as_ref()
+    }
+    /// Returns `true` if the pattern matches the specified entry name.
+    #[inline]
+    pub fn matches(&self, entry_name: &str) -> bool {
+        wildcard_match(self.as_str(), entry_name)
+    }
+}
+impl<S: AsRef<str>> fmt::Display for Pattern<S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// Matches with backtracking to the position after the last `*`, which takes at most quadratic time and no allocations. The positions are byte offsets at character boundaries.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let (mut p, mut n) = (0, 0);
+    let mut last_star = None;
+    while let Some(name_char) = name[n..].chars().next() {
+        match pattern[p..].chars().next() {
+            Some('*') => {
+                last_star = Some((p, n));
+                p += 1;
+            },
+            Some(c) if c == '?' || c == name_char => {
+                p += c.len_utf8();
+                n += name_char.len_utf8();
+            },
+            _ => match last_star {
+                // Let the last `*` swallow one more character and try again.
+                Some((star, star_n)) => {
+                    let star_n = star_n + name[star_n..].chars().next().map_or(0, char::len_utf8);
+                    last_star = Some((star, star_n));
+                    p = star + 1;
+                    n = star_n;
+                },
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].chars().all(|c| c == '*')
+}
+
+/// A [dynamic receiver] or [table receiver] which only forwards notifications about entries whose names match the pattern.
+///
+/// To filter the notifications for a single entry, attach it to the entry with [`DynAdapter`].
+///
+/// # Example
+/// ```
+/// # use snec::{TableReceiver, dispatch::{Pattern, PatternReceiver}};
+/// use std::cell::Cell;
+/// let render_changed = Cell::new(false);
+/// let mut receiver = PatternReceiver::new(Pattern::new("render.*"), &render_changed);
+/// receiver.receive_change("audio.volume");
+/// assert!(!render_changed.get());
+/// receiver.receive_change("render.vsync");
+/// assert!(render_changed.get());
+/// ```
+///
+/// [dynamic receiver]: ../trait.DynReceiver.html " "
+/// [table receiver]: ../trait.TableReceiver.html " "
+/// [`DynAdapter`]: ../struct.DynAdapter.html " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PatternReceiver<R, S = &'static str> {
+    /// The pattern which the names of entries must match.
+    pub pattern: Pattern<S>,
+    /// The receiver which is notified about matching entries.
+    pub receiver: R,
+}
+impl<R, S: AsRef<str>> PatternReceiver<R, S> {
+    /// Creates a receiver forwarding notifications about entries matching the pattern to the specified receiver.
+    #[inline(always)]
+    pub const fn new(pattern: Pattern<S>, receiver: R) -> Self {
+        Self {pattern, receiver}
+    }
+}
+impl<R: DynReceiver, S: AsRef<str>> DynReceiver for PatternReceiver<R, S> {
+    #[inline]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        if self.pattern.matches(entry_name) {
+            self.receiver.receive_dyn(entry_name, new_value);
+        }
+    }
+}
+impl<R: TableReceiver, S: AsRef<str>> TableReceiver for PatternReceiver<R, S> {
+    #[inline]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        if self.pattern.matches(entry_name) {
+            self.receiver.receive_change(entry_name);
+        }
+    }
+}
+
+/// A [dynamic receiver] which holds subscriptions made of a [`Pattern`] and a dynamic receiver, notifying every subscriber whose pattern matches the name of the changed entry, in the order of subscription.
+///
+/// Closures taking the name of the entry and the new value can be subscribed with [`subscribe_fn`]. See the [module-level documentation] for an example.
+///
+/// [dynamic receiver]: ../trait.DynReceiver.html " "
+/// [`Pattern`]: struct.Pattern.html " "
+/// [`subscribe_fn`]: #method.subscribe_fn " "
+/// [module-level documentation]: index.html " "
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+pub struct DynDispatcher {
+    subscriptions: Vec<PatternReceiver<Box<dyn DynReceiver>, String>>,
+}
+#[cfg(feature = "alloc")]
+impl DynDispatcher {
+    /// Creates a dispatcher without any subscriptions.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {subscriptions: Vec::new()}
+    }
+    /// Subscribes the receiver to all entries whose names match the pattern.
+    #[inline]
+    pub fn subscribe(&mut self, pattern: impl Into<String>, receiver: impl DynReceiver + 'static) {
+        self.subscriptions.push(
+            PatternReceiver::new(Pattern::new(pattern.into()), Box::new(receiver)),
+        );
+    }
+    /// Subscribes a closure, which is called with the name of the entry and the new value, to all entries whose names match the pattern.
+    #[inline]
+    pub fn subscribe_fn<F>(&mut self, pattern: impl Into<String>, closure: F)
+    where F: FnMut(&'static str, &dyn Any) + 'static {
+        self.subscribe(pattern, DynFnReceiver(closure))
+    }
+    /// Removes all subscriptions made with exactly the specified pattern, returning the number of removed subscriptions.
+    pub fn unsubscribe(&mut self, pattern: &str) -> usize {
+        let old_len = self.subscriptions.len();
+        self.subscriptions.retain(|subscription| subscription.pattern.as_str() != pattern);
+        old_len - self.subscriptions.len()
+    }
+    /// Returns `true` if any subscriber would be notified about the entry with the specified name.
+    #[inline]
+    pub fn is_watched(&self, entry_name: &str) -> bool {
+        self.subscriptions.iter().any(|subscription| subscription.pattern.matches(entry_name))
+    }
+    /// Returns the number of subscriptions.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+    /// Returns `true` if there are no subscriptions.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+}
+#[cfg(feature = "alloc")]
+impl DynReceiver for DynDispatcher {
+    #[inline]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        for subscription in &mut self.subscriptions {
+            subscription.receive_dyn(entry_name, new_value);
+        }
+    }
+}
+#[cfg(feature = "alloc")]
+impl fmt::Debug for DynDispatcher {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.subscriptions.iter().map(|subscription| subscription.pattern.as_str()))
+            .finish()
+    }
+}
+
+/// Adapts a closure to `DynReceiver`. Closures can't implement it directly, since that would overlap with the implementation for `&mut R`.
+#[cfg(feature = "alloc")]
+struct DynFnReceiver<F>(F);
+#[cfg(feature = "alloc")]
+impl<F> DynReceiver for DynFnReceiver<F>
+where F: FnMut(&'static str, &dyn Any) {
+    #[inline(always)]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        (self.0)(entry_name, new_value)
+    }
+}
+
+/// A shared event bus which decouples the receivers of entries from the config table: the bus is attached to the entries as their [receiver], and any part of the program holding a clone of it can subscribe to changes, either of a specific entry type with [`subscribe_to`] or of all entries whose names match a [`Pattern`] with [`subscribe`].
+///
+/// Subscriptions last as long as the [`Subscription`] returned for them, or forever if it's [detached]. Subscribers are notified in the order of subscription, with the bus unlocked, so they can subscribe and unsubscribe while being notified. Subscribers to names get [`Redacted`] instead of the values of [secret] entries, while subscribers to specific entry types always get the actual values.
+///
+/// The bus is thread-safe, so subscribers have to be `Send`. A subscriber must not change the entry it's subscribed to, since it can't be notified while it's already being notified, which would deadlock.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, GetExt as _, dispatch::ConfigBus};
+/// use std::{any::Any, sync::{Arc, Mutex}};
+/// #[derive(ConfigTable)]
+/// #[snec(receiver({self.bus.clone()}: ConfigBus))]
+/// struct Config {
+///     #[snec(entry, name = "net.timeout_ms")]
+///     timeout_ms: u32,
+///     #[snec(entry, name = "net.proxy")]
+///     proxy: String,
+///     bus: ConfigBus,
+/// }
+/// let bus = ConfigBus::new();
+/// let mut config = Config {timeout_ms: 5000, proxy: String::new(), bus: bus.clone()};
+/// // Somewhere far away, such as in the networking module.
+/// let timeouts = Arc::new(Mutex::new(Vec::new()));
+/// let timeouts_in_subscriber = Arc::clone(&timeouts);
+/// let _timeout_subscription = bus.subscribe_to::<entries::TimeoutMs, _>(move |timeout: &u32| {
+///     timeouts_in_subscriber.lock().unwrap().push(*timeout);
+/// });
+/// let changed = Arc::new(Mutex::new(Vec::new()));
+/// let changed_in_subscriber = Arc::clone(&changed);
+/// let net_subscription = bus.subscribe_fn("net.*", move |entry_name: &'static str, _: &dyn Any| {
+///     changed_in_subscriber.lock().unwrap().push(entry_name);
+/// });
+/// config.get_handle_to::<entries::TimeoutMs>().set(1000);
+/// net_subscription.unsubscribe();
+/// config.get_handle_to::<entries::Proxy>().set("socks5://localhost".to_string());
+/// assert_eq!(*timeouts.lock().unwrap(), [1000]);
+/// assert_eq!(*changed.lock().unwrap(), ["net.timeout_ms"]);
+/// ```
+///
+/// # Panics
+/// Notifying the bus panics if a subscriber it notifies panicked during an earlier notification, like with any other receiver behind a poisoned `Mutex`. Such subscribers should be unsubscribed.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [`subscribe_to`]: #method.subscribe_to " "
+/// [`Pattern`]: struct.Pattern.html " "
+/// [`subscribe`]: #method.subscribe " "
+/// [`Subscription`]: struct.Subscription.html " "
+/// [detached]: struct.Subscription.html#method.detach " "
+/// [`Redacted`]: ../struct.Redacted.html " "
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+#[cfg(feature = "std")]
+#[derive(Clone, Default)]
+pub struct ConfigBus {
+    shared: Arc<Mutex<BusState>>,
+}
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct BusState {
+    next_id: u64,
+    subscribers: Vec<Subscriber>,
+}
+#[cfg(feature = "std")]
+struct Subscriber {
+    id: u64,
+    filter: Filter,
+    receiver: Arc<Mutex<dyn DynReceiver + Send>>,
+}
+#[cfg(feature = "std")]
+enum Filter {
+    Name(Pattern<String>),
+    Type(TypeId),
+}
+#[cfg(feature = "std")]
+impl ConfigBus {
+    /// Creates a bus without any subscriptions.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Subscribes the dynamic receiver to all entries whose names match the pattern.
+    #[inline]
+    pub fn subscribe(&self, pattern: impl Into<String>, receiver: impl DynReceiver + Send + 'static) -> Subscription {
+        self.add(Filter::Name(Pattern::new(pattern.into())), Arc::new(Mutex::new(receiver)))
+    }
+    /// Subscribes a closure, which is called with the name of the entry and the new value, to all entries whose names match the pattern.
+    #[inline]
+    pub fn subscribe_fn<F>(&self, pattern: impl Into<String>, closure: F) -> Subscription
+    where F: FnMut(&'static str, &dyn Any) + Send + 'static {
+        self.subscribe(pattern, DynFnReceiver(closure))
+    }
+    /// Subscribes a closure, which is called with the new value, to the specified entry type.
+    pub fn subscribe_to<E, F>(&self, mut closure: F) -> Subscription
+    where
+        E: Entry + 'static,
+        F: FnMut(&E::Data) + Send + 'static {
+        let receiver = DynFnReceiver(move |_: &'static str, new_value: &dyn Any| {
+            // Only called with the values of entries of type `E`.
+            if let Some(new_value) = new_value.downcast_ref::<E::Data>() {
+                closure(new_value);
+            }
+        });
+        self.add(Filter::Type(TypeId::of::<E>()), Arc::new(Mutex::new(receiver)))
+    }
+    /// Returns the number of subscriptions.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().subscribers.len()
+    }
+    /// Returns `true` if there are no subscriptions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lock().subscribers.is_empty()
+    }
+    fn add(&self, filter: Filter, receiver: Arc<Mutex<dyn DynReceiver + Send>>) -> Subscription {
+        let mut state = self.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.subscribers.push(Subscriber {id, filter, receiver});
+        Subscription {bus: Arc::downgrade(&self.shared), id}
+    }
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, BusState> {
+        lock_ignoring_poison(&self.shared)
+    }
+}
+#[cfg(feature = "std")]
+impl<E> Receiver<E> for ConfigBus
+where
+    E: Entry + 'static,
+    E::Data: Any {
+    fn receive(&mut self, new_value: &E::Data) {
+        let type_id = TypeId::of::<E>();
+        // Collected first, so that the subscribers are notified with the bus unlocked.
+        let matching = self.lock().subscribers.iter()
+            .filter_map(|subscriber| {
+                let by_type = match &subscriber.filter {
+                    Filter::Type(id) if *id == type_id => true,
+                    Filter::Name(pattern) if pattern.matches(E::NAME) => false,
+                    _ => return None,
+                };
+                Some((by_type, Arc::clone(&subscriber.receiver)))
+            })
+            .collect::<Vec<_>>();
+        for (by_type, receiver) in matching {
+            // A subscriber which changes an entry it's subscribed to would otherwise deadlock here.
+            let _notifying = Notifying::receiver(&*receiver, E::NAME);
+            let mut receiver = receiver.lock().expect(POISONING_MSG);
+            if E::SECRET && !by_type {
+                receiver.receive_dyn(E::NAME, &Redacted);
+            } else {
+                receiver.receive_dyn(E::NAME, new_value);
+            }
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl fmt::Debug for ConfigBus {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigBus")
+            .field("subscriptions", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A subscription to a [`ConfigBus`], which is cancelled when dropped.
+///
+/// [`ConfigBus`]: struct.ConfigBus.html " "
+#[cfg(feature = "std")]
+#[must_use = "the subscription is cancelled when dropped, use `detach` to keep it"]
+#[derive(Debug)]
+pub struct Subscription {
+    bus: Weak<Mutex<BusState>>,
+    id: u64,
+}
+#[cfg(feature = "std")]
+impl Subscription {
+    /// Cancels the subscription, which is the same as dropping it.
+    #[inline(always)]
+    pub fn unsubscribe(self) {}
+    /// Keeps the subscription for as long as the bus exists.
+    #[inline]
+    pub fn detach(mut self) {
+        self.bus = Weak::new();
+    }
+}
+#[cfg(feature = "std")]
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(bus) = self.bus.upgrade() {
+            lock_ignoring_poison(&bus).subscribers.retain(|subscriber| subscriber.id != self.id);
+        }
+    }
+}
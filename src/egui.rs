@@ -0,0 +1,302 @@
+//! Generating settings panels for config tables with [egui].
+//!
+//! [`ConfigEditor`] is a widget which lists every entry in the [registry] of a config table, in the order of the registry, with its [name] as the label, its [description] as the tooltip of the label, and a control for editing its value. Edits are checked with the [validator] of the entry and written through handles, so that the receivers of the entries are notified as usual; values which fail validation are not written, but stay in the control, with the validation error displayed next to it, until a valid value is entered. The values of [secret] entries are not displayed and can't be edited.
+//!
+//! The control for an entry is chosen by the type of its data through the [`EditValue`] trait: checkboxes for `bool`, drag values for numbers, which stay within the range of the type, and text fields for `String`. Numbers whose entries specify both bounds of their [UI range] get a slider instead, and the step of the range, if any, sets the increment of either control. Types with a fixed set of values, such as field-less enums, get a combo box by implementing [`Choices`], and other types can implement `EditValue` themselves. Types which can't be edited in a meaningful way can implement `EditValue` without any methods, which displays their values read-only using `Debug`, so that they don't prevent the rest of the table from being edited.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, egui::{ConfigEditor, Choices}};
+//! #[derive(Copy, Clone, Debug, PartialEq)]
+//! pub enum Quality {
+//!     Low,
+//!     High,
+//! }
+//! impl Choices for Quality {
+//!     const CHOICES: &'static [(Self, &'static str)] = &[(Self::Low, "Low"), (Self::High, "High")];
+//! }
+//! #[derive(ConfigTable)]
+//! struct Settings {
+//!     /// The master volume.
+//!     #[snec(entry, ui(min = 0.0, max = 1.0, step = 0.05))]
+//!     volume: f32,
+//!     #[snec]
+//!     vsync: bool,
+//!     #[snec]
+//!     quality: Quality,
+//!     #[snec]
+//!     player_name: String,
+//! }
+//! let mut settings = Settings {
+//!     volume: 0.8,
+//!     vsync: true,
+//!     quality: Quality::High,
+//!     player_name: "Player".to_string(),
+//! };
+//! let ctx = egui::Context::default();
+//! let _ = ctx.run(egui::RawInput::default(), |ctx| {
+//!     egui::CentralPanel::default().show(ctx, |ui| {
+//!         let response = ui.add(ConfigEditor::new(&mut settings));
+//!         assert!(!response.changed());
+//!     });
+//! });
+//! ```
+//!
+//! [egui]: https://docs.rs/egui " "
+//! [`ConfigEditor`]: struct.ConfigEditor.html " "
+//! [registry]: ../trait.ConfigTable.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [description]: ../trait.Entry.html#associatedconstant.DESCRIPTION " "
+//! [validator]: ../trait.Entry.html#method.validate " "
+//! [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [`EditValue`]: trait.EditValue.html " "
+//! [UI range]: ../trait.Entry.html#associatedconstant.UI_RANGE " "
+//! [`Choices`]: trait.Choices.html " "
+
+use core::fmt::{self, Formatter, Debug};
+use egui::{ComboBox, DragValue, Grid, Id, Response, Slider, Ui, Widget};
+use super::{Entry, EntryVisitor, Handle, Receiver, Redacted, UiRange, VisitEntries};
+
+/// Trait for types whose values can be edited with an egui control.
+///
+/// # Example
+/// A type which is displayed, but can't be edited:
+/// ```
+/// use snec::{ConfigTable, egui::{ConfigEditor, EditValue}};
+/// #[derive(Clone, Debug, PartialEq)]
+/// pub struct Certificate {
+///     fingerprint: String,
+/// }
+/// impl EditValue for Certificate {}
+/// #[derive(ConfigTable)]
+/// struct Settings {
+///     #[snec]
+///     port: u16,
+///     #[snec]
+///     certificate: Certificate,
+/// }
+/// let mut settings = Settings {port: 443, certificate: Certificate {fingerprint: "4f:2a".to_string()}};
+/// let ctx = egui::Context::default();
+/// let _ = ctx.run(egui::RawInput::default(), |ctx| {
+///     egui::CentralPanel::default().show(ctx, |ui| {
+///         ui.add(ConfigEditor::new(&mut settings));
+///     });
+/// });
+/// ```
+pub trait EditValue: Debug {
+    /// Adds a control editing the value to the UI, returning its response, which is marked as changed if the value was changed. The default implementation displays the value read-only as a label, using `Debug`.
+    #[inline]
+    fn edit(&mut self, ui: &mut Ui) -> Response {
+        ui.label(format!("{:?}", self))
+    }
+    /// Same as `edit`, but offers the values within the specified [UI range] of the entry. Ignores the range by default.
+    ///
+    /// [UI range]: ../struct.UiRange.html " "
+    #[inline]
+    fn edit_in_range(&mut self, ui: &mut Ui, range: &UiRange) -> Response {
+        let _ = range;
+        self.edit(ui)
+    }
+}
+impl EditValue for bool {
+    #[inline]
+    fn edit(&mut self, ui: &mut Ui) -> Response {
+        ui.checkbox(self, "")
+    }
+}
+impl EditValue for String {
+    #[inline]
+    fn edit(&mut self, ui: &mut Ui) -> Response {
+        ui.text_edit_singleline(self)
+    }
+}
+macro_rules! impl_edit_value_for_numbers {
+    ($($ty:ty),+ $(,)?) => ($(
+        impl EditValue for $ty {
+            #[inline]
+            fn edit(&mut self, ui: &mut Ui) -> Response {
+                ui.add(DragValue::new(self))
+            }
+            fn edit_in_range(&mut self, ui: &mut Ui, range: &UiRange) -> Response {
+                // Float-to-integer `as` casts saturate, so out-of-range bounds are clamped to the range of the type. The range is only a hint, so values outside of it, e.g. ones loaded from a file, are kept until they're edited instead of being clamped right away.
+                if let (Some(min), Some(max)) = (range.min, range.max) {
+                    let mut slider = Slider::new(self, min as $ty..=max as $ty).clamp_to_range(false);
+                    if let Some(step) = range.step {
+                        slider = slider.step_by(step);
+                    }
+                    return ui.add(slider);
+                }
+                let min = range.min.map_or(<$ty>::MIN, |min| min as $ty);
+                let max = range.max.map_or(<$ty>::MAX, |max| max as $ty);
+                let mut drag_value = DragValue::new(self).range(min..=max).clamp_to_range(false);
+                if let Some(step) = range.step {
+                    drag_value = drag_value.speed(step);
+                }
+                ui.add(drag_value)
+            }
+        }
+    )+);
+}
+impl_edit_value_for_numbers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+/// Trait for types with a fixed set of values, which are edited with a combo box.
+pub trait Choices: Clone + PartialEq + Debug + Sized + 'static {
+    /// The values of the type, along with their labels, in the order in which they are listed.
+    const CHOICES: &'static [(Self, &'static str)];
+}
+impl<T: Choices> EditValue for T {
+    fn edit(&mut self, ui: &mut Ui) -> Response {
+        let selected = T::CHOICES.iter()
+            .find(|(value, _)| value == self)
+            .map_or("", |(_, label)| label);
+        let mut changed = false;
+        let mut response = ComboBox::from_id_source(ui.next_auto_id())
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (value, label) in T::CHOICES {
+                    changed |= ui.selectable_value(self, value.clone(), *label).changed();
+                }
+            })
+            .response;
+        if changed {
+            response.mark_changed();
+        }
+        response
+    }
+}
+
+/// A widget editing every entry of a config table. See the [module-level documentation] for more.
+///
+/// The response of the widget is marked as changed if any entry was changed.
+///
+/// [module-level documentation]: index.html " "
+pub struct ConfigEditor<'t, T> {
+    table: &'t mut T,
+    id_source: Id,
+}
+impl<'t, T> ConfigEditor<'t, T> {
+    /// Creates a widget editing the specified table.
+    #[inline]
+    pub fn new(table: &'t mut T) -> Self {
+        Self {table, id_source: Id::new("snec_config_editor")}
+    }
+    /// Sets the source of the ID of the widget, which has to be unique when multiple config tables are edited in the same UI.
+    #[inline]
+    pub fn id_source(mut self, id_source: impl core::hash::Hash) -> Self {
+        self.id_source = Id::new(id_source);
+        self
+    }
+}
+impl<T> Widget for ConfigEditor<'_, T>
+where T: for<'u> VisitEntries<EntryEditor<'u>> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let table = self.table;
+        let id_source = self.id_source;
+        let inner = Grid::new(id_source)
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                let mut editor = EntryEditor::new(ui, id_source);
+                table.visit_entries(&mut editor);
+                editor.changed()
+            });
+        let mut response = inner.response;
+        if inner.inner > 0 {
+            response.mark_changed();
+        }
+        response
+    }
+}
+impl<T> Debug for ConfigEditor<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigEditor")
+            .field("id_source", &self.id_source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [visitor] which adds a row with the label and the control of every entry it visits to a grid, used by [`ConfigEditor`].
+///
+/// [visitor]: ../trait.EntryVisitor.html " "
+/// [`ConfigEditor`]: struct.ConfigEditor.html " "
+pub struct EntryEditor<'u> {
+    ui: &'u mut Ui,
+    id_source: Id,
+    changed: usize,
+}
+impl<'u> EntryEditor<'u> {
+    /// Creates a visitor adding rows to the specified UI, which should be laid out as a grid with two columns, storing the values which failed validation under IDs derived from the specified one.
+    #[inline(always)]
+    pub fn new(ui: &'u mut Ui, id_source: Id) -> Self {
+        Self {ui, id_source, changed: 0}
+    }
+    /// Returns the number of entries which were changed.
+    #[inline(always)]
+    pub fn changed(&self) -> usize {
+        self.changed
+    }
+}
+/// A value which failed validation, along with the validation error.
+type Pending<D> = (D, &'static str);
+impl<E> EntryVisitor<E> for EntryEditor<'_>
+where
+    E: Entry,
+    E::Data: EditValue + Clone + Send + Sync + 'static {
+    fn visit<R: Receiver<E>>(&mut self, mut handle: Handle<'_, E, R>) {
+        let label = self.ui.label(E::NAME);
+        if !E::DESCRIPTION.is_empty() {
+            label.on_hover_text(E::DESCRIPTION);
+        }
+        if E::SECRET {
+            self.ui.label(Redacted.to_string());
+            self.ui.end_row();
+            return;
+        }
+        let pending_id = self.id_source.with(E::NAME);
+        // A value which failed validation is kept until it's fixed, instead of being replaced with the current value on the next frame.
+        let pending = self.ui.data(|data| data.get_temp::<Pending<E::Data>>(pending_id));
+        let mut value = match &pending {
+            Some((value, _)) => value.clone(),
+            None => handle.get().clone(),
+        };
+        let changed = self.ui.horizontal(|ui| {
+            let mut changed = false;
+            let mut error = pending.map(|(_, error)| error);
+            if value.edit_in_range(ui, &E::UI_RANGE).changed() {
+                match E::validate(&value) {
+                    Ok(()) => {
+                        handle.set(value);
+                        changed = true;
+                        error = None;
+                        ui.data_mut(|data| data.remove::<Pending<E::Data>>(pending_id));
+                    },
+                    Err(new_error) => {
+                        error = Some(new_error);
+                        ui.data_mut(|data| data.insert_temp(pending_id, (value, new_error)));
+                    },
+                }
+            }
+            if !E::UNIT.is_empty() {
+                ui.label(E::UNIT);
+            }
+            if let Some(error) = error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+            changed
+        }).inner;
+        if changed {
+            self.changed += 1;
+        }
+        self.ui.end_row();
+    }
+}
+impl Debug for EntryEditor<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntryEditor")
+            .field("id_source", &self.id_source)
+            .field("changed", &self.changed)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,4 +1,7 @@
-use super::{Receiver, Handle};
+use super::{
+    Receiver, Handle, UiRange, __private,
+    split::{GetHandles, HandleTuple, MultiModificationScope, ValidatedEntries, ValidatedModificationScope},
+};
 
 /// Trait for type-level identifiers for config entries.
 ///
@@ -19,6 +22,156 @@ pub trait Entry: Sized {
     type Data;
     /// The textual representation of the name of the entry. Should follow the same naming convention as struct fields and variables, i.e. `snake_case`.
     const NAME: &'static str;
+    /// A human-readable description of the entry, intended to be displayed by tooling such as generated settings menus. Empty by default.
+    ///
+    /// `#[derive(ConfigTable)]` fills this with the documentation comments on the field for which the entry was generated.
+    const DESCRIPTION: &'static str = "";
+    /// The type of the data as written in the source code, such as `Vec<String>`, intended to be displayed by tooling. Empty by default.
+    ///
+    /// `#[derive(ConfigTable)]` fills this with the type of the field for which the entry was generated.
+    const TYPE_NAME: &'static str = "";
+    /// The unit of measurement of the value, such as `ms`, `dB` or `px`, intended to be displayed next to the value by tooling. Empty by default, meaning that the value has no unit.
+    ///
+    /// `#[derive(ConfigTable)]` sets this from a `#[snec(unit = "...")]` attribute on the field. The unit is also listed in the [registry] of the config table, so exporters don't need a separate table of units.
+    ///
+    /// # Example
+    /// ```
+    /// use snec::{ConfigTable, Entry};
+    /// #[derive(ConfigTable)]
+    /// struct AudioConfig {
+    ///     #[snec(entry, unit = "dB")]
+    ///     gain: f32,
+    ///     #[snec(entry, unit = "ms")]
+    ///     latency: u32,
+    ///     #[snec]
+    ///     device: String,
+    /// }
+    /// assert_eq!(entries::Gain::UNIT, "dB");
+    /// let units = AudioConfig::ENTRIES.iter().map(|info| info.unit).collect::<Vec<_>>();
+    /// assert_eq!(units, ["dB", "ms", ""]);
+    /// ```
+    ///
+    /// [registry]: trait.ConfigTable.html#associatedconstant.ENTRIES " "
+    const UNIT: &'static str = "";
+    /// The range of values which user interfaces should offer for the entry, such as the bounds and the step of a slider. Unbounded by default.
+    ///
+    /// Unlike the [validator], this doesn't restrict which values the entry can have. `#[derive(ConfigTable)]` sets this from a `#[snec(ui(min = ..., max = ..., step = ...))]` attribute on the field, in which any of the three can be omitted. See [`UiRange`] for an example.
+    ///
+    /// [validator]: #method.validate " "
+    /// [`UiRange`]: struct.UiRange.html " "
+    const UI_RANGE: UiRange = UiRange::UNBOUNDED;
+    /// Whether the value of the entry is secret, such as a password or an API key. `false` by default.
+    ///
+    /// The values of secret entries are replaced with [`Redacted`] in `Debug` output of handles, in [dumps] of config tables and in the exports of their registries, and are withheld from [dynamic receivers] unless the [`DynAdapter`] is explicitly allowed to reveal them. `#[derive(ConfigTable)]` sets this to `true` for fields with a `#[snec(secret)]` attribute.
+    ///
+    /// # Example
+    /// ```
+    /// use snec::{ConfigTable, GetExt as _};
+    /// #[derive(ConfigTable)]
+    /// struct MyConfigTable {
+    ///     #[snec]
+    ///     user: String,
+    ///     #[snec(entry, secret)]
+    ///     api_key: String,
+    /// }
+    /// let mut table = MyConfigTable {user: "admin".to_string(), api_key: "hunter2".to_string()};
+    /// assert_eq!(table.dump().to_string(), "user = \"admin\"\napi_key = <redacted>\n");
+    /// let handle = table.get_handle_to::<entries::ApiKey>();
+    /// assert!(!format!("{:?}", handle).contains("hunter2"));
+    /// ```
+    ///
+    /// [`Redacted`]: struct.Redacted.html " "
+    /// [dumps]: trait.ConfigTable.html#method.dump " "
+    /// [dynamic receivers]: trait.DynReceiver.html " "
+    /// [`DynAdapter`]: struct.DynAdapter.html " "
+    const SECRET: bool = false;
+    /// Erases a value of the entry which is about to be overwritten or dropped, such as by zeroing the memory it occupies. Does nothing by default.
+    ///
+    /// Handles call this on the old value before overwriting it with [`set`] and similar methods, but not when the old value is returned to the caller, as with [`replace`]. With the `zeroize` feature, `#[snec(secret(zeroize))]` implements this using [`Zeroize`] and additionally implements `Drop` for the config table, erasing the field when the table is dropped. Because of that, the config table can't have its own `Drop` implementation.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "zeroize")] {
+    /// use snec::{ConfigTable, GetExt as _};
+    /// #[derive(ConfigTable)]
+    /// struct MyConfigTable {
+    ///     #[snec(entry, secret(zeroize))]
+    ///     api_key: String,
+    /// }
+    /// let mut table = MyConfigTable {api_key: "hunter2".to_string()};
+    /// // The memory of the old key is zeroed before the new key is moved in.
+    /// table.get_handle_to::<entries::ApiKey>().set("correct horse battery staple".to_string());
+    /// # }
+    /// ```
+    ///
+    /// [`set`]: struct.Handle.html#method.set " "
+    /// [`replace`]: struct.Handle.html#method.replace " "
+    /// [`Zeroize`]: https://docs.rs/zeroize/1/zeroize/trait.Zeroize.html " "
+    #[inline(always)]
+    fn erase(_value: &mut Self::Data) {}
+    /// Returns the default value of the entry, or `None` if the entry doesn't have one, which is the default.
+    ///
+    /// `#[derive(ConfigTable)]` implements this for fields with a `#[snec(default = ...)]` attribute. Defaults are used by [`Handle::reset`] and [`ConfigTable::reset_all`].
+    ///
+    /// [`Handle::reset`]: struct.Handle.html#method.reset " "
+    /// [`ConfigTable::reset_all`]: trait.ConfigTable.html#method.reset_all " "
+    #[inline(always)]
+    fn default_value() -> Option<Self::Data> {
+        None
+    }
+    /// Checks whether the specified value is allowed for the entry, returning an error message if it isn't. All values are allowed by default.
+    ///
+    /// `#[derive(ConfigTable)]` implements this for fields with a `#[snec(validate = path)]` attribute, by calling the function at `path` with a reference to the value. Validators are run by the builders generated by `#[snec(builder)]`.
+    #[inline(always)]
+    fn validate(_value: &Self::Data) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
+
+/// Trait for entries whose data contains the data of another entry, allowing handles to be [projected] onto it.
+///
+/// `Sub` is an entry identifying a part of the data of `Self`, such as a field of a struct. Modifying it through a projection notifies the receiver of `Self` with the whole value, which allows deeply structured entries to be modified one part at a time without writing closures or replacing the entire value.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, Project, FnReceiver};
+/// struct ListenAddress {
+///     host: String,
+///     port: u16,
+/// }
+/// enum Listen {}
+/// impl Entry for Listen {
+///     type Data = ListenAddress;
+///     const NAME: &'static str = "listen";
+/// }
+/// enum Port {}
+/// impl Entry for Port {
+///     type Data = u16;
+///     const NAME: &'static str = "port";
+/// }
+/// impl Project<Port> for Listen {
+///     fn project(data: &ListenAddress) -> &u16 {
+///         &data.port
+///     }
+///     fn project_mut(data: &mut ListenAddress) -> &mut u16 {
+///         &mut data.port
+///     }
+/// }
+/// let mut listen = ListenAddress {host: "localhost".to_string(), port: 8080};
+/// let mut handle = Handle::<Listen, _>::new(
+///     &mut listen,
+///     FnReceiver::new(|new_value: &ListenAddress| println!("Listening on port {}", new_value.port)),
+/// );
+/// *handle.project::<Port>() = 8081;
+/// assert_eq!(handle.get().port, 8081);
+/// ```
+///
+/// [projected]: struct.Handle.html#method.project " "
+pub trait Project<Sub: Entry>: Entry {
+    /// Returns a reference to the part of the data identified by `Sub`.
+    fn project(data: &Self::Data) -> &Sub::Data;
+    /// Returns a mutable reference to the part of the data identified by `Sub`.
+    fn project_mut(data: &mut Self::Data) -> &mut Sub::Data;
 }
 
 /// Trait for getting handles to fields in config tables.
@@ -35,8 +188,94 @@ pub trait Get<E: Entry> {
     ///
     /// [`Handle`]: struct.Handle.html " "
     fn get_handle(&mut self) -> Handle<'_, E, Self::Receiver>;
+    /// Returns a mutable reference to the field without creating its receiver. Only callable by Snec itself, which uses it to build handles with other receivers and to write values which are checked before anyone is notified.
+    #[doc(hidden)]
+    fn get_mut(&mut self, token: __private::Token) -> &mut E::Data;
 }
 
+/// Trait for config tables with invariants spanning several entries, such as a minimum which must not exceed a maximum, which can't be checked by the receivers of the individual entries.
+///
+/// `#[derive(ConfigTable)]` implements this trait for every table. With `#[snec(validate_table = path)]`, it calls the function at `path` with a reference to the table. The function must return `Result<(), &'static str>`, or `Result<(), ErrorType>` if the error type is specified as `#[snec(validate_table = path: ErrorType)]`. Without it, every state of the table is valid and the error type is `Infallible`.
+///
+/// The following changes are checked after the new values are written, and undone without notifying any receivers if the table is no longer valid:
+/// - ones made with [`GetExt::set_validated`] or committed from a [`ValidatedModificationScope`]
+/// - [patches] applied with [`ApplyPatch`]
+/// - values written by a [`Loader`], which includes all of the modules loading tables from files, environment variables, databases, snapshots and remote stores
+///
+/// Changes made through handles, such as with [`Handle::set`] or [`Handle::modify`], including the ones made through a [`MultiModificationScope`] or by [resetting] entries, are **not** validated, since handles only have access to their own field.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, GetExt as _};
+/// #[derive(ConfigTable)]
+/// #[snec(validate_table = check_range)]
+/// struct MyConfigTable {
+///     #[snec]
+///     min_threads: u32,
+///     #[snec]
+///     max_threads: u32,
+/// }
+/// fn check_range(table: &MyConfigTable) -> Result<(), &'static str> {
+///     if table.min_threads <= table.max_threads {
+///         Ok(())
+///     } else {
+///         Err("`min_threads` must not exceed `max_threads`")
+///     }
+/// }
+/// let mut table = MyConfigTable {min_threads: 1, max_threads: 4};
+/// assert!(table.set_validated::<entries::MaxThreads>(8).is_ok());
+/// assert!(table.set_validated::<entries::MinThreads>(16).is_err());
+/// assert_eq!(table.min_threads, 1);
+///
+/// // Raising both at once only works if they're changed together.
+/// let mut scope = table.modify_together_validated::<(entries::MinThreads, entries::MaxThreads)>();
+/// let (min_threads, max_threads) = scope.values();
+/// *min_threads = 16;
+/// *max_threads = 32;
+/// assert!(scope.commit().is_ok());
+/// assert_eq!((table.min_threads, table.max_threads), (16, 32));
+/// ```
+/// Loading values which break the invariants leaves the table as it was:
+/// ```
+/// # #[cfg(feature = "toml")] {
+/// use snec::{ConfigTable, LoadError};
+/// #[derive(ConfigTable)]
+/// #[snec(validate_table = check_range)]
+/// struct MyConfigTable {
+///     #[snec]
+///     min_threads: u32,
+///     #[snec]
+///     max_threads: u32,
+/// }
+/// fn check_range(table: &MyConfigTable) -> Result<(), &'static str> {
+///     if table.min_threads <= table.max_threads {
+///         Ok(())
+///     } else {
+///         Err("`min_threads` must not exceed `max_threads`")
+///     }
+/// }
+/// let mut table = MyConfigTable {min_threads: 1, max_threads: 4};
+/// let result = snec::toml::load_into(&mut table, "min_threads = 8");
+/// assert!(matches!(result, Err(LoadError::InvalidTable(..))));
+/// assert_eq!(table.min_threads, 1);
+/// # }
+/// ```
+///
+/// [`GetExt::set_validated`]: trait.GetExt.html#method.set_validated " "
+/// [`ValidatedModificationScope`]: split/struct.ValidatedModificationScope.html " "
+/// [patches]: patch/trait.Patch.html " "
+/// [`ApplyPatch`]: patch/trait.ApplyPatch.html " "
+/// [`Loader`]: struct.Loader.html " "
+/// [`Handle::set`]: struct.Handle.html#method.set " "
+/// [`Handle::modify`]: struct.Handle.html#method.modify " "
+/// [`MultiModificationScope`]: split/struct.MultiModificationScope.html " "
+/// [resetting]: trait.ConfigTable.html#method.reset_all " "
+pub trait ValidateTable {
+    /// The error produced when an invariant doesn't hold.
+    type Error;
+    /// Checks whether the invariants of the table hold, returning an error if they don't.
+    fn validate_table(&self) -> Result<(), Self::Error>;
+}
 /// A convenience trait for using turbofish syntax to get handles to fields in config tables.
 ///
 /// Using only [`Get`], getting handles to fields is inconvenient when there is no inference to help you, forcing you to use fully qualified trait call syntax. With `GetExt`, this becomes much easier:
@@ -70,5 +309,86 @@ pub trait GetExt {
     where Self: Get<E> {
         <Self as Get<E>>::get_handle(self)
     }
+    /// Returns a [`Handle`] to the field which notifies the specified receiver instead of the one chosen by the config table, for call-sites which need to observe one specific modification.
+    ///
+    /// The receiver of the config table is not created, so any side effects of creating it don't happen, and it is not notified about changes made through the returned handle, which is why this requires the `silent` feature. To notify both, use [`Handle::also_notify`] on a handle from [`get_handle_to`] instead.
+    ///
+    /// # Example
+    /// ```
+    /// use snec::{ConfigTable, FnReceiver, GetExt as _};
+    /// use std::cell::Cell;
+    /// #[derive(ConfigTable)]
+    /// struct MyConfigTable {
+    ///     #[snec]
+    ///     which_year: i64,
+    /// }
+    /// let mut table = MyConfigTable {which_year: 1987};
+    /// let observed = Cell::new(None);
+    /// table.get_handle_to_with::<entries::WhichYear, _>(
+    ///     FnReceiver::new(|new_value: &i64| observed.set(Some(*new_value))),
+    /// ).set(1983);
+    /// assert_eq!(observed.get(), Some(1983));
+    /// ```
+    ///
+    /// [`Handle`]: struct.Handle.html " "
+    /// [`Handle::also_notify`]: struct.Handle.html#method.also_notify " "
+    /// [`get_handle_to`]: #method.get_handle_to " "
+    #[cfg(feature = "silent")]
+    #[inline]
+    fn get_handle_to_with<E: Entry, R: Receiver<E>>(&mut self, receiver: R) -> Handle<'_, E, R>
+    where Self: Get<E> {
+        Handle::new(<Self as Get<E>>::get_mut(self, __private::Token::new()), receiver)
+    }
+    /// Sets the field to the specified value and [validates the table], notifying the receiver if the table is still valid. Otherwise, the previous value is restored without notifying the receiver and the error is returned. The receiver is only created if the change is accepted, and the value which is discarded, either the previous or the rejected one, is [erased].
+    ///
+    /// [validates the table]: trait.ValidateTable.html " "
+    /// [erased]: trait.Entry.html#method.erase " "
+    #[inline]
+    fn set_validated<E: Entry>(&mut self, new_value: E::Data) -> Result<(), <Self as ValidateTable>::Error>
+    where Self: Get<E> + ValidateTable {
+        let target = <Self as Get<E>>::get_mut(self, __private::Token::new());
+        let mut old_value = core::mem::replace(target, new_value);
+        match self.validate_table() {
+            Ok(()) => {
+                E::erase(&mut old_value);
+                <Self as Get<E>>::get_handle(self).notify();
+                Ok(())
+            },
+            Err(error) => {
+                let target = <Self as Get<E>>::get_mut(self, __private::Token::new());
+                let mut rejected_value = core::mem::replace(target, old_value);
+                E::erase(&mut rejected_value);
+                Err(error)
+            },
+        }
+    }
+    /// Returns a tuple of [`Handle`]s to several different fields at once. See the [`split`] module for more.
+    ///
+    /// [`Handle`]: struct.Handle.html " "
+    /// [`split`]: split/index.html " "
+    #[inline(always)]
+    fn get_handles_to<'a, Es>(&'a mut self) -> <Self as GetHandles<'a, Es>>::Handles
+    where Self: GetHandles<'a, Es> {
+        <Self as GetHandles<'a, Es>>::get_handles(self)
+    }
+    /// Returns a [`MultiModificationScope`] for modifying several different fields together, notifying each of their receivers exactly once after all of them were modified.
+    ///
+    /// [`MultiModificationScope`]: split/struct.MultiModificationScope.html " "
+    #[inline(always)]
+    fn modify_together<'a, Es>(&'a mut self) -> MultiModificationScope<<Self as GetHandles<'a, Es>>::Handles>
+    where
+        Self: GetHandles<'a, Es>,
+        <Self as GetHandles<'a, Es>>::Handles: HandleTuple {
+        MultiModificationScope::new(self.get_handles_to::<Es>())
+    }
+    /// Returns a [`ValidatedModificationScope`] for modifying several different fields together and [validating the table] once all of them were modified, notifying each of their receivers exactly once if the table is still valid.
+    ///
+    /// [`ValidatedModificationScope`]: split/struct.ValidatedModificationScope.html " "
+    /// [validating the table]: trait.ValidateTable.html " "
+    #[inline(always)]
+    fn modify_together_validated<Es>(&mut self) -> ValidatedModificationScope<'_, Self, Es>
+    where Es: ValidatedEntries<Self> {
+        ValidatedModificationScope::new(self)
+    }
 }
 impl<T: ?Sized> GetExt for T {}
\ No newline at end of file
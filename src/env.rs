@@ -0,0 +1,156 @@
+//! Overriding config table entries with environment variables.
+//!
+//! Every entry of a config table is mapped to an environment variable whose name consists of a prefix, an underscore and the [name] of the entry in uppercase, with any characters which are not allowed in environment variable names replaced with underscores. For example, with the `MYAPP` prefix, the `which_year` entry is read from the `MYAPP_WHICH_YEAR` variable. The values are parsed using [`FromStr`] and written into the table through handles, notifying the receivers of the entries whose values changed.
+//!
+//! # Example
+//! ```
+//! use snec::ConfigTable;
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     which_year: i64,
+//!     #[snec]
+//!     who: String,
+//! }
+//! let mut table = MyConfigTable {which_year: 1987, who: "Jeremy".to_string()};
+//! std::env::set_var("MYAPP_WHICH_YEAR", "1983");
+//! let applied = snec::env::load_into(&mut table, "MYAPP").unwrap();
+//! assert_eq!(applied, 1);
+//! assert_eq!(table.which_year, 1983);
+//! assert_eq!(table.who, "Jeremy");
+//! ```
+//!
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`FromStr`]: https://doc.rust-lang.org/std/str/trait.FromStr.html " "
+
+use core::{
+    fmt::{self, Formatter, Display},
+    str::FromStr,
+};
+use std::{
+    env::{self, VarError},
+    error,
+    ffi::OsString,
+};
+use super::{Entry, Loader, LoadError, Lookup, LookupEntry, VisitEntries, ValidateTable};
+
+/// Reads environment variables with the specified prefix and writes their values into the corresponding entries of the config table, notifying the receivers of the entries which changed. Returns the number of entries which changed.
+///
+/// Entries without a variable keep their values. A variable which isn't valid Unicode or fails to parse is reported along with its name. The table is left unchanged if an error is returned, including one produced by the [table validator]. See the [module-level documentation] for the details on how variable names are formed.
+///
+/// [table validator]: ../trait.ValidateTable.html " "
+/// [module-level documentation]: index.html " "
+#[inline]
+pub fn load_into<T>(table: &mut T, prefix: &str) -> Result<usize, LoadError<Error, T::Error>>
+where T: VisitEntries<EnvLoader> + ValidateTable {
+    Loader::new(EnvVariables::new(prefix)).load_into(table)
+}
+
+/// Returns the name of the environment variable from which the entry with the specified name is read when using the specified prefix. See the [module-level documentation] for the details.
+///
+/// [module-level documentation]: index.html " "
+pub fn variable_name(prefix: &str, entry_name: &str) -> String {
+    let mut result = String::with_capacity(prefix.len() + 1 + entry_name.len());
+    if !prefix.is_empty() {
+        result.push_str(prefix);
+        result.push('_');
+    }
+    result.extend(
+        entry_name.chars().map(|x| {
+            if x.is_ascii_alphanumeric() {
+                x.to_ascii_uppercase()
+            } else {'_'}
+        })
+    );
+    result
+}
+
+/// The [loader] used by [`load_into`], which writes the values of [`EnvVariables`] into a config table.
+///
+/// [loader]: ../struct.Loader.html " "
+/// [`load_into`]: fn.load_into.html " "
+/// [`EnvVariables`]: struct.EnvVariables.html " "
+pub type EnvLoader = Loader<EnvVariables>;
+
+/// The environment variables with a specific prefix, which [look up] the values of entries by parsing the variables named after them.
+///
+/// [look up]: ../trait.LookupEntry.html " "
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EnvVariables {
+    prefix: String,
+}
+impl EnvVariables {
+    /// Uses the variables with the specified prefix. If the prefix is empty, the variable names consist only of the entry names.
+    #[inline]
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {prefix: prefix.into()}
+    }
+    /// Returns the name of the environment variable from which the entry with the specified name is read.
+    #[inline(always)]
+    pub fn variable_name(&self, entry_name: &str) -> String {
+        variable_name(&self.prefix, entry_name)
+    }
+}
+impl Lookup for EnvVariables {
+    type Error = Error;
+}
+impl<E> LookupEntry<E> for EnvVariables
+where
+    E: Entry,
+    E::Data: FromStr,
+    <E::Data as FromStr>::Err: Display {
+    fn lookup(&mut self) -> Option<Result<E::Data, Error>> {
+        let variable = self.variable_name(E::NAME);
+        let kind = match env::var(&variable) {
+            Ok(value) => match value.parse() {
+                Ok(value) => return Some(Ok(value)),
+                Err(error) => ErrorKind::Parse(error.to_string()),
+            },
+            Err(VarError::NotPresent) => return None,
+            Err(VarError::NotUnicode(value)) => ErrorKind::NotUnicode(value),
+        };
+        Some(
+            Err(Error {variable, entry: E::NAME, kind})
+        )
+    }
+}
+
+/// An error which occurred while reading an entry from an environment variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    /// The name of the variable which could not be read.
+    pub variable: String,
+    /// The name of the entry which was being read.
+    pub entry: &'static str,
+    /// What exactly went wrong.
+    pub kind: ErrorKind,
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ErrorKind::NotUnicode(..) => write!(
+                f,
+                "environment variable `{}` for entry `{}` is not valid Unicode",
+                self.variable, self.entry,
+            ),
+            ErrorKind::Parse(message) => write!(
+                f,
+                "could not parse environment variable `{}` for entry `{}`: {}",
+                self.variable, self.entry, message,
+            ),
+        }
+    }
+}
+impl error::Error for Error {}
+
+/// The kind of an [`Error`].
+///
+/// [`Error`]: struct.Error.html " "
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The variable contains a value which is not valid Unicode.
+    NotUnicode(OsString),
+    /// The value of the variable could not be parsed. Contains the error message produced by the `FromStr` implementation.
+    Parse(String),
+}
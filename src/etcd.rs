@@ -0,0 +1,191 @@
+//! Synchronizing config tables with [etcd].
+//!
+//! Every entry is stored under a key made of a common prefix followed by the [name] of the entry, with the value serialized as JSON, which keeps the values readable with `etcdctl`. [`EtcdSource`] and [`EtcdSink`] are the [remote backends] for etcd: the source reads all keys under the prefix and [watches] them for remote changes, beginning right after the revision which was read, so that no changes are missed, and the sink writes the values of entries with `put` requests. See the documentation of the [`remote`] module for how the synchronization works.
+//!
+//! The client is asynchronous and requires a Tokio runtime.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, remote::RemoteReceiver};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.remote.clone()}: RemoteReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     max_connections: u32,
+//!     #[snec]
+//!     log_level: String,
+//!     remote: RemoteReceiver,
+//! }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = etcd_client::Client::connect(["localhost:2379"], None).await?;
+//! let (mut sync, writer) = snec::etcd::sync(client, "/services/my-service/");
+//! let mut table = MyConfigTable {
+//!     max_connections: 100,
+//!     log_level: "info".to_string(),
+//!     remote: sync.receiver(),
+//! };
+//! sync.load_into(&mut table).await?;
+//! // Usually spawned as a separate task.
+//! let writer = writer.run();
+//! while let Some(changed) = sync.apply_next(&mut table).await? {
+//!     println!("{} entries changed remotely", changed);
+//! }
+//! # drop(writer);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [etcd]: https://etcd.io " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`EtcdSource`]: struct.EtcdSource.html " "
+//! [`EtcdSink`]: struct.EtcdSink.html " "
+//! [remote backends]: ../remote/trait.Source.html " "
+//! [watches]: https://etcd.io/docs/latest/learning/api/#watch-api " "
+//! [`remote`]: ../remote/index.html " "
+
+use core::fmt::{self, Formatter, Debug};
+use etcd_client::{Client, EventType, GetOptions, WatchOptions, WatchStream, Watcher};
+use super::remote::{self, BoxFuture, Values, RemoteSync, RemoteWriter, Source, Sink};
+
+/// Synchronizes config tables with etcd.
+pub type EtcdSync = RemoteSync<EtcdSource>;
+/// Writes local changes to etcd.
+pub type EtcdWriter = RemoteWriter<EtcdSink>;
+/// An error which occurred while synchronizing with etcd.
+pub type Error = remote::Error<etcd_client::Error>;
+
+/// Creates a synchronizer for the entries stored under the specified prefix, along with the writer which writes local changes to etcd.
+#[inline]
+pub fn sync(client: Client, prefix: impl Into<String>) -> (EtcdSync, EtcdWriter) {
+    let prefix = prefix.into();
+    let sink = EtcdSink {client: client.clone(), prefix: prefix.clone()};
+    RemoteSync::new(EtcdSource::new(client, prefix), sink)
+}
+
+/// A [source] which reads the values of entries from the keys under a prefix in etcd and watches them for changes.
+///
+/// [source]: ../remote/trait.Source.html " "
+pub struct EtcdSource {
+    client: Client,
+    prefix: String,
+    revision: Option<i64>,
+    watch: Option<(Watcher, WatchStream)>,
+}
+impl EtcdSource {
+    /// Creates a source for the entries stored under the specified prefix.
+    #[inline]
+    pub fn new(client: Client, prefix: impl Into<String>) -> Self {
+        Self {client, prefix: prefix.into(), revision: None, watch: None}
+    }
+    /// Returns the prefix of the keys of the entries.
+    #[inline(always)]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    /// Returns the revision of etcd which was last read, if any.
+    #[inline(always)]
+    pub fn revision(&self) -> Option<i64> {
+        self.revision
+    }
+}
+impl Source for EtcdSource {
+    type Error = etcd_client::Error;
+    fn load(&mut self) -> BoxFuture<'_, Result<Values, Self::Error>> {
+        Box::pin(async move {
+            let response = self.client
+                .get(self.prefix.as_str(), Some(GetOptions::new().with_prefix()))
+                .await?;
+            if let Some(header) = response.header() {
+                self.revision = Some(header.revision());
+            }
+            Ok(
+                response.kvs().iter()
+                    .filter_map(|kv| Some((entry_name(&self.prefix, kv.key())?, kv.value().to_vec())))
+                    .collect()
+            )
+        })
+    }
+    fn next_changes(&mut self) -> BoxFuture<'_, Result<Option<Values>, Self::Error>> {
+        Box::pin(async move {
+            let stream = match &mut self.watch {
+                Some((_, stream)) => stream,
+                None => {
+                    let mut options = WatchOptions::new().with_prefix();
+                    if let Some(revision) = self.revision {
+                        options = options.with_start_revision(revision + 1);
+                    }
+                    let watch = self.client.watch(self.prefix.as_str(), Some(options)).await?;
+                    &mut self.watch.insert(watch).1
+                },
+            };
+            let response = match stream.message().await? {
+                Some(response) => response,
+                None => {
+                    self.watch = None;
+                    return Ok(None);
+                },
+            };
+            if let Some(header) = response.header() {
+                self.revision = Some(header.revision());
+            }
+            Ok(
+                Some(
+                    response.events().iter()
+                        .filter(|event| event.event_type() == EventType::Put)
+                        .filter_map(|event| event.kv())
+                        .filter_map(|kv| Some((entry_name(&self.prefix, kv.key())?, kv.value().to_vec())))
+                        .collect()
+                )
+            )
+        })
+    }
+}
+impl Debug for EtcdSource {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EtcdSource")
+            .field("prefix", &self.prefix)
+            .field("revision", &self.revision)
+            .field("watching", &self.watch.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [sink] which writes the values of entries to the keys under a prefix in etcd.
+///
+/// [sink]: ../remote/trait.Sink.html " "
+#[derive(Clone)]
+pub struct EtcdSink {
+    client: Client,
+    prefix: String,
+}
+impl EtcdSink {
+    /// Creates a sink for the entries stored under the specified prefix.
+    #[inline]
+    pub fn new(client: Client, prefix: impl Into<String>) -> Self {
+        Self {client, prefix: prefix.into()}
+    }
+}
+impl Sink for EtcdSink {
+    type Error = etcd_client::Error;
+    fn store(&mut self, entry_name: &'static str, value: Vec<u8>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            self.client.put(format!("{}{}", self.prefix, entry_name), value, None).await?;
+            Ok(())
+        })
+    }
+}
+impl Debug for EtcdSink {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EtcdSink")
+            .field("prefix", &self.prefix)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Strips the prefix from a key, returning `None` for keys which aren't valid UTF-8 or don't start with the prefix.
+fn entry_name(prefix: &str, key: &[u8]) -> Option<String> {
+    let key = std::str::from_utf8(key).ok()?;
+    key.strip_prefix(prefix).map(str::to_string)
+}
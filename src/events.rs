@@ -0,0 +1,278 @@
+//! Emitting changes of entries as structured, serializable events.
+//!
+//! A [`ChangeEvent`] describes a single change: the [name] of the entry, its new value as JSON, the time of the change and, if it's known, the previous value. Events implement `Serialize` and `Deserialize`, and serialize as JSON objects such as `{"entry":"volume","value":80,"old_value":50,"timestamp_ms":1700000000000}`, which makes them a common interchange format for consumers in other processes or languages.
+//!
+//! [`EventReceiver`] is a [receiver] which turns the changes of entries into events and emits them to an [`EventSink`]. Sinks are provided for writers, which receive one event per line with [`JsonLines`], for the senders of standard channels and for vectors. The receiver remembers the last value it has seen for every entry, so the old value is included in every event except for the first one of each entry. The values of [secret] entries are emitted as `"<redacted>"`.
+//!
+//! Clones of the receiver share the sink, so that it can be used as the receiver of every entry of a config table. Since receivers can't fail, errors produced by the sink while handling plain notifications are stored until they're retrieved with [`take_error`], while [`Handle::try_set`] returns them right away.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, events::{EventReceiver, JsonLines}};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.events.clone()}: EventReceiver<JsonLines<Vec<u8>>>))]
+//! struct Config {
+//!     #[snec]
+//!     volume: u8,
+//!     events: EventReceiver<JsonLines<Vec<u8>>>,
+//! }
+//! let events = EventReceiver::new(JsonLines::new(Vec::new()));
+//! let mut config = Config {volume: 50, events: events.clone()};
+//! config.get_handle_to::<entries::Volume>().set(80);
+//! config.get_handle_to::<entries::Volume>().set(30);
+//! let output = events.with_sink(|sink| String::from_utf8(sink.get_ref().clone()))?;
+//! let lines = output.lines().collect::<Vec<_>>();
+//! assert!(lines[0].starts_with(r#"{"entry":"volume","value":80,"old_value":null,"timestamp_ms":"#));
+//! assert!(lines[1].starts_with(r#"{"entry":"volume","value":30,"old_value":80,"timestamp_ms":"#));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`ChangeEvent`]: struct.ChangeEvent.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`EventReceiver`]: struct.EventReceiver.html " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`EventSink`]: trait.EventSink.html " "
+//! [`JsonLines`]: struct.JsonLines.html " "
+//! [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [`take_error`]: struct.EventReceiver.html#method.take_error " "
+//! [`Handle::try_set`]: ../struct.Handle.html#method.try_set " "
+
+use core::{
+    convert::{Infallible, TryFrom},
+    fmt::{self, Formatter, Display, Debug},
+    time::Duration,
+};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error,
+    io::{self, Write},
+    sync::{mpsc, Arc, Mutex, MutexGuard},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use super::{Entry, Receiver, TryReceiver, lock_ignoring_poison};
+
+/// A change of an entry. See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// The [name] of the entry which changed.
+    ///
+    /// [name]: ../trait.Entry.html#associatedconstant.NAME " "
+    pub entry: Cow<'static, str>,
+    /// The new value of the entry.
+    pub value: Value,
+    /// The previous value of the entry, or `None` if it's not known.
+    #[serde(default)]
+    pub old_value: Option<Value>,
+    /// The time of the change, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+}
+impl ChangeEvent {
+    /// Creates an event for a change of the entry with the specified name which happened at the specified time.
+    pub fn new(entry: impl Into<Cow<'static, str>>, value: Value, old_value: Option<Value>, time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let timestamp_ms = u64::try_from(since_epoch.as_millis()).unwrap_or(u64::MAX);
+        Self {entry: entry.into(), value, old_value, timestamp_ms}
+    }
+    /// Returns the time of the change.
+    #[inline]
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.timestamp_ms)
+    }
+}
+
+/// Trait for destinations of [change events].
+///
+/// [change events]: struct.ChangeEvent.html " "
+pub trait EventSink {
+    /// The error produced when the event can't be emitted.
+    type Error;
+    /// Emits the event.
+    fn emit(&mut self, event: ChangeEvent) -> Result<(), Self::Error>;
+}
+impl EventSink for Vec<ChangeEvent> {
+    type Error = Infallible;
+    #[inline]
+    fn emit(&mut self, event: ChangeEvent) -> Result<(), Self::Error> {
+        self.push(event);
+        Ok(())
+    }
+}
+impl EventSink for mpsc::Sender<ChangeEvent> {
+    type Error = mpsc::SendError<ChangeEvent>;
+    #[inline]
+    fn emit(&mut self, event: ChangeEvent) -> Result<(), Self::Error> {
+        self.send(event)
+    }
+}
+impl EventSink for mpsc::SyncSender<ChangeEvent> {
+    type Error = mpsc::SendError<ChangeEvent>;
+    /// Blocks while the channel is full.
+    #[inline]
+    fn emit(&mut self, event: ChangeEvent) -> Result<(), Self::Error> {
+        self.send(event)
+    }
+}
+
+/// An [event sink] which writes events to a writer as JSON, one per line, flushing the writer after every event.
+///
+/// [event sink]: trait.EventSink.html " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JsonLines<W> {
+    writer: W,
+}
+impl<W: Write> JsonLines<W> {
+    /// Creates a sink writing to the specified writer.
+    #[inline(always)]
+    pub fn new(writer: W) -> Self {
+        Self {writer}
+    }
+    /// Returns a reference to the writer.
+    #[inline(always)]
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+    /// Returns a mutable reference to the writer.
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+    /// Returns the writer.
+    #[inline(always)]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+impl<W: Write> EventSink for JsonLines<W> {
+    type Error = io::Error;
+    fn emit(&mut self, event: ChangeEvent) -> Result<(), Self::Error> {
+        // Serialized into a buffer first, so that a failing writer never receives half of a line.
+        let mut line = serde_json::to_vec(&event)?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        self.writer.flush()
+    }
+}
+
+/// A [receiver] which emits [change events] to an [event sink]. Clones share the sink. See the [module-level documentation] for more.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [change events]: struct.ChangeEvent.html " "
+/// [event sink]: trait.EventSink.html " "
+/// [module-level documentation]: index.html " "
+pub struct EventReceiver<S: EventSink> {
+    state: Arc<Mutex<State<S>>>,
+}
+struct State<S: EventSink> {
+    sink: S,
+    /// The last values seen for the entries, by their names.
+    last: HashMap<&'static str, Value>,
+    error: Option<Error<S::Error>>,
+}
+impl<S: EventSink> EventReceiver<S> {
+    /// Creates a receiver emitting events to the specified sink.
+    #[inline]
+    pub fn new(sink: S) -> Self {
+        Self {state: Arc::new(Mutex::new(State {sink, last: HashMap::new(), error: None}))}
+    }
+    /// Calls the specified closure with a mutable reference to the sink, returning its result.
+    #[inline]
+    pub fn with_sink<T>(&self, f: impl FnOnce(&mut S) -> T) -> T {
+        f(&mut self.lock().sink)
+    }
+    /// Takes the error which occurred while emitting an event for a plain notification, if any. Only the first error is kept until it's taken.
+    #[inline]
+    pub fn take_error(&self) -> Option<Error<S::Error>> {
+        self.lock().error.take()
+    }
+    /// Emits an event for the change of the entry to the specified value, remembering the value as the old one for the next event.
+    fn emit<E>(&self, new_value: &E::Data) -> Result<(), Error<S::Error>>
+    where
+        E: Entry,
+        E::Data: Serialize {
+        let value = if E::SECRET {
+            Value::from("<redacted>")
+        } else {
+            serde_json::to_value(new_value).map_err(|inner| Error::Serialize {entry: E::NAME, inner})?
+        };
+        let mut state = self.lock();
+        let old_value = state.last.insert(E::NAME, value.clone());
+        state.sink.emit(ChangeEvent::new(E::NAME, value, old_value, SystemTime::now())).map_err(Error::Sink)
+    }
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State<S>> {
+        lock_ignoring_poison(&self.state)
+    }
+}
+impl<E, S> Receiver<E> for EventReceiver<S>
+where
+    E: Entry,
+    E::Data: Serialize,
+    S: EventSink {
+    fn receive(&mut self, new_value: &E::Data) {
+        if let Err(error) = self.emit::<E>(new_value) {
+            self.lock().error.get_or_insert(error);
+        }
+    }
+}
+impl<E, S> TryReceiver<E> for EventReceiver<S>
+where
+    E: Entry,
+    E::Data: Serialize,
+    S: EventSink {
+    type Error = Error<S::Error>;
+    #[inline]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.emit::<E>(new_value)
+    }
+}
+impl<S: EventSink> Clone for EventReceiver<S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {state: Arc::clone(&self.state)}
+    }
+}
+impl<S: EventSink + Debug> Debug for EventReceiver<S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventReceiver")
+            .field("sink", &self.lock().sink)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An error which occurred while emitting a change event.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The new value of the entry could not be serialized.
+    Serialize {
+        /// The name of the entry.
+        entry: &'static str,
+        /// The error produced by the JSON serializer.
+        inner: serde_json::Error,
+    },
+    /// The sink failed to emit the event.
+    Sink(E),
+}
+impl<E: Display> Display for Error<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Serialize {entry, inner} => write!(f, "could not serialize value of entry `{}`: {}", entry, inner),
+            Self::Sink(error) => write!(f, "could not emit change event: {}", error),
+        }
+    }
+}
+impl<E: error::Error + 'static> error::Error for Error<E> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Serialize {inner, ..} => Some(inner),
+            Self::Sink(error) => Some(error),
+        }
+    }
+}
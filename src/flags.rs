@@ -0,0 +1,228 @@
+//! Driving config tables with remote feature flag services.
+//!
+//! Services in the style of LaunchDarkly and Unleash are integrated by implementing [`FlagProvider`], which fetches the current values of all flags as JSON values by their keys, and optionally pushes updates as they happen. [`FlagSource`] turns a provider into a [remote source], mapping the keys of the flags to the [names] of the entries they drive, and polling providers which don't push updates at a regular interval. The interval is timed with [`futures-timer`], which doesn't depend on the executor, so the source works with any runtime. The rest is done by the [`remote`] module: the source is passed to [`sync`], and the returned synchronizer writes the values of the flags into the entries of a config table through handles, so that the receivers of the entries are notified when a flag changes.
+//!
+//! Flags are read-only: local changes of the entries are never sent to the service, and are overwritten when their flags change. Flags whose values can't be converted to the types of their entries stop the synchronization with an [`Error::Value`], like any other invalid remote value.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, flags::{FlagProvider, FlagSource, Flags}, remote::BoxFuture};
+//! use std::time::Duration;
+//! // Usually a client of a feature flag service.
+//! struct FixedFlags(Flags);
+//! impl FlagProvider for FixedFlags {
+//!     type Error = std::convert::Infallible;
+//!     fn fetch(&mut self) -> BoxFuture<'_, Result<Flags, Self::Error>> {
+//!         let flags = self.0.clone();
+//!         Box::pin(async move { Ok(flags) })
+//!     }
+//! }
+//! #[derive(ConfigTable)]
+//! struct Features {
+//!     #[snec]
+//!     new_checkout: bool,
+//!     #[snec]
+//!     max_cart_items: u32,
+//! }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut flags = Flags::new();
+//! flags.insert("new-checkout-flow".to_string(), true.into());
+//! flags.insert("max_cart_items".to_string(), 50.into());
+//! let source = FlagSource::new(FixedFlags(flags))
+//!     .map("new-checkout-flow", "new_checkout")
+//!     .with_interval(Duration::from_secs(60));
+//! let mut sync = snec::flags::sync(source);
+//! let mut table = Features {new_checkout: false, max_cart_items: 20};
+//! sync.load_into(&mut table).await?;
+//! assert!(table.new_checkout);
+//! while let Some(changed) = sync.apply_next(&mut table).await? {
+//!     println!("{} flags changed", changed);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`FlagProvider`]: trait.FlagProvider.html " "
+//! [`FlagSource`]: struct.FlagSource.html " "
+//! [`futures-timer`]: https://docs.rs/futures-timer " "
+//! [remote source]: ../remote/trait.Source.html " "
+//! [names]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`remote`]: ../remote/index.html " "
+//! [`sync`]: fn.sync.html " "
+//! [`Error::Value`]: ../remote/enum.Error.html#variant.Value " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    time::Duration,
+};
+use std::collections::HashMap;
+use serde_json::Value;
+use futures_timer::Delay;
+use super::remote::{self, BoxFuture, Values, RemoteSync, Source};
+
+/// The values of feature flags, by their keys.
+pub type Flags = HashMap<String, Value>;
+/// Synchronizes config tables with a feature flag service.
+pub type FlagSync<P> = RemoteSync<FlagSource<P>>;
+/// An error which occurred while synchronizing with a feature flag service.
+pub type Error<E> = remote::Error<E>;
+
+/// Creates a read-only synchronizer for the flags of the specified source.
+#[inline]
+pub fn sync<P: FlagProvider + Send>(source: FlagSource<P>) -> FlagSync<P> {
+    RemoteSync::read_only(source)
+}
+
+/// Trait for clients of feature flag services.
+pub trait FlagProvider {
+    /// The error produced when the flags can't be retrieved.
+    type Error;
+    /// Fetches the current values of all flags.
+    fn fetch(&mut self) -> BoxFuture<'_, Result<Flags, Self::Error>>;
+    /// Returns a future which waits for the service to push updated flags and resolves to their new values, or `None` if the service doesn't push updates, in which case the flags are polled with [`fetch`] instead. The default implementation returns `None`.
+    ///
+    /// [`fetch`]: #tymethod.fetch " "
+    #[inline(always)]
+    fn next_update(&mut self) -> Option<BoxFuture<'_, Result<Flags, Self::Error>>> {
+        None
+    }
+}
+
+/// A [source] which reads the values of entries from the flags of a [`FlagProvider`]. See the [module-level documentation] for more.
+///
+/// [source]: ../remote/trait.Source.html " "
+/// [`FlagProvider`]: trait.FlagProvider.html " "
+/// [module-level documentation]: index.html " "
+pub struct FlagSource<P> {
+    provider: P,
+    mapping: HashMap<String, String>,
+    interval: Duration,
+    last: Flags,
+}
+impl<P: FlagProvider> FlagSource<P> {
+    /// The default interval between polls of providers which don't push updates.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+    /// Creates a source for the flags of the specified provider, initially driving the entries named the same as the keys of the flags.
+    #[inline]
+    pub fn new(provider: P) -> Self {
+        Self {provider, mapping: HashMap::new(), interval: Self::DEFAULT_INTERVAL, last: Flags::new()}
+    }
+    /// Makes the flag with the specified key drive the entry with the specified name.
+    #[inline]
+    pub fn map(mut self, key: impl Into<String>, entry_name: impl Into<String>) -> Self {
+        self.mapping.insert(key.into(), entry_name.into());
+        self
+    }
+    /// Sets the interval between polls of the provider if it doesn't push updates.
+    ///
+    /// # Example
+    /// Polling a provider whose flags change on every fetch, on smol and on async-std:
+    /// ```
+    /// use snec::{ConfigTable, flags::{FlagProvider, FlagSource, Flags}, remote::BoxFuture};
+    /// use std::time::{Duration, Instant};
+    /// struct Counter(u32);
+    /// impl FlagProvider for Counter {
+    ///     type Error = std::convert::Infallible;
+    ///     fn fetch(&mut self) -> BoxFuture<'_, Result<Flags, Self::Error>> {
+    ///         self.0 += 1;
+    ///         let mut flags = Flags::new();
+    ///         flags.insert("max_cart_items".to_string(), (self.0 * 10).into());
+    ///         Box::pin(async move { Ok(flags) })
+    ///     }
+    /// }
+    /// #[derive(ConfigTable)]
+    /// struct Features {
+    ///     #[snec]
+    ///     max_cart_items: u32,
+    /// }
+    /// async fn poll_once() -> u32 {
+    ///     let source = FlagSource::new(Counter(0)).with_interval(Duration::from_millis(20));
+    ///     let mut sync = snec::flags::sync(source);
+    ///     let mut table = Features {max_cart_items: 0};
+    ///     sync.load_into(&mut table).await.unwrap();
+    ///     assert_eq!(table.max_cart_items, 10);
+    ///     let started = Instant::now();
+    ///     assert_eq!(sync.apply_next(&mut table).await.unwrap(), Some(1));
+    ///     assert!(started.elapsed() >= Duration::from_millis(20));
+    ///     table.max_cart_items
+    /// }
+    /// assert_eq!(smol::block_on(poll_once()), 20);
+    /// assert_eq!(async_std::task::block_on(poll_once()), 20);
+    /// ```
+    #[inline]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+    /// Returns a reference to the provider.
+    #[inline(always)]
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+    /// Returns a mutable reference to the provider.
+    #[inline(always)]
+    pub fn provider_mut(&mut self) -> &mut P {
+        &mut self.provider
+    }
+    /// Returns the values of the flags which were last received.
+    #[inline(always)]
+    pub fn flags(&self) -> &Flags {
+        &self.last
+    }
+    /// Remembers the received flags, returning the values of the entries driven by the flags which changed.
+    fn changes(&mut self, flags: Flags) -> Values {
+        let mut values = Values::new();
+        for (key, value) in flags {
+            if self.last.get(&key) == Some(&value) {
+                continue;
+            }
+            let entry_name = self.mapping.get(&key).cloned().unwrap_or_else(|| key.clone());
+            // Serializing a JSON value can't fail.
+            values.insert(entry_name, serde_json::to_vec(&value).unwrap_or_default());
+            self.last.insert(key, value);
+        }
+        values
+    }
+}
+impl<P> Source for FlagSource<P>
+where P: FlagProvider + Send {
+    type Error = P::Error;
+    fn load(&mut self) -> BoxFuture<'_, Result<Values, Self::Error>> {
+        Box::pin(async move {
+            let flags = self.provider.fetch().await?;
+            self.last.clear();
+            Ok(self.changes(flags))
+        })
+    }
+    fn next_changes(&mut self) -> BoxFuture<'_, Result<Option<Values>, Self::Error>> {
+        Box::pin(async move {
+            loop {
+                let pushed = match self.provider.next_update() {
+                    Some(update) => Some(update.await?),
+                    None => None,
+                };
+                let flags = match pushed {
+                    Some(flags) => flags,
+                    None => {
+                        Delay::new(self.interval).await;
+                        self.provider.fetch().await?
+                    },
+                };
+                let values = self.changes(flags);
+                if !values.is_empty() {
+                    return Ok(Some(values));
+                }
+            }
+        })
+    }
+}
+impl<P: Debug> Debug for FlagSource<P> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlagSource")
+            .field("provider", &self.provider)
+            .field("mapping", &self.mapping)
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,126 @@
+//! Sending changes of entries over [`flume`] channels.
+//!
+//! With the `flume` feature enabled, `flume::Sender<E::Data>` is a [receiver] for the entry `E`, sending a clone of every new value over the channel, so that the thread owning the other end of the channel can react to the changes. The sender implements both [`Receiver`] and [`TryReceiver`]: if the channel is bounded and full, a plain change blocks until there's room, while [`Handle::try_set`] reports the full channel as an error, giving the value back. Values sent after the receiving end is dropped are discarded by `Receiver` and reported as an error by `TryReceiver`.
+//!
+//! A slow consumer of a bounded channel either blocks changes or makes them fail. [`conflating`] creates a channel which never does either: its [`Conflating`] sender replaces the value which hasn't been picked up yet with the new one, so that the consumer lags behind by at most one value and always sees the latest one.
+//!
+//! # Example
+//! ```
+//! # use snec::{Entry, Handle};
+//! # use std::thread;
+//! enum Threads {}
+//! impl Entry for Threads {
+//!     type Data = usize;
+//!     const NAME: &'static str = "threads";
+//! }
+//! let (sender, receiver) = flume::unbounded();
+//! let pool = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+//! let mut threads = 4;
+//! let mut handle = Handle::<Threads, _>::new(&mut threads, sender);
+//! handle.set(8);
+//! handle.set(16);
+//! // Dropping the handle drops the sender, which ends the iteration.
+//! drop(handle);
+//! assert_eq!(pool.join().unwrap(), [8, 16]);
+//! ```
+//!
+//! [`flume`]: https://docs.rs/flume " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`Receiver`]: ../trait.Receiver.html " "
+//! [`TryReceiver`]: ../trait.TryReceiver.html " "
+//! [`conflating`]: fn.conflating.html " "
+//! [`Conflating`]: struct.Conflating.html " "
+//! [`Handle::try_set`]: ../struct.Handle.html#method.try_set " "
+
+use core::fmt::{self, Formatter, Debug};
+use ::flume::{Sender, TrySendError};
+use super::{Entry, Receiver, TryReceiver};
+
+/// Blocks while the channel is full.
+impl<E> Receiver<E> for Sender<E::Data>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        // Sending fails only if the receiving side was dropped, in which case nobody is interested in the change.
+        let _ = self.send(new_value.clone());
+    }
+}
+/// Fails if the channel is full or the receiving side was dropped, giving the value back.
+impl<E> TryReceiver<E> for Sender<E::Data>
+where
+    E: Entry,
+    E::Data: Clone {
+    type Error = TrySendError<E::Data>;
+    #[inline]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.try_send(new_value.clone())
+    }
+}
+
+/// Creates a channel for the values of the entry `E` which only keeps the latest value, returning its [`Conflating`] sender, to be used as the receiver of the entry, and its receiving side.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle};
+/// enum Brightness {}
+/// impl Entry for Brightness {
+///     type Data = u8;
+///     const NAME: &'static str = "brightness";
+/// }
+/// let (sender, receiver) = snec::flume::conflating::<Brightness>();
+/// let mut brightness = 50;
+/// let mut handle = Handle::<Brightness, _>::new(&mut brightness, sender);
+/// handle.set(60);
+/// handle.set(70);
+/// // Neither change blocked, and the display only has to apply the latest one.
+/// handle.set(80);
+/// assert_eq!(receiver.try_recv(), Ok(80));
+/// assert!(receiver.try_recv().is_err());
+/// ```
+///
+/// [`Conflating`]: struct.Conflating.html " "
+pub fn conflating<E: Entry>() -> (Conflating<E>, ::flume::Receiver<E::Data>) {
+    let (sender, receiver) = ::flume::bounded(1);
+    (Conflating {sender, stale: receiver.clone()}, receiver)
+}
+
+/// The sending side of a channel created by [`conflating`], which replaces the value the consumer hasn't picked up yet instead of blocking or failing. See the [module-level documentation] for more.
+///
+/// The sender keeps a receiving side of its own to take the stale value out, so the channel stays connected for as long as the sender exists, even after the consumer drops its side.
+///
+/// [`conflating`]: fn.conflating.html " "
+/// [module-level documentation]: index.html " "
+pub struct Conflating<E: Entry> {
+    sender: Sender<E::Data>,
+    stale: ::flume::Receiver<E::Data>,
+}
+impl<E> Receiver<E> for Conflating<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    fn receive(&mut self, new_value: &E::Data) {
+        let mut value = new_value.clone();
+        // Loops only if another clone of the sender fills the channel between taking out the stale value and retrying.
+        while let Err(TrySendError::Full(rejected)) = self.sender.try_send(value) {
+            // Fails if the consumer picked the value up in the meantime, which frees up the room just as well.
+            let _ = self.stale.try_recv();
+            value = rejected;
+        }
+    }
+}
+impl<E: Entry> Clone for Conflating<E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {sender: self.sender.clone(), stale: self.stale.clone()}
+    }
+}
+impl<E: Entry> Debug for Conflating<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Conflating")
+            .field("entry", &E::NAME)
+            .finish_non_exhaustive()
+    }
+}
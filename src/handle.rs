@@ -2,8 +2,22 @@ use core::{
     fmt::{self, Formatter, Debug},
     ops::{Deref, DerefMut, Drop},
     marker::PhantomData,
+    mem,
+};
+use super::{
+    Entry,
+    Project,
+    Receiver,
+    TryReceiver,
+    PreReceiver,
+    TableReceiver,
+    BorrowedReceiver,
+    DependentReceiver,
+    WithTableReceiver,
+    Redacted,
+    Reentrancy,
+    reentrancy::{self, Notifying},
 };
-use super::{Entry, Receiver};
 
 /// A handle to a config entry value which is being watched by a receiver.
 ///
@@ -15,22 +29,243 @@ pub struct Handle<'a, E: Entry, R: Receiver<E>> {
 }
 impl<'a, E: Entry, R: Receiver<E>> Handle<'a, E, R> {
     /// Creates a handle pointing to the specified value and with the specified receiver.
-    // FIXME make it a const fn when non-Sized bounds in const fn arguments get stabilized
-    #[inline(always)]
+    ///
+    /// # Panics
+    /// In builds with debug assertions and the `std` feature, if the receiver of the entry is being notified about a change to the value on the current thread, since the handle would then alias the one which is notifying it. Use [`try_new`] to handle that case without panicking. See [`Reentrancy`] for more.
+    ///
+    /// [`try_new`]: #method.try_new " "
+    /// [`Reentrancy`]: struct.Reentrancy.html " "
+    #[inline]
+    #[track_caller]
     pub fn new(target: &'a mut E::Data, receiver: R) -> Self {
-        Self {target, receiver, _phantom: PhantomData}
+        match Self::try_new(target, receiver) {
+            Ok(handle) => handle,
+            Err(error) => panic!("{}", error),
+        }
+    }
+    /// Creates a handle pointing to the specified value and with the specified receiver, failing if the receiver of the entry is being notified about a change to the value on the current thread. The check is only performed in builds with debug assertions and the `std` feature. See [`Reentrancy`] for more.
+    ///
+    /// [`Reentrancy`]: struct.Reentrancy.html " "
+    #[inline]
+    pub fn try_new(target: &'a mut E::Data, receiver: R) -> Result<Self, Reentrancy> {
+        reentrancy::check_entry::<E>(target)?;
+        Ok(Self {target, receiver, _phantom: PhantomData})
+    }
+}
+impl<'a, E: Entry, R: Receiver<E>> Handle<'a, E, BorrowedReceiver<R>> {
+    /// Creates a handle pointing to the specified value and notifying a receiver which is borrowed for as long as the handle exists. This is what `#[snec(receiver_field = ...)]` uses to notify a receiver stored in a field of the config table.
+    #[inline(always)]
+    pub fn with_borrowed_receiver(target: &'a mut E::Data, receiver: &'a mut R) -> Self {
+        // SAFETY: the receiver is borrowed for 'a, and the handle never gives it out.
+        let receiver = unsafe { BorrowedReceiver::new(receiver) };
+        Self::new(target, receiver)
+    }
+}
+impl<'a, E, R, T> Handle<'a, E, WithTableReceiver<BorrowedReceiver<R>, T>>
+where
+    E: Entry,
+    R: Receiver<E>,
+    T: TableReceiver {
+    /// Same as [`with_borrowed_receiver`], but also notifies a [table receiver].
+    ///
+    /// [`with_borrowed_receiver`]: #method.with_borrowed_receiver " "
+    /// [table receiver]: trait.TableReceiver.html " "
+    #[inline(always)]
+    pub fn with_borrowed_receiver_and_table_receiver(
+        target: &'a mut E::Data,
+        receiver: &'a mut R,
+        table_receiver: T,
+    ) -> Self {
+        // SAFETY: same as in `with_borrowed_receiver`.
+        let receiver = unsafe { BorrowedReceiver::new(receiver) };
+        Self::new(target, WithTableReceiver::new(receiver, table_receiver))
+    }
+}
+impl<'a, E: Entry, R: Receiver<E>> Handle<'a, E, R> {
+    /// Replaces the receiver of the handle with the specified one, returning a handle to the same value. The previous receiver is dropped without being notified, which is why this requires the `silent` feature.
+    #[cfg(feature = "silent")]
+    #[inline(always)]
+    pub fn with_receiver<R2>(self, receiver: R2) -> Handle<'a, E, R2>
+    where R2: Receiver<E> {
+        Handle {target: self.target, receiver, _phantom: PhantomData}
+    }
+    /// Adds a receiver which is notified after the current one, returning a handle to the same value which notifies both.
+    ///
+    /// # Example
+    /// ```
+    /// use snec::{ConfigTable, Receiver, FnReceiver, GetExt as _};
+    /// use std::cell::Cell;
+    /// #[derive(ConfigTable)]
+    /// #[snec(receiver({Logger}: Logger))]
+    /// struct MyConfigTable {
+    ///     #[snec]
+    ///     which_year: i64,
+    /// }
+    /// struct Logger;
+    /// impl Receiver<entries::WhichYear> for Logger {
+    ///     fn receive(&mut self, new_value: &i64) {
+    ///         println!("Rescheduled to {}", new_value);
+    ///     }
+    /// }
+    /// let mut table = MyConfigTable {which_year: 1987};
+    /// let observed = Cell::new(None);
+    /// table.get_handle_to::<entries::WhichYear>()
+    ///     .also_notify(FnReceiver::new(|new_value: &i64| observed.set(Some(*new_value))))
+    ///     .set(1983);
+    /// assert_eq!(observed.get(), Some(1983));
+    /// ```
+    #[inline(always)]
+    pub fn also_notify<R2>(self, receiver: R2) -> Handle<'a, E, (R, R2)>
+    where R2: Receiver<E> {
+        Handle {target: self.target, receiver: (self.receiver, receiver), _phantom: PhantomData}
+    }
+    /// Makes the handle also notify the receiver of the entry `D` whenever it notifies its own receiver, passing it the current value of `D`, which is borrowed for as long as the handle exists. This is what `#[snec(invalidates(...))]` uses to notify the receivers of entries which depend on the value of this one.
+    #[inline(always)]
+    pub fn invalidating<D, RD>(
+        self,
+        value: &'a D::Data,
+        receiver: RD,
+    ) -> Handle<'a, E, (R, DependentReceiver<D, RD>)>
+    where
+        D: Entry,
+        RD: Receiver<D> {
+        // SAFETY: the value is borrowed for 'a, and the handle never gives the receiver out.
+        let dependent = unsafe { DependentReceiver::new(value, receiver) };
+        self.also_notify(dependent)
+    }
+    /// Returns an immutable reference to the handle's pointee.
+    #[inline(always)]
+    pub fn get(&self) -> &E::Data {
+        self.target
     }
 
-    /// Sets the handle's pointee to the specified value, notifying the receiver.
+    /// Sets the handle's pointee to the specified value, notifying the receiver. If the receiver [rejects] the value, it's dropped without changing the pointee or notifying anyone.
     ///
     /// For large values where partial modification using a mutable reference would improve performance (`Vec` is a good example of such a type), [`modify`] or [`modify_with`] should be used instead.
     ///
     /// [`modify`]: #method.modify " "
     /// [`modify_with`]: #method.modify_with " "
+    /// [rejects]: trait.Receiver.html#method.accepts " "
     #[inline]
     pub fn set(&mut self, new_value: E::Data) {
+        if self.accepts(&new_value) {
+            self.write(new_value);
+        }
+    }
+    /// Consults the [pre-receiver] about the specified value and, if it accepts the change, sets the handle's pointee to the value and notifies the receiver. If the change is rejected, the value is left untouched and the error is returned.
+    ///
+    /// [pre-receiver]: trait.PreReceiver.html " "
+    #[inline]
+    pub fn set_checked(&mut self, new_value: E::Data) -> Result<(), <R as PreReceiver<E>>::Error>
+    where R: PreReceiver<E> {
+        {
+            let _notifying = Notifying::entry::<E>(self.target);
+            self.receiver.pre_receive(self.target, &new_value)?;
+        }
+        self.write(new_value);
+        Ok(())
+    }
+    /// Sets the handle's pointee to the specified value, notifying the [fallible receiver] and returning the error it produced, if any. The value stays changed even if the receiver fails. Like with [`set`], a value [rejected] by the receiver is dropped without changing the pointee.
+    ///
+    /// [fallible receiver]: trait.TryReceiver.html " "
+    /// [`set`]: #method.set " "
+    /// [rejected]: trait.Receiver.html#method.accepts " "
+    #[inline]
+    pub fn try_set(&mut self, new_value: E::Data) -> Result<(), R::Error>
+    where R: TryReceiver<E> {
+        if !self.accepts(&new_value) {
+            return Ok(());
+        }
+        E::erase(self.target);
         *self.target = new_value;
-        self.receiver.receive(self.target);
+        let _notifying = Notifying::entry::<E>(self.target);
+        self.receiver.try_receive(self.target)
+    }
+    /// Sets the handle's pointee to the specified value and notifies the receiver, but only if the new value differs from the current one and the receiver [accepts] it. Returns `true` if the value changed, `false` otherwise.
+    ///
+    /// [accepts]: trait.Receiver.html#method.accepts " "
+    #[inline]
+    pub fn update(&mut self, new_value: E::Data) -> bool
+    where E::Data: PartialEq {
+        if *self.target == new_value || !self.accepts(&new_value) {
+            false
+        } else {
+            self.write(new_value);
+            true
+        }
+    }
+    /// Sets the handle's pointee to the new value and notifies the receiver, but only if the current value equals the expected one, which is usually the value the caller based the new value on, and the receiver [accepts] the new value. Otherwise, the value is left untouched and the [conflict] is returned, holding the current value and giving back the rejected one.
+    ///
+    /// This is optimistic concurrency control: an editor which reads a value, lets the user change it and then writes it back with `compare_and_set` doesn't silently overwrite changes made by another editor in the meantime.
+    ///
+    /// # Example
+    /// ```
+    /// use snec::{ConfigTable, GetExt as _};
+    /// #[derive(ConfigTable)]
+    /// struct MyConfigTable {
+    ///     #[snec]
+    ///     motd: String,
+    /// }
+    /// let mut table = MyConfigTable {motd: "Hello".to_string()};
+    /// // Two admins load the form at the same time.
+    /// let seen_by_alice = table.motd.clone();
+    /// let seen_by_bob = table.motd.clone();
+    /// let mut handle = table.get_handle_to::<entries::Motd>();
+    /// assert!(handle.compare_and_set(&seen_by_alice, "Welcome".to_string()).is_ok());
+    /// let conflict = handle.compare_and_set(&seen_by_bob, "Hi there".to_string()).unwrap_err();
+    /// assert_eq!(conflict.current, "Welcome");
+    /// assert_eq!(conflict.rejected, "Hi there");
+    /// ```
+    ///
+    /// [conflict]: struct.Conflict.html " "
+    /// [accepts]: trait.Receiver.html#method.accepts " "
+    #[inline]
+    pub fn compare_and_set(&mut self, expected: &E::Data, new_value: E::Data) -> Result<(), Conflict<'_, E::Data>>
+    where E::Data: PartialEq {
+        if self.target == expected && self.accepts(&new_value) {
+            self.write(new_value);
+            Ok(())
+        } else {
+            Err(Conflict {current: self.target, rejected: new_value})
+        }
+    }
+    /// Sets the handle's pointee to the specified value, notifying the receiver, and returns the previous value. If the receiver [rejects] the value, the pointee is left untouched and the rejected value is returned instead.
+    ///
+    /// Useful when the old value needs to be cleaned up or otherwise inspected after being replaced, without having to clone it beforehand.
+    ///
+    /// [rejects]: trait.Receiver.html#method.accepts " "
+    #[inline]
+    pub fn replace(&mut self, new_value: E::Data) -> E::Data {
+        if !self.accepts(&new_value) {
+            return new_value;
+        }
+        let old_value = mem::replace(self.target, new_value);
+        self.notify();
+        old_value
+    }
+    /// Moves the value out of the handle's pointee, leaving the `Default` value of the data type in its place, and notifies the receiver of the new value.
+    ///
+    /// Note that this uses the `Default` implementation of the data type rather than the [default value] of the entry.
+    ///
+    /// [default value]: trait.Entry.html#method.default_value " "
+    #[inline]
+    pub fn take(&mut self) -> E::Data
+    where E::Data: Default {
+        self.replace(Default::default())
+    }
+    /// Sets the handle's pointee to the [default value] of the entry, notifying the receiver. Returns `true` if the entry has a default value, the receiver [accepted] it and the reset was performed, `false` otherwise.
+    ///
+    /// [default value]: trait.Entry.html#method.default_value " "
+    /// [accepted]: trait.Receiver.html#method.accepts " "
+    #[inline]
+    pub fn reset(&mut self) -> bool {
+        match E::default_value() {
+            Some(default_value) if self.accepts(&default_value) => {
+                self.write(default_value);
+                true
+            }
+            _ => false,
+        }
     }
     /// Creates a [`ModificationScope`] for modifying the value inside without reallocating/moving and without a closure, while still notifying the receiver when modification is finished. The resulting `ModificationScope` acts like a mutable reference to the stored data, which allows direct modification.
     ///
@@ -43,6 +278,56 @@ impl<'a, E: Entry, R: Receiver<E>> Handle<'a, E, R> {
     pub fn modify<'b>(&'b mut self) -> ModificationScope<'a, 'b, E, R> {
         ModificationScope {handle: self}
     }
+    /// Creates a [`CommitScope`], which, unlike [`ModificationScope`], only notifies the receiver when the modification is explicitly [committed]. If the scope is dropped without being committed, which includes unwinding from a panic, the receiver isn't notified, so that it never observes a half-modified value.
+    ///
+    /// # Example
+    /// ```
+    /// # use snec::{Entry, Handle, FnReceiver};
+    /// # use std::cell::Cell;
+    /// enum Hosts {}
+    /// impl Entry for Hosts {
+    ///     type Data = Vec<String>;
+    ///     const NAME: &'static str = "hosts";
+    /// }
+    /// let notifications = Cell::new(0);
+    /// let mut hosts = Vec::new();
+    /// let mut handle = Handle::<Hosts, _>::new(
+    ///     &mut hosts,
+    ///     FnReceiver::new(|_: &Vec<String>| notifications.set(notifications.get() + 1)),
+    /// );
+    /// {
+    ///     let mut scope = handle.commit_scope();
+    ///     scope.push("localhost".to_string());
+    ///     // Dropped without committing, the receiver isn't notified.
+    /// }
+    /// assert_eq!(notifications.get(), 0);
+    /// let mut scope = handle.commit_scope();
+    /// scope.push("example.com".to_string());
+    /// scope.commit();
+    /// assert_eq!(notifications.get(), 1);
+    /// ```
+    ///
+    /// [`CommitScope`]: struct.CommitScope.html " "
+    /// [`ModificationScope`]: struct.ModificationScope.html " "
+    /// [committed]: struct.CommitScope.html#method.commit " "
+    #[inline(always)]
+    pub fn commit_scope<'b>(&'b mut self) -> CommitScope<'a, 'b, E, R> {
+        CommitScope {handle: self}
+    }
+    /// Creates a [`Projection`] onto the part of the handle's pointee identified by the entry `S`, which acts like a mutable reference to that part and notifies the receiver of the whole entry when dropped, much like a [`ModificationScope`].
+    ///
+    /// See [`Project`] for an example.
+    ///
+    /// [`Projection`]: struct.Projection.html " "
+    /// [`ModificationScope`]: struct.ModificationScope.html " "
+    /// [`Project`]: trait.Project.html " "
+    #[inline(always)]
+    pub fn project<'b, S>(&'b mut self) -> Projection<'a, 'b, E, S, R>
+    where
+        S: Entry,
+        E: Project<S> {
+        Projection {handle: self, _phantom: PhantomData}
+    }
     /// Modifies the handle's pointee using the specified closure, notifying the receiver.
     ///
     /// [`modify`] may be used instead, for simplicity. For small values like integers, [`set`] might be faster.
@@ -52,40 +337,94 @@ impl<'a, E: Entry, R: Receiver<E>> Handle<'a, E, R> {
     #[inline]
     pub fn modify_with<F>(&mut self, mut f: F)
     where F: FnMut(&mut E::Data) {
-        f(&mut self.target);
-        self.receiver.receive(self.target);
+        f(self.target);
+        self.notify();
     }
-
-    /// Sets the handle's pointee to the specified value without notifying the receiver. **Doing this is heavily discouraged and should only be used in special cases.**
-    ///
-    /// For large values where partial modification using a mutable reference would improve performance (`Vec` is a good example of such a type), [`modify_silently`] or [`modify_silently_with`] should be used instead.
+    /// Modifies the handle's pointee using the specified closure, notifying the [fallible receiver] and returning the error it produced, if any. The value stays modified even if the receiver fails.
     ///
-    /// [`modify_silently`]: #method.modify_silently " "
-    /// [`modify_silently_with`]: #method.modify_silently_with " "
-    #[inline(always)]
-    pub fn set_silently(&mut self, new_value: E::Data) {
+    /// [fallible receiver]: trait.TryReceiver.html " "
+    #[inline]
+    pub fn try_modify_with<F>(&mut self, mut f: F) -> Result<(), R::Error>
+    where
+        F: FnMut(&mut E::Data),
+        R: TryReceiver<E> {
+        f(self.target);
+        let _notifying = Notifying::entry::<E>(self.target);
+        self.receiver.try_receive(self.target)
+    }
+    /// Writes the specified value without consulting the receiver, then notifies it.
+    #[inline]
+    fn write(&mut self, new_value: E::Data) {
+        E::erase(self.target);
         *self.target = new_value;
+        self.notify();
+    }
+    /// Asks the receiver whether the pointee may change to the specified value.
+    #[inline]
+    fn accepts(&mut self, new_value: &E::Data) -> bool {
+        let _notifying = Notifying::entry::<E>(self.target);
+        self.receiver.accepts(self.target, new_value)
     }
-    /// Returns a mutable reference to the handle's pointee. **This will not notify any receiver, which is heavily discouraged and should only be used in special cases.**
+    /// Notifies the receiver about the current value, for guards which modify the value through [`target_mut`].
     ///
-    /// [`modify_silently_with`] may be used instead. For small values like integers, [`set_silently`] might be faster.
+    /// [`target_mut`]: #method.target_mut " "
+    #[inline]
+    pub(crate) fn notify(&mut self) {
+        let _notifying = Notifying::entry::<E>(self.target);
+        self.receiver.receive(self.target)
+    }
+    /// Wraps the receiver of the handle, returning a handle to the same value.
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    pub(crate) fn map_receiver<R2>(self, f: impl FnOnce(R) -> R2) -> Handle<'a, E, R2>
+    where R2: Receiver<E> {
+        Handle {target: self.target, receiver: f(self.receiver), _phantom: PhantomData}
+    }
+    /// Returns a mutable reference to the handle's pointee without notifying the receiver, for guards which call [`notify`] afterwards.
     ///
-    /// [`modify_silently_with`]: #method.modify_silently_with " "
-    /// [`set_silently`]: #method.set_silently " "
+    /// [`notify`]: #method.notify " "
     #[inline(always)]
-    pub fn modify_silently(&mut self) -> &mut E::Data {
+    pub(crate) fn target_mut(&mut self) -> &mut E::Data {
         self.target
     }
-    /// Modifies the handle's pointee using the specified closure, without notifying the receiver. **Doing this is heavily discouraged and should only be used in special cases.**
+
+    /// Returns a [`SilentHandle`] to the handle's pointee, which allows modifying it without notifying the receiver. **Doing this is heavily discouraged and should only be used in special cases.**
     ///
-    /// [`modify_silently`] may be used instead, for simplicity. For small values like integers, [`set_silently`] might be faster.
+    /// [`SilentHandle`]: struct.SilentHandle.html " "
+    #[cfg(feature = "silent")]
+    #[inline(always)]
+    pub fn as_silent(&mut self) -> SilentHandle<'_, E> {
+        SilentHandle::new(self.target)
+    }
+    /// Converts the handle into a [`SilentHandle`] to the same value, dropping the receiver without notifying it. **Doing this is heavily discouraged and should only be used in special cases.**
     ///
-    /// [`modify_silently`]: #method.modify_silently " "
-    /// [`set_silently`]: #method.set_silently " "
+    /// [`SilentHandle`]: struct.SilentHandle.html " "
+    #[cfg(feature = "silent")]
+    #[inline(always)]
+    pub fn into_silent(self) -> SilentHandle<'a, E> {
+        SilentHandle::new(self.target)
+    }
+    /// Sets the handle's pointee to the specified value without notifying the receiver.
+    #[cfg(feature = "silent")]
+    #[deprecated(note = "use `as_silent().set(...)` instead")]
     #[inline(always)]
-    pub fn modify_silently_with<F>(&mut self, mut f: F)
+    pub fn set_silently(&mut self, new_value: E::Data) {
+        self.as_silent().set(new_value)
+    }
+    /// Returns a mutable reference to the handle's pointee without notifying the receiver.
+    #[cfg(feature = "silent")]
+    #[deprecated(note = "use `as_silent().into_mut()` instead")]
+    #[inline(always)]
+    pub fn modify_silently(&mut self) -> &mut E::Data {
+        self.as_silent().into_mut()
+    }
+    /// Modifies the handle's pointee using the specified closure, without notifying the receiver.
+    #[cfg(feature = "silent")]
+    #[deprecated(note = "use `as_silent().modify_with(...)` instead")]
+    #[inline(always)]
+    pub fn modify_silently_with<F>(&mut self, f: F)
     where F: FnMut(&mut E::Data) {
-        f(&mut self.target);
+        self.as_silent().modify_with(f)
     }
 }
 
@@ -108,9 +447,117 @@ where
     E::Data: Debug {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value: &dyn Debug = if E::SECRET {&Redacted} else {&self.target};
         f.debug_struct("EntryStorage")
             .field("name", &E::NAME)
-            .field("value", &self.target)
+            .field("value", value)
+            .finish()
+    }
+}
+
+/// The error returned by [`Handle::compare_and_set`] when the current value of the entry differs from the expected one.
+///
+/// [`Handle::compare_and_set`]: struct.Handle.html#method.compare_and_set " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Conflict<'a, T> {
+    /// The current value of the entry.
+    pub current: &'a T,
+    /// The value which was rejected.
+    pub rejected: T,
+}
+impl<T> fmt::Display for Conflict<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("the value was changed since it was last read")
+    }
+}
+#[cfg(feature = "std")]
+impl<T: Debug> std::error::Error for Conflict<'_, T> {}
+
+/// A handle to a config entry value which doesn't notify any receiver when the value is modified, obtained from a [`Handle`] using [`as_silent`] or [`into_silent`].
+///
+/// Silent modifications are heavily discouraged and should only be used in special cases, such as loading the initial configuration before any receivers are interested in it. Keeping them behind a separate type makes every place where they happen easy to find by searching for `as_silent` and `into_silent`.
+///
+/// Silent modifications are only available with the `silent` feature, which is enabled by default. Disabling it removes this type and all other ways of modifying a value through a handle without notifying the receiver, except for leaking guards with `mem::forget`, which guarantees at build time that the receivers observe every change.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, GetExt as _};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec]
+///     hosts: Vec<String>,
+/// }
+/// let mut table = MyConfigTable {hosts: Vec::new()};
+/// let mut handle = table.get_handle_to::<entries::Hosts>();
+/// handle.as_silent().push("localhost".to_string());
+/// handle.as_silent().set(vec!["example.com".to_string()]);
+/// assert_eq!(*handle.get(), ["example.com"]);
+/// ```
+///
+/// [`Handle`]: struct.Handle.html " "
+/// [`as_silent`]: struct.Handle.html#method.as_silent " "
+/// [`into_silent`]: struct.Handle.html#method.into_silent " "
+#[cfg(feature = "silent")]
+pub struct SilentHandle<'a, E: Entry> {
+    target: &'a mut E::Data,
+    _phantom: PhantomData<E>,
+}
+#[cfg(feature = "silent")]
+impl<'a, E: Entry> SilentHandle<'a, E> {
+    #[inline(always)]
+    fn new(target: &'a mut E::Data) -> Self {
+        Self {target, _phantom: PhantomData}
+    }
+    /// Returns an immutable reference to the handle's pointee.
+    #[inline(always)]
+    pub fn get(&self) -> &E::Data {
+        self.target
+    }
+    /// Sets the handle's pointee to the specified value.
+    #[inline(always)]
+    pub fn set(&mut self, new_value: E::Data) {
+        E::erase(self.target);
+        *self.target = new_value;
+    }
+    /// Modifies the handle's pointee using the specified closure.
+    #[inline(always)]
+    pub fn modify_with<F>(&mut self, mut f: F)
+    where F: FnMut(&mut E::Data) {
+        f(self.target);
+    }
+    /// Converts the handle into a mutable reference to its pointee.
+    #[inline(always)]
+    pub fn into_mut(self) -> &'a mut E::Data {
+        self.target
+    }
+}
+#[cfg(feature = "silent")]
+impl<'a, E: Entry> Deref for SilentHandle<'a, E> {
+    type Target = E::Data;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.target
+    }
+}
+#[cfg(feature = "silent")]
+impl<'a, E: Entry> DerefMut for SilentHandle<'a, E> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.target
+    }
+}
+#[cfg(feature = "silent")]
+impl<'a, E> Debug for SilentHandle<'a, E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value: &dyn Debug = if E::SECRET {&Redacted} else {&self.target};
+        f.debug_struct("SilentHandle")
+            .field("name", &E::NAME)
+            .field("value", value)
             .finish()
     }
 }
@@ -119,15 +566,28 @@ where
 ///
 /// Since `Storage` should notify a receiver whenever data inside of it is modified, it cannot simply hand out mutable references to the value, because that'd allow outside code to implicitly perform a silent storage modification. While ways to do so are also provided, it's heavily discouraged and reserved for special cases.
 ///
-/// The solution to the problem is this struct: `ModificationScope`. It's a drop guard which is created by providing a receiver to the storage. While it has little to no differences to a mutable reference to the data inside in terms of functionality, it notifies the receiver when dropped, ensuring that it will get modified even if a panic or any other kind of early return happens.
+/// The solution to the problem is this struct: `ModificationScope`. It's a drop guard which is created by providing a receiver to the storage. While it has little to no differences to a mutable reference to the data inside in terms of functionality, it notifies the receiver when dropped, ensuring that it will get modified even if a panic or any other kind of early return happens. If the receiver shouldn't be notified in those cases, a [`CommitScope`] can be used instead.
 ///
 /// [`Handle`]: struct.Handle.html " "
+/// [`CommitScope`]: struct.CommitScope.html " "
 pub struct ModificationScope<'a, 'b, E, R>
 where
     E: Entry,
     R: Receiver<E> {
     handle: &'b mut Handle<'a, E, R>,
 }
+impl<'a, 'b, E, R> ModificationScope<'a, 'b, E, R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    /// Ends the modification without notifying the receiver, for cases when it turns out that no changes had to be made. **Any changes which were made through the scope are kept, which makes them silent modifications.** Requires the `silent` feature.
+    #[cfg(feature = "silent")]
+    #[inline(always)]
+    pub fn cancel(self) {
+        // The scope only holds a reference, so nothing leaks here.
+        mem::forget(self)
+    }
+}
 impl<'a, 'b, E, R> Deref for ModificationScope<'a, 'b, E, R>
 where
     E: Entry,
@@ -168,7 +628,7 @@ where
     E: Entry,
     R: Receiver<E> {
     fn drop(&mut self) {
-        self.handle.receiver.receive(self.handle.target)
+        self.handle.notify()
     }
 }
 impl<'a, 'b, E, R> Debug for ModificationScope<'a, 'b, E, R>
@@ -184,6 +644,140 @@ where
     }
     }
 
+/// A drop guard for modifying a part of the data behind a [`Handle`], created by [`Handle::project`].
+///
+/// Acts like a mutable reference to the part of the data identified by the entry `S`, and notifies the receiver of the entry `E` with the whole value when dropped.
+///
+/// [`Handle`]: struct.Handle.html " "
+/// [`Handle::project`]: struct.Handle.html#method.project " "
+pub struct Projection<'a, 'b, E, S, R>
+where
+    E: Entry + Project<S>,
+    S: Entry,
+    R: Receiver<E> {
+    handle: &'b mut Handle<'a, E, R>,
+    _phantom: PhantomData<S>,
+}
+impl<'a, 'b, E, S, R> Deref for Projection<'a, 'b, E, S, R>
+where
+    E: Entry + Project<S>,
+    S: Entry,
+    R: Receiver<E> {
+    type Target = S::Data;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        E::project(self.handle.target)
+    }
+}
+impl<'a, 'b, E, S, R> DerefMut for Projection<'a, 'b, E, S, R>
+where
+    E: Entry + Project<S>,
+    S: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        E::project_mut(self.handle.target)
+    }
+}
+impl<'a, 'b, E, S, R> Drop for Projection<'a, 'b, E, S, R>
+where
+    E: Entry + Project<S>,
+    S: Entry,
+    R: Receiver<E> {
+    fn drop(&mut self) {
+        self.handle.notify()
+    }
+}
+impl<'a, 'b, E, S, R> Debug for Projection<'a, 'b, E, S, R>
+where
+    E: Entry + Project<S>,
+    S: Entry,
+    S::Data: Debug,
+    R: Receiver<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Projection")
+            .field("entry", &E::NAME)
+            .field("part", &S::NAME)
+            .field("value", &**self)
+            .finish()
+    }
+}
+
+/// A guard for modifying data behind a [`Handle`] using a mutable reference, which only notifies the receiver when explicitly [committed].
+///
+/// Unlike [`ModificationScope`], dropping a `CommitScope` doesn't notify the receiver, which makes panics and early returns during the modification silent: the receiver isn't run against a value which might have been left half-modified. The changes themselves are not rolled back, though.
+///
+/// Created by [`Handle::commit_scope`].
+///
+/// [`Handle`]: struct.Handle.html " "
+/// [committed]: #method.commit " "
+/// [`ModificationScope`]: struct.ModificationScope.html " "
+/// [`Handle::commit_scope`]: struct.Handle.html#method.commit_scope " "
+pub struct CommitScope<'a, 'b, E, R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    handle: &'b mut Handle<'a, E, R>,
+}
+impl<'a, 'b, E, R> CommitScope<'a, 'b, E, R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    /// Ends the modification, notifying the receiver.
+    #[inline]
+    pub fn commit(self) {
+        self.handle.notify()
+    }
+}
+impl<'a, 'b, E, R> Deref for CommitScope<'a, 'b, E, R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    type Target = E::Data;
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.handle.target
+    }
+}
+impl<'a, 'b, E, R> DerefMut for CommitScope<'a, 'b, E, R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.handle.target
+    }
+}
+impl<'a, 'b, E, R> AsRef<Handle<'a, E, R>> for CommitScope<'a, 'b, E, R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    fn as_ref(&self) -> &Handle<'a, E, R> {
+        self.handle
+    }
+}
+impl<'a, 'b, E, R> AsMut<Handle<'a, E, R>> for CommitScope<'a, 'b, E, R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    fn as_mut(&mut self) -> &mut Handle<'a, E, R> {
+        self.handle
+    }
+}
+impl<'a, 'b, E, R> Debug for CommitScope<'a, 'b, E, R>
+where
+    E: Entry,
+    E::Data: Debug,
+    R: Receiver<E> + Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommitScope")
+            .field("handle", &*self.handle)
+            .finish()
+    }
+}
+
 /////////////////////////////////////////////////
 // Trait implementation forwarding for Storage //
 /////////////////////////////////////////////////
@@ -0,0 +1,141 @@
+//! Fanning out notifications without dynamic allocation, using fixed-capacity collections from [`heapless`].
+//!
+//! With the `heapless` feature enabled, `heapless::Vec<R, N>` implements `Receiver` just like `Vec` does, notifying all of its elements in order. For receivers which are registered and unregistered at runtime, [`Dispatcher`] wraps such a vector and reports when it runs out of capacity instead of panicking, which makes it suitable for firmware on targets without an allocator.
+//!
+//! # Example
+//! ```
+//! # use snec::{Entry, Handle, Receiver, heapless::Dispatcher};
+//! # use core::cell::Cell;
+//! enum Brightness {}
+//! impl Entry for Brightness {
+//!     type Data = u8;
+//!     const NAME: &'static str = "brightness";
+//! }
+//! struct Led<'a> {
+//!     duty_cycle: &'a Cell<u8>,
+//! }
+//! impl Receiver<Brightness> for Led<'_> {
+//!     fn receive(&mut self, new_value: &u8) {
+//!         self.duty_cycle.set(*new_value);
+//!     }
+//! }
+//! let (left, right) = (Cell::new(0), Cell::new(0));
+//! let mut dispatcher = Dispatcher::<_, 2>::new();
+//! assert!(dispatcher.register(Led {duty_cycle: &left}).is_ok());
+//! assert!(dispatcher.register(Led {duty_cycle: &right}).is_ok());
+//! // The dispatcher is full, so the receiver is returned back.
+//! assert!(dispatcher.register(Led {duty_cycle: &right}).is_err());
+//!
+//! let mut brightness = 0;
+//! Handle::<Brightness, _>::new(&mut brightness, &mut dispatcher).set(128);
+//! assert_eq!((left.get(), right.get()), (128, 128));
+//! ```
+//!
+//! [`heapless`]: https://docs.rs/heapless " "
+//! [`Dispatcher`]: struct.Dispatcher.html " "
+
+use core::fmt::{self, Formatter, Debug};
+use super::{Entry, Receiver};
+
+/// A fixed-capacity collection of up to `N` [receivers], all of which are notified in the order of registration.
+///
+/// [receivers]: ../trait.Receiver.html " "
+pub struct Dispatcher<R, const N: usize> {
+    receivers: ::heapless::Vec<R, N>,
+}
+impl<R, const N: usize> Dispatcher<R, N> {
+    /// Creates a dispatcher without any receivers.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {receivers: ::heapless::Vec::new()}
+    }
+    /// Adds a receiver to the end of the list, or returns it back if the dispatcher is full.
+    ///
+    /// # Errors
+    /// If there already are `N` receivers.
+    #[inline]
+    pub fn register(&mut self, receiver: R) -> Result<(), R> {
+        self.receivers.push(receiver)
+    }
+    /// Removes all receivers for which the predicate returns `false`, preserving the order of the rest.
+    #[inline]
+    pub fn retain(&mut self, predicate: impl FnMut(&R) -> bool) {
+        self.receivers.retain(predicate)
+    }
+    /// Removes all receivers.
+    #[inline(always)]
+    pub fn clear(&mut self) {
+        self.receivers.clear()
+    }
+    /// Returns the registered receivers, in the order in which they are notified.
+    #[inline(always)]
+    pub fn receivers(&self) -> &[R] {
+        &self.receivers
+    }
+    /// Returns the registered receivers mutably, in the order in which they are notified.
+    #[inline(always)]
+    pub fn receivers_mut(&mut self) -> &mut [R] {
+        &mut self.receivers
+    }
+    /// Returns the number of registered receivers.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.receivers.len()
+    }
+    /// Returns `true` if no receivers are registered.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.receivers.is_empty()
+    }
+    /// Returns `true` if no more receivers can be registered.
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.receivers.is_full()
+    }
+    /// Returns the maximum number of receivers, which is `N`.
+    #[inline(always)]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+impl<E, R, const N: usize> Receiver<E> for Dispatcher<R, N>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.receivers.receive(new_value);
+    }
+}
+impl<R, const N: usize> Default for Dispatcher<R, N> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<R: Clone, const N: usize> Clone for Dispatcher<R, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {receivers: self.receivers.clone()}
+    }
+}
+impl<R: Debug, const N: usize> Debug for Dispatcher<R, N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Dispatcher")
+            .field("receivers", &self.receivers())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+/// Notifies all receivers in the vector, in order.
+impl<E, R, const N: usize> Receiver<E> for ::heapless::Vec<R, N>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn receive(&mut self, new_value: &E::Data) {
+        self[..].receive(new_value);
+    }
+}
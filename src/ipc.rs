@@ -0,0 +1,331 @@
+//! Sharing changes of config tables between processes over [local sockets].
+//!
+//! One process, such as a daemon, [listens] on a local socket, which is a Unix domain socket or a named pipe depending on the platform, and other processes, such as a GUI, [connect] to it. Every change of an entry is sent to the connected peers as a line with a JSON array of the [name] of the entry and its new value, such as `["volume",80]`, and the listening process relays the changes it receives to the rest of its peers, so that all processes see all changes.
+//!
+//! The changes made locally are sent by [`IpcReceiver`], attached to the entries of the table as their receiver, as soon as they're made. Every peer is written to by its own background thread, so that a peer which stops reading never blocks changes of the table; a peer which falls more than 256 changes behind, or can't be written to, is dropped and no longer receives changes. The changes received from peers are queued by background threads until they're applied to the table with [`apply_pending`], which never blocks and thus suits event loops, or with [`apply_next`], which waits for the next change. They're written through handles, so the receivers of the entries are notified about changes made by other processes just like about local ones, but they aren't sent back to the peers. Messages which aren't arrays of an entry name and a value are ignored.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, ipc::{IpcSync, IpcReceiver}};
+//! use interprocess::local_socket::{GenericNamespaced, ToNsName as _};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.ipc.clone()}: IpcReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     volume: u8,
+//!     ipc: IpcReceiver,
+//! }
+//! let socket_name = format!("snec-example-{}.sock", std::process::id());
+//! // Usually done by different processes.
+//! let daemon = IpcSync::listen(socket_name.clone().to_ns_name::<GenericNamespaced>()?)?;
+//! let mut daemon_table = MyConfigTable {volume: 50, ipc: daemon.receiver()};
+//! let gui = IpcSync::connect(socket_name.to_ns_name::<GenericNamespaced>()?)?;
+//! let mut gui_table = MyConfigTable {volume: 50, ipc: gui.receiver()};
+//!
+//! gui_table.get_handle_to::<entries::Volume>().set(80);
+//! assert_eq!(daemon.apply_next(&mut daemon_table)?, Some(1));
+//! assert_eq!(daemon_table.volume, 80);
+//!
+//! daemon_table.get_handle_to::<entries::Volume>().set(30);
+//! assert_eq!(gui.apply_next(&mut gui_table)?, Some(1));
+//! assert_eq!(gui_table.volume, 30);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [local sockets]: https://docs.rs/interprocess/*/interprocess/local_socket/index.html " "
+//! [listens]: struct.IpcSync.html#method.listen " "
+//! [connect]: struct.IpcSync.html#method.connect " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`IpcReceiver`]: struct.IpcReceiver.html " "
+//! [`apply_pending`]: struct.IpcSync.html#method.apply_pending " "
+//! [`apply_next`]: struct.IpcSync.html#method.apply_next " "
+
+use core::{
+    fmt::{self, Formatter, Display, Debug},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+use std::{
+    error,
+    io::{self, BufRead, BufReader, Write},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+use interprocess::local_socket::{
+    traits::{ListenerExt as _, Stream as _},
+    ListenerOptions,
+    Name,
+    SendHalf,
+    Stream,
+};
+use serde::Serialize;
+use serde_json::value::RawValue;
+use super::{
+    Entry, Receiver, LoadError, VisitEntries, ValidateTable, lock_ignoring_poison,
+    remote::{self, RemoteLoader, Values},
+};
+
+/// A message received from a peer, along with the ID of the peer.
+type Message = (usize, Vec<u8>);
+/// The queue of a thread writing to a peer, along with the ID of the peer.
+type PeerQueue = (usize, mpsc::SyncSender<Arc<[u8]>>);
+
+/// Connects a config table to other processes and applies the changes received from them. See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+pub struct IpcSync {
+    shared: Arc<Shared>,
+    messages: mpsc::Receiver<Message>,
+}
+impl IpcSync {
+    /// Listens for connections from other processes on the local socket with the specified name, accepting them on a background thread.
+    ///
+    /// The socket keeps accepting connections until the first connection attempt made after the `IpcSync` is dropped.
+    pub fn listen(name: Name<'_>) -> Result<Self, Error> {
+        let listener = ListenerOptions::new().name(name).create_sync()?;
+        let (sender, messages) = mpsc::channel();
+        let shared = Arc::new(Shared::default());
+        let accepting = Arc::clone(&shared);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accepting.closed.load(Ordering::Acquire) {
+                    break;
+                }
+                // Failing to accept one connection doesn't prevent accepting the next one.
+                if let Ok(stream) = stream {
+                    accepting.add_peer(stream, sender.clone());
+                }
+            }
+        });
+        Ok(Self {shared, messages})
+    }
+    /// Connects to the process listening on the local socket with the specified name.
+    pub fn connect(name: Name<'_>) -> Result<Self, Error> {
+        let stream = Stream::connect(name)?;
+        let (sender, messages) = mpsc::channel();
+        let shared = Arc::new(Shared::default());
+        shared.add_peer(stream, sender);
+        Ok(Self {shared, messages})
+    }
+    /// Creates a receiver which sends local changes to the connected peers.
+    #[inline]
+    pub fn receiver(&self) -> IpcReceiver {
+        IpcReceiver {shared: Arc::clone(&self.shared)}
+    }
+    /// Returns the number of currently connected peers.
+    #[inline]
+    pub fn peer_count(&self) -> usize {
+        lock_ignoring_poison(&self.shared.peers).len()
+    }
+    /// Writes the changes received from peers so far into the corresponding entries of the config table without waiting for more, notifying the receivers of the entries which changed. Returns the number of entries which changed.
+    ///
+    /// Changes received from peers have already been forwarded to the other peers by the time they're applied here. Values which don't correspond to any entry are ignored; a value which doesn't deserialize as its entry's type fails with [`Error::Value`], and the remaining pending changes are discarded with it. The table is left unchanged if an error is returned, including one produced by the [table validator].
+    ///
+    /// [`Error::Value`]: enum.Error.html#variant.Value " "
+    /// [table validator]: ../trait.ValidateTable.html " "
+    pub fn apply_pending<T>(&self, table: &mut T) -> Result<usize, LoadError<Error, T::Error>>
+    where T: VisitEntries<RemoteLoader> + ValidateTable {
+        let mut values = Values::new();
+        while let Ok(message) = self.messages.try_recv() {
+            self.receive(message, &mut values);
+        }
+        self.apply(table, values)
+    }
+    /// Waits for the next change from a peer and writes it into the config table along with the rest of the changes received so far, like [`apply_pending`]. Returns the number of entries which changed, or `None` if no more changes can be received, which happens when a connecting process loses its connection.
+    ///
+    /// [`apply_pending`]: #method.apply_pending " "
+    pub fn apply_next<T>(&self, table: &mut T) -> Result<Option<usize>, LoadError<Error, T::Error>>
+    where T: VisitEntries<RemoteLoader> + ValidateTable {
+        let message = match self.messages.recv() {
+            Ok(message) => message,
+            Err(..) => return Ok(None),
+        };
+        let mut values = Values::new();
+        self.receive(message, &mut values);
+        while let Ok(message) = self.messages.try_recv() {
+            self.receive(message, &mut values);
+        }
+        self.apply(table, values).map(Some)
+    }
+    /// Returns the error which occurred while serializing a value received as a receiver, if any, and clears it. Only the last error is kept.
+    #[inline]
+    pub fn take_error(&self) -> Option<Error> {
+        lock_ignoring_poison(&self.shared.error).take()
+    }
+    fn receive(&self, (peer, message): Message, values: &mut Values) {
+        if let Some((name, value)) = decode(&message) {
+            self.shared.broadcast(&message, Some(peer));
+            values.insert(name, value);
+        }
+    }
+    #[inline]
+    fn apply<T>(&self, table: &mut T, values: Values) -> Result<usize, LoadError<Error, T::Error>>
+    where T: VisitEntries<RemoteLoader> + ValidateTable {
+        remote::apply(&self.shared.known, table, values)
+            .map_err(|error| error.map_source(|(entry, inner)| Error::Value {entry, inner}))
+    }
+}
+impl Drop for IpcSync {
+    #[inline]
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+    }
+}
+impl Debug for IpcSync {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpcSync")
+            .field("peer_count", &self.peer_count())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The number of messages which can be waiting to be written to a peer before the peer is dropped.
+const PEER_QUEUE_LENGTH: usize = 256;
+
+/// State shared between the synchronizer, its receivers and its background threads.
+#[derive(Default)]
+struct Shared {
+    /// The queues of the threads writing to the peers.
+    peers: Mutex<Vec<PeerQueue>>,
+    next_peer: AtomicUsize,
+    /// The values last received from or sent to the peers, by the names of their entries.
+    known: Mutex<Values>,
+    error: Mutex<Option<Error>>,
+    closed: AtomicBool,
+}
+impl Shared {
+    /// Spawns a thread which writes changes to the peer and one which reads the changes it sends.
+    fn add_peer(self: &Arc<Self>, stream: Stream, messages: mpsc::Sender<Message>) {
+        let id = self.next_peer.fetch_add(1, Ordering::Relaxed);
+        let (recv_half, send_half) = stream.split();
+        let (queue, queued) = mpsc::sync_channel(PEER_QUEUE_LENGTH);
+        lock_ignoring_poison(&self.peers).push((id, queue));
+        thread::spawn(move || write_to_peer(send_half, queued));
+        let shared = Arc::clone(self);
+        thread::spawn(move || {
+            let mut recv_half = BufReader::new(recv_half);
+            loop {
+                let mut line = Vec::new();
+                match recv_half.read_until(b'\n', &mut line) {
+                    Ok(0) | Err(..) => break,
+                    Ok(..) => {
+                        if !line.ends_with(b"\n") {
+                            line.push(b'\n');
+                        }
+                        if messages.send((id, line)).is_err() || shared.closed.load(Ordering::Acquire) {
+                            break;
+                        }
+                    },
+                }
+            }
+            lock_ignoring_poison(&shared.peers).retain(|(peer, _)| *peer != id);
+        });
+    }
+    /// Queues the message for all peers except the specified one without waiting for it to be written, dropping the peers which can't be written to or whose queues are full.
+    fn broadcast(&self, message: &[u8], except: Option<usize>) {
+        let message = Arc::<[u8]>::from(message);
+        lock_ignoring_poison(&self.peers).retain(|(id, queue)| {
+            Some(*id) == except || queue.try_send(Arc::clone(&message)).is_ok()
+        });
+    }
+}
+/// Writes the queued messages to a peer until the peer is dropped or can't be written to.
+fn write_to_peer(mut send_half: SendHalf, queued: mpsc::Receiver<Arc<[u8]>>) {
+    for message in queued {
+        if send_half.write_all(&message).is_err() {
+            break;
+        }
+    }
+}
+
+/// A [receiver] which sends the new values of entries to the peers of an [`IpcSync`], created by [`IpcSync::receiver`].
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [`IpcSync`]: struct.IpcSync.html " "
+/// [`IpcSync::receiver`]: struct.IpcSync.html#method.receiver " "
+#[derive(Clone)]
+pub struct IpcReceiver {
+    shared: Arc<Shared>,
+}
+impl<E> Receiver<E> for IpcReceiver
+where
+    E: Entry,
+    E::Data: Serialize {
+    fn receive(&mut self, new_value: &E::Data) {
+        let value = match serde_json::to_vec(new_value) {
+            Ok(value) => value,
+            Err(error) => {
+                *lock_ignoring_poison(&self.shared.error) = Some(Error::Value {entry: E::NAME, inner: error});
+                return;
+            },
+        };
+        let mut known = lock_ignoring_poison(&self.shared.known);
+        if known.get(E::NAME) == Some(&value) {
+            return;
+        }
+        let message = encode(E::NAME, &value);
+        known.insert(E::NAME.to_string(), value);
+        drop(known);
+        self.shared.broadcast(&message, None);
+    }
+}
+impl Debug for IpcReceiver {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IpcReceiver").finish_non_exhaustive()
+    }
+}
+
+/// An error which occurred while connecting to other processes or exchanging changes with them.
+#[derive(Debug)]
+pub enum Error {
+    /// The local socket could not be created or connected to.
+    Io(io::Error),
+    /// The value of an entry could not be serialized, or the received value could not be converted to the type of the entry.
+    Value {
+        /// The name of the entry.
+        entry: &'static str,
+        /// The error produced by the JSON serializer or deserializer.
+        inner: serde_json::Error,
+    },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "local socket error: {}", error),
+            Self::Value {entry, inner} => write!(f, "invalid value for entry `{}`: {}", entry, inner),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Value {inner, ..} => Some(inner),
+        }
+    }
+}
+impl From<io::Error> for Error {
+    #[inline(always)]
+    fn from(op: io::Error) -> Self {
+        Self::Io(op)
+    }
+}
+
+/// Builds the line announcing the new value of an entry.
+fn encode(entry_name: &str, value: &[u8]) -> Vec<u8> {
+    let mut message = b"[".to_vec();
+    // Serializing a string never fails.
+    message.extend(serde_json::to_vec(entry_name).unwrap_or_default());
+    message.push(b',');
+    message.extend_from_slice(value);
+    message.extend_from_slice(b"]\n");
+    message
+}
+/// Splits a line into the name of the entry and its value, keeping the value exactly as it was sent.
+fn decode(message: &[u8]) -> Option<(String, Vec<u8>)> {
+    let (name, value) = serde_json::from_slice::<(String, Box<RawValue>)>(message).ok()?;
+    Some((name, value.get().as_bytes().to_vec()))
+}
@@ -0,0 +1,380 @@
+//! Layered configuration with priority merging.
+//!
+//! Configuration often comes from several sources at once: the defaults built into the program, a config file, environment variables and command line arguments, with each subsequent source overriding the values of the previous ones. [`Layers`] resolves the effective value of every entry from such a stack of sources (called *layers*) and writes it into a config table through handles, notifying the receivers of the entries whose values changed. It also remembers which layer each value came from, which is invaluable when explaining to the user why a setting has the value it has.
+//!
+//! The lowest layer is always the [default value] of the entry. Entries for which none of the layers provide a value are left untouched.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, layers::{Layers, EnvLayer, ArgsLayer}};
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec(entry, default = 8080)]
+//!     port: u16,
+//!     #[snec(entry, default = "localhost".to_string())]
+//!     host: String,
+//! }
+//! let mut table = MyConfigTable {port: 0, host: String::new()};
+//! std::env::set_var("MYSERVER_HOST", "example.com");
+//! let mut layers = Layers::new()
+//!     .with("env", EnvLayer::new("MYSERVER"))
+//!     .with("cli", ArgsLayer::from_args(vec!["--port=443".to_string()]));
+//! layers.apply_to(&mut table).unwrap();
+//! assert_eq!(table.port, 443);
+//! assert_eq!(table.host, "example.com");
+//! assert_eq!(layers.origin("port"), Some("cli"));
+//! assert_eq!(layers.origin("host"), Some("env"));
+//! ```
+//!
+//! [`Layers`]: struct.Layers.html " "
+//! [default value]: ../trait.Entry.html#method.default_value " "
+
+use core::{
+    fmt::{self, Formatter, Display},
+    str::FromStr,
+};
+use std::{
+    env::{self as std_env, VarError},
+    error,
+};
+use super::{Entry, Receiver, Handle, EntryVisitor, VisitEntries, env};
+
+/// The error type produced by layers when a value for an entry cannot be obtained.
+pub type LayerError = Box<dyn error::Error + Send + Sync>;
+
+/// Trait for sources of values for entries, which can be stacked using [`Layers`].
+///
+/// [`Layers`]: struct.Layers.html " "
+pub trait Layer<E: Entry> {
+    /// Returns the value for the entry, `None` if the layer doesn't have a value for it, or an error if the layer has a value but it is invalid.
+    fn value(&self) -> Option<Result<E::Data, LayerError>>;
+}
+
+/// Trait for stacks of named [layers], which resolves the value from the topmost layer which has a value for the entry.
+///
+/// Implemented by the stacks built by [`Layers`], there's usually no need to implement it manually.
+///
+/// [layers]: trait.Layer.html " "
+/// [`Layers`]: struct.Layers.html " "
+pub trait Resolve<E: Entry> {
+    /// Returns the value for the entry along with the name of the layer which provided it, or `None` if none of the layers have a value for the entry.
+    fn resolve(&self) -> Option<(&'static str, Result<E::Data, LayerError>)>;
+}
+
+/// A [layer] with a name, the basic building block of layer stacks.
+///
+/// [layer]: trait.Layer.html " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Named<L> {
+    /// The name of the layer, as reported by [`Layers::origin`].
+    ///
+    /// [`Layers::origin`]: struct.Layers.html#method.origin " "
+    pub name: &'static str,
+    /// The layer itself.
+    pub layer: L,
+}
+impl<E: Entry, L: Layer<E>> Resolve<E> for Named<L> {
+    #[inline]
+    fn resolve(&self) -> Option<(&'static str, Result<E::Data, LayerError>)> {
+        self.layer.value().map(|value| (self.name, value))
+    }
+}
+/// The second element is the upper layer, which takes priority over the lower one.
+impl<E: Entry, L: Resolve<E>, U: Resolve<E>> Resolve<E> for (L, U) {
+    #[inline]
+    fn resolve(&self) -> Option<(&'static str, Result<E::Data, LayerError>)> {
+        self.1.resolve().or_else(|| self.0.resolve())
+    }
+}
+
+/// A stack of configuration [layers], resolving the effective value of every entry and applying it to config tables.
+///
+/// See the [module-level documentation] for more.
+///
+/// [layers]: trait.Layer.html " "
+/// [module-level documentation]: index.html " "
+#[derive(Debug)]
+pub struct Layers<L> {
+    layers: L,
+    origins: Vec<(&'static str, &'static str)>,
+    changed: usize,
+    error: Option<Error>,
+}
+impl Layers<Named<DefaultLayer>> {
+    /// Creates a stack consisting only of the [`DefaultLayer`], named `"defaults"`.
+    ///
+    /// [`DefaultLayer`]: struct.DefaultLayer.html " "
+    #[inline]
+    pub fn new() -> Self {
+        Self::from_stack(Named {name: "defaults", layer: DefaultLayer})
+    }
+}
+impl Default for Layers<Named<DefaultLayer>> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<L> Layers<L> {
+    /// Creates a `Layers` from an existing stack of layers.
+    #[inline(always)]
+    pub fn from_stack(layers: L) -> Self {
+        Self {layers, origins: Vec::new(), changed: 0, error: None}
+    }
+    /// Adds a layer with the specified name on top of the stack, giving it priority over all existing layers.
+    #[inline]
+    pub fn with<U>(self, name: &'static str, layer: U) -> Layers<(L, Named<U>)> {
+        Layers {
+            layers: (self.layers, Named {name, layer}),
+            origins: self.origins,
+            changed: self.changed,
+            error: self.error,
+        }
+    }
+    /// Resolves the values of all entries of the table and writes them into it, notifying the receivers of the entries which changed. Returns the number of entries which changed.
+    ///
+    /// Stops at the first invalid value, leaving the rest of the entries untouched.
+    #[inline]
+    pub fn apply_to<T>(&mut self, table: &mut T) -> Result<usize, Error>
+    where T: VisitEntries<Self> {
+        self.changed = 0;
+        self.error = None;
+        table.visit_entries(self);
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => Ok(self.changed),
+        }
+    }
+    /// Returns the name of the layer which provided the value for the entry with the specified name when the layers were last applied, or `None` if none of the layers had a value for it.
+    #[inline]
+    pub fn origin(&self, entry_name: &str) -> Option<&'static str> {
+        self.origins.iter()
+            .find(|(entry, _)| *entry == entry_name)
+            .map(|(_, layer)| *layer)
+    }
+    /// Returns the stack of layers.
+    #[inline(always)]
+    pub fn stack(&self) -> &L {
+        &self.layers
+    }
+    fn set_origin(&mut self, entry_name: &'static str, layer_name: Option<&'static str>) {
+        let position = self.origins.iter().position(|(entry, _)| *entry == entry_name);
+        match (position, layer_name) {
+            (Some(position), Some(layer_name)) => self.origins[position].1 = layer_name,
+            (Some(position), None) => {
+                self.origins.remove(position);
+            },
+            (None, Some(layer_name)) => self.origins.push((entry_name, layer_name)),
+            (None, None) => {},
+        }
+    }
+}
+impl<E, L> EntryVisitor<E> for Layers<L>
+where
+    E: Entry,
+    E::Data: PartialEq,
+    L: Resolve<E> {
+    fn visit<R: Receiver<E>>(&mut self, mut handle: Handle<'_, E, R>) {
+        if self.error.is_some() {
+            return;
+        }
+        match self.layers.resolve() {
+            Some((layer, Ok(value))) => {
+                if *handle.get() != value {
+                    handle.set(value);
+                    self.changed += 1;
+                }
+                self.set_origin(E::NAME, Some(layer));
+            },
+            Some((layer, Err(inner))) => {
+                self.error = Some(
+                    Error {entry: E::NAME, layer, inner}
+                );
+            },
+            None => self.set_origin(E::NAME, None),
+        }
+    }
+}
+
+/// A [layer] providing the [default values] of entries.
+///
+/// [layer]: trait.Layer.html " "
+/// [default values]: ../trait.Entry.html#method.default_value " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DefaultLayer;
+impl<E: Entry> Layer<E> for DefaultLayer {
+    #[inline(always)]
+    fn value(&self) -> Option<Result<E::Data, LayerError>> {
+        E::default_value().map(Ok)
+    }
+}
+
+/// A [layer] reading values from environment variables, named as described in the [`env`] module.
+///
+/// [layer]: trait.Layer.html " "
+/// [`env`]: ../env/index.html " "
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct EnvLayer {
+    prefix: String,
+}
+impl EnvLayer {
+    /// Creates a layer reading variables with the specified prefix.
+    #[inline]
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {prefix: prefix.into()}
+    }
+}
+impl<E> Layer<E> for EnvLayer
+where
+    E: Entry,
+    E::Data: FromStr,
+    <E::Data as FromStr>::Err: Display {
+    fn value(&self) -> Option<Result<E::Data, LayerError>> {
+        let variable = env::variable_name(&self.prefix, E::NAME);
+        let kind = match std_env::var(&variable) {
+            Ok(value) => match value.parse() {
+                Ok(value) => return Some(Ok(value)),
+                Err(error) => env::ErrorKind::Parse(error.to_string()),
+            },
+            Err(VarError::NotPresent) => return None,
+            Err(VarError::NotUnicode(value)) => env::ErrorKind::NotUnicode(value),
+        };
+        Some(Err(
+            env::Error {variable, entry: E::NAME, kind}.into()
+        ))
+    }
+}
+
+/// A [layer] reading values from command line arguments or any other collection of textual key-value pairs.
+///
+/// The keys are matched against the [names] of the entries, treating `-` and `_` as the same character, which allows using the conventional `--kebab-case` for argument names. The values are parsed using `FromStr`.
+///
+/// [layer]: trait.Layer.html " "
+/// [names]: ../trait.Entry.html#associatedconstant.NAME " "
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ArgsLayer {
+    values: Vec<(String, String)>,
+}
+impl ArgsLayer {
+    /// Creates an empty layer.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Creates a layer from command line arguments of the form `--name=value` or `--name value`. Arguments which don't start with `--` and aren't values of a preceding argument are ignored.
+    pub fn from_args<I>(args: I) -> Self
+    where I: IntoIterator<Item = String> {
+        let mut result = Self::new();
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let arg = match arg.strip_prefix("--") {
+                Some(arg) => arg,
+                None => continue,
+            };
+            if let Some(equals) = arg.find('=') {
+                result.insert(&arg[..equals], &arg[equals + 1..]);
+            } else if let Some(value) = args.next_if(|next| !next.starts_with("--")) {
+                result.insert(arg, value);
+            }
+        }
+        result
+    }
+    /// Sets the value for the entry with the specified name, overriding the previous one if there was one.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        let value = value.into();
+        match self.values.iter_mut().find(|(key, _)| names_match(key, &name)) {
+            Some(entry) => entry.1 = value,
+            None => self.values.push((name, value)),
+        }
+    }
+    /// Returns the value for the entry with the specified name, if there is one.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.iter()
+            .find(|(key, _)| names_match(key, name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+impl<E> Layer<E> for ArgsLayer
+where
+    E: Entry,
+    E::Data: FromStr,
+    <E::Data as FromStr>::Err: Display {
+    #[inline]
+    fn value(&self) -> Option<Result<E::Data, LayerError>> {
+        self.get(E::NAME).map(|value| {
+            value.parse().map_err(|error: <E::Data as FromStr>::Err| error.to_string().into())
+        })
+    }
+}
+fn names_match(x: &str, y: &str) -> bool {
+    x.len() == y.len() && x.chars().zip(y.chars()).all(|(x, y)| {
+        let normalize = |c| if c == '-' {'_'} else {c};
+        normalize(x) == normalize(y)
+    })
+}
+
+/// A [layer] reading values from a TOML document, matching its top-level keys against the [names] of the entries.
+///
+/// [layer]: trait.Layer.html " "
+/// [names]: ../trait.Entry.html#associatedconstant.NAME " "
+#[cfg(feature = "toml")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TomlLayer {
+    document: ::toml::Table,
+}
+#[cfg(feature = "toml")]
+impl TomlLayer {
+    /// Parses the specified document to create a layer for it.
+    #[inline]
+    pub fn new(document: &str) -> Result<Self, ::toml::de::Error> {
+        Ok(
+            Self::from_table(document.parse()?)
+        )
+    }
+    /// Creates a layer for an already parsed document.
+    #[inline(always)]
+    pub fn from_table(document: ::toml::Table) -> Self {
+        Self {document}
+    }
+}
+#[cfg(feature = "toml")]
+impl<E> Layer<E> for TomlLayer
+where
+    E: Entry,
+    E::Data: serde::de::DeserializeOwned {
+    #[inline]
+    fn value(&self) -> Option<Result<E::Data, LayerError>> {
+        self.document.get(E::NAME).map(|value| {
+            value.clone().try_into().map_err(Into::into)
+        })
+    }
+}
+
+/// An error which occurred while resolving the value of an entry.
+#[derive(Debug)]
+pub struct Error {
+    /// The name of the entry whose value was invalid.
+    pub entry: &'static str,
+    /// The name of the layer which provided the invalid value.
+    pub layer: &'static str,
+    /// The error produced by the layer.
+    pub inner: LayerError,
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid value for entry `{}` from layer `{}`: {}",
+            self.entry, self.layer, self.inner,
+        )
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&*self.inner)
+    }
+}
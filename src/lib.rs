@@ -34,7 +34,8 @@
 //! // a module generated by the `#[derive(ConfigTable)]`. In most cases, it's desirable
 //! // to reexport the contents of the module in a public module with a different name and
 //! // some documentation, or simply in the containing module if you want the entry
-//! // identifiers to be in the same module as the config table.
+//! // identifiers to be in the same module as the config table, which is what
+//! // `#[snec(reexport_entries)]` does.
 //! let mut handle = config_table.get_handle_to::<entries::InWhichCountry>();
 //! // After we got the handle, we can use it to get a
 //! // mutable reference to the field and modify it:
@@ -47,7 +48,7 @@
 //! // that we'll look into in the next example. Since we don't have any, it won't really
 //! // hurt if we did this as well:
 //! {
-//!     let in_which_country = handle.modify_silently();
+//!     let mut in_which_country = handle.as_silent();
 //!     *in_which_country = "Australia".to_string();
 //! }
 //! ```
@@ -94,20 +95,266 @@
 //! }
 //! // When the scope ends, the `which_year` guard is dropped and the receiver is informed.
 //! ```
+//! Tuple structs work as well, with the entries named after the positions of the fields:
+//! ```
+//! use snec::{ConfigTable, Entry, GetExt as _};
+//! #[derive(ConfigTable)]
+//! struct Timeouts(
+//!     #[snec]
+//!     u64,
+//!     #[snec(entry(Retries))]
+//!     u32,
+//! );
+//! assert_eq!(entries::Field0::NAME, "0");
+//! assert_eq!(entries::Retries::NAME, "1");
+//!
+//! let mut config_table = Timeouts(30, 3);
+//! config_table.get_handle_to::<entries::Retries>().set(5);
+//! assert_eq!(config_table.1, 5);
+//! ```
+//! In large tables, forgetting the `#[snec]` attribute on a field is easy. `#[snec(all_fields)]` makes every field an entry, with `#[snec(skip)]` opting individual fields out:
+//! ```
+//! use snec::ConfigTable;
+//! #[derive(ConfigTable)]
+//! #[snec(all_fields)]
+//! struct MyConfigTable {
+//!     which_year: i64,
+//!     #[snec(default = "Jeremy".to_string())]
+//!     who: String,
+//!     #[snec(skip)]
+//!     last_saved: Option<std::time::SystemTime>,
+//! }
+//! assert_eq!(MyConfigTable::ENTRIES.len(), 2);
+//! ```
+//! A malformed `#[snec(...)]` attribute, such as one with a misspelled command, is a compile error rather than a field which silently isn't an entry:
+//! ```compile_fail
+//! use snec::ConfigTable;
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec(entyr)]
+//!     which_year: i64,
+//! }
+//! ```
+//! Fields can be gated by `#[cfg(...)]`, in which case so are their entries:
+//! ```
+//! use snec::ConfigTable;
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     which_year: i64,
+//!     #[cfg(feature = "experimental")]
+//!     #[snec]
+//!     experimental_mode: bool,
+//! }
+//! assert_eq!(MyConfigTable::ENTRIES.len(), 1);
+//! ```
+//! The name of an entry, which is what loaders and other external tooling see, can be changed independently of the name of the field:
+//! ```
+//! use snec::{ConfigTable, Entry};
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec(entry, name = "max-connections")]
+//!     max_conns: u32,
+//! }
+//! assert_eq!(entries::MaxConns::NAME, "max-connections");
+//! ```
+//! The entry types can also be generated into nested modules, or next to the struct itself with `entry_module(self)`:
+//! ```
+//! use snec::{ConfigTable, GetExt as _};
+//! #[derive(ConfigTable)]
+//! #[snec(entry_module(config::entries))]
+//! struct Network {
+//!     #[snec]
+//!     port: u16,
+//! }
+//! #[derive(ConfigTable)]
+//! #[snec(entry_module(self))]
+//! struct Audio {
+//!     #[snec]
+//!     volume: f32,
+//! }
+//! let mut network = Network {port: 8080};
+//! network.get_handle_to::<config::entries::Port>().set(8081);
+//! let mut audio = Audio {volume: 1.0};
+//! audio.get_handle_to::<Volume>().set(0.5);
+//! ```
+//! Alternatively, `#[snec(reexport_entries)]` keeps the module, but also re-exports its contents next to the struct:
+//! ```
+//! use snec::{ConfigTable, GetExt as _};
+//! #[derive(ConfigTable)]
+//! #[snec(reexport_entries)]
+//! struct Network {
+//!     #[snec]
+//!     port: u16,
+//! }
+//! let mut network = Network {port: 8080};
+//! network.get_handle_to::<Port>().set(8081);
+//! network.get_handle_to::<entries::Port>().set(8082);
+//! ```
+//!
+//! # Minimum supported Rust version
+//! Snec builds on Rust 1.71 and newer with the default features. Some optional integrations require a newer compiler, as dictated by the libraries they're built on. Running the test suite requires Rust 1.85, since the smol and async-std executors it uses to exercise the async APIs need it.
 
 #![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
 mod entry;
 mod handle;
+#[cfg(feature = "alloc")]
+mod loader;
 mod receiver;
+mod registry;
+mod reentrancy;
 pub use entry::*;
 pub use handle::*;
+#[cfg(feature = "alloc")]
+pub use loader::*;
 pub use receiver::*;
+pub use registry::*;
+pub use reentrancy::*;
 
+pub mod split;
+pub mod dispatch;
+pub mod patch;
+pub mod builder;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "critical-section")]
+pub mod critical_section;
+#[cfg(feature = "defmt")]
+pub mod defmt;
+#[cfg(feature = "std")]
+pub mod env;
+#[cfg(feature = "std")]
+pub mod layers;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod rollout;
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "toml")]
+pub mod toml;
+#[cfg(feature = "toml")]
+pub mod persist;
+#[cfg(feature = "hot-reload")]
+pub mod watch;
+#[cfg(feature = "json-schema")]
+pub mod schema;
+#[cfg(feature = "clap")]
+pub mod clap;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "etcd")]
+pub mod etcd;
+#[cfg(feature = "consul")]
+pub mod consul;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "flags")]
+pub mod flags;
+#[cfg(feature = "zbus")]
+pub mod dbus;
+#[cfg(feature = "interprocess")]
+pub mod ipc;
+#[cfg(feature = "shm")]
+pub mod shm;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+#[cfg(feature = "egui")]
+pub mod egui;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "events")]
+pub mod events;
+#[cfg(feature = "stream")]
+pub mod stream;
+#[cfg(feature = "actix")]
+pub mod actix;
+#[cfg(feature = "flume")]
+pub mod flume;
+#[cfg(feature = "crossbeam-channel")]
+pub mod crossbeam_channel;
+
+/// Items used by the code generated by `#[derive(ConfigTable)]`, which are not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    /// Proof that a method is being called by Snec itself. It can't be constructed outside of the crate, so trait methods taking it can be implemented by generated code but only called by Snec.
+    pub struct Token(());
+    impl Token {
+        #[inline(always)]
+        pub(crate) fn new() -> Self {
+            Self(())
+        }
+    }
+
+    use super::{Entry, Get, EntryVisitor, EntryTypeVisitor, split::{Cons, Nil}};
+
+    /// A type-level list of entries, made of `Cons` and `Nil`, whose handles can be passed to the visitor `V`. The derive macro lists the entries of a table this way, since the elements of the list can be gated by `#[cfg(...)]` through type aliases, unlike the bounds of a where clause.
+    pub trait VisitList<T: ?Sized, V: ?Sized> {
+        fn visit_entries(table: &mut T, visitor: &mut V);
+    }
+    impl<T: ?Sized, V: ?Sized> VisitList<T, V> for Nil {
+        #[inline(always)]
+        fn visit_entries(_: &mut T, _: &mut V) {}
+    }
+    impl<T, V, E, Es> VisitList<T, V> for Cons<E, Es>
+    where
+        T: Get<E> + ?Sized,
+        V: EntryVisitor<E> + ?Sized,
+        E: Entry,
+        Es: VisitList<T, V> {
+        #[inline]
+        fn visit_entries(table: &mut T, visitor: &mut V) {
+            <V as EntryVisitor<E>>::visit(visitor, <T as Get<E>>::get_handle(table));
+            Es::visit_entries(table, visitor);
+        }
+    }
+    /// Like `VisitList`, but for type visitors.
+    pub trait VisitTypeList<V: ?Sized> {
+        fn visit_entry_types(visitor: &mut V);
+    }
+    impl<V: ?Sized> VisitTypeList<V> for Nil {
+        #[inline(always)]
+        fn visit_entry_types(_: &mut V) {}
+    }
+    impl<V, E, Es> VisitTypeList<V> for Cons<E, Es>
+    where
+        V: EntryTypeVisitor<E> + ?Sized,
+        E: Entry,
+        Es: VisitTypeList<V> {
+        #[inline]
+        fn visit_entry_types(visitor: &mut V) {
+            <V as EntryTypeVisitor<E>>::visit_type(visitor);
+            Es::visit_entry_types(visitor);
+        }
+    }
+}
+
+/// Locks the mutex, recovering the guard if another thread panicked while holding it.
+///
+/// Only used for the crate's own queues, buffers and bookkeeping, which are updated by pushing, removing or replacing values as a whole and never notify receivers or modify config tables with the lock held, so a panic can't leave them half-updated. Locks which guard receivers or config tables respect the poisoning instead.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn lock_ignoring_poison<T: ?Sized>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(feature = "zeroize")]
+pub extern crate zeroize;
 #[cfg(feature = "macros")]
 pub extern crate snec_macros as macros;
 #[doc(inline)]
+#[cfg(feature = "macros")]
 pub use macros::*;
 
 // To make derive macros work when called from inside of Snec itself.
@@ -0,0 +1,258 @@
+use core::{
+    any::{Any, TypeId},
+    fmt::{self, Formatter, Debug, Display},
+    mem,
+};
+use alloc::{boxed::Box, vec::Vec};
+use super::{Entry, Receiver, Handle, EntryVisitor, VisitEntries, ValidateTable};
+
+/// Trait for sources of stored values which can be written into config tables by a [`Loader`].
+///
+/// The error type is shared by all entries, while the lookup itself is implemented separately for every entry by [`LookupEntry`], which allows it to put its own bounds on the type of the entry.
+///
+/// [`Loader`]: struct.Loader.html " "
+/// [`LookupEntry`]: trait.LookupEntry.html " "
+pub trait Lookup {
+    /// The error produced when a stored value could not be read or converted to the type of its entry.
+    type Error;
+}
+/// Trait for sources of stored values which can look up the value of the entry `E`.
+pub trait LookupEntry<E: Entry>: Lookup {
+    /// Returns the stored value of the entry, `None` if it isn't stored, or the error which prevented it from being read.
+    fn lookup(&mut self) -> Option<Result<E::Data, Self::Error>>;
+}
+
+/// A [visitor] which writes the values looked up in a [source] into the entries of a config table, notifying the receivers of the entries which changed.
+///
+/// Entries which aren't stored are left untouched. The values are written without notifying anyone at first, and the table is then [validated]: if it's still valid, the receivers of the entries which changed are notified, otherwise the previous values are restored without notifying any receivers. The same happens if a value fails to be looked up, so that the table is either changed fully or not at all. This is the loader used by the modules reading config tables from TOML documents, environment variables, databases, snapshots and remote stores, which only implement the lookup.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, Entry, Loader, Lookup, LookupEntry};
+/// #[derive(ConfigTable)]
+/// struct Window {
+///     #[snec]
+///     width: u32,
+///     #[snec]
+///     height: u32,
+/// }
+/// /// Stores every entry as its name followed by a colon and its value, one per line.
+/// struct Lines<'a>(&'a str);
+/// impl Lookup for Lines<'_> {
+///     type Error = std::num::ParseIntError;
+/// }
+/// impl<E: Entry<Data = u32>> LookupEntry<E> for Lines<'_> {
+///     fn lookup(&mut self) -> Option<Result<u32, Self::Error>> {
+///         self.0.lines()
+///             .find_map(|line| line.strip_prefix(E::NAME)?.strip_prefix(':'))
+///             .map(str::parse)
+///     }
+/// }
+/// let mut window = Window {width: 640, height: 480};
+/// let changed = Loader::new(Lines("width:1280\nheight:480")).load_into(&mut window);
+/// assert_eq!(changed, Ok(1));
+/// assert_eq!(window.width, 1280);
+/// ```
+///
+/// [visitor]: trait.EntryVisitor.html " "
+/// [source]: trait.LookupEntry.html " "
+/// [validated]: trait.ValidateTable.html " "
+pub struct Loader<S: Lookup> {
+    source: S,
+    changed: usize,
+    error: Option<S::Error>,
+    pass: Pass,
+    // The number of entries visited in the current pass.
+    position: usize,
+    // One record for every entry which changed, in the order in which the entries are visited.
+    staged: Vec<Staged>,
+    // The index of the next record to be finished.
+    next: usize,
+}
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Pass {
+    Stage,
+    Commit,
+    Rollback,
+}
+struct Staged {
+    position: usize,
+    // The address and type of the field, which identify it if it has several entries.
+    field: (usize, TypeId),
+    // Only kept for the first entry of the field which changed, since restoring that value undoes the changes of the others.
+    previous: Option<Box<dyn Any>>,
+}
+impl<S: Lookup> Loader<S> {
+    /// Creates a loader which writes the values from the specified source.
+    #[inline(always)]
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            changed: 0,
+            error: None,
+            pass: Pass::Stage,
+            position: 0,
+            staged: Vec::new(),
+            next: 0,
+        }
+    }
+    /// Returns a reference to the source.
+    #[inline(always)]
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+    /// Returns a mutable reference to the source.
+    #[inline(always)]
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+    /// Writes the values into the table and validates it, returning the number of entries which changed, the first error produced by the source or the error produced by the [table validator]. The table is left unchanged if an error is returned.
+    ///
+    /// [table validator]: trait.ValidateTable.html " "
+    pub fn load_into<T>(mut self, table: &mut T) -> Result<usize, LoadError<S::Error, T::Error>>
+    where T: VisitEntries<Self> + ValidateTable {
+        table.visit_entries(&mut self);
+        let result = match self.error.take() {
+            Some(error) => Err(LoadError::Source(error)),
+            None if self.staged.is_empty() => Ok(()),
+            None => table.validate_table().map_err(LoadError::InvalidTable),
+        };
+        if !self.staged.is_empty() {
+            self.pass = if result.is_ok() {Pass::Commit} else {Pass::Rollback};
+            self.position = 0;
+            table.visit_entries(&mut self);
+        }
+        result.map(|()| self.changed)
+    }
+    fn stage<E, R>(&mut self, mut handle: Handle<'_, E, R>)
+    where
+        E: Entry,
+        E::Data: PartialEq + 'static,
+        R: Receiver<E>,
+        S: LookupEntry<E> {
+        if self.error.is_some() {
+            return;
+        }
+        let value = match self.source.lookup() {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                self.error = Some(error);
+                return;
+            },
+            None => return,
+        };
+        let target = handle.target_mut();
+        if *target == value {
+            return;
+        }
+        let field = (target as *mut E::Data as usize, TypeId::of::<E::Data>());
+        let mut previous = mem::replace(target, value);
+        let previous = if self.staged.iter().any(|staged| staged.field == field) {
+            E::erase(&mut previous);
+            None
+        } else {
+            Some(Box::new(previous) as Box<dyn Any>)
+        };
+        self.staged.push(Staged {position: self.position, field, previous});
+        self.changed += 1;
+    }
+    fn finish<E, R>(&mut self, mut handle: Handle<'_, E, R>)
+    where
+        E: Entry,
+        E::Data: 'static,
+        R: Receiver<E> {
+        let staged = match self.staged.get_mut(self.next) {
+            Some(staged) if staged.position == self.position => staged,
+            _ => return,
+        };
+        self.next += 1;
+        let previous = staged.previous.take().map(|previous| {
+            *previous.downcast::<E::Data>().expect("the entries are visited in the same order")
+        });
+        match (self.pass, previous) {
+            (Pass::Commit, previous) => {
+                if let Some(mut previous) = previous {
+                    E::erase(&mut previous);
+                }
+                handle.notify();
+            },
+            (_, Some(previous)) => {
+                let mut rejected = mem::replace(handle.target_mut(), previous);
+                E::erase(&mut rejected);
+            },
+            (_, None) => {},
+        }
+    }
+}
+impl<E, S> EntryVisitor<E> for Loader<S>
+where
+    E: Entry,
+    E::Data: PartialEq + 'static,
+    S: LookupEntry<E> {
+    fn visit<R: Receiver<E>>(&mut self, handle: Handle<'_, E, R>) {
+        if self.pass == Pass::Stage {
+            self.stage(handle);
+        } else {
+            self.finish(handle);
+        }
+        self.position += 1;
+    }
+}
+impl<S> Debug for Loader<S>
+where
+    S: Lookup + Debug,
+    S::Error: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Loader")
+            .field("source", &self.source)
+            .field("changed", &self.changed)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+/// The error produced by [`Loader::load_into`].
+///
+/// [`Loader::load_into`]: struct.Loader.html#method.load_into " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LoadError<S, T> {
+    /// A value failed to be looked up in the source.
+    Source(S),
+    /// The table with the loaded values was rejected by its [table validator].
+    ///
+    /// [table validator]: trait.ValidateTable.html " "
+    InvalidTable(T),
+}
+impl<S, T> LoadError<S, T> {
+    /// Converts the error produced by the source using the specified function, leaving the error produced by the table validator as is.
+    #[inline]
+    pub fn map_source<S2>(self, f: impl FnOnce(S) -> S2) -> LoadError<S2, T> {
+        match self {
+            Self::Source(error) => LoadError::Source(f(error)),
+            Self::InvalidTable(error) => LoadError::InvalidTable(error),
+        }
+    }
+}
+impl<S: Display, T: Display> Display for LoadError<S, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source(error) => Display::fmt(error, f),
+            Self::InvalidTable(error) => write!(f, "invalid config table: {}", error),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl<S, T> std::error::Error for LoadError<S, T>
+where
+    S: std::error::Error + 'static,
+    T: Debug + Display {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Source(error) => Some(error),
+            Self::InvalidTable(..) => None,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Exporting changes and values of entries as [Prometheus] metrics.
+//!
+//! [`Metrics`] is a [receiver] which counts the changes of every entry it's attached to in the `snec_entry_changes_total` counter, labelled with the [name] of the entry, so that configuration drift shows up on dashboards. For numeric entries, the receiver returned by [`Metrics::gauged`] additionally exports the current value in the `snec_entry_value` gauge, with the same label. Since the values of [secret] entries must not end up in monitoring systems, they are only counted.
+//!
+//! `Metrics` is a [collector], and clones share the metrics, so one clone is registered in a [registry] while the others are used as receivers. Gauges only appear once their entries are changed or exported with [`GaugeReceiver::export`], which is useful for exporting the initial values.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, metrics::{GaugeReceiver, Metrics}};
+//! use prometheus::{Encoder as _, Registry, TextEncoder};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.metrics.clone()}: Metrics))]
+//! struct Config {
+//!     #[snec(entry, receiver({self.metrics.gauged()}: GaugeReceiver))]
+//!     max_connections: u32,
+//!     #[snec]
+//!     log_level: String,
+//!     metrics: Metrics,
+//! }
+//! let metrics = Metrics::new();
+//! let registry = Registry::new();
+//! registry.register(Box::new(metrics.clone()))?;
+//! let mut config = Config {max_connections: 100, log_level: "info".to_string(), metrics};
+//! config.get_handle_to::<entries::MaxConnections>().set(200);
+//! config.get_handle_to::<entries::LogLevel>().set("debug".to_string());
+//! let mut text = Vec::new();
+//! TextEncoder::new().encode(&registry.gather(), &mut text)?;
+//! let text = String::from_utf8(text)?;
+//! assert!(text.contains(r#"snec_entry_changes_total{entry="log_level"} 1"#));
+//! assert!(text.contains(r#"snec_entry_value{entry="max_connections"} 200"#));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [Prometheus]: https://prometheus.io " "
+//! [`Metrics`]: struct.Metrics.html " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`Metrics::gauged`]: struct.Metrics.html#method.gauged " "
+//! [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [collector]: https://docs.rs/prometheus/*/prometheus/core/trait.Collector.html " "
+//! [registry]: https://docs.rs/prometheus/*/prometheus/struct.Registry.html " "
+//! [`GaugeReceiver::export`]: struct.GaugeReceiver.html#method.export " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    time::Duration,
+};
+use prometheus::{
+    core::{Collector, Desc},
+    proto::MetricFamily,
+    GaugeVec, IntCounterVec, Opts,
+};
+use super::{Entry, Receiver};
+
+/// The name of the label holding the name of the entry.
+const LABEL: &str = "entry";
+
+/// A [receiver] counting the changes of entries, and a [collector] exporting the counters and the gauges of the [`GaugeReceiver`]s created from it. Clones share the metrics. See the [module-level documentation] for more.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [collector]: https://docs.rs/prometheus/*/prometheus/core/trait.Collector.html " "
+/// [`GaugeReceiver`]: struct.GaugeReceiver.html " "
+/// [module-level documentation]: index.html " "
+#[derive(Clone)]
+pub struct Metrics {
+    changes: IntCounterVec,
+    values: GaugeVec,
+}
+impl Metrics {
+    /// Creates the metrics with the `snec` namespace.
+    #[inline]
+    pub fn new() -> Self {
+        // The names are valid, so creating the metrics can't fail.
+        Self::with_namespace("snec").unwrap_or_else(|_| unreachable!())
+    }
+    /// Creates the metrics with the specified namespace instead of `snec`, which is used as the prefix of their names. Fails if the namespace is not a valid metric name.
+    pub fn with_namespace(namespace: &str) -> Result<Self, prometheus::Error> {
+        let changes = Opts::new("entry_changes_total", "Number of changes of config entries.").namespace(namespace);
+        let values = Opts::new("entry_value", "Current values of numeric config entries.").namespace(namespace);
+        Ok(Self {
+            changes: IntCounterVec::new(changes, &[LABEL])?,
+            values: GaugeVec::new(values, &[LABEL])?,
+        })
+    }
+    /// Creates a receiver which also exports the values of numeric entries as gauges.
+    #[inline]
+    pub fn gauged(&self) -> GaugeReceiver {
+        GaugeReceiver {metrics: self.clone()}
+    }
+    /// Returns the number of changes of the entry counted so far.
+    #[inline]
+    pub fn changes<E: Entry>(&self) -> u64 {
+        self.changes.with_label_values(&[E::NAME]).get()
+    }
+    /// Returns the counters of changes, labelled with the names of their entries.
+    #[inline(always)]
+    pub fn change_counters(&self) -> &IntCounterVec {
+        &self.changes
+    }
+    /// Returns the gauges of values, labelled with the names of their entries.
+    #[inline(always)]
+    pub fn value_gauges(&self) -> &GaugeVec {
+        &self.values
+    }
+}
+impl Default for Metrics {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E: Entry> Receiver<E> for Metrics {
+    #[inline]
+    fn receive(&mut self, _new_value: &E::Data) {
+        self.changes.with_label_values(&[E::NAME]).inc();
+    }
+}
+impl Collector for Metrics {
+    #[inline]
+    fn desc(&self) -> Vec<&Desc> {
+        let mut desc = self.changes.desc();
+        desc.extend(self.values.desc());
+        desc
+    }
+    #[inline]
+    fn collect(&self) -> Vec<MetricFamily> {
+        let mut families = self.changes.collect();
+        families.extend(self.values.collect());
+        families
+    }
+}
+impl Debug for Metrics {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Metrics").finish_non_exhaustive()
+    }
+}
+
+/// A [receiver] which counts the changes of numeric entries like [`Metrics`] and exports their values as gauges, created by [`Metrics::gauged`].
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [`Metrics`]: struct.Metrics.html " "
+/// [`Metrics::gauged`]: struct.Metrics.html#method.gauged " "
+#[derive(Clone, Debug)]
+pub struct GaugeReceiver {
+    metrics: Metrics,
+}
+impl GaugeReceiver {
+    /// Exports the value of the entry without counting a change, for exporting the initial values of entries. Does nothing for [secret] entries.
+    ///
+    /// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+    #[inline]
+    pub fn export<E>(&self, value: &E::Data)
+    where
+        E: Entry,
+        E::Data: GaugeValue {
+        if !E::SECRET {
+            self.metrics.values.with_label_values(&[E::NAME]).set(value.gauge_value());
+        }
+    }
+    /// Returns the metrics the receiver updates.
+    #[inline(always)]
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+}
+impl<E> Receiver<E> for GaugeReceiver
+where
+    E: Entry,
+    E::Data: GaugeValue {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        Receiver::<E>::receive(&mut self.metrics, new_value);
+        self.export::<E>(new_value);
+    }
+}
+
+/// Trait for the types of entries whose values can be exported as gauges.
+pub trait GaugeValue {
+    /// Returns the value of the gauge, which may lose precision for large integers.
+    fn gauge_value(&self) -> f64;
+}
+macro_rules! impl_gauge_value {
+    ($($ty:ty),+) => {$(
+        impl GaugeValue for $ty {
+            #[inline(always)]
+            #[allow(clippy::cast_lossless, clippy::cast_precision_loss)]
+            fn gauge_value(&self) -> f64 {
+                *self as f64
+            }
+        }
+    )+};
+}
+impl_gauge_value!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+impl GaugeValue for bool {
+    /// Exports `true` as 1 and `false` as 0.
+    #[inline(always)]
+    fn gauge_value(&self) -> f64 {
+        if *self { 1.0 } else { 0.0 }
+    }
+}
+impl GaugeValue for Duration {
+    /// Exports the duration in seconds, as is customary for Prometheus.
+    #[inline(always)]
+    fn gauge_value(&self) -> f64 {
+        self.as_secs_f64()
+    }
+}
@@ -0,0 +1,222 @@
+//! Synchronizing config tables over [MQTT].
+//!
+//! Every entry is published as a retained message to a topic made of a common prefix followed by the [name] of the entry, with the value serialized as JSON, such as `fleet/device-42/config/sampling_rate`, and every device subscribed to the topics under the prefix applies the messages to its own table. Since the broker keeps the last retained message of each topic, devices receive the current configuration of the fleet when they connect, and the changes made afterwards while they stay connected. [`MqttSource`] and [`MqttSink`] are the [remote backends] for MQTT: the source subscribes to the topics and receives the messages, and the sink publishes them. See the documentation of the [`remote`] module for how the synchronization works.
+//!
+//! The source owns the [event loop] of the client, which also sends the messages published by the sink, and thus has to be polled continuously with [`apply_next`] for local changes to reach the broker. The source subscribes to the topics whenever it connects, so that the retained messages are received again after reconnecting, making up for the changes missed while disconnected. The broker delivers retained messages after acknowledging the subscription, which is why [`load_into`] mostly finds no values and the retained ones are applied by the following calls to [`apply_next`]. Messages with empty payloads, which delete retained messages, are ignored, and so are the messages each device receives for its own changes.
+//!
+//! The client is asynchronous and requires a Tokio runtime.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, GetExt as _, remote::RemoteReceiver};
+//! use rumqttc::{AsyncClient, MqttOptions};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.remote.clone()}: RemoteReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     sampling_rate: u32,
+//!     remote: RemoteReceiver,
+//! }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let (client, event_loop) = AsyncClient::new(MqttOptions::new("device-42", "broker.local", 1883), 16);
+//! let (mut sync, writer) = snec::mqtt::sync(client, event_loop, "fleet/device-42/config/");
+//! let mut table = MyConfigTable {sampling_rate: 10, remote: sync.receiver()};
+//! sync.load_into(&mut table).await?;
+//! // Usually spawned as a separate task.
+//! let writer = writer.run();
+//! // Published as a retained message.
+//! table.get_handle_to::<entries::SamplingRate>().set(20);
+//! while let Some(changed) = sync.apply_next(&mut table).await? {
+//!     println!("{} entries changed remotely", changed);
+//! }
+//! # drop(writer);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [MQTT]: https://mqtt.org " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`MqttSource`]: struct.MqttSource.html " "
+//! [`MqttSink`]: struct.MqttSink.html " "
+//! [remote backends]: ../remote/trait.Source.html " "
+//! [`remote`]: ../remote/index.html " "
+//! [event loop]: https://docs.rs/rumqttc/0.24/rumqttc/struct.EventLoop.html " "
+//! [`apply_next`]: ../remote/struct.RemoteSync.html#method.apply_next " "
+//! [`load_into`]: ../remote/struct.RemoteSync.html#method.load_into " "
+
+use core::fmt::{self, Formatter, Debug};
+use rumqttc::{
+    AsyncClient,
+    ClientError,
+    ConnectionError,
+    Event,
+    EventLoop,
+    Packet,
+    QoS,
+    Request,
+    Subscribe,
+};
+use super::remote::{self, BoxFuture, Values, RemoteSync, RemoteWriter, Source, Sink};
+
+/// Synchronizes config tables over MQTT.
+pub type MqttSync = RemoteSync<MqttSource>;
+/// Publishes local changes over MQTT.
+pub type MqttWriter = RemoteWriter<MqttSink>;
+/// An error which occurred while receiving changes over MQTT.
+pub type Error = remote::Error<ConnectionError>;
+
+/// Creates a synchronizer for the entries published to the topics under the specified prefix, along with the writer which publishes local changes to them.
+#[inline]
+pub fn sync(client: AsyncClient, event_loop: EventLoop, prefix: impl Into<String>) -> (MqttSync, MqttWriter) {
+    let prefix = prefix.into();
+    let sink = MqttSink::new(client, prefix.clone());
+    RemoteSync::new(MqttSource::new(event_loop, prefix), sink)
+}
+
+/// A [source] which receives the values of entries published to the topics under a prefix.
+///
+/// [source]: ../remote/trait.Source.html " "
+pub struct MqttSource {
+    event_loop: EventLoop,
+    prefix: String,
+    qos: QoS,
+    connected: bool,
+}
+impl MqttSource {
+    /// Creates a source for the entries published to the topics under the specified prefix, which should end with `/`. The event loop is polled by the source, and subscribes to the topics when it connects.
+    #[inline]
+    pub fn new(event_loop: EventLoop, prefix: impl Into<String>) -> Self {
+        Self {event_loop, prefix: prefix.into(), qos: QoS::AtLeastOnce, connected: false}
+    }
+    /// Sets the quality of service of the subscription, which is "at least once" by default.
+    #[inline]
+    pub fn with_qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+    /// Returns the prefix of the topics of the entries.
+    #[inline(always)]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+    /// Returns a reference to the event loop of the client.
+    #[inline(always)]
+    pub fn event_loop(&self) -> &EventLoop {
+        &self.event_loop
+    }
+    /// Queues the subscription to the topics, to be sent before any other requests.
+    fn subscribe(&mut self) {
+        let subscription = Subscribe::new(format!("{}#", self.prefix), self.qos);
+        self.event_loop.pending.push_back(Request::Subscribe(subscription));
+    }
+    /// Polls the event loop once, collecting the received value if there is one. Returns whether the subscription was acknowledged, or `None` if the client was dropped.
+    async fn poll(&mut self, values: &mut Values) -> Result<Option<bool>, ConnectionError> {
+        let event = match self.event_loop.poll().await {
+            Ok(event) => event,
+            Err(ConnectionError::RequestsDone) => return Ok(None),
+            Err(error) => {
+                self.connected = false;
+                return Err(error);
+            },
+        };
+        match event {
+            Event::Incoming(Packet::ConnAck(_)) => {
+                self.connected = true;
+                self.subscribe();
+            },
+            Event::Incoming(Packet::Publish(message)) => {
+                if let Some(name) = message.topic.strip_prefix(&self.prefix) {
+                    if !message.payload.is_empty() {
+                        values.insert(name.to_string(), message.payload.to_vec());
+                    }
+                }
+            },
+            Event::Incoming(Packet::SubAck(_)) => return Ok(Some(true)),
+            _ => {},
+        }
+        Ok(Some(false))
+    }
+}
+impl Source for MqttSource {
+    type Error = ConnectionError;
+    fn load(&mut self) -> BoxFuture<'_, Result<Values, Self::Error>> {
+        Box::pin(async move {
+            if self.connected {
+                // Subscribing again makes the broker send the retained messages again.
+                self.subscribe();
+            }
+            let mut values = Values::new();
+            while let Some(false) = self.poll(&mut values).await? {}
+            Ok(values)
+        })
+    }
+    fn next_changes(&mut self) -> BoxFuture<'_, Result<Option<Values>, Self::Error>> {
+        Box::pin(async move {
+            let mut values = Values::new();
+            loop {
+                if self.poll(&mut values).await?.is_none() {
+                    return Ok(None);
+                }
+                if !values.is_empty() {
+                    return Ok(Some(values));
+                }
+            }
+        })
+    }
+}
+impl Debug for MqttSource {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MqttSource")
+            .field("prefix", &self.prefix)
+            .field("qos", &self.qos)
+            .field("connected", &self.connected)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [sink] which publishes the values of entries as retained messages to the topics under a prefix.
+///
+/// [sink]: ../remote/trait.Sink.html " "
+#[derive(Clone)]
+pub struct MqttSink {
+    client: AsyncClient,
+    prefix: String,
+    qos: QoS,
+}
+impl MqttSink {
+    /// Creates a sink which publishes to the topics under the specified prefix, which should end with `/`.
+    #[inline]
+    pub fn new(client: AsyncClient, prefix: impl Into<String>) -> Self {
+        Self {client, prefix: prefix.into(), qos: QoS::AtLeastOnce}
+    }
+    /// Sets the quality of service of the published messages, which is "at least once" by default.
+    #[inline]
+    pub fn with_qos(mut self, qos: QoS) -> Self {
+        self.qos = qos;
+        self
+    }
+    /// Returns the prefix of the topics of the entries.
+    #[inline(always)]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+}
+impl Sink for MqttSink {
+    type Error = ClientError;
+    fn store(&mut self, entry_name: &'static str, value: Vec<u8>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let topic = format!("{}{}", self.prefix, entry_name);
+            self.client.publish(topic, self.qos, true, value).await
+        })
+    }
+}
+impl Debug for MqttSink {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MqttSink")
+            .field("prefix", &self.prefix)
+            .field("qos", &self.qos)
+            .finish_non_exhaustive()
+    }
+}
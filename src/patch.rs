@@ -0,0 +1,165 @@
+//! Partial updates of config tables, which change some entries and leave the rest as they are.
+//!
+//! `#[snec(patch)]` generates a companion struct for a config table, named after it with `Patch` appended unless another name is specified with `#[snec(patch(MyPatchType))]`. The patch struct has a public field of type `Option<T>` for every field of the config table which has an entry, where `None` means that the entry is left unchanged, and implements [`Patch`] and `Default`. Fields of the config table without entries, such as ones used as receivers, are left out, which shifts the indices of the fields of patches for tuple structs.
+//!
+//! Attributes can be added to the patch struct with `#[snec(patch_attributes(...))]` on the config table, and to the fields of the patch struct with the same command on the corresponding fields of the config table, which allows patches to be deserialized from partial updates received over the network, e.g. with `#[snec(patch_attributes(#[derive(serde::Deserialize)]))]`. The documentation comments and `#[cfg(...)]` attributes of the fields are copied to the patch struct.
+//!
+//! Patches are applied with [`ApplyPatch::apply_patch`], which writes every field which is `Some` into the table and [validates] it, notifying only the receivers of the entries which were written, and returns the information about those entries. If the table is no longer valid, the previous values are restored without notifying any receivers. Patches whose fields all have types implementing `PartialEq` also implement [`ComparingPatch`], and can be applied with [`ApplyPatch::apply_patch_changed`] instead, which skips the fields which are equal to the current values, so that neither their receivers are notified nor are they reported.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, patch::Patch};
+//! #[derive(ConfigTable)]
+//! #[snec(patch, patch_attributes(#[derive(Debug, Clone, PartialEq)]))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     window_title: String,
+//!     #[snec]
+//!     vsync: bool,
+//! }
+//! let mut patch = MyConfigTablePatch::new();
+//! assert!(patch.is_empty());
+//! patch.vsync = Some(false);
+//! assert!(!patch.is_empty());
+//! assert_eq!(patch, MyConfigTablePatch {window_title: None, vsync: Some(false)});
+//! ```
+//! Applying a patch:
+//! ```
+//! use snec::{ConfigTable, Receiver, Entry, patch::ApplyPatch as _};
+//! use std::{rc::Rc, cell::Cell};
+//! struct CountingReceiver(Rc<Cell<u32>>);
+//! impl<E: Entry> Receiver<E> for CountingReceiver {
+//!     fn receive(&mut self, _new_value: &E::Data) {
+//!         self.0.set(self.0.get() + 1);
+//!     }
+//! }
+//! #[derive(ConfigTable)]
+//! #[snec(patch, receiver({CountingReceiver(Rc::clone(&self.notifications))}: CountingReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     window_title: String,
+//!     #[snec]
+//!     vsync: bool,
+//!     notifications: Rc<Cell<u32>>,
+//! }
+//! let mut table = MyConfigTable {
+//!     window_title: "Untitled".to_string(),
+//!     vsync: true,
+//!     notifications: Rc::new(Cell::new(0)),
+//! };
+//! let changed = table.apply_patch(MyConfigTablePatch {window_title: None, vsync: Some(false)}).unwrap();
+//! assert_eq!(changed, [MyConfigTable::entry_info("vsync").unwrap()]);
+//! assert_eq!(table.notifications.get(), 1);
+//! assert!(!table.vsync);
+//!
+//! // Values which are already set don't count as changes when comparing.
+//! let changed = table.apply_patch_changed(MyConfigTablePatch {
+//!     window_title: Some("Snec".to_string()),
+//!     vsync: Some(false),
+//! }).unwrap();
+//! assert_eq!(changed, [MyConfigTable::entry_info("window_title").unwrap()]);
+//! assert_eq!(table.notifications.get(), 2);
+//! ```
+//!
+//! [`Patch`]: trait.Patch.html " "
+//! [validates]: ../trait.ValidateTable.html " "
+//! [`ComparingPatch`]: trait.ComparingPatch.html " "
+//! [`ApplyPatch::apply_patch`]: trait.ApplyPatch.html#method.apply_patch " "
+//! [`ApplyPatch::apply_patch_changed`]: trait.ApplyPatch.html#method.apply_patch_changed " "
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use super::{ConfigTable, EntryInfo, ValidateTable};
+
+/// Trait for partial updates of config tables, which are implemented by the structs generated by `#[snec(patch)]`.
+///
+/// See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+pub trait Patch: Default {
+    /// The config table which the patch updates.
+    type Table: ConfigTable + ValidateTable;
+    /// Returns `true` if the patch doesn't change any entries.
+    fn is_empty(&self) -> bool;
+    /// Writes every field which is `Some` to the table and [validates] it. If the table is still valid, the receivers of the entries of the written fields are notified, and `changed` is called with the information about each of those entries. Otherwise, the previous values are restored without notifying any receivers and the error is returned. The receivers of the entries whose fields are `None` are not notified.
+    ///
+    /// If a field has several entries, the receivers of all of them are notified.
+    ///
+    /// [validates]: ../trait.ValidateTable.html " "
+    fn apply_with<F>(self, table: &mut Self::Table, changed: F) -> Result<(), <Self::Table as ValidateTable>::Error>
+    where F: FnMut(&'static EntryInfo);
+}
+
+/// Trait for [patches] which can compare their fields with the current values of the entries, so that only the entries which actually change are written. Implemented by the structs generated by `#[snec(patch)]` if the types of all of their fields implement `PartialEq`.
+///
+/// Patches of tables with other fields can still be applied with [`ApplyPatch::apply_patch`]:
+/// ```
+/// use snec::{ConfigTable, patch::ApplyPatch as _};
+/// pub struct Color(u8, u8, u8);
+/// #[derive(ConfigTable)]
+/// #[snec(patch)]
+/// struct MyConfigTable {
+///     #[snec]
+///     background: Color,
+/// }
+/// let mut table = MyConfigTable {background: Color(0, 0, 0)};
+/// let changed = table.apply_patch(MyConfigTablePatch {background: Some(Color(255, 255, 255))}).unwrap();
+/// assert_eq!(changed.len(), 1);
+/// assert_eq!(table.background.0, 255);
+/// ```
+///
+/// [`ApplyPatch::apply_patch`]: trait.ApplyPatch.html#method.apply_patch " "
+/// [patches]: trait.Patch.html " "
+pub trait ComparingPatch: Patch {
+    /// Same as [`Patch::apply_with`], except that the fields which are equal to the current values are neither written nor reported, and the receivers of their entries are not notified.
+    ///
+    /// [`Patch::apply_with`]: trait.Patch.html#tymethod.apply_with " "
+    fn apply_changed_with<F>(self, table: &mut Self::Table, changed: F) -> Result<(), <Self::Table as ValidateTable>::Error>
+    where F: FnMut(&'static EntryInfo);
+}
+
+/// Extension trait for applying [patches] to config tables.
+///
+/// This trait is implemented for all config tables. See the [module-level documentation] for an example.
+///
+/// [patches]: trait.Patch.html " "
+/// [module-level documentation]: index.html " "
+pub trait ApplyPatch: ConfigTable + ValidateTable + Sized {
+    /// Applies the patch, notifying only the receivers of the entries which it changes, and returns the information about those entries, in the order in which the fields are declared. If the table is no longer [valid] with the values from the patch, it's left unchanged and the error is returned.
+    ///
+    /// [valid]: ../trait.ValidateTable.html " "
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn apply_patch<P>(&mut self, patch: P) -> Result<Vec<&'static EntryInfo>, <Self as ValidateTable>::Error>
+    where P: Patch<Table = Self> {
+        let mut changed = Vec::new();
+        patch.apply_with(self, |info| changed.push(info))?;
+        Ok(changed)
+    }
+    /// Applies the patch, notifying only the receivers of the entries which it changes, and calls `changed` with the information about each of those entries, which doesn't require the `alloc` feature.
+    #[inline(always)]
+    fn apply_patch_with<P, F>(&mut self, patch: P, changed: F) -> Result<(), <Self as ValidateTable>::Error>
+    where
+        P: Patch<Table = Self>,
+        F: FnMut(&'static EntryInfo) {
+        patch.apply_with(self, changed)
+    }
+    /// Applies the patch, notifying only the receivers of the entries whose values it changes, and returns the information about those entries, in the order in which the fields are declared. Fields which are `Some` but equal to the current values are neither written nor reported.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn apply_patch_changed<P>(&mut self, patch: P) -> Result<Vec<&'static EntryInfo>, <Self as ValidateTable>::Error>
+    where P: ComparingPatch<Table = Self> {
+        let mut changed = Vec::new();
+        patch.apply_changed_with(self, |info| changed.push(info))?;
+        Ok(changed)
+    }
+    /// Applies the patch, notifying only the receivers of the entries whose values it changes, and calls `changed` with the information about each of those entries, which doesn't require the `alloc` feature.
+    #[inline(always)]
+    fn apply_patch_changed_with<P, F>(&mut self, patch: P, changed: F) -> Result<(), <Self as ValidateTable>::Error>
+    where
+        P: ComparingPatch<Table = Self>,
+        F: FnMut(&'static EntryInfo) {
+        patch.apply_changed_with(self, changed)
+    }
+}
+impl<T: ConfigTable + ValidateTable> ApplyPatch for T {}
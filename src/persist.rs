@@ -0,0 +1,214 @@
+//! Saving config tables to TOML files as their entries change.
+//!
+//! A [`PersistReceiver`] is a [table receiver] which records when the entries of the config table change. Since table receivers don't have access to the table itself, the file is written by calling [`save_if_due`] periodically (in an event loop, for example), which saves the table once no entries have changed for the debounce delay, so that a burst of changes, such as dragging a slider, results in a single write. An optional maximum delay makes sure that the file is eventually written even if the entries keep changing.
+//!
+//! The file is written to a temporary file next to it first and then moved into place, so that a crash in the middle of saving doesn't leave a truncated config file behind. [Secret] entries can be encrypted by setting a [cipher].
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, persist::PersistReceiver};
+//! use std::{rc::Rc, time::Duration};
+//! #[derive(ConfigTable)]
+//! #[snec(table_receiver({Rc::clone(&self.persist)}: Rc<PersistReceiver>))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     which_year: i64,
+//!     persist: Rc<PersistReceiver>,
+//! }
+//! let path = std::env::temp_dir().join("snec-persist-example.toml");
+//! let mut table = MyConfigTable {
+//!     which_year: 1987,
+//!     persist: Rc::new(PersistReceiver::new(&path, Duration::ZERO)),
+//! };
+//! table.get_handle_to::<entries::WhichYear>().set(1983);
+//! assert!(table.persist.is_dirty());
+//! // Usually called periodically from an event loop.
+//! assert!(table.persist.save_if_due(&table)?);
+//! assert!(!table.persist.is_dirty());
+//! assert_eq!(std::fs::read_to_string(&path)?, "which_year = 1983\n");
+//! # std::fs::remove_file(&path)?;
+//! # Ok::<(), snec::persist::Error>(())
+//! ```
+//!
+//! [`PersistReceiver`]: struct.PersistReceiver.html " "
+//! [table receiver]: ../trait.TableReceiver.html " "
+//! [`save_if_due`]: struct.PersistReceiver.html#method.save_if_due " "
+//! [Secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [cipher]: struct.PersistReceiver.html#method.with_cipher " "
+
+use core::{
+    cell::Cell,
+    fmt::{self, Formatter, Display, Debug},
+    time::Duration,
+};
+use std::{
+    error,
+    ffi::OsString,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use super::{
+    TableReceiver,
+    VisitEntryTypes,
+    toml::{self, Cipher, TomlSaver},
+};
+
+/// A [table receiver] which saves the config table to a TOML file after its entries change, debounced to avoid writing the file on every change. See the [module-level documentation] for more.
+///
+/// The receiver is shared between the handles of the table, which is why `TableReceiver` is implemented for `&PersistReceiver` rather than `PersistReceiver`, which allows it to be attached as an `Rc<PersistReceiver>`.
+///
+/// [table receiver]: ../trait.TableReceiver.html " "
+/// [module-level documentation]: index.html " "
+pub struct PersistReceiver {
+    path: PathBuf,
+    delay: Duration,
+    max_delay: Option<Duration>,
+    cipher: Option<Box<dyn Cipher>>,
+    first_change: Cell<Option<Instant>>,
+    last_change: Cell<Option<Instant>>,
+}
+impl PersistReceiver {
+    /// Creates a receiver which saves the table to the specified file once no entries have changed for `delay`.
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>, delay: Duration) -> Self {
+        Self {
+            path: path.into(),
+            delay,
+            max_delay: None,
+            cipher: None,
+            first_change: Cell::new(None),
+            last_change: Cell::new(None),
+        }
+    }
+    /// Makes the table be saved once `max_delay` has passed since the first unsaved change, even if the entries are still changing.
+    #[inline(always)]
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+    /// Makes the values of [secret] entries be encrypted using the specified cipher, as done by [`toml::save_encrypted`]. The file can be loaded back with [`toml::load_into_decrypted`].
+    ///
+    /// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+    /// [`toml::save_encrypted`]: ../toml/fn.save_encrypted.html " "
+    /// [`toml::load_into_decrypted`]: ../toml/fn.load_into_decrypted.html " "
+    #[inline(always)]
+    pub fn with_cipher(mut self, cipher: impl Cipher + 'static) -> Self {
+        self.cipher = Some(Box::new(cipher));
+        self
+    }
+    /// Returns the path to the file which the table is saved to.
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Returns `true` if any entries changed since the table was last saved.
+    #[inline(always)]
+    pub fn is_dirty(&self) -> bool {
+        self.first_change.get().is_some()
+    }
+    /// Returns `true` if there are unsaved changes and either no entries have changed for the debounce delay or the maximum delay has passed since the first unsaved change.
+    pub fn is_due(&self) -> bool {
+        let (first_change, last_change) = match (self.first_change.get(), self.last_change.get()) {
+            (Some(first_change), Some(last_change)) => (first_change, last_change),
+            _ => return false,
+        };
+        last_change.elapsed() >= self.delay
+            || matches!(self.max_delay, Some(max_delay) if first_change.elapsed() >= max_delay)
+    }
+    /// Saves the table if [saving is due], returning whether it was saved.
+    ///
+    /// [saving is due]: #method.is_due " "
+    #[inline]
+    pub fn save_if_due<T>(&self, table: &T) -> Result<bool, Error>
+    where T: for<'a> VisitEntryTypes<TomlSaver<'a, T>> {
+        if self.is_due() {
+            self.save(table)?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+    /// Saves the table immediately, regardless of whether there are unsaved changes, such as before exiting.
+    ///
+    /// If saving fails, the changes stay unsaved, and the next call to [`save_if_due`] will retry.
+    ///
+    /// [`save_if_due`]: #method.save_if_due " "
+    pub fn save<T>(&self, table: &T) -> Result<(), Error>
+    where T: for<'a> VisitEntryTypes<TomlSaver<'a, T>> {
+        let document = match &self.cipher {
+            Some(cipher) => toml::save_encrypted(table, &**cipher)?,
+            None => toml::save(table)?,
+        };
+        let mut temporary_name = self.path.file_name().map_or_else(OsString::new, OsString::from);
+        temporary_name.push(".tmp");
+        let temporary_path = self.path.with_file_name(temporary_name);
+        fs::write(&temporary_path, document)?;
+        fs::rename(&temporary_path, &self.path)?;
+        self.first_change.set(None);
+        self.last_change.set(None);
+        Ok(())
+    }
+}
+impl TableReceiver for &PersistReceiver {
+    #[inline]
+    fn receive_change(&mut self, _entry_name: &'static str) {
+        let now = Instant::now();
+        if self.first_change.get().is_none() {
+            self.first_change.set(Some(now));
+        }
+        self.last_change.set(Some(now));
+    }
+}
+impl Debug for PersistReceiver {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PersistReceiver")
+            .field("path", &self.path)
+            .field("delay", &self.delay)
+            .field("max_delay", &self.max_delay)
+            .field("encrypting", &self.cipher.is_some())
+            .field("dirty", &self.is_dirty())
+            .finish()
+    }
+}
+
+/// An error which occurred while saving a config table to a file.
+#[derive(Debug)]
+pub enum Error {
+    /// The config table could not be rendered as a TOML document.
+    Toml(toml::SaveError),
+    /// The file could not be written.
+    Io(io::Error),
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Toml(error) => Display::fmt(error, f),
+            Self::Io(error) => write!(f, "could not write config file: {}", error),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Toml(error) => Some(error),
+            Self::Io(error) => Some(error),
+        }
+    }
+}
+impl From<toml::SaveError> for Error {
+    #[inline(always)]
+    fn from(op: toml::SaveError) -> Self {
+        Self::Toml(op)
+    }
+}
+impl From<io::Error> for Error {
+    #[inline(always)]
+    fn from(op: io::Error) -> Self {
+        Self::Io(op)
+    }
+}
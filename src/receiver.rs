@@ -1,24 +1,65 @@
 use core::{
-    fmt::{self, Formatter, Debug},
-    ops::DerefMut,
-    cell::RefCell,
+    any::Any,
+    borrow::Borrow,
+    convert::Infallible,
+    fmt::{self, Formatter, Debug, Display},
+    cell::{Cell, RefCell},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
     marker::PhantomData,
 };
+#[cfg(feature = "alloc")]
+use core::{mem, ops::DerefMut};
+#[cfg(feature = "alloc")]
 use alloc::{
     boxed::Box,
+    collections::VecDeque,
+    vec::Vec,
     sync::Arc,
     rc::Rc,
 };
 #[cfg(feature = "std")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+#[cfg(feature = "std")]
 use std::{
-    sync::{Mutex, RwLock},
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Condvar, Mutex, MutexGuard, PoisonError, RwLock},
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
-use super::Entry;
+#[cfg(feature = "rayon")]
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use super::{Entry, reentrancy::Notifying};
+#[cfg(feature = "std")]
+use super::{Get, lock_ignoring_poison};
 
 /// Trait for types which wish to be notified when the specified configuration table entry changes.
 ///
 /// Several reference types and standard library types implement `Receiver`:
 /// - A mutable borrow of any type can be used as a receiver
+/// - `Box`, `Rc` and `Arc` forward notifications to their contents; these implementations, along with the one for `Vec`, require the `alloc` feature, which is enabled by `std`
+/// - Slices, arrays and `Vec`s of receivers notify all of their elements in order
+/// - Tuples of up to 8 receivers notify all of them in order, which allows attaching several receivers to one entry:
+/// ```
+/// use snec::{ConfigTable, GetExt as _};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec(entry, receiver({(Logger, Logger)}: (Logger, Logger)))]
+///     which_year: i64,
+/// }
+/// struct Logger;
+/// impl snec::Receiver<entries::WhichYear> for Logger {
+///     fn receive(&mut self, new_value: &i64) {
+///         println!("Changed to {}", new_value);
+///     }
+/// }
+/// let mut table = MyConfigTable {which_year: 1987};
+/// // Prints the message twice.
+/// table.get_handle_to::<entries::WhichYear>().set(1983);
+/// ```
 pub trait Receiver<E: Entry> {
     /// Receive a notification about the value of the entry changing to the specified new value.
     ///
@@ -26,13 +67,530 @@ pub trait Receiver<E: Entry> {
     ///
     /// [`EntryStorage`]: struct.EntryStorage.html " "
     fn receive(&mut self, new_value: &E::Data);
+    /// Checks whether the value of the entry is allowed to change from the current value to the specified new value, before it's written. Returning `false` rejects the change, in which case [`Handle::set`], [`Handle::update`], [`Handle::replace`] and the other methods of handles which write a whole new value leave the value untouched and notify nobody.
+    ///
+    /// Accepts every change by default. Modifications made in place, such as with [`Handle::modify`], can't be rejected, since the previous value isn't kept around to compare against. [`Checked`] implements this method by consulting a [pre-receiver].
+    ///
+    /// [`Handle::set`]: struct.Handle.html#method.set " "
+    /// [`Handle::update`]: struct.Handle.html#method.update " "
+    /// [`Handle::replace`]: struct.Handle.html#method.replace " "
+    /// [`Handle::modify`]: struct.Handle.html#method.modify " "
+    /// [`Checked`]: struct.Checked.html " "
+    /// [pre-receiver]: trait.PreReceiver.html " "
+    #[inline(always)]
+    fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+        let _ = (old_value, new_value);
+        true
+    }
+}
+
+/// Trait for [receivers] which can fail to process a notification, reporting the error back to the code which made the change.
+///
+/// Fallible receivers are notified through [`Handle::try_set`] and [`Handle::try_modify_with`], which return the error to the caller. Since handles can be used without those methods, `TryReceiver` requires `Receiver` to be implemented as well, which defines what happens with errors when the caller isn't interested in them — ignoring, logging or panicking are all valid choices.
+///
+/// The value of the entry is already changed by the time the receiver is notified, and stays changed if the receiver fails.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, Receiver, TryReceiver};
+/// enum Brightness {}
+/// impl Entry for Brightness {
+///     type Data = u8;
+///     const NAME: &'static str = "brightness";
+/// }
+/// struct Backlight {
+///     connected: bool,
+/// }
+/// impl TryReceiver<Brightness> for Backlight {
+///     type Error = &'static str;
+///     fn try_receive(&mut self, _new_value: &u8) -> Result<(), Self::Error> {
+///         if self.connected {Ok(())} else {Err("backlight is disconnected")}
+///     }
+/// }
+/// impl Receiver<Brightness> for Backlight {
+///     fn receive(&mut self, new_value: &u8) {
+///         let _ = self.try_receive(new_value);
+///     }
+/// }
+/// let mut brightness = 100;
+/// let mut handle = Handle::<Brightness, _>::new(&mut brightness, Backlight {connected: false});
+/// assert_eq!(handle.try_set(50), Err("backlight is disconnected"));
+/// ```
+///
+/// [receivers]: trait.Receiver.html " "
+/// [`Handle::try_set`]: struct.Handle.html#method.try_set " "
+/// [`Handle::try_modify_with`]: struct.Handle.html#method.try_modify_with " "
+pub trait TryReceiver<E: Entry>: Receiver<E> {
+    /// The error produced when the notification could not be processed.
+    type Error;
+    /// Receive a notification about the value of the entry changing to the specified new value, returning an error if it could not be processed.
+    ///
+    /// Like [`Receiver::receive`], this shouldn't be called manually.
+    ///
+    /// [`Receiver::receive`]: trait.Receiver.html#tymethod.receive " "
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error>;
+}
+
+/// Trait for [receivers] which are consulted *before* the value of the entry changes and can reject the change.
+///
+/// Pre-receivers are consulted by [`Handle::set_checked`], which only writes the value and notifies the receiver if the pre-receiver accepted it, returning the error otherwise. This allows validation to happen before any other part of the program observes the new value. Wrapping the receiver into [`Checked`] makes every handle write, including [`Handle::set`], [`Handle::update`] and [`Handle::replace`], consult the pre-receiver as well, discarding rejected values, which is how pre-receivers should be attached to config tables.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, Receiver, PreReceiver};
+/// enum Port {}
+/// impl Entry for Port {
+///     type Data = u16;
+///     const NAME: &'static str = "port";
+/// }
+/// struct Listener;
+/// impl PreReceiver<Port> for Listener {
+///     type Error = &'static str;
+///     fn pre_receive(&mut self, _old_value: &u16, new_value: &u16) -> Result<(), Self::Error> {
+///         if *new_value >= 1024 {Ok(())} else {Err("privileged ports are not allowed")}
+///     }
+/// }
+/// impl Receiver<Port> for Listener {
+///     fn receive(&mut self, new_value: &u16) {
+///         println!("Rebinding to port {}", new_value);
+///     }
+/// }
+/// let mut port = 8080;
+/// let mut handle = Handle::<Port, _>::new(&mut port, Listener);
+/// assert_eq!(handle.set_checked(80), Err("privileged ports are not allowed"));
+/// assert_eq!(*handle.get(), 8080);
+/// ```
+/// Attached to a config table through [`Checked`]:
+/// ```
+/// # use snec::{ConfigTable, Receiver, PreReceiver, Checked, GetExt as _};
+/// #[derive(ConfigTable)]
+/// struct Server {
+///     #[snec(entry, receiver({Checked::new(Listener)}: Checked<Listener>))]
+///     port: u16,
+/// }
+/// struct Listener;
+/// impl PreReceiver<entries::Port> for Listener {
+///     type Error = &'static str;
+///     fn pre_receive(&mut self, _old_value: &u16, new_value: &u16) -> Result<(), Self::Error> {
+///         if *new_value >= 1024 {Ok(())} else {Err("privileged ports are not allowed")}
+///     }
+/// }
+/// impl Receiver<entries::Port> for Listener {
+///     fn receive(&mut self, new_value: &u16) {
+///         println!("Rebinding to port {}", new_value);
+///     }
+/// }
+/// let mut server = Server {port: 8080};
+/// let mut handle = server.get_handle_to::<entries::Port>();
+/// handle.set(80);
+/// assert!(!handle.update(443));
+/// assert_eq!(handle.replace(22), 22);
+/// assert_eq!(*handle.get(), 8080);
+/// assert_eq!(handle.set_checked(80), Err("privileged ports are not allowed"));
+/// ```
+///
+/// [receivers]: trait.Receiver.html " "
+/// [`Handle::set_checked`]: struct.Handle.html#method.set_checked " "
+/// [`Handle::set`]: struct.Handle.html#method.set " "
+/// [`Handle::update`]: struct.Handle.html#method.update " "
+/// [`Handle::replace`]: struct.Handle.html#method.replace " "
+/// [`Checked`]: struct.Checked.html " "
+pub trait PreReceiver<E: Entry>: Receiver<E> {
+    /// The error produced when the change is rejected.
+    type Error;
+    /// Checks whether the value of the entry is allowed to change from the current value to the specified new value, returning an error to reject the change.
+    fn pre_receive(&mut self, old_value: &E::Data, new_value: &E::Data) -> Result<(), Self::Error>;
+}
+
+/// A [receiver] which consults the [pre-receiver] it wraps before every change made through a [handle], rejecting the values it doesn't accept.
+///
+/// The error produced by the pre-receiver is discarded, except when the value is set with [`Handle::set_checked`], which returns it. See [`PreReceiver`] for an example.
+///
+/// [receiver]: trait.Receiver.html " "
+/// [pre-receiver]: trait.PreReceiver.html " "
+/// [handle]: struct.Handle.html " "
+/// [`Handle::set_checked`]: struct.Handle.html#method.set_checked " "
+/// [`PreReceiver`]: trait.PreReceiver.html " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Checked<R> {
+    /// The pre-receiver, which is also notified about the changes it accepted.
+    pub inner: R,
+}
+impl<R> Checked<R> {
+    /// Wraps the specified pre-receiver.
+    #[inline(always)]
+    pub const fn new(inner: R) -> Self {
+        Self {inner}
+    }
+}
+impl<E, R> Receiver<E> for Checked<R>
+where
+    E: Entry,
+    R: PreReceiver<E> {
+    #[inline(always)]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.inner.receive(new_value);
+    }
+    #[inline]
+    fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+        self.inner.pre_receive(old_value, new_value).is_ok()
+    }
+}
+impl<E, R> PreReceiver<E> for Checked<R>
+where
+    E: Entry,
+    R: PreReceiver<E> {
+    type Error = R::Error;
+    #[inline(always)]
+    fn pre_receive(&mut self, old_value: &E::Data, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.inner.pre_receive(old_value, new_value)
+    }
+}
+
+/// Trait for types which wish to be notified when any entry of a config table changes, regardless of which one it is.
+///
+/// Table receivers are attached to a config table with `#[snec(table_receiver(...))]`, which makes all of its handles notify the table receiver with the [name] of the entry after notifying the receiver of the entry itself. This is useful for tasks which don't depend on which entry changed, such as marking the config as unsaved.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, GetExt as _};
+/// use std::{rc::Rc, cell::Cell};
+/// #[derive(ConfigTable)]
+/// #[snec(table_receiver({Rc::clone(&self.dirty)}: Rc<Cell<bool>>))]
+/// struct MyConfigTable {
+///     #[snec]
+///     which_year: i64,
+///     #[snec]
+///     who: String,
+///     dirty: Rc<Cell<bool>>,
+/// }
+/// let mut table = MyConfigTable {
+///     which_year: 1987,
+///     who: "Jeremy".to_string(),
+///     dirty: Rc::new(Cell::new(false)),
+/// };
+/// table.get_handle_to::<entries::Who>().set("Sherlock".to_string());
+/// assert!(table.dirty.get());
+/// ```
+///
+/// [name]: trait.Entry.html#associatedconstant.NAME " "
+pub trait TableReceiver {
+    /// Receive a notification about the entry with the specified name changing.
+    fn receive_change(&mut self, entry_name: &'static str);
+}
+
+/// A [receiver] which notifies both the receiver of an entry and a [table receiver]. Used by `#[snec(table_receiver(...))]`.
+///
+/// [receiver]: trait.Receiver.html " "
+/// [table receiver]: trait.TableReceiver.html " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WithTableReceiver<R, T> {
+    /// The receiver of the entry, which is notified first.
+    pub receiver: R,
+    /// The table receiver, which is notified after the receiver of the entry.
+    pub table_receiver: T,
+}
+impl<R, T> WithTableReceiver<R, T> {
+    /// Combines the receiver of an entry with a table receiver.
+    #[inline(always)]
+    pub const fn new(receiver: R, table_receiver: T) -> Self {
+        Self {receiver, table_receiver}
+    }
+}
+impl<E, R, T> Receiver<E> for WithTableReceiver<R, T>
+where
+    E: Entry,
+    R: Receiver<E>,
+    T: TableReceiver {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.receiver.receive(new_value);
+        self.table_receiver.receive_change(E::NAME);
+    }
+    #[inline(always)]
+    fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+        self.receiver.accepts(old_value, new_value)
+    }
+}
+
+/// A [receiver] which forwards notifications to a receiver borrowed for the lifetime of a [handle]. Used by `#[snec(receiver_field = ...)]`.
+///
+/// The receiver is usually a field of the config table itself, which allows stateful receivers, such as channels or dispatchers, to be stored alongside the configuration instead of in global variables. Since the type of the receiver of a config table entry cannot depend on the lifetime of the borrow, the borrow is stored as a pointer, which is why values of this type can only exist inside of a handle created with [`Handle::with_borrowed_receiver`] and never leave it.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, Receiver, GetExt as _};
+/// #[derive(ConfigTable)]
+/// #[snec(receiver_field = history)]
+/// struct MyConfigTable {
+///     #[snec]
+///     which_year: i64,
+///     history: History,
+/// }
+/// #[derive(Default)]
+/// struct History(Vec<i64>);
+/// impl Receiver<entries::WhichYear> for History {
+///     fn receive(&mut self, new_value: &i64) {
+///         self.0.push(*new_value);
+///     }
+/// }
+/// let mut table = MyConfigTable {which_year: 1987, history: History::default()};
+/// table.get_handle_to::<entries::WhichYear>().set(1983);
+/// table.get_handle_to::<entries::WhichYear>().set(1991);
+/// assert_eq!(table.history.0, [1983, 1991]);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [handle]: struct.Handle.html " "
+/// [`Handle::with_borrowed_receiver`]: struct.Handle.html#method.with_borrowed_receiver " "
+pub struct BorrowedReceiver<R> {
+    receiver: *mut R,
+}
+impl<R> BorrowedReceiver<R> {
+    /// Wraps a pointer to the receiver.
+    ///
+    /// # Safety
+    /// The pointer must stay valid for writes, and the pointee must not be accessed in any other way, for as long as the resulting value exists.
+    #[inline(always)]
+    pub(crate) unsafe fn new(receiver: *mut R) -> Self {
+        Self {receiver}
+    }
+    #[inline(always)]
+    fn get(&mut self) -> &mut R {
+        // SAFETY: guaranteed by the contract of `new`.
+        unsafe { &mut *self.receiver }
+    }
+}
+// SAFETY: the same as for `&mut R`.
+unsafe impl<R: Send> Send for BorrowedReceiver<R> {}
+// SAFETY: `&BorrowedReceiver` doesn't provide any access to the receiver.
+unsafe impl<R> Sync for BorrowedReceiver<R> {}
+impl<R> Debug for BorrowedReceiver<R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("BorrowedReceiver")
+    }
+}
+impl<E, R> Receiver<E> for BorrowedReceiver<R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.get().receive(new_value);
+    }
+    #[inline(always)]
+    fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+        self.get().accepts(old_value, new_value)
+    }
+}
+impl<E, R> TryReceiver<E> for BorrowedReceiver<R>
+where
+    E: Entry,
+    R: TryReceiver<E> {
+    type Error = R::Error;
+    #[inline(always)]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.get().try_receive(new_value)
+    }
+}
+impl<E, R> PreReceiver<E> for BorrowedReceiver<R>
+where
+    E: Entry,
+    R: PreReceiver<E> {
+    type Error = R::Error;
+    #[inline(always)]
+    fn pre_receive(&mut self, old_value: &E::Data, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.get().pre_receive(old_value, new_value)
+    }
+}
+
+/// A [receiver] which, when notified about a change of any entry, notifies the receiver of another entry `D` about the current value of `D`, which is borrowed for the lifetime of a [handle]. Used by `#[snec(invalidates(...))]`.
+///
+/// This is how entries which depend on other entries, such as a layout cache depending on the font size, are woken up when the entry they depend on changes. Like [`BorrowedReceiver`], values of this type can only exist inside of a handle, which is created with [`Handle::invalidating`].
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, Receiver, GetExt as _};
+/// use std::{rc::Rc, cell::Cell};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec(entry, invalidates(line_height))]
+///     font_size: u32,
+///     #[snec(entry, receiver({LayoutCache(Rc::clone(&self.relayouts))}: LayoutCache))]
+///     line_height: f32,
+///     relayouts: Rc<Cell<u32>>,
+/// }
+/// struct LayoutCache(Rc<Cell<u32>>);
+/// impl Receiver<entries::LineHeight> for LayoutCache {
+///     fn receive(&mut self, _: &f32) {
+///         self.0.set(self.0.get() + 1);
+///     }
+/// }
+/// let mut table = MyConfigTable {font_size: 12, line_height: 1.2, relayouts: Rc::default()};
+/// table.get_handle_to::<entries::FontSize>().set(14);
+/// assert_eq!(table.relayouts.get(), 1);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [handle]: struct.Handle.html " "
+/// [`BorrowedReceiver`]: struct.BorrowedReceiver.html " "
+/// [`Handle::invalidating`]: struct.Handle.html#method.invalidating " "
+pub struct DependentReceiver<D: Entry, R> {
+    value: *const D::Data,
+    receiver: R,
+}
+impl<D: Entry, R> DependentReceiver<D, R> {
+    /// Wraps a pointer to the value of the dependent entry and its receiver.
+    ///
+    /// # Safety
+    /// The pointer must stay valid for reads, and the pointee must not be modified, for as long as the resulting value exists.
+    #[inline(always)]
+    pub(crate) unsafe fn new(value: *const D::Data, receiver: R) -> Self {
+        Self {value, receiver}
+    }
+}
+// SAFETY: the same as for `(&D::Data, R)`.
+unsafe impl<D: Entry, R: Send> Send for DependentReceiver<D, R>
+where D::Data: Sync {}
+// SAFETY: the same as for `(&D::Data, R)`.
+unsafe impl<D: Entry, R: Sync> Sync for DependentReceiver<D, R>
+where D::Data: Sync {}
+impl<D: Entry, R: Debug> Debug for DependentReceiver<D, R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DependentReceiver")
+            .field("name", &D::NAME)
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
+impl<E, D, R> Receiver<E> for DependentReceiver<D, R>
+where
+    E: Entry,
+    D: Entry,
+    R: Receiver<D> {
+    #[inline]
+    fn receive(&mut self, _: &E::Data) {
+        // SAFETY: guaranteed by the contract of `new`.
+        let value = unsafe { &*self.value };
+        let _notifying = Notifying::entry::<D>(value);
+        self.receiver.receive(value);
+    }
+}
+
+/// Trait for type-erased receivers, which are notified about changes of entries without knowing their types.
+///
+/// Dynamic receivers are attached to entries using [`DynAdapter`], which implements `Receiver<E>` for any entry whose data type is `'static`. The value is passed as `&dyn Any`, which can be downcast to the concrete type if it's known to the receiver. This allows tools such as inspectors and plugins to observe config tables without being compiled against their entry types.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, DynReceiver, DynAdapter};
+/// use std::any::Any;
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = f32;
+///     const NAME: &'static str = "volume";
+/// }
+/// #[derive(Default)]
+/// struct Inspector {
+///     log: Vec<String>,
+/// }
+/// impl DynReceiver for Inspector {
+///     fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+///         match new_value.downcast_ref::<f32>() {
+///             Some(value) => self.log.push(format!("{} = {}", entry_name, value)),
+///             None => self.log.push(format!("{} changed", entry_name)),
+///         }
+///     }
+/// }
+/// let mut inspector = Inspector::default();
+/// let mut volume = 1.0;
+/// Handle::<Volume, _>::new(&mut volume, DynAdapter::new(&mut inspector)).set(0.5);
+/// assert_eq!(inspector.log, ["volume = 0.5"]);
+/// ```
+///
+/// [`DynAdapter`]: struct.DynAdapter.html " "
+pub trait DynReceiver {
+    /// Receive a notification about the entry with the specified name changing to the specified new value.
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any);
+}
+
+/// A [receiver] which forwards notifications to a [dynamic receiver], erasing the type of the entry.
+///
+/// The values of [secret] entries are withheld from the dynamic receiver, which gets [`Redacted`] instead, unless the adapter was created with [`revealing_secrets`].
+///
+/// [receiver]: trait.Receiver.html " "
+/// [dynamic receiver]: trait.DynReceiver.html " "
+/// [secret]: trait.Entry.html#associatedconstant.SECRET " "
+/// [`Redacted`]: struct.Redacted.html " "
+/// [`revealing_secrets`]: #method.revealing_secrets " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DynAdapter<R> {
+    /// The dynamic receiver which is notified.
+    pub receiver: R,
+    /// Whether the values of secret entries are passed to the dynamic receiver instead of [`Redacted`].
+    ///
+    /// [`Redacted`]: struct.Redacted.html " "
+    pub reveal_secrets: bool,
+}
+impl<R> DynAdapter<R> {
+    /// Creates an adapter forwarding notifications to the specified dynamic receiver, withholding the values of secret entries.
+    #[inline(always)]
+    pub const fn new(receiver: R) -> Self {
+        Self {receiver, reveal_secrets: false}
+    }
+    /// Creates an adapter forwarding notifications to the specified dynamic receiver, including the values of secret entries.
+    #[inline(always)]
+    pub const fn revealing_secrets(receiver: R) -> Self {
+        Self {receiver, reveal_secrets: true}
+    }
+}
+impl<E, R> Receiver<E> for DynAdapter<R>
+where
+    E: Entry,
+    E::Data: Any,
+    R: DynReceiver {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        if E::SECRET && !self.reveal_secrets {
+            self.receiver.receive_dyn(E::NAME, &Redacted);
+        } else {
+            self.receiver.receive_dyn(E::NAME, new_value);
+        }
+    }
+}
+
+/// A placeholder for the values of [secret] entries, which is displayed as `<redacted>` and passed to [dynamic receivers] instead of the actual values.
+///
+/// [secret]: trait.Entry.html#associatedconstant.SECRET " "
+/// [dynamic receivers]: trait.DynReceiver.html " "
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Redacted;
+impl Debug for Redacted {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+impl Display for Redacted {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad("<redacted>")
+    }
 }
 
+/// The closure type used by `FnReceiver` when none is specified: a boxed closure if the `alloc` feature is enabled, or a function pointer otherwise.
+#[cfg(feature = "alloc")]
+type DefaultClosure<T> = Box<dyn FnMut(&T)>;
+#[cfg(not(feature = "alloc"))]
+type DefaultClosure<T> = fn(&T);
+
 /// A [receiver] which calls a closure when notified.
 ///
 /// [receiver]: trait.Receiver.html " "
 #[allow(clippy::module_name_repetitions)]
-pub struct FnReceiver<E: Entry, F: FnMut(&E::Data) = Box<dyn FnMut(&<E as Entry>::Data)>> {
+pub struct FnReceiver<E: Entry, F: FnMut(&E::Data) = DefaultClosure<<E as Entry>::Data>> {
     _phantom: PhantomData<E>,
     /// The closure which is called when the receiver is notified.
     pub closure: F,
@@ -63,22 +621,1342 @@ impl<E: Entry, F: FnMut(&E::Data) + Clone> Clone for FnReceiver<E, F> {
         Self {closure: self.closure.clone(), _phantom: PhantomData}
     }
 }
-impl<E: Entry, F: FnMut(&E::Data) + Copy> Copy for FnReceiver<E, F>
-{}
-
-impl<E: Entry, F: FnMut(&E::Data) + Default> Default for FnReceiver<E, F> {
+impl<E: Entry, F: FnMut(&E::Data) + Copy> Copy for FnReceiver<E, F>
+{}
+
+impl<E: Entry, F: FnMut(&E::Data) + Default> Default for FnReceiver<E, F> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {closure: F::default(), _phantom: PhantomData}
+    }
+}
+
+impl<E: Entry, F: FnMut(&E::Data) + Debug> Debug for FnReceiver<E, F> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnReceiver")
+            .field("closure", &self.closure)
+            .finish()
+    }
+}
+
+/// A [receiver] which only forwards notifications to the inner receiver if the predicate returns `true` for the new value.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, FnReceiver, FilterReceiver};
+/// # use std::cell::Cell;
+/// enum Temperature {}
+/// impl Entry for Temperature {
+///     type Data = f64;
+///     const NAME: &'static str = "temperature";
+/// }
+/// let alarms = Cell::new(0);
+/// let mut temperature = 20.0;
+/// let mut handle = Handle::<Temperature, _>::new(
+///     &mut temperature,
+///     FilterReceiver::new(
+///         |new_value: &f64| *new_value > 90.0,
+///         FnReceiver::new(|_: &f64| alarms.set(alarms.get() + 1)),
+///     ),
+/// );
+/// handle.set(25.0);
+/// handle.set(95.0);
+/// assert_eq!(alarms.get(), 1);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+#[allow(clippy::module_name_repetitions)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FilterReceiver<P, R> {
+    /// The predicate which decides whether a notification is forwarded.
+    pub predicate: P,
+    /// The receiver to which the notifications are forwarded.
+    pub inner: R,
+}
+impl<P, R> FilterReceiver<P, R> {
+    /// Creates a receiver which forwards the notifications for which the predicate returns `true` to the specified receiver.
+    #[inline(always)]
+    pub const fn new(predicate: P, inner: R) -> Self {
+        Self {predicate, inner}
+    }
+}
+impl<E, P, R> Receiver<E> for FilterReceiver<P, R>
+where
+    E: Entry,
+    P: FnMut(&E::Data) -> bool,
+    R: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        if (self.predicate)(new_value) {
+            self.inner.receive(new_value);
+        }
+    }
+}
+
+/// A [receiver] which applies a function to the new value and forwards the result to an inner receiver of the entry `T`, which typically has a different data type.
+///
+/// The entry `T` only serves to describe the data type the inner receiver expects, and doesn't have to be a part of any config table.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, Receiver, MapReceiver};
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = f32;
+///     const NAME: &'static str = "volume";
+/// }
+/// enum DisplayText {}
+/// impl Entry for DisplayText {
+///     type Data = String;
+///     const NAME: &'static str = "display_text";
+/// }
+/// #[derive(Default)]
+/// struct Label {
+///     text: String,
+/// }
+/// impl Receiver<DisplayText> for &mut Label {
+///     fn receive(&mut self, new_value: &String) {
+///         self.text = new_value.clone();
+///     }
+/// }
+/// let mut label = Label::default();
+/// let mut volume = 1.0;
+/// Handle::<Volume, _>::new(
+///     &mut volume,
+///     MapReceiver::<DisplayText, _, _>::new(|volume: &f32| format!("{:.0}%", volume * 100.0), &mut label),
+/// ).set(0.5);
+/// assert_eq!(label.text, "50%");
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+#[allow(clippy::module_name_repetitions)]
+pub struct MapReceiver<T: Entry, F, R> {
+    /// The function which transforms the values.
+    pub function: F,
+    /// The receiver to which the transformed values are forwarded.
+    pub inner: R,
+    _phantom: PhantomData<T>,
+}
+impl<T: Entry, F, R: Receiver<T>> MapReceiver<T, F, R> {
+    /// Creates a receiver which forwards the values transformed by the specified function to the specified receiver.
+    // FIXME make it a const fn when non-Sized bounds in const fn arguments get stabilized
+    #[inline(always)]
+    pub fn new(function: F, inner: R) -> Self {
+        Self {function, inner, _phantom: PhantomData}
+    }
+}
+impl<E, T, F, R> Receiver<E> for MapReceiver<T, F, R>
+where
+    E: Entry,
+    T: Entry,
+    F: FnMut(&E::Data) -> T::Data,
+    R: Receiver<T> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        let mapped = (self.function)(new_value);
+        self.inner.receive(&mapped);
+    }
+}
+impl<T: Entry, F: Clone, R: Clone> Clone for MapReceiver<T, F, R> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {function: self.function.clone(), inner: self.inner.clone(), _phantom: PhantomData}
+    }
+}
+impl<T: Entry, F: Copy, R: Copy> Copy for MapReceiver<T, F, R> {}
+impl<T: Entry, F: Debug, R: Debug> Debug for MapReceiver<T, F, R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapReceiver")
+            .field("function", &self.function)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A [receiver] which forwards notifications to the inner receiver only while a shared flag is set, allowing notifications to be muted at runtime.
+///
+/// The flag is an `Arc<AtomicBool>`, which can be shared between several receivers and flipped from anywhere, including other threads. Muting notifications this way, for example during bulk initialization, avoids having to use the silent modification API.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, FnReceiver, ToggleReceiver};
+/// # use std::{cell::Cell, sync::{Arc, atomic::{AtomicBool, Ordering}}};
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = f32;
+///     const NAME: &'static str = "volume";
+/// }
+/// let notifications = Cell::new(0);
+/// let flag = Arc::new(AtomicBool::new(false));
+/// let mut volume = 1.0;
+/// let mut handle = Handle::<Volume, _>::new(
+///     &mut volume,
+///     ToggleReceiver::from_flag(
+///         Arc::clone(&flag),
+///         FnReceiver::new(|_: &f32| notifications.set(notifications.get() + 1)),
+///     ),
+/// );
+/// handle.set(0.5); // Muted
+/// flag.store(true, Ordering::Release);
+/// handle.set(0.7);
+/// assert_eq!(notifications.get(), 1);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Debug)]
+#[cfg(feature = "alloc")]
+pub struct ToggleReceiver<R> {
+    flag: Arc<AtomicBool>,
+    /// The receiver to which the notifications are forwarded.
+    pub inner: R,
+}
+#[cfg(feature = "alloc")]
+impl<R> ToggleReceiver<R> {
+    /// Creates a receiver which forwards notifications to the specified receiver, with a new flag which is initially set.
+    #[inline]
+    pub fn new(inner: R) -> Self {
+        Self::from_flag(Arc::new(AtomicBool::new(true)), inner)
+    }
+    /// Creates a receiver which forwards notifications to the specified receiver while the specified shared flag is set.
+    #[inline(always)]
+    pub fn from_flag(flag: Arc<AtomicBool>, inner: R) -> Self {
+        Self {flag, inner}
+    }
+    /// Returns the shared flag, which can be cloned to toggle the receiver from elsewhere.
+    #[inline(always)]
+    pub fn flag(&self) -> &Arc<AtomicBool> {
+        &self.flag
+    }
+    /// Returns whether notifications are currently forwarded.
+    #[inline(always)]
+    pub fn is_enabled(&self) -> bool {
+        self.flag.load(Ordering::Acquire)
+    }
+    /// Enables or disables forwarding notifications, affecting all receivers sharing the flag.
+    #[inline(always)]
+    pub fn set_enabled(&self, enabled: bool) {
+        self.flag.store(enabled, Ordering::Release)
+    }
+}
+#[cfg(feature = "alloc")]
+impl<E, R> Receiver<E> for ToggleReceiver<R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        if self.is_enabled() {
+            self.inner.receive(new_value);
+        }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<R: Default> Default for ToggleReceiver<R> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new(R::default())
+    }
+}
+
+/// A [receiver] which delivers at most one notification per interval to the inner receiver, coalescing bursts of changes while still guaranteeing that the last value of a burst is delivered.
+///
+/// The first change is delivered immediately, opening a window of the specified length. Changes which happen during the window are not delivered right away — only the latest of them is kept, and delivered by [`poll`] once the window closes, which opens a new window. Since no timer thread is involved, `poll` must be called periodically, or at the time returned by [`deadline`], for the last value to be delivered.
+///
+/// Since the state has to persist between handles, a rate-limiting receiver is usually shared, for example using `Rc<RefCell<...>>`, with the receiver expression cloning the `Rc`.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, FnReceiver, RateLimitReceiver};
+/// # use std::{cell::RefCell, thread, time::Duration};
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = u8;
+///     const NAME: &'static str = "volume";
+/// }
+/// let delivered = RefCell::new(Vec::new());
+/// let mut receiver = RateLimitReceiver::<Volume, _>::new(
+///     Duration::from_millis(50),
+///     FnReceiver::new(|new_value: &u8| delivered.borrow_mut().push(*new_value)),
+/// );
+/// let mut volume = 0;
+/// let mut handle = Handle::<Volume, _>::new(&mut volume, &mut receiver);
+/// for i in 1..=10 {
+///     handle.set(i);
+/// }
+/// drop(handle);
+/// assert_eq!(*delivered.borrow(), [1]);
+/// thread::sleep(Duration::from_millis(60));
+/// receiver.poll();
+/// assert_eq!(*delivered.borrow(), [1, 10]);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [`poll`]: #method.poll " "
+/// [`deadline`]: #method.deadline " "
+#[cfg(feature = "std")]
+#[allow(clippy::module_name_repetitions)]
+pub struct RateLimitReceiver<E: Entry, R> {
+    interval: Duration,
+    last_delivery: Option<Instant>,
+    pending: Option<E::Data>,
+    /// The receiver to which the notifications are delivered.
+    pub inner: R,
+}
+#[cfg(feature = "std")]
+impl<E: Entry, R: Receiver<E>> RateLimitReceiver<E, R> {
+    /// Creates a receiver which delivers notifications to the specified receiver at most once per the specified interval.
+    #[inline(always)]
+    pub fn new(interval: Duration, inner: R) -> Self {
+        Self {interval, last_delivery: None, pending: None, inner}
+    }
+    /// Returns the minimal interval between deliveries.
+    #[inline(always)]
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+    /// Returns the value which is waiting to be delivered, if there is one.
+    #[inline(always)]
+    pub fn pending(&self) -> Option<&E::Data> {
+        self.pending.as_ref()
+    }
+    /// Returns the time at which the pending value can be delivered, or `None` if there is no pending value.
+    #[inline]
+    pub fn deadline(&self) -> Option<Instant> {
+        match (&self.pending, self.last_delivery) {
+            (Some(..), Some(last_delivery)) => Some(last_delivery + self.interval),
+            (Some(..), None) => Some(Instant::now()),
+            (None, _) => None,
+        }
+    }
+    /// Delivers the pending value if the current window has closed. Returns `true` if a value was delivered.
+    #[inline]
+    pub fn poll(&mut self) -> bool {
+        if self.pending.is_some() && self.window_closed(Instant::now()) {
+            self.flush()
+        } else {
+            false
+        }
+    }
+    /// Delivers the pending value immediately, regardless of the window. Returns `true` if there was a value to deliver.
+    #[inline]
+    pub fn flush(&mut self) -> bool {
+        if let Some(value) = self.pending.take() {
+            self.deliver(&value, Instant::now());
+            true
+        } else {
+            false
+        }
+    }
+    fn window_closed(&self, now: Instant) -> bool {
+        match self.last_delivery {
+            Some(last_delivery) => now.duration_since(last_delivery) >= self.interval,
+            None => true,
+        }
+    }
+    fn deliver(&mut self, value: &E::Data, now: Instant) {
+        self.last_delivery = Some(now);
+        self.inner.receive(value);
+    }
+}
+#[cfg(feature = "std")]
+impl<E, R> Receiver<E> for RateLimitReceiver<E, R>
+where
+    E: Entry,
+    E::Data: Clone,
+    R: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        let now = Instant::now();
+        if self.window_closed(now) {
+            // The new value supersedes the pending one, if any.
+            self.pending = None;
+            self.deliver(new_value, now);
+        } else {
+            self.pending = Some(new_value.clone());
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl<E, R> Debug for RateLimitReceiver<E, R>
+where
+    E: Entry,
+    E::Data: Debug,
+    R: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimitReceiver")
+            .field("interval", &self.interval)
+            .field("last_delivery", &self.last_delivery)
+            .field("pending", &self.pending)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+/// A [receiver] which stores a clone of the most recent value in a shared slot, allowing other threads to poll the latest configuration without channels.
+///
+/// Cloning the receiver produces another receiver sharing the same slot, which can be kept by the reading side.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, LatestValueReceiver};
+/// # use std::thread;
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = f32;
+///     const NAME: &'static str = "volume";
+/// }
+/// let receiver = LatestValueReceiver::<Volume>::new();
+/// let reader = receiver.clone();
+/// let mut volume = 1.0;
+/// Handle::<Volume, _>::new(&mut volume, receiver).set(0.5);
+/// let latest = thread::spawn(move || reader.latest()).join().unwrap();
+/// assert_eq!(latest, Some(0.5));
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+#[cfg(feature = "std")]
+#[allow(clippy::module_name_repetitions)]
+pub struct LatestValueReceiver<E: Entry> {
+    slot: Arc<Mutex<Option<E::Data>>>,
+}
+#[cfg(feature = "std")]
+impl<E: Entry> LatestValueReceiver<E> {
+    /// Creates a receiver with a new empty slot.
+    #[inline]
+    pub fn new() -> Self {
+        Self::from_slot(Arc::new(Mutex::new(None)))
+    }
+    /// Creates a receiver which stores the values in the specified shared slot.
+    #[inline(always)]
+    pub fn from_slot(slot: Arc<Mutex<Option<E::Data>>>) -> Self {
+        Self {slot}
+    }
+    /// Returns the shared slot in which the values are stored.
+    #[inline(always)]
+    pub fn slot(&self) -> &Arc<Mutex<Option<E::Data>>> {
+        &self.slot
+    }
+    /// Returns a clone of the most recent value, or `None` if no value was received yet.
+    #[inline]
+    pub fn latest(&self) -> Option<E::Data>
+    where E::Data: Clone {
+        self.lock().clone()
+    }
+    /// Takes the most recent value out of the slot, so that the next call returns `None` until a new value is received.
+    #[inline]
+    pub fn take(&self) -> Option<E::Data> {
+        self.lock().take()
+    }
+    fn lock(&self) -> MutexGuard<'_, Option<E::Data>> {
+        lock_ignoring_poison(&self.slot)
+    }
+}
+#[cfg(feature = "std")]
+impl<E> Receiver<E> for LatestValueReceiver<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        let mut slot = self.lock();
+        match &mut *slot {
+            Some(value) => value.clone_from(new_value),
+            None => *slot = Some(new_value.clone()),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Clone for LatestValueReceiver<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {slot: Arc::clone(&self.slot)}
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Default for LatestValueReceiver<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(feature = "std")]
+impl<E> Debug for LatestValueReceiver<E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LatestValueReceiver")
+            .field("slot", &self.slot)
+            .finish()
+    }
+}
+
+/// The time at which the value of an entry changed, as both a monotonic `Instant`, for measuring latency, and a `SystemTime`, for logging and correlating with other machines.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ChangeTime {
+    /// The monotonic time of the change.
+    pub instant: Instant,
+    /// The wall-clock time of the change.
+    pub system_time: SystemTime,
+}
+#[cfg(feature = "std")]
+impl ChangeTime {
+    /// Returns the current time.
+    #[inline]
+    pub fn now() -> Self {
+        Self {instant: Instant::now(), system_time: SystemTime::now()}
+    }
+    /// Returns the time which has passed since the change.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.instant.elapsed()
+    }
+}
+
+/// Trait for [receivers] which are notified along with the time of the change, captured when the handle committed it.
+///
+/// Timed receivers are attached to entries by wrapping them in [`Timestamped`]. This matters for receivers which queue changes to be processed later, such as ones sending them through channels, since the time at which the change is processed says nothing about when it was made.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, ChangeTime, Timestamped};
+/// # use std::{sync::mpsc, thread, time::Duration};
+/// enum Quality {}
+/// impl Entry for Quality {
+///     type Data = u8;
+///     const NAME: &'static str = "quality";
+/// }
+/// let (sender, receiver) = mpsc::channel::<(u8, ChangeTime)>();
+/// let mut quality = 80;
+/// Handle::<Quality, _>::new(&mut quality, Timestamped::new(sender)).set(60);
+/// thread::sleep(Duration::from_millis(5));
+/// let (quality, time) = receiver.recv().unwrap();
+/// assert_eq!(quality, 60);
+/// // The latency of propagating the change, including the time it spent in the channel.
+/// assert!(time.elapsed() >= Duration::from_millis(5));
+/// ```
+///
+/// [receivers]: trait.Receiver.html " "
+/// [`Timestamped`]: struct.Timestamped.html " "
+#[cfg(feature = "std")]
+pub trait TimedReceiver<E: Entry> {
+    /// Receive a notification about the value of the entry changing to the specified new value at the specified time.
+    fn receive_at(&mut self, new_value: &E::Data, time: ChangeTime);
+}
+#[cfg(feature = "std")]
+impl<E, R> TimedReceiver<E> for &mut R
+where
+    E: Entry,
+    R: TimedReceiver<E> + ?Sized {
+    #[inline(always)]
+    fn receive_at(&mut self, new_value: &E::Data, time: ChangeTime) {
+        (*self).receive_at(new_value, time)
+    }
+}
+#[cfg(feature = "std")]
+impl<E> TimedReceiver<E> for std::sync::mpsc::Sender<(E::Data, ChangeTime)>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn receive_at(&mut self, new_value: &E::Data, time: ChangeTime) {
+        // Sending fails only if the receiving side was dropped, in which case nobody is interested in the change.
+        let _ = self.send((new_value.clone(), time));
+    }
+}
+
+/// A [receiver] which captures the time of every change and notifies the contained [timed receiver] along with it.
+///
+/// [receiver]: trait.Receiver.html " "
+/// [timed receiver]: trait.TimedReceiver.html " "
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Timestamped<R> {
+    /// The timed receiver which is notified.
+    pub receiver: R,
+}
+#[cfg(feature = "std")]
+impl<R> Timestamped<R> {
+    /// Wraps the specified timed receiver.
+    #[inline(always)]
+    pub const fn new(receiver: R) -> Self {
+        Self {receiver}
+    }
+}
+#[cfg(feature = "std")]
+impl<E, R> Receiver<E> for Timestamped<R>
+where
+    E: Entry,
+    R: TimedReceiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.receiver.receive_at(new_value, ChangeTime::now());
+    }
+}
+
+/// A [receiver] which keeps clones of the most recent values of the entry, along with the times at which they were received, in a ring buffer of a fixed capacity.
+///
+/// Cloning the receiver produces another receiver sharing the same buffer, which can be kept by the code querying the history, such as a debug overlay plotting the recent values of a tunable parameter. When wrapped in [`Timestamped`], the receiver keeps the times at which the changes were made instead.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, HistoryBufferReceiver};
+/// enum Exposure {}
+/// impl Entry for Exposure {
+///     type Data = f32;
+///     const NAME: &'static str = "exposure";
+/// }
+/// let receiver = HistoryBufferReceiver::<Exposure>::new(3);
+/// let history = receiver.clone();
+/// let mut exposure = 1.0;
+/// let mut handle = Handle::<Exposure, _>::new(&mut exposure, receiver);
+/// for value in [1.5, 2.0, 1.8, 1.2] {
+///     handle.set(value);
+/// }
+/// let values = history.values();
+/// assert_eq!(values, [2.0, 1.8, 1.2]);
+/// let (first, last) = (history.history()[0].0, history.history()[2].0);
+/// assert!(first <= last);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [`Timestamped`]: struct.Timestamped.html " "
+#[cfg(feature = "std")]
+pub struct HistoryBufferReceiver<E: Entry> {
+    buffer: Arc<Mutex<History<E::Data>>>,
+    capacity: usize,
+}
+/// Values along with the times at which they were received, from the oldest to the newest.
+#[cfg(feature = "std")]
+type History<D> = VecDeque<(Instant, D)>;
+#[cfg(feature = "std")]
+impl<E: Entry> HistoryBufferReceiver<E> {
+    /// Creates a receiver which keeps at most the specified number of values.
+    #[inline]
+    pub fn new(capacity: usize) -> Self {
+        Self {buffer: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))), capacity}
+    }
+    /// Returns clones of the kept values along with the times at which they were received, from the oldest to the newest.
+    #[inline]
+    pub fn history(&self) -> Vec<(Instant, E::Data)>
+    where E::Data: Clone {
+        self.lock().iter().cloned().collect()
+    }
+    /// Returns clones of the kept values, from the oldest to the newest.
+    #[inline]
+    pub fn values(&self) -> Vec<E::Data>
+    where E::Data: Clone {
+        self.lock().iter().map(|(_, value)| value.clone()).collect()
+    }
+    /// Returns clones of the values received after the specified time, along with the times at which they were received, from the oldest to the newest.
+    #[inline]
+    pub fn since(&self, time: Instant) -> Vec<(Instant, E::Data)>
+    where E::Data: Clone {
+        self.lock().iter().filter(|(received, _)| *received > time).cloned().collect()
+    }
+    /// Returns the number of kept values.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+    /// Returns `true` if no values are kept.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+    /// Returns the maximum number of kept values.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Removes all kept values.
+    #[inline]
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, History<E::Data>> {
+        lock_ignoring_poison(&self.buffer)
+    }
+}
+#[cfg(feature = "std")]
+impl<E> Receiver<E> for HistoryBufferReceiver<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.receive_at(new_value, ChangeTime::now());
+    }
+}
+#[cfg(feature = "std")]
+impl<E> TimedReceiver<E> for HistoryBufferReceiver<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    /// Keeps the value along with the time of the change instead of the time at which it was received.
+    fn receive_at(&mut self, new_value: &E::Data, time: ChangeTime) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut buffer = self.lock();
+        if buffer.len() >= self.capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back((time.instant, new_value.clone()));
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Clone for HistoryBufferReceiver<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {buffer: Arc::clone(&self.buffer), capacity: self.capacity}
+    }
+}
+#[cfg(feature = "std")]
+impl<E> Debug for HistoryBufferReceiver<E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HistoryBufferReceiver")
+            .field("buffer", &self.buffer)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// A notification waiting to be delivered by a [`Worker`].
+///
+/// [`Worker`]: struct.Worker.html " "
+#[cfg(feature = "std")]
+type PendingNotification = Box<dyn FnOnce() + Send>;
+
+/// Trait for config tables whose receivers aren't notified right away, but only when the table is asked to [dispatch the pending notifications]. Implemented by `#[derive(ConfigTable)]` for tables with `#[snec(deferred = field)]`, where `field` is a [`DeferredQueue`] which records the changes.
+///
+/// Deferring notifications makes changes safe to make from code which the receivers could interfere with, such as code holding locks which the receivers take or code iterating over data which the receivers modify. Every change is recorded along with a clone of the new value, which is why the data types of the entries must implement `Clone`. The receivers are only created when the queue is drained, at a point where running them is known to be safe, such as the start of the next frame or the end of a request, and can borrow the rest of the table, like with tables whose notifications aren't deferred.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, DeferredQueue, DeferredTable as _, Receiver, GetExt as _};
+/// #[derive(ConfigTable)]
+/// #[snec(deferred = pending, receiver_field = renderer)]
+/// struct Config {
+///     #[snec]
+///     fps: u32,
+///     renderer: Renderer,
+///     pending: DeferredQueue<Config>,
+/// }
+/// #[derive(Default)]
+/// struct Renderer {
+///     fps: u32,
+/// }
+/// impl Receiver<entries::Fps> for Renderer {
+///     fn receive(&mut self, new_value: &u32) {
+///         self.fps = *new_value;
+///     }
+/// }
+/// let mut config = Config {fps: 60, renderer: Renderer::default(), pending: DeferredQueue::new()};
+/// config.get_handle_to::<entries::Fps>().set(144);
+/// // The receiver hasn't run yet.
+/// assert_eq!(config.renderer.fps, 0);
+/// assert_eq!(config.pending.pending(), 1);
+/// assert_eq!(config.dispatch_pending(), 1);
+/// assert_eq!(config.renderer.fps, 144);
+/// ```
+///
+/// [dispatch the pending notifications]: #tymethod.dispatch_pending " "
+/// [`DeferredQueue`]: struct.DeferredQueue.html " "
+pub trait DeferredTable {
+    /// A recorded change of one of the entries of the table, holding the new value. Generated by the derive macro.
+    type Record;
+    /// Notifies the receivers about the changes which were recorded before the call, in the order in which they were made, returning their number.
+    ///
+    /// Changes made by the receivers themselves are recorded and delivered by the next call rather than this one, so that receivers which change each other's entries can't keep this call from returning.
+    fn dispatch_pending(&mut self) -> usize;
+}
+
+/// Records a change of the entry `E` of a [deferred table]. Implemented by `#[derive(ConfigTable)]`.
+///
+/// [deferred table]: trait.DeferredTable.html " "
+#[doc(hidden)]
+pub trait RecordChange<E: Entry>: DeferredTable {
+    /// Creates the record of the entry changing to the specified value.
+    fn record(new_value: &E::Data) -> Self::Record;
+}
+
+/// The queue of changes of a [deferred table], stored in a field of the table named by `#[snec(deferred = ...)]`, which is used as the receiver of all of its entries.
+///
+/// [deferred table]: trait.DeferredTable.html " "
+#[cfg(feature = "alloc")]
+pub struct DeferredQueue<T: DeferredTable> {
+    records: VecDeque<T::Record>,
+}
+#[cfg(feature = "alloc")]
+impl<T: DeferredTable> DeferredQueue<T> {
+    /// Creates an empty queue.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {records: VecDeque::new()}
+    }
+    /// Returns the number of changes waiting to be delivered.
+    #[inline(always)]
+    pub fn pending(&self) -> usize {
+        self.records.len()
+    }
+    /// Drops all changes waiting to be delivered without notifying anyone, returning their number.
+    #[inline]
+    pub fn clear(&mut self) -> usize {
+        let count = self.records.len();
+        self.records.clear();
+        count
+    }
+    /// Takes the recorded changes out of the queue, for the `dispatch_pending` implementation generated by the derive macro.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn take(&mut self) -> VecDeque<T::Record> {
+        mem::take(&mut self.records)
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: DeferredTable> Default for DeferredQueue<T> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: DeferredTable> Debug for DeferredQueue<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeferredQueue")
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+#[cfg(feature = "alloc")]
+impl<E, T> Receiver<E> for DeferredQueue<T>
+where
+    E: Entry,
+    T: RecordChange<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.records.push_back(T::record(new_value));
+    }
+}
+
+/// A thread which delivers notifications handed to it by [`SpawnReceiver`]s, one by one, in the order in which the changes were made. Clones share the thread, which exits once the worker and all of its clones, including the ones held by receivers, are dropped, after delivering the remaining notifications.
+///
+/// A receiver which panics doesn't stop the worker: the panic is reported by the panic hook as usual, and the worker moves on to the next notification.
+///
+/// [`SpawnReceiver`]: struct.SpawnReceiver.html " "
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct Worker {
+    sender: mpsc::Sender<WorkerMessage>,
+}
+#[cfg(feature = "std")]
+enum WorkerMessage {
+    Notify(PendingNotification),
+    Flush(mpsc::SyncSender<()>),
+}
+#[cfg(feature = "std")]
+impl Worker {
+    /// Spawns the worker thread.
+    ///
+    /// # Panics
+    /// If the operating system fails to create the thread, just like `thread::spawn`.
+    pub fn new() -> Self {
+        let (sender, messages) = mpsc::channel();
+        thread::Builder::new()
+            .name("snec-worker".to_string())
+            .spawn(move || {
+                for message in messages {
+                    match message {
+                        WorkerMessage::Notify(notification) => {
+                            // The panic was already reported by the hook, and the receiver which panicked is dropped along with the closure.
+                            let _ = panic::catch_unwind(AssertUnwindSafe(notification));
+                        },
+                        WorkerMessage::Flush(done) => {
+                            let _ = done.send(());
+                        },
+                    }
+                }
+            })
+            .expect("failed to spawn the worker thread");
+        Self {sender}
+    }
+    /// Blocks until the notifications which were handed to the worker before the call are delivered. **Deadlocks if called by a receiver running on the worker.**
+    pub fn flush(&self) {
+        let (done, flushed) = mpsc::sync_channel(1);
+        if self.sender.send(WorkerMessage::Flush(done)).is_ok() {
+            let _ = flushed.recv();
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl Default for Worker {
+    /// Spawns the worker thread, the same as `new`.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(feature = "std")]
+impl Debug for Worker {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker").finish_non_exhaustive()
+    }
+}
+
+/// A [receiver] which hands the notification, along with a clone of the new value, to a background [`Worker`] or a thread pool, so that the change returns right away instead of waiting for the contained receiver.
+///
+/// Receivers which perform disk or network I/O, such as ones saving the configuration or pushing it to other machines, would otherwise block whoever makes the change. A worker delivers the notifications in the order in which the changes were made. A thread pool, used with [`with_spawner`], delivers them concurrently and in any order, and should thus only be used with receivers for which this doesn't matter.
+///
+/// # Example
+/// ```
+/// use snec::{Entry, Handle, Receiver, SpawnReceiver, Worker};
+/// use std::sync::{Arc, Mutex};
+/// enum Theme {}
+/// impl Entry for Theme {
+///     type Data = String;
+///     const NAME: &'static str = "theme";
+/// }
+/// #[derive(Clone, Default)]
+/// struct Saver {
+///     saved: Arc<Mutex<Vec<String>>>,
+/// }
+/// impl Receiver<Theme> for Saver {
+///     fn receive(&mut self, new_value: &String) {
+///         // Writing to a slow disk.
+///         self.saved.lock().unwrap().push(new_value.clone());
+///     }
+/// }
+/// let saver = Saver::default();
+/// let worker = Worker::new();
+/// let mut theme = "light".to_string();
+/// let mut handle = Handle::<Theme, _>::new(&mut theme, SpawnReceiver::new(&worker, saver.clone()));
+/// handle.set("dark".to_string());
+/// handle.set("solarized".to_string());
+/// worker.flush();
+/// assert_eq!(*saver.saved.lock().unwrap(), ["dark", "solarized"]);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [`Worker`]: struct.Worker.html " "
+/// [`with_spawner`]: #method.with_spawner " "
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct SpawnReceiver<R> {
+    spawner: Spawner,
+    /// The receiver which is notified in the background. It's cloned for every notification.
+    pub receiver: R,
+}
+#[cfg(feature = "std")]
+#[derive(Clone)]
+enum Spawner {
+    Worker(mpsc::Sender<WorkerMessage>),
+    Custom(Arc<dyn Fn(PendingNotification) + Send + Sync>),
+}
+#[cfg(feature = "std")]
+impl<R> SpawnReceiver<R> {
+    /// Creates a receiver handing notifications for the specified receiver to the specified worker.
+    #[inline]
+    pub fn new(worker: &Worker, receiver: R) -> Self {
+        Self {spawner: Spawner::Worker(worker.sender.clone()), receiver}
+    }
+    /// Creates a receiver handing notifications for the specified receiver to the specified closure, which is expected to run them on a thread pool, such as with `rayon::spawn` or the `execute` method of a pool.
+    #[inline]
+    pub fn with_spawner<F>(spawn: F, receiver: R) -> Self
+    where F: Fn(Box<dyn FnOnce() + Send>) + Send + Sync + 'static {
+        Self {spawner: Spawner::Custom(Arc::new(spawn)), receiver}
+    }
+}
+#[cfg(feature = "std")]
+impl<E, R> Receiver<E> for SpawnReceiver<R>
+where
+    E: Entry,
+    E::Data: Clone + Send + 'static,
+    R: Receiver<E> + Clone + Send + 'static {
+    fn receive(&mut self, new_value: &E::Data) {
+        let mut receiver = self.receiver.clone();
+        let new_value = new_value.clone();
+        let notification: PendingNotification = Box::new(move || receiver.receive(&new_value));
+        match &self.spawner {
+            // Fails only if the worker thread was killed, in which case there's nowhere to deliver the notification.
+            Spawner::Worker(sender) => {
+                let _ = sender.send(WorkerMessage::Notify(notification));
+            },
+            Spawner::Custom(spawn) => spawn(notification),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl<R: Debug> Debug for SpawnReceiver<R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpawnReceiver")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [receiver] which wakes up threads blocked waiting for the entry to change, handing them a clone of the new value.
+///
+/// Cloning the waiter produces another waiter sharing the same state, which can be moved to the thread which waits. Every waiter remembers which change it has last seen, so that changes made while its thread was busy aren't missed: [`wait`] returns right away if the entry changed since the waiter was created or last returned from waiting, and blocks otherwise. Changes made in the meantime are coalesced, and only the latest value is returned.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, Waiter};
+/// # use std::{thread, time::Duration};
+/// enum Workers {}
+/// impl Entry for Workers {
+///     type Data = usize;
+///     const NAME: &'static str = "workers";
+/// }
+/// let receiver = Waiter::<Workers>::new();
+/// let mut waiter = receiver.clone();
+/// let worker = thread::spawn(move || (waiter.wait(), waiter));
+/// let mut workers = 4;
+/// Handle::<Workers, _>::new(&mut workers, receiver).set(8);
+/// let (workers, mut waiter) = worker.join().unwrap();
+/// assert_eq!(workers, 8);
+/// assert_eq!(waiter.wait_timeout(Duration::from_millis(10)), None);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [`wait`]: #method.wait " "
+#[cfg(feature = "std")]
+pub struct Waiter<E: Entry> {
+    shared: Arc<(Mutex<WaiterState<E::Data>>, Condvar)>,
+    /// The generation of the entry which this waiter has last seen.
+    seen: u64,
+}
+#[cfg(feature = "std")]
+struct WaiterState<D> {
+    /// The number of changes so far.
+    generation: u64,
+    value: Option<D>,
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Waiter<E> {
+    /// Creates a waiter which hasn't seen any changes yet.
+    #[inline]
+    pub fn new() -> Self {
+        Self {shared: Arc::new((Mutex::new(WaiterState {generation: 0, value: None}), Condvar::new())), seen: 0}
+    }
+    /// Blocks the thread until the entry changes, returning a clone of the new value. Returns right away if the entry changed since the waiter was created or last returned from waiting.
+    pub fn wait(&mut self) -> E::Data
+    where E::Data: Clone {
+        let (mutex, condvar) = &*self.shared;
+        let seen = self.seen;
+        let state = condvar.wait_while(Self::lock(mutex), |state| state.generation == seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        Self::observe(&mut self.seen, &state)
+    }
+    /// Like [`wait`], but gives up and returns `None` if the entry doesn't change within the specified duration.
+    ///
+    /// [`wait`]: #method.wait " "
+    pub fn wait_timeout(&mut self, timeout: Duration) -> Option<E::Data>
+    where E::Data: Clone {
+        let (mutex, condvar) = &*self.shared;
+        let seen = self.seen;
+        let (state, result) = condvar.wait_timeout_while(Self::lock(mutex), timeout, |state| state.generation == seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        if result.timed_out() {
+            return None;
+        }
+        Some(Self::observe(&mut self.seen, &state))
+    }
+    /// Returns `true` if the entry changed since the waiter was created or last returned from waiting, in which case [`wait`] doesn't block.
+    ///
+    /// [`wait`]: #method.wait " "
+    #[inline]
+    pub fn has_changed(&self) -> bool {
+        Self::lock(&self.shared.0).generation != self.seen
+    }
+    fn observe(seen: &mut u64, state: &WaiterState<E::Data>) -> E::Data
+    where E::Data: Clone {
+        *seen = state.generation;
+        // The generation only advances when a value is stored.
+        state.value.clone().unwrap_or_else(|| unreachable!())
+    }
+    #[inline]
+    fn lock(mutex: &Mutex<WaiterState<E::Data>>) -> MutexGuard<'_, WaiterState<E::Data>> {
+        lock_ignoring_poison(mutex)
+    }
+}
+#[cfg(feature = "std")]
+impl<E> Receiver<E> for Waiter<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        let (mutex, condvar) = &*self.shared;
+        let mut state = Self::lock(mutex);
+        match &mut state.value {
+            Some(value) => value.clone_from(new_value),
+            None => state.value = Some(new_value.clone()),
+        }
+        state.generation += 1;
+        condvar.notify_all();
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Clone for Waiter<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {shared: Arc::clone(&self.shared), seen: self.seen}
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Default for Waiter<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Debug for Waiter<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waiter")
+            .field("seen", &self.seen)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [receiver] which wakes up tasks awaiting the next change of the entry, handing them a clone of the new value. The asynchronous counterpart of [`Waiter`].
+///
+/// The future returned by [`changed`] works with any executor, including smol, async-std and Tokio, since it only relies on the standard waker mechanism. Like with `Waiter`, cloning produces another receiver sharing the same state, and every clone remembers which change it has last seen, so that the future resolves right away if the entry changed while the task was busy.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, Notified};
+/// enum Theme {}
+/// impl Entry for Theme {
+///     type Data = String;
+///     const NAME: &'static str = "theme";
+/// }
+/// let receiver = Notified::<Theme>::new();
+/// let mut notified = receiver.clone();
+/// smol::block_on(async {
+///     let task = smol::spawn(async move { notified.changed().await });
+///     let mut theme = "light".to_string();
+///     Handle::<Theme, _>::new(&mut theme, receiver).set("dark".to_string());
+///     assert_eq!(task.await, "dark");
+/// });
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [`Waiter`]: struct.Waiter.html " "
+/// [`changed`]: #method.changed " "
+#[cfg(feature = "std")]
+pub struct Notified<E: Entry> {
+    shared: Arc<NotifiedState<E::Data>>,
+    /// The generation of the entry which this receiver has last seen.
+    seen: usize,
+}
+#[cfg(feature = "std")]
+struct NotifiedState<D> {
+    /// The number of changes so far, only incremented with the slot locked.
+    generation: AtomicUsize,
+    slot: Mutex<NotifiedSlot<D>>,
+}
+#[cfg(feature = "std")]
+struct NotifiedSlot<D> {
+    value: Option<D>,
+    /// The wakers of the tasks awaiting the next change.
+    wakers: Vec<Waker>,
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Notified<E> {
+    /// Creates a receiver which hasn't seen any changes yet.
+    #[inline]
+    pub fn new() -> Self {
+        let slot = Mutex::new(NotifiedSlot {value: None, wakers: Vec::new()});
+        Self {shared: Arc::new(NotifiedState {generation: AtomicUsize::new(0), slot}), seen: 0}
+    }
+    /// Returns a future which resolves to a clone of the new value once the entry changes, or right away if the entry changed since the receiver was created or a future returned by this method last resolved.
+    #[inline(always)]
+    pub fn changed(&mut self) -> Changed<'_, E> {
+        Changed {notified: self}
+    }
+    /// Returns `true` if the entry changed since the receiver was created or a future returned by [`changed`] last resolved, in which case the next such future resolves right away.
+    ///
+    /// [`changed`]: #method.changed " "
+    #[inline]
+    pub fn has_changed(&self) -> bool {
+        self.shared.generation.load(Ordering::Acquire) != self.seen
+    }
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, NotifiedSlot<E::Data>> {
+        lock_ignoring_poison(&self.shared.slot)
+    }
+}
+#[cfg(feature = "std")]
+impl<E> Receiver<E> for Notified<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    fn receive(&mut self, new_value: &E::Data) {
+        let wakers = {
+            let mut slot = self.lock();
+            match &mut slot.value {
+                Some(value) => value.clone_from(new_value),
+                None => slot.value = Some(new_value.clone()),
+            }
+            self.shared.generation.fetch_add(1, Ordering::Release);
+            core::mem::take(&mut slot.wakers)
+        };
+        // Woken with the slot unlocked, so that executors polling inline don't deadlock.
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Clone for Notified<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {shared: Arc::clone(&self.shared), seen: self.seen}
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Default for Notified<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Debug for Notified<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notified")
+            .field("seen", &self.seen)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The future returned by [`Notified::changed`].
+///
+/// [`Notified::changed`]: struct.Notified.html#method.changed " "
+#[cfg(feature = "std")]
+#[must_use = "futures do nothing unless awaited"]
+pub struct Changed<'a, E: Entry> {
+    notified: &'a mut Notified<E>,
+}
+#[cfg(feature = "std")]
+impl<E> Future for Changed<'_, E>
+where
+    E: Entry,
+    E::Data: Clone {
+    type Output = E::Data;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let notified = &mut *self.notified;
+        if notified.shared.generation.load(Ordering::Acquire) == notified.seen {
+            let mut slot = notified.lock();
+            // Checked again with the slot locked, since a change could have been made in the meantime.
+            if notified.shared.generation.load(Ordering::Acquire) == notified.seen {
+                if !slot.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+                    slot.wakers.push(cx.waker().clone());
+                }
+                return Poll::Pending;
+            }
+        }
+        let (generation, value) = {
+            let slot = notified.lock();
+            (notified.shared.generation.load(Ordering::Acquire), slot.value.clone())
+        };
+        notified.seen = generation;
+        // The generation only advances when a value is stored.
+        Poll::Ready(value.unwrap_or_else(|| unreachable!()))
+    }
+}
+#[cfg(feature = "std")]
+impl<E: Entry> Debug for Changed<'_, E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Changed")
+            .field("notified", &self.notified)
+            .finish()
+    }
+}
+
+/// A [receiver] which applies every change to the corresponding entry of another config table, such as a shadow copy kept as the last known good configuration, through a handle, so that the receivers of the other table are notified as well.
+///
+/// The other table is shared behind a mutex, which is locked while the change is applied. Because of that, the receivers of the other table must not lead back to the table this receiver is attached to, and the other table must not be locked by the thread changing this one, or the change deadlocks.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, GetExt as _, MirrorReceiver};
+/// use std::sync::{Arc, Mutex};
+/// #[derive(ConfigTable)]
+/// #[snec(receiver({self.mirror.clone()}: Option<MirrorReceiver<Config>>))]
+/// struct Config {
+///     #[snec]
+///     port: u16,
+///     mirror: Option<MirrorReceiver<Config>>,
+/// }
+/// let last_known_good = Arc::new(Mutex::new(Config {port: 8080, mirror: None}));
+/// let mut config = Config {port: 8080, mirror: Some(MirrorReceiver::new(Arc::clone(&last_known_good)))};
+/// config.get_handle_to::<entries::Port>().set(8081);
+/// assert_eq!(last_known_good.lock().unwrap().port, 8081);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+#[cfg(feature = "std")]
+#[allow(clippy::module_name_repetitions)]
+pub struct MirrorReceiver<T> {
+    mirror: Arc<Mutex<T>>,
+}
+#[cfg(feature = "std")]
+impl<T> MirrorReceiver<T> {
+    /// Creates a receiver applying changes to the specified table.
+    #[inline(always)]
+    pub fn new(mirror: Arc<Mutex<T>>) -> Self {
+        Self {mirror}
+    }
+    /// Returns the table to which the changes are applied.
+    #[inline(always)]
+    pub fn mirror(&self) -> &Arc<Mutex<T>> {
+        &self.mirror
+    }
+}
+#[cfg(feature = "std")]
+impl<E, T> Receiver<E> for MirrorReceiver<T>
+where
+    E: Entry,
+    E::Data: Clone,
+    T: Get<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        let mut mirror = self.mirror.lock().expect(POISONING_MSG);
+        mirror.get_handle().set(new_value.clone());
+    }
+}
+#[cfg(feature = "std")]
+impl<T> Clone for MirrorReceiver<T> {
     #[inline(always)]
-    fn default() -> Self {
-        Self {closure: F::default(), _phantom: PhantomData}
+    fn clone(&self) -> Self {
+        Self {mirror: Arc::clone(&self.mirror)}
     }
 }
-
-impl<E: Entry, F: FnMut(&E::Data) + Debug> Debug for FnReceiver<E, F> {
+#[cfg(feature = "std")]
+impl<T> Debug for MirrorReceiver<T> {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.debug_struct("FnReceiver")
-            .field("closure", &self.closure)
-            .finish()
+        f.debug_struct("MirrorReceiver").finish_non_exhaustive()
     }
 }
 
@@ -165,6 +2043,115 @@ where
     }
 }
 
+/// A [receiver] like [`IterReceiver`], but which notifies the items produced by the parallel iterator created from a reference to the contained value on the [rayon] thread pool, all at once. Requires the `rayon` feature.
+///
+/// Notifying many receivers which take long to handle a change in parallel keeps the thread making the change from being stalled for as long as it takes to notify them one by one. The change is still only finished once all receivers are notified, and if any of them panics, the panic is propagated to the thread making the change after the other receivers are notified. Since the receivers are notified on other threads, they have to be `Send`, and the data of the entry has to be `Sync`. The order in which the receivers are notified is unspecified.
+///
+/// # Example
+/// ```
+/// use snec::{Entry, Handle, Receiver, ParIterReceiver};
+/// enum Resolution {}
+/// impl Entry for Resolution {
+///     type Data = (u32, u32);
+///     const NAME: &'static str = "resolution";
+/// }
+/// #[derive(Default)]
+/// struct Viewport {
+///     pixels: Vec<u8>,
+/// }
+/// impl Receiver<Resolution> for Viewport {
+///     fn receive(&mut self, &(width, height): &(u32, u32)) {
+///         // Reallocating framebuffers takes a while.
+///         self.pixels = vec![0; (width * height * 4) as usize];
+///     }
+/// }
+/// let mut viewports = ParIterReceiver::<Resolution, Vec<Viewport>>::new((0..16).map(|_| Viewport::default()).collect());
+/// let mut resolution = (640, 480);
+/// Handle::<Resolution, _>::new(&mut resolution, &mut viewports).set((1280, 720));
+/// assert!(viewports.iter.iter().all(|viewport| viewport.pixels.len() == 1280 * 720 * 4));
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [`IterReceiver`]: struct.IterReceiver.html " "
+/// [rayon]: https://docs.rs/rayon " "
+#[cfg(feature = "rayon")]
+#[allow(clippy::module_name_repetitions)]
+pub struct ParIterReceiver<E: Entry, I>
+where
+    for<'a> &'a mut I: IntoParallelIterator,
+    for<'a> <&'a mut I as IntoParallelIterator>::Item: Receiver<E> {
+    /// The iterable which produces parallel iterators over the receivers.
+    pub iter: I,
+    _phantom: PhantomData<E>,
+}
+#[cfg(feature = "rayon")]
+impl<E: Entry, I> Receiver<E> for ParIterReceiver<E, I>
+where
+    E::Data: Sync,
+    for<'a> &'a mut I: IntoParallelIterator,
+    for<'a> <&'a mut I as IntoParallelIterator>::Item: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        (&mut self.iter).into_par_iter().for_each(|mut receiver| receiver.receive(new_value));
+    }
+}
+#[cfg(feature = "rayon")]
+impl<E: Entry, I> Receiver<E> for &ParIterReceiver<E, I>
+where
+    E::Data: Sync,
+    for<'a> &'a mut I: IntoParallelIterator,
+    for<'a> &'a I: IntoParallelIterator,
+    for<'a> <&'a mut I as IntoParallelIterator>::Item: Receiver<E>,
+    for<'a> <&'a I as IntoParallelIterator>::Item: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        (&self.iter).into_par_iter().for_each(|mut receiver| receiver.receive(new_value));
+    }
+}
+#[cfg(feature = "rayon")]
+impl<E: Entry, I> ParIterReceiver<E, I>
+where
+    for<'a> &'a mut I: IntoParallelIterator,
+    for<'a> <&'a mut I as IntoParallelIterator>::Item: Receiver<E> {
+    /// Creates a new receiver which notifies the specified iterable of receivers in parallel.
+    #[inline(always)]
+    pub fn new(iter: I) -> Self {
+        Self {iter, _phantom: PhantomData}
+    }
+}
+#[cfg(feature = "rayon")]
+impl<E: Entry, I: Clone> Clone for ParIterReceiver<E, I>
+where
+    for<'a> &'a mut I: IntoParallelIterator,
+    for<'a> <&'a mut I as IntoParallelIterator>::Item: Receiver<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {iter: self.iter.clone(), _phantom: PhantomData}
+    }
+}
+#[cfg(feature = "rayon")]
+impl<E: Entry, I: Default> Default for ParIterReceiver<E, I>
+where
+    for<'a> &'a mut I: IntoParallelIterator,
+    for<'a> <&'a mut I as IntoParallelIterator>::Item: Receiver<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {iter: I::default(), _phantom: PhantomData}
+    }
+}
+#[cfg(feature = "rayon")]
+impl<E: Entry, I: Debug> Debug for ParIterReceiver<E, I>
+where
+    for<'a> &'a mut I: IntoParallelIterator,
+    for<'a> <&'a mut I as IntoParallelIterator>::Item: Receiver<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParIterReceiver")
+            .field("iter", &self.iter)
+            .finish()
+    }
+}
+
 /// A [receiver] which does nothing when notified.
 ///
 /// [receiver]: trait.Receiver.html " "
@@ -178,6 +2165,208 @@ impl<E: Entry> Receiver<E> for &EmptyReceiver {
     #[inline(always)]
     fn receive(&mut self, _: &E::Data) {}
 }
+impl<E: Entry> TryReceiver<E> for EmptyReceiver {
+    type Error = Infallible;
+    #[inline(always)]
+    fn try_receive(&mut self, _: &E::Data) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+impl<E: Entry> PreReceiver<E> for EmptyReceiver {
+    type Error = Infallible;
+    #[inline(always)]
+    fn pre_receive(&mut self, _: &E::Data, _: &E::Data) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// A [receiver] which panics when notified, naming the entry which was modified.
+///
+/// This is the opposite of [`EmptyReceiver`]: instead of ignoring notifications, it treats any of them as a bug. It's intended for tests asserting that certain code paths never modify configuration.
+///
+/// # Example
+/// ```should_panic
+/// # use snec::{Entry, Handle, PanicReceiver};
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = u8;
+///     const NAME: &'static str = "volume";
+/// }
+/// let mut volume = 100;
+/// let mut handle = Handle::<Volume, _>::new(&mut volume, PanicReceiver);
+/// handle.get(); // Reading is fine,
+/// handle.set(50); // but this panics.
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+/// [`EmptyReceiver`]: struct.EmptyReceiver.html " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PanicReceiver;
+impl<E: Entry> Receiver<E> for PanicReceiver {
+    #[track_caller]
+    fn receive(&mut self, _: &E::Data) {
+        panic!("entry `{}` was modified, but no modifications were expected", E::NAME);
+    }
+}
+impl<E: Entry> Receiver<E> for &PanicReceiver {
+    #[track_caller]
+    fn receive(&mut self, _: &E::Data) {
+        panic!("entry `{}` was modified, but no modifications were expected", E::NAME);
+    }
+}
+
+/// A [receiver] which counts the notifications it receives using an atomic counter.
+///
+/// The counter can be owned by the receiver or shared with other code, using any type which can be borrowed as an `AtomicUsize`, such as `&AtomicUsize` or `Arc<AtomicUsize>`. Counting is the cheapest form of instrumentation, useful for detecting notification storms and checking debouncing in tests and benchmarks.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, CountingReceiver};
+/// # use std::sync::atomic::AtomicUsize;
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = f32;
+///     const NAME: &'static str = "volume";
+/// }
+/// let counter = AtomicUsize::new(0);
+/// let mut volume = 1.0;
+/// let mut handle = Handle::<Volume, _>::new(&mut volume, CountingReceiver::shared(&counter));
+/// handle.set(0.5);
+/// handle.set(0.7);
+/// assert_eq!(CountingReceiver::shared(&counter).count(), 2);
+/// ```
+///
+/// [receiver]: trait.Receiver.html " "
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Default)]
+pub struct CountingReceiver<C = AtomicUsize> {
+    /// The counter which is incremented for every notification.
+    pub counter: C,
+}
+impl CountingReceiver {
+    /// Creates a receiver with its own counter, starting at zero.
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {counter: AtomicUsize::new(0)}
+    }
+}
+impl<C: Borrow<AtomicUsize>> CountingReceiver<C> {
+    /// Creates a receiver which increments the specified shared counter.
+    #[inline(always)]
+    pub const fn shared(counter: C) -> Self {
+        Self {counter}
+    }
+    /// Returns the number of notifications counted so far.
+    #[inline(always)]
+    pub fn count(&self) -> usize {
+        self.counter.borrow().load(Ordering::Relaxed)
+    }
+    /// Resets the counter to zero, returning the previous count.
+    #[inline(always)]
+    pub fn reset(&self) -> usize {
+        self.counter.borrow().swap(0, Ordering::Relaxed)
+    }
+    /// Creates a [change token] for polling whether the entry changed after this call. Requires the counter to be shared, such as `&AtomicUsize` or `Arc<AtomicUsize>`.
+    ///
+    /// [change token]: struct.ChangeToken.html " "
+    #[inline]
+    pub fn token(&self) -> ChangeToken<C>
+    where C: Clone {
+        ChangeToken::new(self.counter.clone())
+    }
+}
+impl<E: Entry, C: Borrow<AtomicUsize>> Receiver<E> for CountingReceiver<C> {
+    #[inline(always)]
+    fn receive(&mut self, _: &E::Data) {
+        self.counter.borrow().fetch_add(1, Ordering::Relaxed);
+    }
+}
+impl<E: Entry, C: Borrow<AtomicUsize>> Receiver<E> for &CountingReceiver<C> {
+    #[inline(always)]
+    fn receive(&mut self, _: &E::Data) {
+        self.counter.borrow().fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A token for polling whether an entry changed, for consumers which check for changes at their own pace instead of being notified.
+///
+/// The token remembers the generation of the entry, which is the count of a [`CountingReceiver`] attached to it, at the time the token was created or last [acknowledged], and reports a change once the counter no longer matches. Checking the token is a single atomic load, so it's cheap enough to do on every frame or every request, after which the new value is read only if it actually changed.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, CountingReceiver, GetExt as _};
+/// use std::sync::{Arc, atomic::AtomicUsize};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec(entry, receiver({CountingReceiver::shared(Arc::clone(&self.font_generation))}: CountingReceiver<Arc<AtomicUsize>>))]
+///     font: String,
+///     font_generation: Arc<AtomicUsize>,
+/// }
+/// let mut table = MyConfigTable {font: "Fira Sans".to_string(), font_generation: Arc::default()};
+/// let mut token = CountingReceiver::shared(Arc::clone(&table.font_generation)).token();
+/// assert!(!token.has_changed());
+/// table.get_handle_to::<entries::Font>().set("Inter".to_string());
+/// // Rebuild the glyph cache once, no matter how many times the font changed since the last check.
+/// assert!(token.acknowledge());
+/// assert!(!token.has_changed());
+/// ```
+///
+/// The counter can also be attached to a whole config table as its [table receiver], which makes it the epoch of the table, incremented whenever any of its entries change. Since the counter is shared, the epoch stays accessible while the table itself is behind a lock or another shared wrapper, and checking whether anything changed doesn't require locking the table:
+/// ```
+/// use snec::{ChangeToken, ConfigTable, GetExt as _};
+/// use std::sync::{Arc, Mutex, atomic::AtomicUsize};
+/// #[derive(ConfigTable)]
+/// #[snec(table_receiver({Arc::clone(&self.epoch)}: Arc<AtomicUsize>))]
+/// struct MyConfigTable {
+///     #[snec]
+///     width: u32,
+///     #[snec]
+///     height: u32,
+///     epoch: Arc<AtomicUsize>,
+/// }
+/// let epoch = Arc::new(AtomicUsize::new(0));
+/// let table = Arc::new(Mutex::new(MyConfigTable {width: 800, height: 600, epoch: Arc::clone(&epoch)}));
+/// let mut token = ChangeToken::new(epoch);
+/// (*table.lock().unwrap()).get_handle_to::<entries::Width>().set(1024);
+/// (*table.lock().unwrap()).get_handle_to::<entries::Height>().set(768);
+/// assert!(token.acknowledge());
+/// assert_eq!(token.generation(), 2);
+/// ```
+///
+/// [`CountingReceiver`]: struct.CountingReceiver.html " "
+/// [acknowledged]: #method.acknowledge " "
+/// [table receiver]: trait.TableReceiver.html " "
+#[derive(Clone, Debug)]
+pub struct ChangeToken<C> {
+    counter: C,
+    generation: usize,
+}
+impl<C: Borrow<AtomicUsize>> ChangeToken<C> {
+    /// Creates a token for the entry whose changes are counted by the specified counter, considering its current generation as seen.
+    #[inline]
+    pub fn new(counter: C) -> Self {
+        let generation = counter.borrow().load(Ordering::Relaxed);
+        Self {counter, generation}
+    }
+    /// Returns `true` if the entry changed since the token was created or last acknowledged.
+    #[inline(always)]
+    pub fn has_changed(&self) -> bool {
+        self.counter.borrow().load(Ordering::Relaxed) != self.generation
+    }
+    /// Considers the current generation of the entry as seen, returning `true` if the entry changed since the token was created or last acknowledged.
+    #[inline]
+    pub fn acknowledge(&mut self) -> bool {
+        let generation = self.counter.borrow().load(Ordering::Relaxed);
+        let changed = generation != self.generation;
+        self.generation = generation;
+        changed
+    }
+    /// Returns the generation of the entry which was last seen by the token.
+    #[inline(always)]
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
 
 //────────────────────────────────────────────────────—┐
 // Receiver implementations for builtins and std types |
@@ -191,6 +2380,30 @@ where
     fn receive(&mut self, new_value: &E::Data) {
         (*self).receive(new_value);
     }
+    #[inline(always)]
+    fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+        (*self).accepts(old_value, new_value)
+    }
+}
+impl<E, R> TryReceiver<E> for &mut R
+where
+    E: Entry,
+    R: TryReceiver<E> + ?Sized {
+    type Error = R::Error;
+    #[inline(always)]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        (*self).try_receive(new_value)
+    }
+}
+impl<E, R> PreReceiver<E> for &mut R
+where
+    E: Entry,
+    R: PreReceiver<E> + ?Sized {
+    type Error = R::Error;
+    #[inline(always)]
+    fn pre_receive(&mut self, old_value: &E::Data, new_value: &E::Data) -> Result<(), Self::Error> {
+        (*self).pre_receive(old_value, new_value)
+    }
 }
 impl<E, R> Receiver<E> for Option<R>
 where
@@ -202,6 +2415,39 @@ where
             receiver.receive(new_value);
         }
     }
+    #[inline]
+    fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+        match self.as_mut() {
+            Some(receiver) => receiver.accepts(old_value, new_value),
+            None => true,
+        }
+    }
+}
+impl<E, R> TryReceiver<E> for Option<R>
+where
+    E: Entry,
+    R: TryReceiver<E> {
+    type Error = R::Error;
+    #[inline]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        match self.as_mut() {
+            Some(receiver) => receiver.try_receive(new_value),
+            None => Ok(()),
+        }
+    }
+}
+impl<E, R> PreReceiver<E> for Option<R>
+where
+    E: Entry,
+    R: PreReceiver<E> {
+    type Error = R::Error;
+    #[inline]
+    fn pre_receive(&mut self, old_value: &E::Data, new_value: &E::Data) -> Result<(), Self::Error> {
+        match self.as_mut() {
+            Some(receiver) => receiver.pre_receive(old_value, new_value),
+            None => Ok(()),
+        }
+    }
 }
 impl<E, R> Receiver<E> for &Option<R>
 where
@@ -214,6 +2460,7 @@ where
         }
     }
 }
+#[cfg(feature = "alloc")]
 impl<E, R> Receiver<E> for Box<R>
 where
     E: Entry,
@@ -222,7 +2469,34 @@ where
     fn receive(&mut self, new_value: &E::Data) {
         self.deref_mut().receive(new_value);
     }
+    #[inline(always)]
+    fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+        self.deref_mut().accepts(old_value, new_value)
+    }
+}
+#[cfg(feature = "alloc")]
+impl<E, R> TryReceiver<E> for Box<R>
+where
+    E: Entry,
+    R: TryReceiver<E> + ?Sized {
+    type Error = R::Error;
+    #[inline(always)]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.deref_mut().try_receive(new_value)
+    }
 }
+#[cfg(feature = "alloc")]
+impl<E, R> PreReceiver<E> for Box<R>
+where
+    E: Entry,
+    R: PreReceiver<E> + ?Sized {
+    type Error = R::Error;
+    #[inline(always)]
+    fn pre_receive(&mut self, old_value: &E::Data, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.deref_mut().pre_receive(old_value, new_value)
+    }
+}
+#[cfg(feature = "alloc")]
 impl<E, R> Receiver<E> for &Box<R>
 where
     E: Entry,
@@ -233,6 +2507,7 @@ where
         (&***self).receive(new_value);
     }
 }
+#[cfg(feature = "alloc")]
 impl<E, R> Receiver<E> for Rc<R>
 where
     E: Entry,
@@ -243,6 +2518,7 @@ where
         (&**self).receive(new_value);
     }
 }
+#[cfg(feature = "alloc")]
 impl<E, R> Receiver<E> for Arc<R>
 where
     E: Entry,
@@ -267,14 +2543,73 @@ impl<E, R> Receiver<E> for &RefCell<R>
 where
     E: Entry,
     R: Receiver<E> + ?Sized {
-    #[inline(always)]
+    #[inline]
+    #[track_caller]
     fn receive(&mut self, new_value: &E::Data) {
+        let _notifying = Notifying::receiver(*self, E::NAME);
         (*self.borrow_mut()).receive(new_value);
     }
 }
 
+/// Notifies all receivers in the slice, in order.
+impl<E, R> Receiver<E> for [R]
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        for receiver in self {
+            receiver.receive(new_value);
+        }
+    }
+}
+/// Notifies all receivers in the array, in order.
+impl<E, R, const N: usize> Receiver<E> for [R; N]
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn receive(&mut self, new_value: &E::Data) {
+        self[..].receive(new_value);
+    }
+}
+/// Notifies all receivers in the vector, in order.
+#[cfg(feature = "alloc")]
+impl<E, R> Receiver<E> for Vec<R>
+where
+    E: Entry,
+    R: Receiver<E> {
+    #[inline(always)]
+    fn receive(&mut self, new_value: &E::Data) {
+        self[..].receive(new_value);
+    }
+}
+macro_rules! impl_receiver_for_tuples {
+    ($(($ty:ident, $index:tt)),+) => {
+        /// Notifies all receivers in the tuple, in order. A change is only accepted if all of them accept it.
+        impl<E: Entry, $($ty: Receiver<E>),+> Receiver<E> for ($($ty,)+) {
+            #[inline]
+            fn receive(&mut self, new_value: &E::Data) {
+                $(self.$index.receive(new_value);)+
+            }
+            #[inline]
+            fn accepts(&mut self, old_value: &E::Data, new_value: &E::Data) -> bool {
+                true $(&& self.$index.accepts(old_value, new_value))+
+            }
+        }
+    };
+}
+impl_receiver_for_tuples!((A, 0));
+impl_receiver_for_tuples!((A, 0), (B, 1));
+impl_receiver_for_tuples!((A, 0), (B, 1), (C, 2));
+impl_receiver_for_tuples!((A, 0), (B, 1), (C, 2), (D, 3));
+impl_receiver_for_tuples!((A, 0), (B, 1), (C, 2), (D, 3), (F, 4));
+impl_receiver_for_tuples!((A, 0), (B, 1), (C, 2), (D, 3), (F, 4), (G, 5));
+impl_receiver_for_tuples!((A, 0), (B, 1), (C, 2), (D, 3), (F, 4), (G, 5), (H, 6));
+impl_receiver_for_tuples!((A, 0), (B, 1), (C, 2), (D, 3), (F, 4), (G, 5), (H, 6), (I, 7));
+
 #[cfg(feature = "std")]
-static POISONING_MSG: &str = "attempt to use a poisoned lock as a receiver";
+pub(crate) static POISONING_MSG: &str = "attempt to use a poisoned lock as a receiver";
 #[cfg(feature = "std")]
 impl<E, R> Receiver<E> for Mutex<R>
 where
@@ -290,8 +2625,10 @@ impl<E, R> Receiver<E> for &Mutex<R>
 where
     E: Entry,
     R: Receiver<E> + ?Sized {
-    #[inline(always)]
+    #[inline]
+    #[track_caller]
     fn receive(&mut self, new_value: &E::Data) {
+        let _notifying = Notifying::receiver(*self, E::NAME);
         self.lock().expect(POISONING_MSG).receive(new_value);
     }
 }
@@ -310,8 +2647,167 @@ impl<E, R> Receiver<E> for &RwLock<R>
 where
     E: Entry,
     R: Receiver<E> + ?Sized {
-    #[inline(always)]
+    #[inline]
+    #[track_caller]
     fn receive(&mut self, new_value: &E::Data) {
+        let _notifying = Notifying::receiver(*self, E::NAME);
         self.write().expect(POISONING_MSG).receive(new_value);
     }
-}
\ No newline at end of file
+}
+
+//───────────────────────────────────────────┐
+// TableReceiver implementations for builtins |
+//───────────────────────────────────────────┘
+
+impl TableReceiver for EmptyReceiver {
+    #[inline(always)]
+    fn receive_change(&mut self, _: &'static str) {}
+}
+impl TableReceiver for PanicReceiver {
+    #[track_caller]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        panic!("entry `{}` was modified, but no modifications were expected", entry_name);
+    }
+}
+impl<T: TableReceiver + ?Sized> TableReceiver for &mut T {
+    #[inline(always)]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        (*self).receive_change(entry_name);
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: TableReceiver + ?Sized> TableReceiver for Box<T> {
+    #[inline(always)]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        self.deref_mut().receive_change(entry_name);
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> TableReceiver for Rc<T>
+where for<'a> &'a T: TableReceiver {
+    #[inline(always)]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        (&**self).receive_change(entry_name);
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: ?Sized> TableReceiver for Arc<T>
+where for<'a> &'a T: TableReceiver {
+    #[inline(always)]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        (&**self).receive_change(entry_name);
+    }
+}
+impl<T: TableReceiver + ?Sized> TableReceiver for &RefCell<T> {
+    #[inline]
+    #[track_caller]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        let _notifying = Notifying::receiver(*self, entry_name);
+        self.borrow_mut().receive_change(entry_name);
+    }
+}
+/// Sets the flag to `true` when notified, marking the table as changed.
+impl TableReceiver for &Cell<bool> {
+    #[inline(always)]
+    fn receive_change(&mut self, _: &'static str) {
+        self.set(true);
+    }
+}
+/// Sets the flag to `true` when notified, marking the table as changed.
+impl TableReceiver for &AtomicBool {
+    #[inline(always)]
+    fn receive_change(&mut self, _: &'static str) {
+        self.store(true, Ordering::Release);
+    }
+}
+/// Increments the counter when notified, making it the epoch of the table, which can be polled with a [`ChangeToken`].
+///
+/// [`ChangeToken`]: struct.ChangeToken.html " "
+impl TableReceiver for &AtomicUsize {
+    #[inline(always)]
+    fn receive_change(&mut self, _: &'static str) {
+        self.fetch_add(1, Ordering::Relaxed);
+    }
+}
+impl<C: Borrow<AtomicUsize>> TableReceiver for CountingReceiver<C> {
+    #[inline(always)]
+    fn receive_change(&mut self, _: &'static str) {
+        self.counter.borrow().fetch_add(1, Ordering::Relaxed);
+    }
+}
+impl<C: Borrow<AtomicUsize>> TableReceiver for &CountingReceiver<C> {
+    #[inline(always)]
+    fn receive_change(&mut self, _: &'static str) {
+        self.counter.borrow().fetch_add(1, Ordering::Relaxed);
+    }
+}
+#[cfg(feature = "std")]
+impl<T: TableReceiver + ?Sized> TableReceiver for &Mutex<T> {
+    #[inline]
+    #[track_caller]
+    fn receive_change(&mut self, entry_name: &'static str) {
+        let _notifying = Notifying::receiver(*self, entry_name);
+        self.lock().expect(POISONING_MSG).receive_change(entry_name);
+    }
+}
+
+//─────────────────────────────────────────┐
+// DynReceiver implementations for builtins |
+//─────────────────────────────────────────┘
+
+impl DynReceiver for EmptyReceiver {
+    #[inline(always)]
+    fn receive_dyn(&mut self, _: &'static str, _: &dyn Any) {}
+}
+impl DynReceiver for PanicReceiver {
+    #[track_caller]
+    fn receive_dyn(&mut self, entry_name: &'static str, _: &dyn Any) {
+        panic!("entry `{}` was modified, but no modifications were expected", entry_name);
+    }
+}
+impl<R: DynReceiver + ?Sized> DynReceiver for &mut R {
+    #[inline(always)]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        (*self).receive_dyn(entry_name, new_value);
+    }
+}
+#[cfg(feature = "alloc")]
+impl<R: DynReceiver + ?Sized> DynReceiver for Box<R> {
+    #[inline(always)]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        self.deref_mut().receive_dyn(entry_name, new_value);
+    }
+}
+#[cfg(feature = "alloc")]
+impl<R: ?Sized> DynReceiver for Rc<R>
+where for<'a> &'a R: DynReceiver {
+    #[inline(always)]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        (&**self).receive_dyn(entry_name, new_value);
+    }
+}
+#[cfg(feature = "alloc")]
+impl<R: ?Sized> DynReceiver for Arc<R>
+where for<'a> &'a R: DynReceiver {
+    #[inline(always)]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        (&**self).receive_dyn(entry_name, new_value);
+    }
+}
+impl<R: DynReceiver + ?Sized> DynReceiver for &RefCell<R> {
+    #[inline]
+    #[track_caller]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        let _notifying = Notifying::receiver(*self, entry_name);
+        self.borrow_mut().receive_dyn(entry_name, new_value);
+    }
+}
+#[cfg(feature = "std")]
+impl<R: DynReceiver + ?Sized> DynReceiver for &Mutex<R> {
+    #[inline]
+    #[track_caller]
+    fn receive_dyn(&mut self, entry_name: &'static str, new_value: &dyn Any) {
+        let _notifying = Notifying::receiver(*self, entry_name);
+        self.lock().expect(POISONING_MSG).receive_dyn(entry_name, new_value);
+    }
+}
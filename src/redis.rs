@@ -0,0 +1,206 @@
+//! Broadcasting changes of config tables over [Redis pub/sub].
+//!
+//! Every change of an entry is published to a channel as a JSON array of the [name] of the entry and its new value, such as `["max_connections",200]`, and every instance subscribed to the channel applies the messages to its own table, which keeps the tables of multiple instances of a service in sync without storing them anywhere. [`RedisSource`] and [`RedisSink`] are the [remote backends] for Redis: the source subscribes to the channel and receives the published changes, and the sink publishes them. See the documentation of the [`remote`] module for how the synchronization works.
+//!
+//! Since pub/sub doesn't store the messages, [`load_into`] only subscribes to the channel and doesn't change the table, and instances only receive the changes published after they subscribed. Messages which aren't arrays of an entry name and a value are ignored. Each instance also receives its own changes, which don't change anything unless they arrive after a newer local change of the same entry, in which case the entry briefly takes the older value until the newer one arrives.
+//!
+//! The client is asynchronous and doesn't depend on a specific runtime: the connections are driven by async-std, which runs their tasks and I/O on its own threads, so they work with any executor, such as smol, async-std or Tokio. With the `redis-tokio` feature, connections created inside of a Tokio runtime use Tokio's I/O instead.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, GetExt as _, remote::RemoteReceiver};
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.remote.clone()}: RemoteReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     max_connections: u32,
+//!     remote: RemoteReceiver,
+//! }
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = redis::Client::open("redis://localhost")?;
+//! let (mut sync, writer) = snec::redis::sync(&client, "my-service:config").await?;
+//! let mut table = MyConfigTable {max_connections: 100, remote: sync.receiver()};
+//! sync.load_into(&mut table).await?;
+//! // Usually spawned as a separate task.
+//! let writer = writer.run();
+//! // Published to all other instances.
+//! table.get_handle_to::<entries::MaxConnections>().set(200);
+//! while let Some(changed) = sync.apply_next(&mut table).await? {
+//!     println!("{} entries changed remotely", changed);
+//! }
+//! # drop(writer);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [Redis pub/sub]: https://redis.io/docs/latest/develop/interact/pubsub/ " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`RedisSource`]: struct.RedisSource.html " "
+//! [`RedisSink`]: struct.RedisSink.html " "
+//! [remote backends]: ../remote/trait.Source.html " "
+//! [`remote`]: ../remote/index.html " "
+//! [`load_into`]: ../remote/struct.RemoteSync.html#method.load_into " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    mem,
+    pin::Pin,
+};
+use ::redis::{
+    aio::{MultiplexedConnection, PubSub},
+    AsyncCommands as _,
+    Client,
+    Msg,
+    RedisError,
+};
+use futures_util::stream::{Stream, StreamExt as _};
+use serde_json::value::RawValue;
+use super::remote::{self, BoxFuture, Values, RemoteSync, RemoteWriter, Source, Sink};
+
+/// Synchronizes config tables over Redis pub/sub.
+pub type RedisSync = RemoteSync<RedisSource>;
+/// Publishes local changes to Redis.
+pub type RedisWriter = RemoteWriter<RedisSink>;
+/// An error which occurred while synchronizing over Redis pub/sub.
+pub type Error = remote::Error<RedisError>;
+
+/// Connects to Redis to create a synchronizer for the changes published to the specified channel, along with the writer which publishes local changes to it.
+pub async fn sync(client: &Client, channel: impl Into<String>) -> Result<(RedisSync, RedisWriter), RedisError> {
+    let channel = channel.into();
+    let sink = RedisSink::new(client.get_multiplexed_async_connection().await?, channel.clone());
+    let source = RedisSource::new(client.get_async_pubsub().await?, channel);
+    Ok(RemoteSync::new(source, sink))
+}
+
+/// The stream of messages received by a subscribed connection.
+type Messages = Pin<Box<dyn Stream<Item = Msg> + Send>>;
+
+/// A [source] which receives the changes published to a channel.
+///
+/// [source]: ../remote/trait.Source.html " "
+pub struct RedisSource {
+    state: State,
+    channel: String,
+}
+enum State {
+    Connected(PubSub),
+    Subscribed(Messages),
+    Closed,
+}
+impl RedisSource {
+    /// Creates a source for the changes published to the specified channel, which subscribes to it with the specified connection when the changes are first loaded or waited for.
+    #[inline]
+    pub fn new(connection: PubSub, channel: impl Into<String>) -> Self {
+        Self {state: State::Connected(connection), channel: channel.into()}
+    }
+    /// Returns the name of the channel.
+    #[inline(always)]
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+    /// Returns whether the source has subscribed to the channel.
+    #[inline]
+    pub fn is_subscribed(&self) -> bool {
+        matches!(self.state, State::Subscribed(_))
+    }
+    async fn subscribe(&mut self) -> Result<(), RedisError> {
+        if let State::Connected(connection) = &mut self.state {
+            connection.subscribe(self.channel.as_str()).await?;
+            if let State::Connected(connection) = mem::replace(&mut self.state, State::Closed) {
+                self.state = State::Subscribed(Box::pin(connection.into_on_message()));
+            }
+        }
+        Ok(())
+    }
+}
+impl Source for RedisSource {
+    type Error = RedisError;
+    fn load(&mut self) -> BoxFuture<'_, Result<Values, Self::Error>> {
+        Box::pin(async move {
+            self.subscribe().await?;
+            Ok(Values::new())
+        })
+    }
+    fn next_changes(&mut self) -> BoxFuture<'_, Result<Option<Values>, Self::Error>> {
+        Box::pin(async move {
+            self.subscribe().await?;
+            let messages = match &mut self.state {
+                State::Subscribed(messages) => messages,
+                _ => return Ok(None),
+            };
+            loop {
+                let message = match messages.next().await {
+                    Some(message) => message,
+                    None => {
+                        self.state = State::Closed;
+                        return Ok(None);
+                    },
+                };
+                if let Some((name, value)) = decode(message.get_payload_bytes()) {
+                    let mut values = Values::new();
+                    values.insert(name, value);
+                    return Ok(Some(values));
+                }
+            }
+        })
+    }
+}
+impl Debug for RedisSource {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisSource")
+            .field("channel", &self.channel)
+            .field("subscribed", &self.is_subscribed())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [sink] which publishes the new values of entries to a channel.
+///
+/// [sink]: ../remote/trait.Sink.html " "
+#[derive(Clone)]
+pub struct RedisSink {
+    connection: MultiplexedConnection,
+    channel: String,
+}
+impl RedisSink {
+    /// Creates a sink which publishes to the specified channel.
+    #[inline]
+    pub fn new(connection: MultiplexedConnection, channel: impl Into<String>) -> Self {
+        Self {connection, channel: channel.into()}
+    }
+    /// Returns the name of the channel.
+    #[inline(always)]
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+}
+impl Sink for RedisSink {
+    type Error = RedisError;
+    fn store(&mut self, entry_name: &'static str, value: Vec<u8>) -> BoxFuture<'_, Result<(), Self::Error>> {
+        Box::pin(async move {
+            let message = encode(entry_name, value);
+            self.connection.publish::<_, _, ()>(self.channel.as_str(), message).await
+        })
+    }
+}
+impl Debug for RedisSink {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RedisSink")
+            .field("channel", &self.channel)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds the message announcing the new value of an entry.
+fn encode(entry_name: &str, value: Vec<u8>) -> Vec<u8> {
+    // The values are always produced by the JSON serializer, and thus are valid UTF-8.
+    let value = String::from_utf8(value).ok().and_then(|value| RawValue::from_string(value).ok());
+    serde_json::to_vec(&(entry_name, value)).unwrap_or_default()
+}
+/// Splits a message into the name of the entry and its value, keeping the value exactly as it was published.
+fn decode(message: &[u8]) -> Option<(String, Vec<u8>)> {
+    let (name, value) = serde_json::from_slice::<(String, Box<RawValue>)>(message).ok()?;
+    Some((name, value.get().as_bytes().to_vec()))
+}
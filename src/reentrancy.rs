@@ -0,0 +1,174 @@
+use core::fmt::{self, Formatter, Display};
+#[cfg(all(feature = "std", debug_assertions))]
+use core::cell::RefCell;
+use super::Entry;
+
+/// The error produced by [`Handle::try_new`] when the handle would point to the value of an entry whose receiver is being notified about a change to it on the current thread.
+///
+/// This happens when a receiver, directly or through other receivers it notifies, tries to obtain a handle to the very entry it's being notified about, usually through raw pointers or a shared memory mapping, since the borrow checker prevents it otherwise. Such a handle would alias the handle which is notifying the receiver, and a change made through it would be followed by the rest of the outer notification with a value which isn't the one the outer change set. [`Handle::new`] panics with the same message instead.
+///
+/// Receivers shared through a `&RefCell`, `&Mutex` or `&RwLock`, as well as the subscribers of a [`ConfigBus`], are checked in the same way: notifying such a receiver while it's already being notified on the same thread panics with a message naming both entries, instead of panicking with a generic borrowing error or deadlocking. Detection is only compiled in with both the `std` feature and debug assertions, since it costs a thread-local lookup for every handle and notification; in release builds the checks compile to nothing, `try_new` always succeeds, and such mistakes are left to the borrow checker, `RefCell` and the deadlock they would otherwise cause.
+///
+/// # Example
+/// ```should_panic
+/// use snec::{Entry, Handle, dispatch::ConfigBus};
+/// use std::{any::Any, sync::{Arc, Mutex}};
+/// enum Celsius {}
+/// impl Entry for Celsius {
+///     type Data = i32;
+///     const NAME: &'static str = "celsius";
+/// }
+/// enum Fahrenheit {}
+/// impl Entry for Fahrenheit {
+///     type Data = i32;
+///     const NAME: &'static str = "fahrenheit";
+/// }
+/// let bus = ConfigBus::new();
+/// let fahrenheit = Arc::new(Mutex::new(32));
+/// let (bus_in_subscriber, fahrenheit_in_subscriber) = (bus.clone(), Arc::clone(&fahrenheit));
+/// bus.subscribe_fn("*", move |entry_name: &'static str, new_value: &dyn Any| {
+///     if let (Some(celsius), "celsius") = (new_value.downcast_ref::<i32>(), entry_name) {
+///         // Keeping the other unit in sync notifies this very subscriber again, while it's
+///         // still being notified about the first change.
+///         let mut fahrenheit = fahrenheit_in_subscriber.lock().unwrap();
+///         Handle::<Fahrenheit, _>::new(&mut fahrenheit, bus_in_subscriber.clone()).set(celsius * 9 / 5 + 32);
+///     }
+/// }).detach();
+/// let mut celsius = 0;
+/// // Panics instead of deadlocking.
+/// Handle::<Celsius, _>::new(&mut celsius, bus.clone()).set(100);
+/// ```
+///
+/// [`Handle::try_new`]: struct.Handle.html#method.try_new " "
+/// [`Handle::new`]: struct.Handle.html#method.new " "
+/// [`ConfigBus`]: dispatch/struct.ConfigBus.html " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Reentrancy {
+    /// The [name] of the entry whose receiver is being notified.
+    ///
+    /// [name]: trait.Entry.html#associatedconstant.NAME " "
+    pub entry: &'static str,
+}
+impl Display for Reentrancy {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "attempt to obtain a handle to entry `{}` while its receiver is being notified about a change to it",
+            self.entry,
+        )
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for Reentrancy {}
+
+/// What is being notified: the value of an entry, or a shared receiver.
+#[cfg(all(feature = "std", debug_assertions))]
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Site {
+    Entry,
+    Receiver,
+}
+#[cfg(all(feature = "std", debug_assertions))]
+std::thread_local! {
+    /// The values of entries and the shared receivers which are being notified on this thread, by their addresses, along with the names of the entries.
+    static NOTIFYING: RefCell<Vec<(Site, usize, &'static str)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A guard which marks a value or a shared receiver as being notified on the current thread for as long as it exists.
+pub(crate) struct Notifying {
+    #[cfg(all(feature = "std", debug_assertions))]
+    key: (Site, usize, &'static str),
+}
+impl Notifying {
+    /// Marks the value of the entry as being notified about.
+    #[inline]
+    pub(crate) fn entry<E: Entry>(value: &E::Data) -> Self {
+        #[cfg(all(feature = "std", debug_assertions))]
+        {
+            Self::enter(Site::Entry, address(value), E::NAME)
+        }
+        #[cfg(not(all(feature = "std", debug_assertions)))]
+        {
+            let _ = value;
+            Self {}
+        }
+    }
+    /// Marks the shared receiver as being notified about the entry with the specified name, panicking if it already is.
+    #[inline]
+    #[track_caller]
+    pub(crate) fn receiver<T: ?Sized>(receiver: &T, entry: &'static str) -> Self {
+        #[cfg(all(feature = "std", debug_assertions))]
+        {
+            let address = address(receiver);
+            if let Some(outer) = find_receiver(address) {
+                panic!(
+                    "shared receiver was notified about a change to entry `{}` while it was still being notified about a change to entry `{}` on the same thread, which means that it has changed an entry it's attached to",
+                    entry, outer,
+                );
+            }
+            Self::enter(Site::Receiver, address, entry)
+        }
+        #[cfg(not(all(feature = "std", debug_assertions)))]
+        {
+            let _ = (receiver, entry);
+            Self {}
+        }
+    }
+    #[cfg(all(feature = "std", debug_assertions))]
+    #[inline]
+    fn enter(site: Site, address: usize, entry: &'static str) -> Self {
+        NOTIFYING.with(|notifying| notifying.borrow_mut().push((site, address, entry)));
+        Self {key: (site, address, entry)}
+    }
+}
+#[cfg(all(feature = "std", debug_assertions))]
+impl Drop for Notifying {
+    #[inline]
+    fn drop(&mut self) {
+        // Fails only if the thread-local was already destroyed, in which case there's nothing to unmark.
+        let _ = NOTIFYING.try_with(|notifying| {
+            let mut notifying = notifying.borrow_mut();
+            if let Some(index) = notifying.iter().rposition(|key| *key == self.key) {
+                notifying.remove(index);
+            }
+        });
+    }
+}
+
+/// Fails if the value of the entry is being notified about on the current thread.
+#[inline]
+pub(crate) fn check_entry<E: Entry>(value: &E::Data) -> Result<(), Reentrancy> {
+    #[cfg(all(feature = "std", debug_assertions))]
+    {
+        // The name is compared too, so that zero-sized values of different entries which happen to share an address aren't confused.
+        let key = (Site::Entry, address(value), E::NAME);
+        let notifying = NOTIFYING.try_with(|notifying| notifying.borrow().contains(&key)).unwrap_or(false);
+        if notifying {
+            Err(Reentrancy {entry: E::NAME})
+        } else {
+            Ok(())
+        }
+    }
+    #[cfg(not(all(feature = "std", debug_assertions)))]
+    {
+        let _ = value;
+        Ok(())
+    }
+}
+
+/// Returns the name of the entry which the shared receiver at the address is being notified about on the current thread, if any.
+#[cfg(all(feature = "std", debug_assertions))]
+#[inline]
+fn find_receiver(address: usize) -> Option<&'static str> {
+    NOTIFYING.try_with(|notifying| {
+        notifying.borrow().iter()
+            .find(|&&(site, other_address, _)| site == Site::Receiver && other_address == address)
+            .map(|&(_, _, entry)| entry)
+    }).ok().flatten()
+}
+#[cfg(all(feature = "std", debug_assertions))]
+#[inline(always)]
+fn address<T: ?Sized>(value: &T) -> usize {
+    value as *const T as *const () as usize
+}
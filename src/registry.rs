@@ -0,0 +1,394 @@
+use core::{
+    fmt::{self, Formatter, Debug, Display, Write},
+    hash::{Hash, Hasher},
+};
+use super::{Entry, Get, Handle, Receiver, Redacted};
+
+/// Trait for config tables which provide a runtime registry of their entries.
+///
+/// While the [`Get`] trait allows compile-time lookup of fields, tooling which works with arbitrary config tables (settings menus, loaders, exporters) needs to know which entries a table has without knowing their types in advance. The registry provides exactly that.
+///
+/// This trait is implemented automatically by `#[derive(ConfigTable)]`, listing every entry which has a `Get` implementation generated for it, in the order the fields are declared.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, Entry};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     /// How many times the user has been greeted.
+///     #[snec]
+///     greeting_count: u32,
+/// }
+/// assert_eq!(
+///     entries::GreetingCount::DESCRIPTION,
+///     "How many times the user has been greeted.",
+/// );
+/// let info = MyConfigTable::entry_info("greeting_count").unwrap();
+/// assert_eq!(info.description, entries::GreetingCount::DESCRIPTION);
+/// ```
+/// Restoring defaults:
+/// ```
+/// use snec::{ConfigTable, GetExt as _};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec(entry, default = 8080)]
+///     port: u16,
+///     #[snec(entry, default = "localhost".to_string())]
+///     host: String,
+/// }
+/// let mut table = MyConfigTable {port: 80, host: "example.com".to_string()};
+/// table.get_handle_to::<entries::Port>().set(443);
+/// table.reset_all();
+/// assert_eq!(table.port, 8080);
+/// assert_eq!(table.host, "localhost");
+/// ```
+/// The same default values can be used to implement `Default` for the table with `#[snec(derive_default)]`, which uses the `Default` implementations of the types of the fields without them:
+/// ```
+/// use snec::ConfigTable;
+/// #[derive(ConfigTable)]
+/// #[snec(derive_default)]
+/// struct MyConfigTable {
+///     #[snec(entry, default = 8080)]
+///     port: u16,
+///     #[snec]
+///     verbose: bool,
+/// }
+/// let table = MyConfigTable::default();
+/// assert_eq!(table.port, 8080);
+/// assert!(!table.verbose);
+/// ```
+///
+/// [`Get`]: trait.Get.html " "
+pub trait ConfigTable {
+    /// Information about every entry of the config table.
+    const ENTRIES: &'static [EntryInfo];
+    /// Looks up the information about an entry by its [name].
+    ///
+    /// [name]: trait.Entry.html#associatedconstant.NAME " "
+    #[inline]
+    fn entry_info(name: &str) -> Option<&'static EntryInfo>
+    where Self: Sized {
+        Self::ENTRIES.iter().find(|info| info.name == name)
+    }
+    /// Resets every entry which has a [default value] to that value, notifying the receivers of those entries.
+    ///
+    /// [default value]: trait.Entry.html#method.default_value " "
+    #[inline]
+    fn reset_all(&mut self)
+    where Self: Sized + VisitEntries<ResetVisitor> {
+        self.visit_entries(&mut ResetVisitor);
+    }
+    /// Returns a value which displays the [name] and the current value of every entry, one per line, such as `port = 8080`, for printing the effective config at startup. The values are formatted using `Debug`, except for the values of [secret] entries, which are displayed as `<redacted>`.
+    ///
+    /// # Example
+    /// ```
+    /// use snec::ConfigTable;
+    /// #[derive(ConfigTable)]
+    /// struct MyConfigTable {
+    ///     #[snec]
+    ///     port: u16,
+    ///     #[snec]
+    ///     host: String,
+    /// }
+    /// let table = MyConfigTable {port: 8080, host: "localhost".to_string()};
+    /// assert_eq!(table.dump().to_string(), "port = 8080\nhost = \"localhost\"\n");
+    /// ```
+    ///
+    /// [name]: trait.Entry.html#associatedconstant.NAME " "
+    /// [secret]: trait.Entry.html#associatedconstant.SECRET " "
+    #[inline(always)]
+    fn dump(&self) -> Dump<'_, Self>
+    where Self: Sized {
+        Dump {table: self}
+    }
+}
+
+/// Trait for visitors which can be passed handles to entries of config tables via [`VisitEntries`].
+///
+/// A visitor usually implements this trait for every `E`, possibly with additional bounds on `E::Data`. Since [`VisitEntries`] is only implemented for visitors which can visit every entry of a table, such bounds are checked at the point where the visitor is used.
+///
+/// [`VisitEntries`]: trait.VisitEntries.html " "
+pub trait EntryVisitor<E: Entry> {
+    /// Visits an entry, receiving a handle to it.
+    fn visit<R: Receiver<E>>(&mut self, handle: Handle<'_, E, R>);
+}
+
+/// Trait for config tables which can pass handles to all of their entries to a [visitor].
+///
+/// This trait is implemented automatically by `#[derive(ConfigTable)]` for every visitor which implements [`EntryVisitor`] for all entries listed in the [registry] of the table. The entries are visited in the same order as they are listed in the registry.
+///
+/// [visitor]: trait.EntryVisitor.html " "
+/// [`EntryVisitor`]: trait.EntryVisitor.html " "
+/// [registry]: trait.ConfigTable.html " "
+pub trait VisitEntries<V: ?Sized> {
+    /// Passes handles to all entries of the table to the visitor.
+    fn visit_entries(&mut self, visitor: &mut V);
+}
+
+/// Trait for visitors which can be passed entries of config tables as types, without needing an instance of the table, via [`VisitEntryTypes`].
+///
+/// Like with [`EntryVisitor`], a type visitor usually implements this trait for every `E`, possibly with additional bounds which are checked at the point where the visitor is used.
+///
+/// [`VisitEntryTypes`]: trait.VisitEntryTypes.html " "
+/// [`EntryVisitor`]: trait.EntryVisitor.html " "
+pub trait EntryTypeVisitor<E: Entry> {
+    /// Visits an entry, which is passed as the generic parameter of the trait.
+    fn visit_type(&mut self);
+}
+
+/// Trait for config tables which can pass all of their entries to a [type visitor].
+///
+/// This trait is implemented automatically by `#[derive(ConfigTable)]` for every type visitor which implements [`EntryTypeVisitor`] for all entries listed in the [registry] of the table. The entries are visited in the same order as they are listed in the registry.
+///
+/// [type visitor]: trait.EntryTypeVisitor.html " "
+/// [`EntryTypeVisitor`]: trait.EntryTypeVisitor.html " "
+/// [registry]: trait.ConfigTable.html " "
+pub trait VisitEntryTypes<V: ?Sized> {
+    /// Passes all entries of the table to the type visitor.
+    fn visit_entry_types(visitor: &mut V);
+}
+
+/// A [visitor] which [resets] every entry it visits, used by [`ConfigTable::reset_all`].
+///
+/// [visitor]: trait.EntryVisitor.html " "
+/// [resets]: struct.Handle.html#method.reset " "
+/// [`ConfigTable::reset_all`]: trait.ConfigTable.html#method.reset_all " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ResetVisitor;
+impl<E: Entry> EntryVisitor<E> for ResetVisitor {
+    #[inline(always)]
+    fn visit<R: Receiver<E>>(&mut self, mut handle: Handle<'_, E, R>) {
+        handle.reset();
+    }
+}
+
+/// Displays the names and the current values of all entries of a config table, as returned by [`ConfigTable::dump`].
+///
+/// [`ConfigTable::dump`]: trait.ConfigTable.html#method.dump " "
+#[derive(Copy, Clone, Debug)]
+pub struct Dump<'a, T> {
+    table: &'a T,
+}
+impl<'a, T> Display for Dump<'a, T>
+where T: for<'w> VisitEntryTypes<DumpVisitor<'a, 'w, T>> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut visitor = DumpVisitor::new(self.table, f);
+        T::visit_entry_types(&mut visitor);
+        visitor.finish()
+    }
+}
+
+/// A [type visitor] which writes the name and the current value of every entry it visits, used by [`Dump`].
+///
+/// [type visitor]: trait.EntryTypeVisitor.html " "
+/// [`Dump`]: struct.Dump.html " "
+pub struct DumpVisitor<'a, 'w, T> {
+    table: &'a T,
+    output: &'w mut dyn Write,
+    result: fmt::Result,
+}
+impl<'a, 'w, T> DumpVisitor<'a, 'w, T> {
+    /// Creates a visitor which writes the entries of the table into the specified output.
+    #[inline(always)]
+    pub fn new(table: &'a T, output: &'w mut dyn Write) -> Self {
+        Self {table, output, result: Ok(())}
+    }
+    /// Returns the first error which occurred while writing, if any.
+    #[inline(always)]
+    pub fn finish(self) -> fmt::Result {
+        self.result
+    }
+}
+impl<'a, 'w, T, E> EntryTypeVisitor<E> for DumpVisitor<'a, 'w, T>
+where
+    T: Get<E>,
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn visit_type(&mut self) {
+        if self.result.is_ok() {
+            self.result = if E::SECRET {
+                writeln!(self.output, "{} = {:?}", E::NAME, Redacted)
+            } else {
+                writeln!(self.output, "{} = {:?}", E::NAME, self.table.get_ref())
+            };
+        }
+    }
+}
+impl<T> Debug for DumpVisitor<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DumpVisitor")
+            .field("result", &self.result)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runtime information about an [entry], as stored in the [registry] of a config table.
+///
+/// With the `serde` feature, `EntryInfo` implements `Serialize` as a struct with the `name`, `type`, `description`, `group`, `unit`, `ui_range` and `secret` fields, the unit and the UI range being `null` if the entry doesn't have them, which allows the whole registry of a config table to be exported for external tooling, such as web admin interfaces and documentation generators. The `json-schema` feature additionally provides [`schema::registry`], which includes the default values of the entries.
+///
+/// # Example
+/// ```
+/// use snec::ConfigTable;
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     /// Whether to wait for vertical synchronization.
+///     #[snec(entry, name = "render.vsync")]
+///     vsync: bool,
+///     #[snec]
+///     hosts: Vec<String>,
+/// }
+/// let (vsync, hosts) = (&MyConfigTable::ENTRIES[0], &MyConfigTable::ENTRIES[1]);
+/// assert_eq!(vsync.type_name, "bool");
+/// assert_eq!(vsync.group(), Some("render"));
+/// assert_eq!(hosts.type_name, "Vec<String>");
+/// assert_eq!(hosts.group(), None);
+/// ```
+///
+/// [entry]: trait.Entry.html " "
+/// [registry]: trait.ConfigTable.html " "
+/// [`schema::registry`]: schema/fn.registry.html " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct EntryInfo {
+    /// The textual name of the entry, as specified by [`Entry::NAME`].
+    ///
+    /// [`Entry::NAME`]: trait.Entry.html#associatedconstant.NAME " "
+    pub name: &'static str,
+    /// The human-readable description of the entry, as specified by [`Entry::DESCRIPTION`].
+    ///
+    /// [`Entry::DESCRIPTION`]: trait.Entry.html#associatedconstant.DESCRIPTION " "
+    pub description: &'static str,
+    /// The type of the data of the entry as written in the source code, as specified by [`Entry::TYPE_NAME`].
+    ///
+    /// [`Entry::TYPE_NAME`]: trait.Entry.html#associatedconstant.TYPE_NAME " "
+    pub type_name: &'static str,
+    /// The unit of measurement of the value of the entry, as specified by [`Entry::UNIT`].
+    ///
+    /// [`Entry::UNIT`]: trait.Entry.html#associatedconstant.UNIT " "
+    pub unit: &'static str,
+    /// The range of values which user interfaces should offer for the entry, as specified by [`Entry::UI_RANGE`].
+    ///
+    /// [`Entry::UI_RANGE`]: trait.Entry.html#associatedconstant.UI_RANGE " "
+    pub ui_range: UiRange,
+    /// Whether the value of the entry is secret, as specified by [`Entry::SECRET`].
+    ///
+    /// [`Entry::SECRET`]: trait.Entry.html#associatedconstant.SECRET " "
+    pub secret: bool,
+}
+impl EntryInfo {
+    /// Collects the information about the specified entry.
+    #[inline(always)]
+    pub const fn of<E: Entry>() -> Self {
+        Self {
+            name: E::NAME,
+            description: E::DESCRIPTION,
+            type_name: E::TYPE_NAME,
+            unit: E::UNIT,
+            ui_range: E::UI_RANGE,
+            secret: E::SECRET,
+        }
+    }
+    /// Returns the group of the entry, which is the part of its name before the last `.`, e.g. `render` for `render.vsync`, or `None` if the name has no dots. This is the same convention as the one used for [wildcard subscriptions].
+    ///
+    /// [wildcard subscriptions]: dispatch/index.html " "
+    #[inline]
+    pub fn group(&self) -> Option<&'static str> {
+        self.name.rfind('.').map(|index| &self.name[..index])
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for EntryInfo {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct as _;
+        let mut state = serializer.serialize_struct("EntryInfo", 7)?;
+        state.serialize_field("name", self.name)?;
+        state.serialize_field("type", self.type_name)?;
+        state.serialize_field("description", self.description)?;
+        state.serialize_field("group", &self.group())?;
+        state.serialize_field("unit", &Some(self.unit).filter(|unit| !unit.is_empty()))?;
+        state.serialize_field("ui_range", &Some(self.ui_range).filter(|range| !range.is_unbounded()))?;
+        state.serialize_field("secret", &self.secret)?;
+        state.end()
+    }
+}
+
+/// The range of values which user interfaces should offer for an [entry], such as the bounds and the step of a slider, as specified by [`Entry::UI_RANGE`].
+///
+/// This is a hint for editors rather than a constraint: values outside of the range can still be set through handles and loaded from files, and only the [validator] of the entry can reject them. The two are often different, e.g. a volume which may be boosted up to 200% in a config file, while a slider only goes up to 100%. The bounds and the step are `f64` regardless of the type of the entry, and editors convert them to the type of the value.
+///
+/// With the `serde` feature, `UiRange` implements `Serialize` as a struct with the `min`, `max` and `step` fields, each being `null` if unspecified. Ranges compare equal and hash the same if their bounds and steps have the same bit patterns, which allows `EntryInfo` to implement `Eq` and `Hash`.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, Entry, UiRange};
+/// #[derive(ConfigTable)]
+/// struct MixerConfig {
+///     /// Loudness of the music, from silent to twice the recorded volume.
+///     #[snec(entry, ui(min = 0.0, max = 1.0, step = 0.05), validate = within_gain_limits)]
+///     music: f32,
+///     #[snec(entry, ui(min = 1, max = 8))]
+///     channels: u8,
+///     #[snec]
+///     muted: bool,
+/// }
+/// fn within_gain_limits(gain: &f32) -> Result<(), &'static str> {
+///     if (0.0..=2.0).contains(gain) { Ok(()) } else { Err("gain must be between 0 and 2") }
+/// }
+/// assert_eq!(entries::Music::UI_RANGE, UiRange {min: Some(0.0), max: Some(1.0), step: Some(0.05)});
+/// let channels = MixerConfig::ENTRIES[1].ui_range;
+/// assert_eq!((channels.min, channels.max, channels.step), (Some(1.0), Some(8.0), None));
+/// assert!(MixerConfig::ENTRIES[2].ui_range.is_unbounded());
+/// ```
+///
+/// [entry]: trait.Entry.html " "
+/// [`Entry::UI_RANGE`]: trait.Entry.html#associatedconstant.UI_RANGE " "
+/// [validator]: trait.Entry.html#method.validate " "
+#[derive(Copy, Clone, Debug, Default)]
+pub struct UiRange {
+    /// The smallest value to offer, or `None` to only be limited by the type of the value.
+    pub min: Option<f64>,
+    /// The largest value to offer, or `None` to only be limited by the type of the value.
+    pub max: Option<f64>,
+    /// The difference between adjacent values to offer, or `None` to let the editor decide.
+    pub step: Option<f64>,
+}
+impl UiRange {
+    /// A range without bounds or a step, the default for entries.
+    pub const UNBOUNDED: Self = Self {min: None, max: None, step: None};
+    /// Returns `true` if neither the bounds nor the step are specified.
+    #[inline]
+    pub const fn is_unbounded(&self) -> bool {
+        self.min.is_none() && self.max.is_none() && self.step.is_none()
+    }
+    #[inline]
+    fn bits(&self) -> [Option<u64>; 3] {
+        [self.min, self.max, self.step].map(|value| value.map(f64::to_bits))
+    }
+}
+impl PartialEq for UiRange {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.bits() == other.bits()
+    }
+}
+impl Eq for UiRange {}
+impl Hash for UiRange {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.bits().hash(state)
+    }
+}
+#[cfg(feature = "serde")]
+impl serde::Serialize for UiRange {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct as _;
+        let mut state = serializer.serialize_struct("UiRange", 3)?;
+        state.serialize_field("min", &self.min)?;
+        state.serialize_field("max", &self.max)?;
+        state.serialize_field("step", &self.step)?;
+        state.end()
+    }
+}
@@ -0,0 +1,325 @@
+//! Synchronizing config tables with remote key-value stores, independently of the store.
+//!
+//! Backends for specific stores, such as [etcd] and [Consul], implement the [`Source`] trait, which reads the values of entries and waits for remote changes, and the [`Sink`] trait, which stores the values of entries. Values are serialized as JSON and identified by the [names] of their entries, with the mapping between names and keys, such as adding a common prefix, left to the backend. The rest of the synchronization is shared between the backends:
+//! - [`RemoteSync`] loads the values into a config table with [`load_into`] and applies remote changes with [`apply_next`], through handles, so that the receivers are notified about remote changes just like about local ones
+//! - [`RemoteReceiver`], attached to the entries of the table as their receiver, forwards local changes to a [`RemoteWriter`], which runs as a separate task and stores them in the order in which they were made
+//!
+//! Values which were just received from the store are not written back, so applying a remote change doesn't bounce it back to the store. Remote deletions of keys are ignored, leaving the entries with their current values.
+//!
+//! The futures of backends are boxed, since traits can't have `async` methods, and have to be `Send`, so that the writer can be spawned as a task of a multi-threaded runtime. The synchronization itself doesn't depend on a specific runtime and works with any executor, such as smol, async-std or Tokio, although some backends require the runtime their client library is built for.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, remote::{BoxFuture, RemoteReceiver, RemoteSync, Sink, Source, Values}};
+//! use std::{convert::Infallible, sync::{Arc, Mutex}};
+//! // A store kept in memory, standing in for a real backend.
+//! #[derive(Clone, Default)]
+//! struct Memory(Arc<Mutex<Values>>);
+//! impl Source for Memory {
+//!     type Error = Infallible;
+//!     fn load(&mut self) -> BoxFuture<'_, Result<Values, Self::Error>> {
+//!         let values = self.0.lock().unwrap().clone();
+//!         Box::pin(async move { Ok(values) })
+//!     }
+//!     fn next_changes(&mut self) -> BoxFuture<'_, Result<Option<Values>, Self::Error>> {
+//!         Box::pin(async { Ok(None) })
+//!     }
+//! }
+//! impl Sink for Memory {
+//!     type Error = Infallible;
+//!     fn store(&mut self, entry_name: &'static str, value: Vec<u8>) -> BoxFuture<'_, Result<(), Self::Error>> {
+//!         self.0.lock().unwrap().insert(entry_name.to_string(), value);
+//!         Box::pin(async { Ok(()) })
+//!     }
+//! }
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.remote.clone()}: RemoteReceiver))]
+//! struct Config {
+//!     #[snec]
+//!     workers: u32,
+//!     remote: RemoteReceiver,
+//! }
+//! let store = Memory::default();
+//! store.0.lock().unwrap().insert("workers".to_string(), b"8".to_vec());
+//! smol::block_on(async {
+//!     let (mut sync, writer) = RemoteSync::new(store.clone(), store.clone());
+//!     let writer = smol::spawn(writer.run());
+//!     let mut config = Config {workers: 1, remote: sync.receiver()};
+//!     assert_eq!(sync.load_into(&mut config).await.unwrap(), 1);
+//!     assert_eq!(config.workers, 8);
+//!     config.get_handle_to::<entries::Workers>().set(16);
+//!     // Dropping the synchronizer and its receivers lets the writer finish.
+//!     drop((sync, config));
+//!     writer.await.unwrap();
+//! });
+//! assert_eq!(store.0.lock().unwrap()["workers"], b"16");
+//! ```
+//!
+//! [etcd]: ../etcd/index.html " "
+//! [Consul]: ../consul/index.html " "
+//! [`Source`]: trait.Source.html " "
+//! [`Sink`]: trait.Sink.html " "
+//! [names]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`RemoteSync`]: struct.RemoteSync.html " "
+//! [`load_into`]: struct.RemoteSync.html#method.load_into " "
+//! [`apply_next`]: struct.RemoteSync.html#method.apply_next " "
+//! [`RemoteReceiver`]: struct.RemoteReceiver.html " "
+//! [`RemoteWriter`]: struct.RemoteWriter.html " "
+
+use core::{
+    fmt::{self, Formatter, Display, Debug},
+    future::Future,
+    pin::Pin,
+};
+use std::{
+    collections::HashMap,
+    error,
+    sync::{Arc, Mutex},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use futures_channel::mpsc;
+use futures_util::StreamExt as _;
+use super::{Entry, Receiver, Loader, LoadError, Lookup, LookupEntry, VisitEntries, ValidateTable, lock_ignoring_poison};
+
+/// A boxed future returned by the methods of backends.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+/// Values of entries serialized as JSON, by the names of the entries.
+pub type Values = HashMap<String, Vec<u8>>;
+
+/// The values last seen in the store or sent to it, by the names of their entries.
+type KnownValues = Arc<Mutex<Values>>;
+/// A change made locally, waiting to be stored, or the error which occurred while serializing it.
+type Change = Result<(&'static str, Vec<u8>), (&'static str, serde_json::Error)>;
+
+/// A backend which reads the values of entries from a store and waits for them to change.
+pub trait Source {
+    /// The error produced when the store can't be read.
+    type Error;
+    /// Reads the current values of all entries in the store.
+    fn load(&mut self) -> BoxFuture<'_, Result<Values, Self::Error>>;
+    /// Waits for the values of entries to change, returning the new values of the entries which changed after the last call to `load` or `next_changes`, or `None` if no more changes will be received.
+    fn next_changes(&mut self) -> BoxFuture<'_, Result<Option<Values>, Self::Error>>;
+}
+/// A backend which writes the values of entries to a store.
+pub trait Sink {
+    /// The error produced when the store can't be written.
+    type Error;
+    /// Stores the value of the entry with the specified name.
+    fn store(&mut self, entry_name: &'static str, value: Vec<u8>) -> BoxFuture<'_, Result<(), Self::Error>>;
+}
+
+/// Loads config tables from a [source] and applies remote changes to them. See the [module-level documentation] for more.
+///
+/// [source]: trait.Source.html " "
+/// [module-level documentation]: index.html " "
+pub struct RemoteSync<S> {
+    source: S,
+    known: KnownValues,
+    changes: mpsc::UnboundedSender<Change>,
+}
+impl<S: Source> RemoteSync<S> {
+    /// Creates a synchronizer reading from the specified source, along with the writer which writes local changes to the specified sink.
+    pub fn new<K: Sink>(source: S, sink: K) -> (Self, RemoteWriter<K>) {
+        let (sender, receiver) = mpsc::unbounded();
+        let sync = Self {source, known: KnownValues::default(), changes: sender};
+        (sync, RemoteWriter {sink, changes: receiver})
+    }
+    /// Creates a synchronizer reading from the specified source without writing local changes anywhere, for stores which can't be written to, such as [feature flag services]. Its receivers ignore all changes.
+    ///
+    /// [feature flag services]: ../flags/index.html " "
+    #[inline]
+    pub fn read_only(source: S) -> Self {
+        let (sender, _) = mpsc::unbounded();
+        Self {source, known: KnownValues::default(), changes: sender}
+    }
+    /// Creates a receiver which sends local changes to the writer.
+    #[inline]
+    pub fn receiver(&self) -> RemoteReceiver {
+        RemoteReceiver {known: Arc::clone(&self.known), changes: self.changes.clone()}
+    }
+    /// Returns a reference to the source.
+    #[inline(always)]
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+    /// Returns a mutable reference to the source.
+    #[inline(always)]
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+    /// Reads the values of all entries from the source and writes them into the corresponding entries of the config table, notifying the receivers of the entries which changed. Returns the number of entries which changed.
+    ///
+    /// Values which don't correspond to any entry are ignored, and so are entries which aren't stored. A value which doesn't deserialize as its entry's type is returned as [`Error::Value`]. The table is left unchanged if an error is returned, including one produced by the [table validator]; the source isn't read again, so the next [`apply_next`] continues with newer changes.
+    ///
+    /// [`Error::Value`]: enum.Error.html#variant.Value " "
+    /// [table validator]: ../trait.ValidateTable.html " "
+    /// [`apply_next`]: #method.apply_next " "
+    pub async fn load_into<T>(&mut self, table: &mut T) -> Result<usize, LoadError<Error<S::Error>, T::Error>>
+    where T: VisitEntries<RemoteLoader> + ValidateTable {
+        let values = self.source.load().await.map_err(|error| LoadError::Source(Error::Backend(error)))?;
+        self.apply(table, values)
+    }
+    /// Waits for the next batch of remote changes and writes them into the config table, like [`load_into`]. Returns the number of entries which changed, or `None` if no more changes will be received.
+    ///
+    /// [`load_into`]: #method.load_into " "
+    pub async fn apply_next<T>(&mut self, table: &mut T) -> Result<Option<usize>, LoadError<Error<S::Error>, T::Error>>
+    where T: VisitEntries<RemoteLoader> + ValidateTable {
+        match self.source.next_changes().await.map_err(|error| LoadError::Source(Error::Backend(error)))? {
+            Some(values) => self.apply(table, values).map(Some),
+            None => Ok(None),
+        }
+    }
+    #[inline]
+    fn apply<T>(&mut self, table: &mut T, values: Values) -> Result<usize, LoadError<Error<S::Error>, T::Error>>
+    where T: VisitEntries<RemoteLoader> + ValidateTable {
+        apply(&self.known, table, values).map_err(|error| error.map_source(|(entry, inner)| Error::Value {entry, inner}))
+    }
+}
+impl<S: Debug> Debug for RemoteSync<S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteSync")
+            .field("source", &self.source)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [receiver] which sends the new values of entries to the [`RemoteWriter`], created by [`RemoteSync::receiver`].
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [`RemoteWriter`]: struct.RemoteWriter.html " "
+/// [`RemoteSync::receiver`]: struct.RemoteSync.html#method.receiver " "
+#[derive(Clone)]
+pub struct RemoteReceiver {
+    known: KnownValues,
+    changes: mpsc::UnboundedSender<Change>,
+}
+impl<E> Receiver<E> for RemoteReceiver
+where
+    E: Entry,
+    E::Data: Serialize {
+    fn receive(&mut self, new_value: &E::Data) {
+        // Sending fails only if the writer was dropped, in which case writing back isn't wanted.
+        let value = match serde_json::to_vec(new_value) {
+            Ok(value) => value,
+            Err(error) => {
+                let _ = self.changes.unbounded_send(Err((E::NAME, error)));
+                return;
+            },
+        };
+        let mut known = lock_ignoring_poison(&self.known);
+        if known.get(E::NAME) != Some(&value) {
+            known.insert(E::NAME.to_string(), value.clone());
+            let _ = self.changes.unbounded_send(Ok((E::NAME, value)));
+        }
+    }
+}
+impl Debug for RemoteReceiver {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteReceiver").finish_non_exhaustive()
+    }
+}
+
+/// Writes the local changes sent by [`RemoteReceiver`]s to a [sink], in the order in which they were made.
+///
+/// [`RemoteReceiver`]: struct.RemoteReceiver.html " "
+/// [sink]: trait.Sink.html " "
+pub struct RemoteWriter<K> {
+    sink: K,
+    changes: mpsc::UnboundedReceiver<Change>,
+}
+impl<K: Sink> RemoteWriter<K> {
+    /// Writes changes until the [`RemoteSync`] and all receivers created by it are dropped, or until writing fails.
+    ///
+    /// [`RemoteSync`]: struct.RemoteSync.html " "
+    pub async fn run(mut self) -> Result<(), Error<K::Error>> {
+        while let Some(change) = self.changes.next().await {
+            let (entry_name, value) = change.map_err(|(entry, inner)| Error::Value {entry, inner})?;
+            self.sink.store(entry_name, value).await.map_err(Error::Backend)?;
+        }
+        Ok(())
+    }
+}
+impl<K: Debug> Debug for RemoteWriter<K> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteWriter")
+            .field("sink", &self.sink)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The [loader] used by [`RemoteSync`], which writes [`RemoteValues`] into a config table.
+///
+/// [loader]: ../struct.Loader.html " "
+/// [`RemoteSync`]: struct.RemoteSync.html " "
+/// [`RemoteValues`]: struct.RemoteValues.html " "
+pub type RemoteLoader = Loader<RemoteValues>;
+
+/// A batch of JSON values received from a store, which [look up] the values of entries by deserializing the values stored under their names. A value which can't be deserialized is reported along with the name of its entry.
+///
+/// [look up]: ../trait.LookupEntry.html " "
+#[derive(Clone, Debug)]
+pub struct RemoteValues {
+    values: Values,
+}
+impl Lookup for RemoteValues {
+    type Error = (&'static str, serde_json::Error);
+}
+impl<E> LookupEntry<E> for RemoteValues
+where
+    E: Entry,
+    E::Data: DeserializeOwned {
+    #[inline]
+    fn lookup(&mut self) -> Option<Result<E::Data, Self::Error>> {
+        let value = self.values.get(E::NAME)?;
+        Some(
+            serde_json::from_slice(value).map_err(|error| (E::NAME, error))
+        )
+    }
+}
+
+/// Remembers the values as the ones last seen remotely, so that the receivers don't send them back, and writes them into the corresponding entries of the config table. Returns the number of entries which changed, or either the name of the entry whose value couldn't be converted along with the error or the error produced by the table validator.
+pub(crate) fn apply<T>(
+    known: &Mutex<Values>,
+    table: &mut T,
+    values: Values,
+) -> Result<usize, LoadError<(&'static str, serde_json::Error), T::Error>>
+where T: VisitEntries<RemoteLoader> + ValidateTable {
+    lock_ignoring_poison(known)
+        .extend(values.iter().map(|(name, value)| (name.clone(), value.clone())));
+    Loader::new(RemoteValues {values}).load_into(table)
+}
+
+/// An error which occurred while synchronizing with a store.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The backend failed to read from or write to the store.
+    Backend(E),
+    /// The value of an entry could not be serialized, or the stored value could not be converted to the type of the entry.
+    Value {
+        /// The name of the entry.
+        entry: &'static str,
+        /// The error produced by the JSON serializer or deserializer.
+        inner: serde_json::Error,
+    },
+}
+impl<E: Display> Display for Error<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Backend(error) => Display::fmt(error, f),
+            Self::Value {entry, inner} => write!(f, "invalid value for entry `{}`: {}", entry, inner),
+        }
+    }
+}
+impl<E: error::Error + 'static> error::Error for Error<E> {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Backend(error) => Some(error),
+            Self::Value {inner, ..} => Some(inner),
+        }
+    }
+}
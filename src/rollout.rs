@@ -0,0 +1,228 @@
+//! Percentage rollouts and A/B variants driven by entries.
+//!
+//! A rollout is an entry whose value lists the variants of a setting along with their weights, such as `[(true, 10), (false, 90)]` for enabling a feature for 10% of the users. [`Rollout`] resolves which variant applies to a unit, such as a user or a device, by hashing the bytes of the identifier of the unit together with the [name] of the entry into one of 10000 buckets and picking the variant whose share of the buckets contains the bucket. Since the hash is computed with FNV-1a over the bytes of the identifier rather than with the `Hash` trait, whose output may differ between targets and Rust versions, every instance of a service assigns a unit to the same variant without storing the assignments anywhere, and different rollouts assign units independently of each other. Identifiers therefore have to provide their bytes through `AsRef<[u8]>`, as `String` does.
+//!
+//! `Rollout` is also the [receiver] of the entry: whenever the variants change, it re-evaluates the assignments of the units it has resolved so far and remembers the units whose variant changed, which can be retrieved with [`take_reassigned`]. Units keep their buckets across changes, and the variants cover consecutive ranges of buckets in order, starting from the first bucket. Increasing the share of the total weight of the first variant, such as by going from `[(true, 10), (false, 90)]` to `[(true, 20), (false, 90)]`, thus only moves units into it, and units which already had it keep it. The same goes for the last variant, while the ranges of the variants in between can shift when the weights before them change.
+//!
+//! Variants are stored as `Vec<(T, u32)>`, which serializes as an array of pairs and can thus be loaded like any other entry. Other types can be used by implementing [`Variants`] for them.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, rollout::Rollout};
+//! #[derive(ConfigTable)]
+//! struct Experiments {
+//!     #[snec(entry, receiver({self.button_rollout.clone()}: Rollout<entries::ButtonColor>))]
+//!     button_color: Vec<(String, u32)>,
+//!     button_rollout: Rollout<entries::ButtonColor>,
+//! }
+//! let variants = vec![("blue".to_string(), 50), ("green".to_string(), 50)];
+//! let mut experiments = Experiments {
+//!     button_rollout: Rollout::new(variants.clone()),
+//!     button_color: variants,
+//! };
+//! let rollout = experiments.button_rollout.clone();
+//! let color = rollout.variant(&"user-42".to_string()).unwrap();
+//! assert!(color == "blue" || color == "green");
+//! // The same unit always gets the same variant.
+//! assert_eq!(rollout.variant(&"user-42".to_string()), Some(color));
+//! // Ending the experiment reassigns everyone who had the green button.
+//! experiments.get_handle_to::<entries::ButtonColor>().set(vec![("blue".to_string(), 1)]);
+//! assert_eq!(rollout.variant(&"user-42".to_string()).as_deref(), Some("blue"));
+//! ```
+//! Widening a percentage rollout never takes the feature away from anyone:
+//! ```
+//! use snec::{ConfigTable, GetExt as _, rollout::Rollout};
+//! #[derive(ConfigTable)]
+//! struct Features {
+//!     #[snec(entry, receiver({self.dark_mode_rollout.clone()}: Rollout<entries::DarkMode>))]
+//!     dark_mode: Vec<(bool, u32)>,
+//!     dark_mode_rollout: Rollout<entries::DarkMode>,
+//! }
+//! let variants = vec![(true, 10), (false, 90)];
+//! let mut features = Features {dark_mode_rollout: Rollout::new(variants.clone()), dark_mode: variants};
+//! let rollout = features.dark_mode_rollout.clone();
+//! let users = (0..1000).map(|user| format!("user-{}", user)).collect::<Vec<_>>();
+//! let before = users.iter().map(|user| rollout.variant(user) == Some(true)).collect::<Vec<_>>();
+//! let enabled = before.iter().filter(|&&enabled| enabled).count();
+//! assert!((50..150).contains(&enabled));
+//! features.get_handle_to::<entries::DarkMode>().set(vec![(true, 20), (false, 90)]);
+//! for (user, &had_it) in users.iter().zip(&before) {
+//!     let has_it = rollout.variant(user) == Some(true);
+//!     assert!(has_it || !had_it, "{} lost the feature", user);
+//! }
+//! // Everyone whose variant changed got the feature.
+//! let reassigned = rollout.take_reassigned();
+//! assert!(!reassigned.is_empty());
+//! assert!(reassigned.iter().all(|user| rollout.variant(user) == Some(true)));
+//! ```
+//!
+//! [`Rollout`]: struct.Rollout.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`take_reassigned`]: struct.Rollout.html#method.take_reassigned " "
+//! [`Variants`]: trait.Variants.html " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    hash::Hash,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use super::{Entry, Receiver, lock_ignoring_poison};
+
+/// Trait for the types of entries which list weighted variants of a value.
+pub trait Variants {
+    /// The type of the values of the variants.
+    type Value;
+    /// Returns the variants along with their weights, in order. Variants with a weight of zero are never picked.
+    fn weighted(&self) -> &[(Self::Value, u32)];
+}
+impl<T> Variants for Vec<(T, u32)> {
+    type Value = T;
+    #[inline(always)]
+    fn weighted(&self) -> &[(T, u32)] {
+        self
+    }
+}
+
+/// Resolves the variants of an entry for units identified by the bytes of values of type `U`, and a [receiver] re-evaluating the assignments when the entry changes. Clones share their state. See the [module-level documentation] for more.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [module-level documentation]: index.html " "
+pub struct Rollout<E: Entry, U = String> {
+    state: Arc<Mutex<State<E::Data, U>>>,
+}
+struct State<D, U> {
+    variants: D,
+    /// The indices of the variants of the units resolved so far.
+    assignments: HashMap<U, Option<usize>>,
+    reassigned: Vec<U>,
+}
+impl<E, U> Rollout<E, U>
+where
+    E: Entry,
+    E::Data: Variants,
+    U: Hash + Eq + Clone + AsRef<[u8]> {
+    /// Creates a rollout with the specified variants, which should be the current value of the entry.
+    #[inline]
+    pub fn new(variants: E::Data) -> Self {
+        Self {state: Arc::new(Mutex::new(State {variants, assignments: HashMap::new(), reassigned: Vec::new()}))}
+    }
+    /// Returns the index of the variant of the unit, or `None` if there are no variants with a nonzero weight. The unit is remembered, so that its assignment is re-evaluated when the variants change.
+    pub fn index(&self, unit: &U) -> Option<usize> {
+        let mut state = self.lock();
+        if let Some(&index) = state.assignments.get(unit) {
+            return index;
+        }
+        let index = pick::<E>(state.variants.weighted(), unit.as_ref());
+        state.assignments.insert(unit.clone(), index);
+        index
+    }
+    /// Returns a clone of the value of the variant of the unit, or `None` if there are no variants with a nonzero weight. The unit is remembered, so that its assignment is re-evaluated when the variants change.
+    #[inline]
+    pub fn variant(&self, unit: &U) -> Option<<E::Data as Variants>::Value>
+    where <E::Data as Variants>::Value: Clone {
+        let index = self.index(unit)?;
+        self.lock().variants.weighted().get(index).map(|(value, _)| value.clone())
+    }
+    /// Returns the units whose variant changed because the variants changed, since the last call, and clears them.
+    #[inline]
+    pub fn take_reassigned(&self) -> Vec<U> {
+        core::mem::take(&mut self.lock().reassigned)
+    }
+    /// Stops re-evaluating the assignment of the unit.
+    #[inline]
+    pub fn forget(&self, unit: &U) {
+        self.lock().assignments.remove(unit);
+    }
+    /// Returns the number of units whose assignments are re-evaluated.
+    #[inline]
+    pub fn tracked(&self) -> usize {
+        self.lock().assignments.len()
+    }
+}
+impl<E: Entry, U> Rollout<E, U> {
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, State<E::Data, U>> {
+        lock_ignoring_poison(&self.state)
+    }
+}
+impl<E, U> Receiver<E> for Rollout<E, U>
+where
+    E: Entry,
+    E::Data: Variants + Clone,
+    U: Hash + Eq + Clone + AsRef<[u8]> {
+    fn receive(&mut self, new_value: &E::Data) {
+        let mut state = self.lock();
+        let state = &mut *state;
+        state.variants = new_value.clone();
+        for (unit, index) in &mut state.assignments {
+            let new_index = pick::<E>(state.variants.weighted(), unit.as_ref());
+            if *index != new_index {
+                *index = new_index;
+                state.reassigned.push(unit.clone());
+            }
+        }
+    }
+}
+impl<E: Entry, U> Clone for Rollout<E, U> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {state: Arc::clone(&self.state)}
+    }
+}
+impl<E: Entry, U> Debug for Rollout<E, U> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rollout")
+            .field("entry", &E::NAME)
+            .field("tracked", &self.lock().assignments.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The number of buckets into which units are hashed. It doesn't depend on the weights, so that units keep their buckets when the weights change.
+const BUCKETS: u64 = 10_000;
+
+/// Picks the variant whose share of the buckets contains the bucket of the unit.
+fn pick<E: Entry>(variants: &[(impl Sized, u32)], unit: &[u8]) -> Option<usize> {
+    let total = variants.iter().map(|&(_, weight)| u64::from(weight)).sum::<u64>();
+    if total == 0 {
+        return None;
+    }
+    let mut hash = Fnv::default();
+    hash.write(E::NAME.as_bytes());
+    // Separates the name from the unit, so that `a` + `bc` and `ab` + `c` hash differently.
+    hash.write(&[0xff]);
+    hash.write(unit);
+    let bucket = hash.0 % BUCKETS;
+    let mut cumulative = 0;
+    for (index, &(_, weight)) in variants.iter().enumerate() {
+        cumulative += u64::from(weight);
+        // The end of the range of the variant, scaled from the weights to the buckets.
+        if bucket < cumulative * BUCKETS / total {
+            return Some(index);
+        }
+    }
+    None
+}
+
+/// The FNV-1a hash, which, unlike the standard hasher, is guaranteed to stay the same across Rust versions and targets.
+struct Fnv(u64);
+impl Default for Fnv {
+    #[inline(always)]
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+impl Fnv {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! Exporting the shape of config tables as JSON Schema.
+//!
+//! [`json_schema`] describes a config table as a JSON object with one property per entry, named after the [name] of the entry, with the type of the property derived from the type of the entry's data via the [`JsonSchema`] trait, and the description taken from the [description] of the entry. Integer types additionally specify their ranges. The [unit] and the [UI range] of the entry, if it has them, are added to the property as the `unit` and `ui_range` annotations, which validators ignore.
+//!
+//! # Example
+//! ```
+//! use snec::ConfigTable;
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     /// The port to listen on.
+//!     #[snec]
+//!     port: u16,
+//!     #[snec]
+//!     hosts: Vec<String>,
+//!     #[snec(entry, unit = "s", ui(min = 1, max = 60))]
+//!     timeout: u32,
+//! }
+//! let schema = snec::schema::json_schema::<MyConfigTable>();
+//! assert_eq!(schema["properties"]["port"]["type"], "integer");
+//! assert_eq!(schema["properties"]["port"]["maximum"], 65535);
+//! assert_eq!(schema["properties"]["port"]["description"], "The port to listen on.");
+//! assert_eq!(schema["properties"]["hosts"]["items"]["type"], "string");
+//! assert_eq!(schema["properties"]["timeout"]["unit"], "s");
+//! assert_eq!(schema["properties"]["timeout"]["ui_range"], serde_json::json!({"min": 1.0, "max": 60.0, "step": null}));
+//! assert!(schema["properties"]["port"].get("ui_range").is_none());
+//! ```
+//!
+//! For tooling which needs more than the shape of the values, such as web admin interfaces and documentation generators, [`registry`] exports the [registry] of a config table as a JSON array with the name, type, description, group and default value of every entry.
+//!
+//! [`json_schema`]: fn.json_schema.html " "
+//! [`JsonSchema`]: trait.JsonSchema.html " "
+//! [`registry`]: fn.registry.html " "
+//! [registry]: ../trait.ConfigTable.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [description]: ../trait.Entry.html#associatedconstant.DESCRIPTION " "
+//! [unit]: ../trait.Entry.html#associatedconstant.UNIT " "
+//! [UI range]: ../trait.Entry.html#associatedconstant.UI_RANGE " "
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    path::PathBuf,
+    rc::Rc,
+    sync::Arc,
+};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use super::{Entry, EntryInfo, EntryTypeVisitor, VisitEntryTypes};
+
+/// The URI of the JSON Schema dialect produced by this module.
+pub const DIALECT: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Produces a JSON Schema describing the config table. See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+#[inline]
+pub fn json_schema<T>() -> Value
+where T: VisitEntryTypes<SchemaBuilder> {
+    let mut builder = SchemaBuilder::new();
+    T::visit_entry_types(&mut builder);
+    builder.finish()
+}
+
+/// A [type visitor] which collects the schemas of entries, used by [`json_schema`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`json_schema`]: fn.json_schema.html " "
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaBuilder {
+    properties: Map<String, Value>,
+}
+impl SchemaBuilder {
+    /// Creates a builder without any entries.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns the schemas of the entries collected so far, keyed by entry names.
+    #[inline(always)]
+    pub fn properties(&self) -> &Map<String, Value> {
+        &self.properties
+    }
+    /// Produces the schema of the whole config table.
+    #[inline]
+    pub fn finish(self) -> Value {
+        json!({
+            "$schema": DIALECT,
+            "type": "object",
+            "properties": self.properties,
+        })
+    }
+}
+impl<E> EntryTypeVisitor<E> for SchemaBuilder
+where
+    E: Entry,
+    E::Data: JsonSchema {
+    fn visit_type(&mut self) {
+        let mut schema = E::Data::json_schema();
+        if !E::DESCRIPTION.is_empty() {
+            schema.insert("description".to_string(), E::DESCRIPTION.into());
+        }
+        if !E::UNIT.is_empty() {
+            schema.insert("unit".to_string(), E::UNIT.into());
+        }
+        if !E::UI_RANGE.is_unbounded() {
+            // Serializing a UI range can't fail.
+            schema.insert("ui_range".to_string(), serde_json::to_value(E::UI_RANGE).unwrap_or_default());
+        }
+        self.properties.insert(E::NAME.to_string(), Value::Object(schema));
+    }
+}
+
+/// Exports the registry of the config table as a JSON array with one object per entry, in the order of the entries, each with the fields of the [serialized `EntryInfo`] and a `default` field with the [default value] of the entry, which is `null` if the entry doesn't have one, if it can't be represented in JSON or if the entry is [secret].
+///
+/// # Example
+/// ```
+/// use snec::ConfigTable;
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     /// The port to listen on.
+///     #[snec(entry, name = "server.port", default = 8080)]
+///     port: u16,
+///     #[snec(entry, name = "server.timeout", unit = "s", ui(min = 1, max = 60, step = 1))]
+///     timeout: u32,
+/// }
+/// let registry = snec::schema::registry::<MyConfigTable>();
+/// assert_eq!(registry, serde_json::json!([{
+///     "name": "server.port",
+///     "type": "u16",
+///     "description": "The port to listen on.",
+///     "group": "server",
+///     "unit": null,
+///     "ui_range": null,
+///     "secret": false,
+///     "default": 8080,
+/// }, {
+///     "name": "server.timeout",
+///     "type": "u32",
+///     "description": "",
+///     "group": "server",
+///     "unit": "s",
+///     "ui_range": {"min": 1.0, "max": 60.0, "step": 1.0},
+///     "secret": false,
+///     "default": null,
+/// }]));
+/// ```
+///
+/// [serialized `EntryInfo`]: ../struct.EntryInfo.html " "
+/// [default value]: ../trait.Entry.html#method.default_value " "
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+#[inline]
+pub fn registry<T>() -> Value
+where T: VisitEntryTypes<RegistryExporter> {
+    let mut exporter = RegistryExporter::new();
+    T::visit_entry_types(&mut exporter);
+    exporter.finish()
+}
+
+/// A [type visitor] which collects the descriptions of entries, used by [`registry`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`registry`]: fn.registry.html " "
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegistryExporter {
+    entries: Vec<Value>,
+}
+impl RegistryExporter {
+    /// Creates an exporter without any entries.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Produces the JSON array of the descriptions of all entries.
+    #[inline(always)]
+    pub fn finish(self) -> Value {
+        Value::Array(self.entries)
+    }
+}
+impl<E> EntryTypeVisitor<E> for RegistryExporter
+where
+    E: Entry,
+    E::Data: Serialize {
+    fn visit_type(&mut self) {
+        let mut description = match serde_json::to_value(EntryInfo::of::<E>()) {
+            Ok(Value::Object(map)) => map,
+            _ => unreachable!("entry information always serializes into an object"),
+        };
+        let default_value = E::default_value()
+            .filter(|_| !E::SECRET)
+            .and_then(|value| serde_json::to_value(value).ok())
+            .unwrap_or(Value::Null);
+        description.insert("default".to_string(), default_value);
+        self.entries.push(Value::Object(description));
+    }
+}
+
+/// Trait for types which can be described using JSON Schema.
+///
+/// Implemented for the primitive types and the most common standard library types. Implementations for other types can be written manually, or simply return an empty map, which allows any value.
+pub trait JsonSchema {
+    /// Returns the JSON Schema describing the type, as a JSON object.
+    fn json_schema() -> Map<String, Value>;
+}
+
+/// Turns a `json!({...})` object literal into a map.
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!("schema literals are always objects"),
+    }
+}
+
+macro_rules! impl_json_schema_for_integers {
+    ($($ty:ty),+ $(,)?) => ($(
+        impl JsonSchema for $ty {
+            #[inline]
+            fn json_schema() -> Map<String, Value> {
+                object(json!({
+                    "type": "integer",
+                    "minimum": <$ty>::MIN,
+                    "maximum": <$ty>::MAX,
+                }))
+            }
+        }
+    )+);
+}
+impl_json_schema_for_integers!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+// 128-bit integers don't fit into JSON numbers, so only the sign is constrained.
+impl JsonSchema for i128 {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        object(json!({"type": "integer"}))
+    }
+}
+impl JsonSchema for u128 {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        object(json!({"type": "integer", "minimum": 0}))
+    }
+}
+
+macro_rules! impl_json_schema_with_type {
+    ($type_name:literal: $($ty:ty),+ $(,)?) => ($(
+        impl JsonSchema for $ty {
+            #[inline]
+            fn json_schema() -> Map<String, Value> {
+                object(json!({"type": $type_name}))
+            }
+        }
+    )+);
+}
+impl_json_schema_with_type!("number": f32, f64);
+impl_json_schema_with_type!("boolean": bool);
+impl_json_schema_with_type!("string": String, str, PathBuf);
+
+impl JsonSchema for char {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        object(json!({"type": "string", "minLength": 1, "maxLength": 1}))
+    }
+}
+impl<T: JsonSchema> JsonSchema for Option<T> {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        object(json!({
+            "anyOf": [T::json_schema(), {"type": "null"}],
+        }))
+    }
+}
+
+macro_rules! impl_json_schema_for_pointers {
+    ($($ty:ident),+ $(,)?) => ($(
+        impl<T: JsonSchema + ?Sized> JsonSchema for $ty<T> {
+            #[inline(always)]
+            fn json_schema() -> Map<String, Value> {
+                T::json_schema()
+            }
+        }
+    )+);
+}
+impl_json_schema_for_pointers!(Box, Rc, Arc);
+
+macro_rules! impl_json_schema_for_sequences {
+    ($unique:literal: $($ty:ident),+ $(,)?) => ($(
+        impl<T: JsonSchema> JsonSchema for $ty<T> {
+            #[inline]
+            fn json_schema() -> Map<String, Value> {
+                object(json!({
+                    "type": "array",
+                    "items": T::json_schema(),
+                    "uniqueItems": $unique,
+                }))
+            }
+        }
+    )+);
+}
+impl_json_schema_for_sequences!(false: Vec, VecDeque);
+impl_json_schema_for_sequences!(true: BTreeSet, HashSet);
+impl<T: JsonSchema> JsonSchema for [T] {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        <Vec<T>>::json_schema()
+    }
+}
+impl<T: JsonSchema, const N: usize> JsonSchema for [T; N] {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        object(json!({
+            "type": "array",
+            "items": T::json_schema(),
+            "minItems": N,
+            "maxItems": N,
+        }))
+    }
+}
+
+impl<T: JsonSchema, S> JsonSchema for HashMap<String, T, S> {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        object(json!({
+            "type": "object",
+            "additionalProperties": T::json_schema(),
+        }))
+    }
+}
+impl<T: JsonSchema> JsonSchema for BTreeMap<String, T> {
+    #[inline]
+    fn json_schema() -> Map<String, Value> {
+        <HashMap<String, T>>::json_schema()
+    }
+}
@@ -0,0 +1,518 @@
+//! Mirroring entries of config tables in shared memory.
+//!
+//! A [`ShmWriter`] keeps a copy of the entries marked with `#[snec(shm)]` in a memory-mapped file, which other processes, such as sidecars, can map with a [`ShmReader`] to read the current values directly, without any round-trips to the process owning the table. On Linux, placing the file into `/dev/shm` keeps it in memory.
+//!
+//! The writer is a [receiver] which updates the copy of an entry whenever its value changes, and ignores the entries which aren't mirrored, which allows it to be the receiver of the whole table. Since readers can't see the table, only plain data can be mirrored: the types of the mirrored entries have to implement [`ShmData`], which is the case for integers, floating-point numbers, `bool`, `char` and arrays of them. Since any process which can open the file can write anything into it, readers check the bytes they read, and report values which aren't valid for their types, such as a `bool` which is neither 0 nor 1, as errors. The layout of the mirrored entries is generated by `#[derive(ConfigTable)]` in the form of the [`ShmTable`] trait, and readers check that the file was created for the same layout before reading it.
+//!
+//! Writes are made consistent with a [seqlock]: readers never block the writer, and retry reading when they catch it in the middle of a write, so that they never see a partially written value. Since the values are copied in and out of the shared memory, reading an entry returns its value rather than a reference to it.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, shm::{ShmWriter, ShmReader}};
+//! use std::sync::Arc;
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({Arc::clone(&self.shm)}: Arc<ShmWriter>))]
+//! struct MyConfigTable {
+//!     #[snec(entry, shm)]
+//!     max_connections: u32,
+//!     #[snec(entry, shm)]
+//!     sampling_rate: f64,
+//!     #[snec]
+//!     log_file: String,
+//!     shm: Arc<ShmWriter>,
+//! }
+//! let path = std::env::temp_dir().join(format!("snec-shm-example-{}", std::process::id()));
+//! let shm = Arc::new(ShmWriter::create::<MyConfigTable>(&path)?);
+//! let mut table = MyConfigTable {
+//!     max_connections: 100,
+//!     sampling_rate: 0.5,
+//!     log_file: "app.log".to_string(),
+//!     shm,
+//! };
+//! table.shm.write_all(&table);
+//!
+//! // Usually done by a different process.
+//! let reader = ShmReader::<MyConfigTable>::open(&path)?;
+//! assert_eq!(reader.get::<entries::MaxConnections>()?, Some(100));
+//! table.get_handle_to::<entries::MaxConnections>().set(250);
+//! assert_eq!(reader.get::<entries::MaxConnections>()?, Some(250));
+//! assert_eq!(reader.get::<entries::SamplingRate>()?, Some(0.5));
+//! # std::fs::remove_file(&path)?;
+//! # Ok::<(), snec::shm::Error>(())
+//! ```
+//!
+//! [`ShmWriter`]: struct.ShmWriter.html " "
+//! [`ShmReader`]: struct.ShmReader.html " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`ShmData`]: trait.ShmData.html " "
+//! [`ShmTable`]: trait.ShmTable.html " "
+//! [seqlock]: https://en.wikipedia.org/wiki/Seqlock " "
+
+use core::{
+    any::TypeId,
+    fmt::{self, Formatter, Display, Debug},
+    hint,
+    marker::PhantomData,
+    convert::TryInto,
+    mem::{self, MaybeUninit},
+    slice,
+    sync::atomic::{self, AtomicU8, AtomicU32, Ordering},
+};
+use std::{
+    error,
+    fs::{self, OpenOptions},
+    io,
+    path::Path,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+use memmap2::{Mmap, MmapMut};
+use super::{Entry, Get, Receiver, lock_ignoring_poison};
+
+/// Types which can be copied into shared memory and read back by another process.
+///
+/// Values are written into shared memory as their bytes in memory, and are read back with [`from_bytes`], which has to reject the bytes which don't form a valid value, since they can come from any process which can write to the file.
+///
+/// # Safety
+/// The type must not contain any pointers, references or other values which are only meaningful within one process, and must not have any padding bytes.
+///
+/// [`from_bytes`]: #tymethod.from_bytes " "
+pub unsafe trait ShmData: Copy + 'static {
+    /// Reconstructs a value from its bytes in memory, of which there are as many as the size of the type, or returns `None` if they don't form a valid value.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+macro_rules! impl_shm_data {
+    ($($ty:ty),+ $(,)?) => {$(
+        // Every bit pattern is a valid value of these types.
+        unsafe impl ShmData for $ty {
+            #[inline]
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                bytes.try_into().ok().map(Self::from_ne_bytes)
+            }
+        }
+    )+};
+}
+impl_shm_data! {
+    u8, u16, u32, u64, u128, usize,
+    i8, i16, i32, i64, i128, isize,
+    f32, f64,
+}
+unsafe impl ShmData for bool {
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+}
+unsafe impl ShmData for char {
+    /// Rejects surrogates and values above `char::MAX`.
+    #[inline]
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        u32::from_bytes(bytes).and_then(char::from_u32)
+    }
+}
+unsafe impl<T: ShmData, const N: usize> ShmData for [T; N] {
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let size = mem::size_of::<T>();
+        if bytes.len() != size * N {
+            return None;
+        }
+        let mut array = MaybeUninit::<[T; N]>::uninit();
+        let elements = array.as_mut_ptr().cast::<T>();
+        for index in 0..N {
+            let element = T::from_bytes(&bytes[index * size..][..size])?;
+            // SAFETY: the index is within the array.
+            unsafe { elements.add(index).write(element) };
+        }
+        // SAFETY: all N elements were written, and T is Copy, so returning early above leaks nothing.
+        Some(unsafe { array.assume_init() })
+    }
+}
+
+/// Config tables with entries mirrored in shared memory, implemented by `#[derive(ConfigTable)]` for the fields marked with `#[snec(shm)]`.
+pub trait ShmTable: Sized {
+    /// Calls the [`visit`] method of the visitor for every mirrored entry, in the order of the layout.
+    ///
+    /// [`visit`]: trait.ShmVisitor.html#tymethod.visit " "
+    fn visit_shm_entries<V: ShmVisitor<Self>>(visitor: &mut V);
+}
+/// Visitors for the entries of a config table which are mirrored in shared memory.
+pub trait ShmVisitor<T> {
+    /// Visits a mirrored entry.
+    fn visit<E: Entry + 'static>(&mut self)
+    where
+        E::Data: ShmData,
+        T: Get<E>;
+}
+
+/// The magic number at the beginning of the file.
+const MAGIC: [u8; 8] = *b"SNECSHM1";
+/// The offset of the sequence number of the seqlock, which is odd while a write is in progress.
+const SEQUENCE_OFFSET: usize = 8;
+/// The offset of the fingerprint of the layout.
+const FINGERPRINT_OFFSET: usize = 16;
+/// The offset of the length of the values.
+const LENGTH_OFFSET: usize = 24;
+/// The length of the header, after which the values are placed.
+const HEADER_LENGTH: usize = 32;
+/// How long a reader waits for a write in progress to finish before reporting it as an error. A write only copies a few bytes, so a write taking this long means that the writing process was terminated in the middle of it.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+/// How many times a reader spins while waiting for a write in progress before it starts yielding the rest of its time slice.
+const SPIN_LIMIT: u32 = 64;
+
+/// Writes the values of mirrored entries into a memory-mapped file, which is also a [receiver] doing so whenever they change. See the [module-level documentation] for more.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [module-level documentation]: index.html " "
+pub struct ShmWriter {
+    map: MmapMut,
+    /// Taken from the map once, so that the values can be written through atomics while the writer is shared.
+    memory: *mut u8,
+    layout: Layout,
+    lock: Mutex<()>,
+}
+// SAFETY: the memory is only accessed through atomics, and writes are serialized by the lock.
+unsafe impl Send for ShmWriter {}
+unsafe impl Sync for ShmWriter {}
+impl ShmWriter {
+    /// Creates the file at the specified path with the layout of the mirrored entries of the config table `T`, replacing the file if it exists. The values start out zeroed until they're written, so reading an entry of a type for which zero isn't a valid value fails until then.
+    ///
+    /// The file is prepared under a temporary name and then renamed, so that readers never see it incomplete. Readers which mapped a previous file at the same path keep reading it, and need to be reopened to see the new one.
+    pub fn create<T: ShmTable>(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut temporary_name = path.file_name().unwrap_or_default().to_os_string();
+        temporary_name.push(".tmp");
+        let temporary_path = path.with_file_name(temporary_name);
+        let layout = Layout::of::<T>();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&temporary_path)?;
+        file.set_len((HEADER_LENGTH + layout.length) as u64)?;
+        // SAFETY: the file was just created, so nothing else modifies it yet.
+        let mut map = unsafe { MmapMut::map_mut(&file)? };
+        map[..MAGIC.len()].copy_from_slice(&MAGIC);
+        map[FINGERPRINT_OFFSET..][..8].copy_from_slice(&layout.fingerprint.to_le_bytes());
+        map[LENGTH_OFFSET..][..8].copy_from_slice(&(layout.length as u64).to_le_bytes());
+        map.flush()?;
+        fs::rename(&temporary_path, path)?;
+        let memory = map.as_mut_ptr();
+        Ok(Self {map, memory, layout, lock: Mutex::new(())})
+    }
+    /// Writes the value of the entry, returning `false` if the entry isn't mirrored.
+    pub fn write<E: Entry + 'static>(&self, value: &E::Data) -> bool {
+        let slot = match self.layout.slot::<E>() {
+            Some(slot) => slot,
+            None => return false,
+        };
+        let _guard = lock_ignoring_poison(&self.lock);
+        let sequence = self.begin_write();
+        // SAFETY: the slot was registered for E, which requires E::Data to be ShmData.
+        unsafe { self.store(slot, value) };
+        self.end_write(sequence);
+        true
+    }
+    /// Writes the values of all mirrored entries of the config table at once, so that readers see either all of the old values or all of the new ones.
+    pub fn write_all<T: ShmTable>(&self, table: &T) {
+        let _guard = lock_ignoring_poison(&self.lock);
+        let sequence = self.begin_write();
+        T::visit_shm_entries(&mut ShmInitializer {writer: self, table});
+        self.end_write(sequence);
+    }
+    fn begin_write(&self) -> u32 {
+        let sequence = sequence(self.memory);
+        let value = sequence.load(Ordering::Relaxed);
+        sequence.store(value.wrapping_add(1), Ordering::Relaxed);
+        atomic::fence(Ordering::Release);
+        value
+    }
+    #[inline]
+    fn end_write(&self, value: u32) {
+        sequence(self.memory).store(value.wrapping_add(2), Ordering::Release);
+    }
+    /// Copies the value into its slot.
+    ///
+    /// # Safety
+    /// The slot must be the one of the entry whose data has type `D`.
+    unsafe fn store<D>(&self, slot: &Slot, value: &D) {
+        debug_assert_eq!(slot.size, mem::size_of::<D>());
+        let bytes = (value as *const D).cast::<u8>();
+        for index in 0..slot.size {
+            byte(self.memory, HEADER_LENGTH + slot.offset + index)
+                .store(*bytes.add(index), Ordering::Relaxed);
+        }
+    }
+}
+impl<E: Entry + 'static> Receiver<E> for &ShmWriter {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.write::<E>(new_value);
+    }
+}
+impl Debug for ShmWriter {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShmWriter")
+            .field("length", &self.map.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Reads the values of mirrored entries from a memory-mapped file created by a [`ShmWriter`] for the config table `T`. See the [module-level documentation] for more.
+///
+/// [`ShmWriter`]: struct.ShmWriter.html " "
+/// [module-level documentation]: index.html " "
+pub struct ShmReader<T> {
+    map: Mmap,
+    layout: Layout,
+    table: PhantomData<fn() -> T>,
+}
+impl<T: ShmTable> ShmReader<T> {
+    /// Maps the file at the specified path, checking that it was created for the same layout of mirrored entries.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = OpenOptions::new().read(true).open(path)?;
+        // SAFETY: the writer only modifies the values, which are only read through atomics.
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < HEADER_LENGTH || map[..MAGIC.len()] != MAGIC {
+            return Err(Error::NotShm);
+        }
+        let layout = Layout::of::<T>();
+        let fingerprint = u64::from_le_bytes(read_header(&map, FINGERPRINT_OFFSET));
+        let length = u64::from_le_bytes(read_header(&map, LENGTH_OFFSET));
+        if fingerprint != layout.fingerprint
+            || length != layout.length as u64
+            || map.len() < HEADER_LENGTH + layout.length {
+            return Err(Error::LayoutMismatch);
+        }
+        Ok(Self {map, layout, table: PhantomData})
+    }
+    /// Reads the current value of the entry, or returns `None` if the entry isn't mirrored. Fails with [`Error::InvalidValue`] if the bytes in the file aren't a valid value of the type of the entry, which means that another process has corrupted the file.
+    ///
+    /// Waits for the write in progress to finish, if there is one. Fails with [`Error::WriteInProgress`] if it doesn't finish within a second, which happens if the writing process was terminated in the middle of a write.
+    ///
+    /// # Example
+    /// ```
+    /// use snec::{ConfigTable, shm::{Error, ShmWriter, ShmReader}};
+    /// use std::{fs::OpenOptions, io::{Seek, SeekFrom, Write}};
+    /// #[derive(ConfigTable)]
+    /// struct MyConfigTable {
+    ///     #[snec(entry, shm)]
+    ///     maintenance: bool,
+    /// }
+    /// let path = std::env::temp_dir().join(format!("snec-shm-invalid-{}", std::process::id()));
+    /// let writer = ShmWriter::create::<MyConfigTable>(&path)?;
+    /// writer.write_all(&MyConfigTable {maintenance: true});
+    /// let reader = ShmReader::<MyConfigTable>::open(&path)?;
+    /// assert_eq!(reader.get::<entries::Maintenance>()?, Some(true));
+    /// // Another process scribbles over the value, which is placed right after the 32-byte header.
+    /// let mut file = OpenOptions::new().write(true).open(&path)?;
+    /// file.seek(SeekFrom::Start(32))?;
+    /// file.write_all(&[2])?;
+    /// file.sync_all()?;
+    /// assert!(matches!(reader.get::<entries::Maintenance>(), Err(Error::InvalidValue("maintenance"))));
+    /// // The writer is terminated in the middle of a write, leaving the sequence number, which is placed
+    /// // right after the 8-byte magic number, odd.
+    /// file.seek(SeekFrom::Start(8))?;
+    /// file.write_all(&1_u32.to_ne_bytes())?;
+    /// file.sync_all()?;
+    /// assert!(matches!(reader.get::<entries::Maintenance>(), Err(Error::WriteInProgress)));
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    ///
+    /// [`Error::InvalidValue`]: enum.Error.html#variant.InvalidValue " "
+    /// [`Error::WriteInProgress`]: enum.Error.html#variant.WriteInProgress " "
+    pub fn get<E: Entry + 'static>(&self) -> Result<Option<E::Data>, Error>
+    where E::Data: ShmData {
+        let slot = match self.layout.slot::<E>() {
+            Some(slot) => slot,
+            None => return Ok(None),
+        };
+        let memory = self.map.as_ptr() as *mut u8;
+        let sequence = sequence(memory);
+        let mut value = MaybeUninit::<E::Data>::uninit();
+        let bytes = value.as_mut_ptr().cast::<u8>();
+        let mut retries = 0_u32;
+        let mut deadline = None;
+        let mut retry = || {
+            if retries < SPIN_LIMIT {
+                retries += 1;
+                hint::spin_loop();
+                return Ok(());
+            }
+            let deadline = *deadline.get_or_insert_with(|| Instant::now() + WRITE_TIMEOUT);
+            if Instant::now() >= deadline {
+                return Err(Error::WriteInProgress);
+            }
+            thread::yield_now();
+            Ok(())
+        };
+        loop {
+            let before = sequence.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                retry()?;
+                continue;
+            }
+            for index in 0..slot.size {
+                let value = byte(memory, HEADER_LENGTH + slot.offset + index).load(Ordering::Relaxed);
+                // SAFETY: the slot has the size of E::Data.
+                unsafe { bytes.add(index).write(value) };
+            }
+            atomic::fence(Ordering::Acquire);
+            if sequence.load(Ordering::Relaxed) == before {
+                break;
+            }
+            retry()?;
+        }
+        // SAFETY: all bytes of the buffer were written by the loop above, since the slot has the size of E::Data.
+        let bytes = unsafe { slice::from_raw_parts(bytes, slot.size) };
+        E::Data::from_bytes(bytes).map(Some).ok_or(Error::InvalidValue(E::NAME))
+    }
+    /// Returns a number which changes whenever the values are written, which allows cheaply checking for changes.
+    #[inline]
+    pub fn version(&self) -> u32 {
+        sequence(self.map.as_ptr() as *mut u8).load(Ordering::Acquire) / 2
+    }
+}
+impl<T> Debug for ShmReader<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShmReader")
+            .field("length", &self.map.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Where the value of an entry is placed, relative to the end of the header.
+#[derive(Debug)]
+struct Slot {
+    entry: TypeId,
+    offset: usize,
+    size: usize,
+}
+/// The placement of the values of all mirrored entries.
+#[derive(Debug)]
+struct Layout {
+    slots: Vec<Slot>,
+    length: usize,
+    /// A hash of the names, types and sizes of the entries, which identifies the layout across processes.
+    fingerprint: u64,
+}
+impl Layout {
+    fn of<T: ShmTable>() -> Self {
+        let mut layout = Self {slots: Vec::new(), length: 0, fingerprint: 0xcbf2_9ce4_8422_2325};
+        T::visit_shm_entries(&mut layout);
+        layout
+    }
+    #[inline]
+    fn slot<E: 'static>(&self) -> Option<&Slot> {
+        self.slots.iter().find(|slot| slot.entry == TypeId::of::<E>())
+    }
+    /// Feeds the bytes into the FNV-1a hash of the layout.
+    fn hash(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.fingerprint ^= u64::from(byte);
+            self.fingerprint = self.fingerprint.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+impl<T> ShmVisitor<T> for Layout {
+    fn visit<E: Entry + 'static>(&mut self)
+    where
+        E::Data: ShmData,
+        T: Get<E> {
+        let size = mem::size_of::<E::Data>();
+        self.hash(E::NAME.as_bytes());
+        self.hash(&[0]);
+        self.hash(E::TYPE_NAME.as_bytes());
+        self.hash(&(size as u64).to_le_bytes());
+        self.slots.push(Slot {entry: TypeId::of::<E>(), offset: self.length, size});
+        self.length += size;
+    }
+}
+
+/// Writes the values of all mirrored entries, used by [`ShmWriter::write_all`].
+///
+/// [`ShmWriter::write_all`]: struct.ShmWriter.html#method.write_all " "
+struct ShmInitializer<'a, T> {
+    writer: &'a ShmWriter,
+    table: &'a T,
+}
+impl<T> ShmVisitor<T> for ShmInitializer<'_, T> {
+    fn visit<E: Entry + 'static>(&mut self)
+    where
+        E::Data: ShmData,
+        T: Get<E> {
+        if let Some(slot) = self.writer.layout.slot::<E>() {
+            // SAFETY: the slot was registered for E.
+            unsafe { self.writer.store(slot, self.table.get_ref()) };
+        }
+    }
+}
+
+/// An error which occurred while creating, opening or reading a shared memory mirror.
+#[derive(Debug)]
+pub enum Error {
+    /// The file could not be created, opened or mapped.
+    Io(io::Error),
+    /// The file is not a shared memory mirror of a config table.
+    NotShm,
+    /// The file was created for a different layout of mirrored entries.
+    LayoutMismatch,
+    /// The bytes of the value of the entry with the specified [name] aren't a valid value of its type.
+    ///
+    /// [name]: ../trait.Entry.html#associatedconstant.NAME " "
+    InvalidValue(&'static str),
+    /// A write didn't finish in time for the value to be read, which means that the writing process was terminated in the middle of it.
+    WriteInProgress,
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "shared memory error: {}", error),
+            Self::NotShm => f.write_str("the file is not a shared memory mirror of a config table"),
+            Self::LayoutMismatch => f.write_str("the shared memory mirror was created for a different layout of entries"),
+            Self::InvalidValue(entry) => write!(f, "the shared memory mirror contains an invalid value for entry `{}`", entry),
+            Self::WriteInProgress => f.write_str("timed out waiting for a write to the shared memory mirror to finish"),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::NotShm | Self::LayoutMismatch | Self::InvalidValue(..) | Self::WriteInProgress => None,
+        }
+    }
+}
+impl From<io::Error> for Error {
+    #[inline(always)]
+    fn from(op: io::Error) -> Self {
+        Self::Io(op)
+    }
+}
+
+#[inline(always)]
+fn sequence<'a>(memory: *mut u8) -> &'a AtomicU32 {
+    // SAFETY: the mapping is page-aligned and at least as long as the header.
+    unsafe { &*memory.add(SEQUENCE_OFFSET).cast::<AtomicU32>() }
+}
+#[inline(always)]
+fn byte<'a>(memory: *mut u8, offset: usize) -> &'a AtomicU8 {
+    // SAFETY: the offset is within the mapping, as checked against the layout.
+    unsafe { &*memory.add(offset).cast::<AtomicU8>() }
+}
+#[inline]
+fn read_header(map: &[u8], offset: usize) -> [u8; 8] {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&map[offset..][..8]);
+    bytes
+}
@@ -0,0 +1,321 @@
+//! Compact binary snapshots of whole config tables, encoded with [Postcard].
+//!
+//! A [`Snapshot`] stores the [name] of every entry of a config table along with its value encoded in the Postcard format, prefixed with a version number chosen by the application. Snapshots are meant for places where a text format is unwanted, such as save files of games or configuration stored in the flash memory of embedded devices, and only require the `alloc` feature.
+//!
+//! [`Snapshot::restore_into`] writes the values from a snapshot into a config table through handles, so that only the receivers of the entries which actually changed are notified. Entries which are missing from the snapshot are left as they are, and values of entries which the table doesn't have are ignored, which means that entries can be added and removed between versions without migrating older snapshots. Changes which need more than that, such as renaming entries or changing their types, are handled by [`Snapshot::migrate`], which runs a migration step for every version between the one of the snapshot and the current one.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, snapshot::Snapshot};
+//! #[derive(ConfigTable)]
+//! struct SaveFile {
+//!     #[snec]
+//!     level: u32,
+//!     #[snec]
+//!     player_name: String,
+//! }
+//! let table = SaveFile {level: 7, player_name: "Jeremy".to_string()};
+//! let bytes = Snapshot::capture(&table, 1)?.to_bytes()?;
+//!
+//! let mut restored = SaveFile {level: 1, player_name: String::new()};
+//! let changed = Snapshot::from_bytes(&bytes)?.restore_into(&mut restored)?;
+//! assert_eq!(changed, 2);
+//! assert_eq!(restored.level, 7);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//! Migrating a snapshot from an older version, in which the level was stored as a `u8` under a different name:
+//! ```
+//! use snec::{ConfigTable, Entry, snapshot::Snapshot};
+//! #[derive(ConfigTable)]
+//! struct SaveFile {
+//!     #[snec]
+//!     level: u32,
+//! }
+//! # let old_bytes = {
+//! #     let mut snapshot = Snapshot::new(1);
+//! #     snapshot.set_raw("stage", 3_u8)?;
+//! #     snapshot.to_bytes()?
+//! # };
+//! let mut snapshot = Snapshot::from_bytes(&old_bytes)?;
+//! snapshot.migrate(2, |from_version, snapshot| {
+//!     if from_version == 1 {
+//!         if let Some(stage) = snapshot.get_raw::<u8>("stage")? {
+//!             snapshot.remove("stage");
+//!             snapshot.set::<entries::Level>(&u32::from(stage))?;
+//!         }
+//!     }
+//!     Ok(())
+//! })?;
+//! let mut table = SaveFile {level: 1};
+//! snapshot.restore_into(&mut table)?;
+//! assert_eq!(table.level, 3);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [Postcard]: https://docs.rs/postcard " "
+//! [`Snapshot`]: struct.Snapshot.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`Snapshot::restore_into`]: struct.Snapshot.html#method.restore_into " "
+//! [`Snapshot::migrate`]: struct.Snapshot.html#method.migrate " "
+
+use core::fmt::{self, Formatter, Display};
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use super::{
+    Entry,
+    Get,
+    Loader,
+    LoadError,
+    Lookup,
+    LookupEntry,
+    VisitEntries,
+    ValidateTable,
+    EntryTypeVisitor,
+    VisitEntryTypes,
+};
+
+/// A versioned binary snapshot of the values of entries. See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Snapshot {
+    version: u32,
+    entries: Vec<(String, Vec<u8>)>,
+}
+impl Snapshot {
+    /// Creates an empty snapshot with the specified version.
+    #[inline(always)]
+    pub const fn new(version: u32) -> Self {
+        Self {version, entries: Vec::new()}
+    }
+    /// Creates a snapshot of the current values of all entries of the config table, with the specified version.
+    pub fn capture<T>(table: &T, version: u32) -> Result<Self, Error>
+    where T: for<'a> VisitEntryTypes<SnapshotWriter<'a, T>> {
+        let mut writer = SnapshotWriter {table, snapshot: Self::new(version), error: None};
+        T::visit_entry_types(&mut writer);
+        match writer.error {
+            Some(error) => Err(error),
+            None => Ok(writer.snapshot),
+        }
+    }
+    /// Decodes a snapshot previously encoded with [`to_bytes`].
+    ///
+    /// [`to_bytes`]: #method.to_bytes " "
+    #[inline]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (version, entries) = postcard::from_bytes(bytes)
+            .map_err(|error| Error::Postcard {entry: None, inner: error})?;
+        Ok(
+            Self {version, entries}
+        )
+    }
+    /// Encodes the snapshot.
+    #[inline]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        postcard::to_allocvec(&(self.version, &self.entries))
+            .map_err(|error| Error::Postcard {entry: None, inner: error})
+    }
+    /// Returns the version of the snapshot.
+    #[inline(always)]
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    /// Migrates the snapshot to the specified version, calling `step` with every version from the one of the snapshot up to, but not including, `target_version`, each of which should change the snapshot to fit the next version.
+    ///
+    /// Fails if the version of the snapshot is newer than `target_version`, which means that it was made by a newer version of the application.
+    pub fn migrate<F>(&mut self, target_version: u32, mut step: F) -> Result<(), Error>
+    where F: FnMut(u32, &mut Self) -> Result<(), Error> {
+        if self.version > target_version {
+            return Err(
+                Error::UnsupportedVersion {found: self.version, expected: target_version}
+            );
+        }
+        while self.version < target_version {
+            step(self.version, self)?;
+            self.version += 1;
+        }
+        Ok(())
+    }
+    /// Returns the value of the entry stored in the snapshot, or `None` if the snapshot doesn't contain it.
+    #[inline]
+    pub fn get<E>(&self) -> Result<Option<E::Data>, Error>
+    where
+        E: Entry,
+        E::Data: DeserializeOwned {
+        self.get_raw(E::NAME).map_err(|error| error.with_entry(E::NAME))
+    }
+    /// Returns the value stored under the specified name, decoded as the specified type, or `None` if the snapshot doesn't contain it. Useful in migrations, for values of entries which the config table no longer has.
+    pub fn get_raw<D: DeserializeOwned>(&self, name: &str) -> Result<Option<D>, Error> {
+        self.find(name)
+            .map(|index| {
+                postcard::from_bytes(&self.entries[index].1)
+                    .map_err(|error| Error::Postcard {entry: None, inner: error})
+            })
+            .transpose()
+    }
+    /// Stores the value of the entry in the snapshot, replacing the previously stored one.
+    #[inline]
+    pub fn set<E>(&mut self, value: &E::Data) -> Result<(), Error>
+    where
+        E: Entry,
+        E::Data: Serialize {
+        self.set_raw(E::NAME, value).map_err(|error| error.with_entry(E::NAME))
+    }
+    /// Stores the value under the specified name, replacing the previously stored one.
+    pub fn set_raw<D: Serialize>(&mut self, name: &str, value: D) -> Result<(), Error> {
+        let bytes = postcard::to_allocvec(&value)
+            .map_err(|error| Error::Postcard {entry: None, inner: error})?;
+        match self.find(name) {
+            Some(index) => self.entries[index].1 = bytes,
+            None => self.entries.push((name.to_string(), bytes)),
+        }
+        Ok(())
+    }
+    /// Removes the value stored under the specified name, returning `true` if there was one.
+    pub fn remove(&mut self, name: &str) -> bool {
+        match self.find(name) {
+            Some(index) => {
+                self.entries.remove(index);
+                true
+            },
+            None => false,
+        }
+    }
+    /// Renames the value stored under `old_name` to `new_name`, returning `true` if there was one. A value already stored under `new_name` is removed.
+    pub fn rename(&mut self, old_name: &str, new_name: &str) -> bool {
+        if old_name == new_name {
+            return self.find(old_name).is_some();
+        }
+        match self.find(old_name) {
+            Some(index) => {
+                self.remove(new_name);
+                let index = self.find(old_name).unwrap_or(index);
+                self.entries[index].0 = new_name.to_string();
+                true
+            },
+            None => false,
+        }
+    }
+    /// Writes the values from the snapshot into the corresponding entries of the config table, notifying the receivers of the entries which changed. Returns the number of entries which changed.
+    ///
+    /// Entries missing from the snapshot, such as ones added to the table after it was captured, keep their current values. Restoring fails with the first value whose bytes can't be decoded as the type of its entry, typically because the type changed without a [migration] for it. The table is left unchanged if an error is returned, including one produced by the [table validator].
+    ///
+    /// [migration]: #method.migrate " "
+    /// [table validator]: ../trait.ValidateTable.html " "
+    #[inline]
+    pub fn restore_into<T>(&self, table: &mut T) -> Result<usize, LoadError<Error, T::Error>>
+    where T: for<'a> VisitEntries<SnapshotLoader<'a>> + ValidateTable {
+        Loader::new(self).load_into(table)
+    }
+    fn find(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|(entry_name, _)| entry_name == name)
+    }
+}
+
+/// A [type visitor] which stores the current values of the entries of a config table in a snapshot, used by [`Snapshot::capture`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`Snapshot::capture`]: struct.Snapshot.html#method.capture " "
+pub struct SnapshotWriter<'a, T> {
+    table: &'a T,
+    snapshot: Snapshot,
+    error: Option<Error>,
+}
+impl<'a, T, E> EntryTypeVisitor<E> for SnapshotWriter<'a, T>
+where
+    T: Get<E>,
+    E: Entry,
+    E::Data: Serialize {
+    #[inline]
+    fn visit_type(&mut self) {
+        if self.error.is_none() {
+            self.error = self.snapshot.set::<E>(self.table.get_ref()).err();
+        }
+    }
+}
+impl<T> fmt::Debug for SnapshotWriter<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapshotWriter")
+            .field("snapshot", &self.snapshot)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The [loader] used by [`Snapshot::restore_into`], which writes the values from a snapshot into a config table.
+///
+/// [loader]: ../struct.Loader.html " "
+/// [`Snapshot::restore_into`]: struct.Snapshot.html#method.restore_into " "
+pub type SnapshotLoader<'a> = Loader<&'a Snapshot>;
+
+impl Lookup for &Snapshot {
+    type Error = Error;
+}
+/// Looks up the values with [`Snapshot::get`].
+///
+/// [`Snapshot::get`]: struct.Snapshot.html#method.get " "
+impl<E> LookupEntry<E> for &Snapshot
+where
+    E: Entry,
+    E::Data: DeserializeOwned {
+    #[inline(always)]
+    fn lookup(&mut self) -> Option<Result<E::Data, Error>> {
+        self.get::<E>().transpose()
+    }
+}
+
+/// An error which occurred while creating, encoding, decoding or restoring a snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A value or the snapshot itself could not be encoded or decoded.
+    Postcard {
+        /// The name of the entry whose value could not be encoded or decoded, or `None` if the error concerns the snapshot as a whole.
+        entry: Option<&'static str>,
+        /// The error produced by Postcard.
+        inner: postcard::Error,
+    },
+    /// The version of the snapshot is newer than the one it was migrated to.
+    UnsupportedVersion {
+        /// The version of the snapshot.
+        found: u32,
+        /// The version which the snapshot was migrated to.
+        expected: u32,
+    },
+}
+impl Error {
+    fn with_entry(self, entry_name: &'static str) -> Self {
+        match self {
+            Self::Postcard {entry: None, inner} => Self::Postcard {entry: Some(entry_name), inner},
+            other => other,
+        }
+    }
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Postcard {entry: Some(entry), inner} => write!(f, "invalid value for entry `{}`: {}", entry, inner),
+            Self::Postcard {entry: None, inner} => write!(f, "invalid snapshot: {}", inner),
+            Self::UnsupportedVersion {found, expected} => write!(
+                f,
+                "snapshot version {} is newer than the supported version {}",
+                found,
+                expected,
+            ),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Postcard {inner, ..} => Some(inner),
+            Self::UnsupportedVersion {..} => None,
+        }
+    }
+}
@@ -0,0 +1,470 @@
+//! Acquiring handles to several entries of a config table at once.
+//!
+//! Since [`Get::get_handle`] borrows the whole table mutably, only one handle obtained through it can exist at a time, even though the handles point to different fields which never alias. `#[derive(ConfigTable)]` solves this by implementing [`SplitHandles`], which splits the table into handles to all of its entries at once, and [`EntryIndex`], which tells where the handle to each entry is located among them. Based on those, [`GetExt::get_handles_to`] picks out the handles to the requested entries and returns them as a tuple:
+//! ```
+//! use snec::{ConfigTable, GetExt as _};
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     width: u32,
+//!     #[snec]
+//!     height: u32,
+//!     #[snec]
+//!     title: String,
+//! }
+//! let mut table = MyConfigTable {width: 800, height: 600, title: "Snec".to_string()};
+//! let (mut width, mut height) = table.get_handles_to::<(entries::Width, entries::Height)>();
+//! width.set(1024);
+//! height.set(768);
+//! assert_eq!((table.width, table.height), (1024, 768));
+//! ```
+//! Requesting the same entry twice is a compile-time error, since the handle to it can only be taken once:
+//! ```compile_fail
+//! # use snec::{ConfigTable, GetExt as _};
+//! # #[derive(ConfigTable)]
+//! # struct MyConfigTable {
+//! #     #[snec]
+//! #     width: u32,
+//! # }
+//! # let mut table = MyConfigTable {width: 800};
+//! let (width, also_width) = table.get_handles_to::<(entries::Width, entries::Width)>();
+//! ```
+//! To modify several entries together, for example when they depend on each other, [`GetExt::modify_together`] creates a [`MultiModificationScope`], which notifies the receiver of each entry exactly once when it's dropped, after all of the entries were modified. For tables with invariants spanning several entries, [`GetExt::modify_together_validated`] creates a [`ValidatedModificationScope`] instead, which checks the invariants when the changes are committed and undoes them if they don't hold.
+//!
+//! The derive macro only implements `SplitHandles` if none of the receiver expressions refer to `self`, no receivers are borrowed from fields with `receiver_field`, no entries invalidate other entries and no field is used for more than one entry, since in all of those cases the handles would not be disjoint. Note that splitting the table evaluates the receiver expressions of all entries, including the ones whose handles are not requested.
+//!
+//! The types in this module are the type-level machinery behind this, and rarely need to be named directly. Tuples of up to 8 entries are supported.
+//!
+//! [`Get::get_handle`]: ../trait.Get.html#tymethod.get_handle " "
+//! [`SplitHandles`]: trait.SplitHandles.html " "
+//! [`EntryIndex`]: trait.EntryIndex.html " "
+//! [`GetExt::get_handles_to`]: ../trait.GetExt.html#method.get_handles_to " "
+//! [`GetExt::modify_together`]: ../trait.GetExt.html#method.modify_together " "
+//! [`MultiModificationScope`]: struct.MultiModificationScope.html " "
+//! [`GetExt::modify_together_validated`]: ../trait.GetExt.html#method.modify_together_validated " "
+//! [`ValidatedModificationScope`]: struct.ValidatedModificationScope.html " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    marker::PhantomData,
+};
+use super::{Entry, Get, Handle, Receiver, ValidateTable, __private};
+
+/// Trait for config tables which can be split into handles to all of their entries at once. Implemented by `#[derive(ConfigTable)]`.
+///
+/// See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+pub trait SplitHandles<'a> {
+    /// A list of handles to all entries of the table, made of [`Cons`] and [`Nil`].
+    ///
+    /// [`Cons`]: struct.Cons.html " "
+    /// [`Nil`]: struct.Nil.html " "
+    type Handles;
+    /// Splits the table into handles to all of its entries.
+    fn split_handles(&'a mut self) -> Self::Handles;
+}
+
+/// Trait specifying the position of the handle to the entry `E` in the [list of handles] of a config table. Implemented by `#[derive(ConfigTable)]`.
+///
+/// [list of handles]: trait.SplitHandles.html#associatedtype.Handles " "
+pub trait EntryIndex<E: Entry> {
+    /// The position of the handle, made of [`Here`] and [`There`].
+    ///
+    /// [`Here`]: struct.Here.html " "
+    /// [`There`]: struct.There.html " "
+    type Index;
+}
+
+/// The end of a type-level list.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Nil;
+/// A non-empty type-level list, consisting of the first element and the rest of the list.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Cons<H, T>(pub H, pub T);
+/// A placeholder left in a list in place of an element which was [taken] out of it.
+///
+/// [taken]: trait.Take.html " "
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Vacant;
+
+/// A type-level index pointing to the first element of a list.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Here;
+/// A type-level index pointing to the element after the one pointed to by `I`.
+pub struct There<I>(PhantomData<I>);
+
+/// Trait for taking an element out of a type-level list at the index `I`, leaving a [`Vacant`] placeholder in its place so that the indices of the other elements don't change.
+///
+/// [`Vacant`]: struct.Vacant.html " "
+pub trait Take<I> {
+    /// The element which is taken.
+    type Taken;
+    /// The list with the taken element replaced with [`Vacant`].
+    ///
+    /// [`Vacant`]: struct.Vacant.html " "
+    type Rest;
+    /// Takes the element out of the list.
+    fn take(self) -> (Self::Taken, Self::Rest);
+}
+impl<H, T> Take<Here> for Cons<H, T> {
+    type Taken = H;
+    type Rest = Cons<Vacant, T>;
+    #[inline(always)]
+    fn take(self) -> (Self::Taken, Self::Rest) {
+        (self.0, Cons(Vacant, self.1))
+    }
+}
+impl<H, T, I> Take<There<I>> for Cons<H, T>
+where T: Take<I> {
+    type Taken = T::Taken;
+    type Rest = Cons<H, T::Rest>;
+    #[inline(always)]
+    fn take(self) -> (Self::Taken, Self::Rest) {
+        let (taken, rest) = self.1.take();
+        (taken, Cons(self.0, rest))
+    }
+}
+
+/// Trait for taking the handles to all entries in the type-level list `Es` out of the list of handles of the config table `T`.
+pub trait TakeHandles<'a, T: ?Sized, Es> {
+    /// The list of taken handles, in the order of the entries in `Es`.
+    type Taken;
+    /// Takes the handles out of the list.
+    fn take_handles(self) -> Self::Taken;
+}
+impl<'a, T: ?Sized, L> TakeHandles<'a, T, Nil> for L {
+    type Taken = Nil;
+    #[inline(always)]
+    fn take_handles(self) -> Self::Taken {
+        Nil
+    }
+}
+impl<'a, T, L, E, Es> TakeHandles<'a, T, Cons<E, Es>> for L
+where
+    T: Get<E> + EntryIndex<E> + ?Sized,
+    E: Entry + 'a,
+    L: Take<<T as EntryIndex<E>>::Index, Taken = Handle<'a, E, <T as Get<E>>::Receiver>>,
+    L::Rest: TakeHandles<'a, T, Es> {
+    type Taken = Cons<L::Taken, <L::Rest as TakeHandles<'a, T, Es>>::Taken>;
+    #[inline(always)]
+    fn take_handles(self) -> Self::Taken {
+        let (taken, rest) = self.take();
+        Cons(taken, rest.take_handles())
+    }
+}
+
+/// Trait for tuples of entries, which converts them into type-level lists.
+pub trait EntryTuple {
+    /// The entries as a type-level list.
+    type List;
+}
+/// Trait for type-level lists which can be converted into tuples.
+pub trait IntoTuple {
+    /// The resulting tuple.
+    type Tuple;
+    /// Converts the list into a tuple.
+    fn into_tuple(self) -> Self::Tuple;
+}
+
+/// Trait for tuples of [`Handle`]s, allowing them to be used in a [`MultiModificationScope`].
+///
+/// [`Handle`]: ../struct.Handle.html " "
+/// [`MultiModificationScope`]: struct.MultiModificationScope.html " "
+pub trait HandleTuple {
+    /// Notifies the receivers of all handles in the tuple about the current values.
+    fn notify_all(&mut self);
+}
+/// Trait for giving out mutable references to the pointees of a tuple of [`Handle`]s at once. Implemented for [`MultiModificationScope`], which notifies the receivers afterwards, and, with the `silent` feature, for the tuples themselves, in which case the receivers are not notified.
+///
+/// [`Handle`]: ../struct.Handle.html " "
+/// [`MultiModificationScope`]: struct.MultiModificationScope.html " "
+pub trait ValuesMut<'b> {
+    /// A tuple of mutable references to the values.
+    type Values;
+    /// Returns mutable references to the values.
+    fn values_mut(&'b mut self) -> Self::Values;
+}
+
+/// A drop guard for modifying the values behind several [`Handle`]s together, which notifies the receiver of each of them exactly once when dropped.
+///
+/// The receivers are only notified after all of the values were modified, which means that none of them can observe some of the values being updated while the others are not.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, GetExt as _};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec]
+///     width: u32,
+///     #[snec]
+///     height: u32,
+///     #[snec]
+///     aspect_ratio: f64,
+/// }
+/// let mut table = MyConfigTable {width: 800, height: 600, aspect_ratio: 4.0 / 3.0};
+/// {
+///     let mut scope = table.modify_together::<(entries::Width, entries::Height, entries::AspectRatio)>();
+///     let (width, height, aspect_ratio) = scope.values();
+///     *width = 1920;
+///     *height = 1080;
+///     *aspect_ratio = 16.0 / 9.0;
+///     // All three receivers are notified here.
+/// }
+/// assert_eq!(table.width, 1920);
+/// ```
+///
+/// [`Handle`]: ../struct.Handle.html " "
+pub struct MultiModificationScope<H: HandleTuple> {
+    handles: H,
+}
+impl<H: HandleTuple> MultiModificationScope<H> {
+    /// Creates a scope modifying the values behind the specified tuple of handles.
+    #[inline(always)]
+    pub fn new(handles: H) -> Self {
+        Self {handles}
+    }
+    /// Returns a tuple of mutable references to the values behind the handles.
+    #[inline(always)]
+    pub fn values<'b>(&'b mut self) -> <Self as ValuesMut<'b>>::Values
+    where Self: ValuesMut<'b> {
+        self.values_mut()
+    }
+}
+impl<H: HandleTuple> Drop for MultiModificationScope<H> {
+    fn drop(&mut self) {
+        self.handles.notify_all()
+    }
+}
+impl<H: HandleTuple + Debug> Debug for MultiModificationScope<H> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiModificationScope")
+            .field("handles", &self.handles)
+            .finish()
+    }
+}
+
+/// Trait for tuples of entries of the config table `T` which can be modified together in a [`ValidatedModificationScope`]. Implemented for tuples of up to 8 entries of [validated tables] whose values implement `Clone`.
+///
+/// [`ValidatedModificationScope`]: struct.ValidatedModificationScope.html " "
+/// [validated tables]: ../trait.ValidateTable.html " "
+pub trait ValidatedEntries<T: ?Sized> {
+    /// A tuple of the values of the entries.
+    type Values;
+    /// Clones the current values of the entries.
+    fn current_values(table: &T) -> Self::Values;
+    /// Writes the values to the table without notifying the receivers, returning the previous ones. Only callable by Snec itself, so that values which weren't validated can't be written this way.
+    #[doc(hidden)]
+    fn replace_all(table: &mut T, values: Self::Values, token: __private::Token) -> Self::Values;
+    /// Notifies the receiver of every entry about its current value.
+    fn notify_all(table: &mut T);
+    /// [Erases] the values.
+    ///
+    /// [Erases]: ../trait.Entry.html#method.erase " "
+    fn erase_all(values: &mut Self::Values);
+}
+
+/// A scope for modifying several entries of a [validated table] together, which only applies the changes when they're [committed] and the invariants of the table still hold.
+///
+/// The values are cloned when the scope is created and modified in the scope. Committing writes them to the table and validates it: if the table is valid, the receiver of each entry is notified exactly once, otherwise the previous values are restored without notifying any receivers and the error is returned. Dropping the scope without committing it discards the changes.
+///
+/// # Example
+/// ```
+/// use snec::{ConfigTable, GetExt as _};
+/// #[derive(ConfigTable)]
+/// #[snec(validate_table = check_range)]
+/// struct MyConfigTable {
+///     #[snec]
+///     min_threads: u32,
+///     #[snec]
+///     max_threads: u32,
+/// }
+/// fn check_range(table: &MyConfigTable) -> Result<(), &'static str> {
+///     if table.min_threads <= table.max_threads {
+///         Ok(())
+///     } else {
+///         Err("`min_threads` must not exceed `max_threads`")
+///     }
+/// }
+/// let mut table = MyConfigTable {min_threads: 1, max_threads: 4};
+/// let mut scope = table.modify_together_validated::<(entries::MinThreads, entries::MaxThreads)>();
+/// let (min_threads, max_threads) = scope.values();
+/// *min_threads = 16;
+/// *max_threads = 8;
+/// assert!(scope.commit().is_err());
+/// assert_eq!((table.min_threads, table.max_threads), (1, 4));
+/// ```
+///
+/// [validated table]: ../trait.ValidateTable.html " "
+/// [committed]: #method.commit " "
+pub struct ValidatedModificationScope<'a, T: ?Sized, Es: ValidatedEntries<T>> {
+    table: &'a mut T,
+    // Only taken by `commit`.
+    values: Option<Es::Values>,
+}
+impl<'a, T: ?Sized, Es: ValidatedEntries<T>> ValidatedModificationScope<'a, T, Es> {
+    /// Creates a scope modifying the values of the entries `Es` of the specified table.
+    #[inline]
+    pub fn new(table: &'a mut T) -> Self {
+        let values = Some(Es::current_values(table));
+        Self {table, values}
+    }
+    /// Returns a tuple of mutable references to the modified values.
+    #[inline(always)]
+    pub fn values<'b>(&'b mut self) -> <Self as ValuesMut<'b>>::Values
+    where Self: ValuesMut<'b> {
+        self.values_mut()
+    }
+    /// Writes the modified values to the table and validates it. If the table is valid, the receivers are notified, otherwise the previous values are restored and the error is returned.
+    pub fn commit(mut self) -> Result<(), <T as ValidateTable>::Error>
+    where T: ValidateTable {
+        let new_values = self.values.take().expect("the values are only taken when committing");
+        let mut old_values = Es::replace_all(self.table, new_values, __private::Token::new());
+        match self.table.validate_table() {
+            Ok(()) => {
+                Es::erase_all(&mut old_values);
+                Es::notify_all(self.table);
+                Ok(())
+            },
+            Err(error) => {
+                let mut rejected_values = Es::replace_all(self.table, old_values, __private::Token::new());
+                Es::erase_all(&mut rejected_values);
+                Err(error)
+            },
+        }
+    }
+}
+impl<'a, T: ?Sized, Es: ValidatedEntries<T>> Drop for ValidatedModificationScope<'a, T, Es> {
+    fn drop(&mut self) {
+        if let Some(values) = &mut self.values {
+            Es::erase_all(values);
+        }
+    }
+}
+impl<'a, T: ?Sized, Es: ValidatedEntries<T>> Debug for ValidatedModificationScope<'a, T, Es>
+where Es::Values: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValidatedModificationScope")
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+macro_rules! cons_list {
+    () => (Nil);
+    ($first:ident $(, $rest:ident)*) => (Cons<$first, cons_list!($($rest),*)>);
+}
+macro_rules! cons_pattern {
+    () => (Nil);
+    ($first:ident $(, $rest:ident)*) => (Cons($first, cons_pattern!($($rest),*)));
+}
+macro_rules! impl_tuples {
+    ($(($ty:ident, $receiver:ident, $index:tt)),+) => {
+        impl<$($ty: Entry),+> EntryTuple for ($($ty,)+) {
+            type List = cons_list!($($ty),+);
+        }
+        impl<$($ty),+> IntoTuple for cons_list!($($ty),+) {
+            type Tuple = ($($ty,)+);
+            #[inline(always)]
+            #[allow(non_snake_case)]
+            fn into_tuple(self) -> Self::Tuple {
+                let cons_pattern!($($ty),+) = self;
+                ($($ty,)+)
+            }
+        }
+        impl<'a, $($ty: Entry, $receiver: Receiver<$ty>),+> HandleTuple for ($(Handle<'a, $ty, $receiver>,)+) {
+            #[inline]
+            fn notify_all(&mut self) {
+                $(self.$index.notify();)+
+            }
+        }
+        #[cfg(feature = "silent")]
+        impl<'a, 'b, $($ty: Entry, $receiver: Receiver<$ty>),+> ValuesMut<'b> for ($(Handle<'a, $ty, $receiver>,)+)
+        where 'a: 'b, $($ty::Data: 'b,)+ {
+            type Values = ($(&'b mut $ty::Data,)+);
+            #[inline]
+            fn values_mut(&'b mut self) -> Self::Values {
+                ($(self.$index.target_mut(),)+)
+            }
+        }
+        impl<'a, 'b, $($ty: Entry, $receiver: Receiver<$ty>),+> ValuesMut<'b> for MultiModificationScope<($(Handle<'a, $ty, $receiver>,)+)>
+        where 'a: 'b, $($ty::Data: 'b,)+ {
+            type Values = ($(&'b mut $ty::Data,)+);
+            #[inline]
+            fn values_mut(&'b mut self) -> Self::Values {
+                ($(self.handles.$index.target_mut(),)+)
+            }
+        }
+        impl<T, $($ty),+> ValidatedEntries<T> for ($($ty,)+)
+        where
+            T: ?Sized + ValidateTable $(+ Get<$ty>)+,
+            $($ty: Entry, $ty::Data: Clone,)+ {
+            type Values = ($($ty::Data,)+);
+            #[inline]
+            fn current_values(table: &T) -> Self::Values {
+                ($(<T as Get<$ty>>::get_ref(table).clone(),)+)
+            }
+            #[inline]
+            fn replace_all(table: &mut T, values: Self::Values, _: __private::Token) -> Self::Values {
+                ($(
+                    core::mem::replace(<T as Get<$ty>>::get_mut(table, __private::Token::new()), values.$index),
+                )+)
+            }
+            #[inline]
+            fn notify_all(table: &mut T) {
+                $(<T as Get<$ty>>::get_handle(table).notify();)+
+            }
+            #[inline]
+            fn erase_all(values: &mut Self::Values) {
+                $($ty::erase(&mut values.$index);)+
+            }
+        }
+        impl<'a, 'b, T, $($ty),+> ValuesMut<'b> for ValidatedModificationScope<'a, T, ($($ty,)+)>
+        where
+            T: ?Sized + ValidateTable $(+ Get<$ty>)+,
+            $($ty: Entry, $ty::Data: Clone + 'b,)+ {
+            type Values = ($(&'b mut $ty::Data,)+);
+            #[inline]
+            fn values_mut(&'b mut self) -> Self::Values {
+                let values = self.values.as_mut().expect("the values are only taken when committing");
+                ($(&mut values.$index,)+)
+            }
+        }
+    };
+}
+impl_tuples!((A, RA, 0));
+impl_tuples!((A, RA, 0), (B, RB, 1));
+impl_tuples!((A, RA, 0), (B, RB, 1), (C, RC, 2));
+impl_tuples!((A, RA, 0), (B, RB, 1), (C, RC, 2), (D, RD, 3));
+impl_tuples!((A, RA, 0), (B, RB, 1), (C, RC, 2), (D, RD, 3), (F, RF, 4));
+impl_tuples!((A, RA, 0), (B, RB, 1), (C, RC, 2), (D, RD, 3), (F, RF, 4), (G, RG, 5));
+impl_tuples!((A, RA, 0), (B, RB, 1), (C, RC, 2), (D, RD, 3), (F, RF, 4), (G, RG, 5), (H, RH, 6));
+impl_tuples!((A, RA, 0), (B, RB, 1), (C, RC, 2), (D, RD, 3), (F, RF, 4), (G, RG, 5), (H, RH, 6), (I, RI, 7));
+
+/// Trait for config tables from which handles to the entries in the tuple `Es` can be obtained at the same time. Implemented for all tables which implement [`SplitHandles`] and [`EntryIndex`] for the requested entries.
+///
+/// This is the trait behind [`GetExt::get_handles_to`], which should be used instead.
+///
+/// [`SplitHandles`]: trait.SplitHandles.html " "
+/// [`EntryIndex`]: trait.EntryIndex.html " "
+/// [`GetExt::get_handles_to`]: ../trait.GetExt.html#method.get_handles_to " "
+pub trait GetHandles<'a, Es> {
+    /// A tuple of handles to the requested entries.
+    type Handles;
+    /// Returns handles to the requested entries.
+    fn get_handles(&'a mut self) -> Self::Handles;
+}
+impl<'a, T, Es> GetHandles<'a, Es> for T
+where
+    T: SplitHandles<'a> + ?Sized,
+    Es: EntryTuple,
+    T::Handles: TakeHandles<'a, T, Es::List>,
+    <T::Handles as TakeHandles<'a, T, Es::List>>::Taken: IntoTuple {
+    type Handles = <<T::Handles as TakeHandles<'a, T, Es::List>>::Taken as IntoTuple>::Tuple;
+    #[inline(always)]
+    fn get_handles(&'a mut self) -> Self::Handles {
+        self.split_handles().take_handles().into_tuple()
+    }
+}
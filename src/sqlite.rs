@@ -0,0 +1,297 @@
+//! Storing config tables in SQLite databases.
+//!
+//! A [`SqliteStore`] keeps the values of entries in a database table with one row per entry, keyed by the [name] of the entry, with the value serialized as JSON. [`load_into`] writes the stored values into a config table through handles, notifying the receivers of the entries whose values actually changed, and [`save`] stores the values of all entries in a single transaction.
+//!
+//! A shared reference to the store is also a [receiver] which upserts the row of an entry whenever its value changes, which makes every change be stored as soon as it's made. Since the receiver can't return errors from [`Handle::set`], failed writes are kept in the store and can be retrieved with [`take_error`]. `&SqliteStore` also implements [`TryReceiver`], which makes [`Handle::try_set`] return them directly.
+//!
+//! # Example
+//! ```
+//! use snec::{ConfigTable, GetExt as _, sqlite::SqliteStore};
+//! use std::rc::Rc;
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({Rc::clone(&self.store)}: Rc<SqliteStore>))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     which_year: i64,
+//!     #[snec]
+//!     who: String,
+//!     store: Rc<SqliteStore>,
+//! }
+//! let connection = rusqlite::Connection::open_in_memory()?;
+//! let store = Rc::new(SqliteStore::new(connection, "config")?);
+//! let mut table = MyConfigTable {which_year: 1987, who: "Jeremy".to_string(), store};
+//! table.get_handle_to::<entries::WhichYear>().set(1983);
+//!
+//! let mut other_table = MyConfigTable {
+//!     which_year: 1987,
+//!     who: "Jeremy".to_string(),
+//!     store: Rc::clone(&table.store),
+//! };
+//! assert_eq!(table.store.load_into(&mut other_table)?, 1);
+//! assert_eq!(other_table.which_year, 1983);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`SqliteStore`]: struct.SqliteStore.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`load_into`]: struct.SqliteStore.html#method.load_into " "
+//! [`save`]: struct.SqliteStore.html#method.save " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [`Handle::set`]: ../struct.Handle.html#method.set " "
+//! [`take_error`]: struct.SqliteStore.html#method.take_error " "
+//! [`TryReceiver`]: ../trait.TryReceiver.html " "
+//! [`Handle::try_set`]: ../struct.Handle.html#method.try_set " "
+
+use core::{
+    cell::RefCell,
+    fmt::{self, Formatter, Display, Debug},
+};
+use std::{
+    collections::HashMap,
+    error,
+};
+use rusqlite::{Connection, OptionalExtension as _};
+use serde::{Serialize, de::DeserializeOwned};
+use super::{
+    Entry,
+    Get,
+    Receiver,
+    TryReceiver,
+    Loader,
+    LoadError,
+    Lookup,
+    LookupEntry,
+    VisitEntries,
+    ValidateTable,
+    EntryTypeVisitor,
+    VisitEntryTypes,
+};
+
+/// A database table storing the values of entries, which is also a [receiver] storing every change. See the [module-level documentation] for more.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [module-level documentation]: index.html " "
+pub struct SqliteStore {
+    connection: Connection,
+    upsert: String,
+    select_all: String,
+    error: RefCell<Option<Error>>,
+}
+impl SqliteStore {
+    /// Creates a store which keeps the values in the database table with the specified name, creating the table if it doesn't exist yet.
+    pub fn new(connection: Connection, table_name: &str) -> Result<Self, Error> {
+        let table_name = format!("\"{}\"", table_name.replace('"', "\"\""));
+        connection.execute(
+            &format!(
+                "CREATE TABLE IF NOT EXISTS {} (name TEXT PRIMARY KEY NOT NULL, value TEXT NOT NULL)",
+                table_name,
+            ),
+            [],
+        )?;
+        Ok(
+            Self {
+                connection,
+                upsert: format!(
+                    "INSERT INTO {} (name, value) VALUES (?1, ?2) \
+ON CONFLICT (name) DO UPDATE SET value = excluded.value",
+                    table_name,
+                ),
+                select_all: format!("SELECT name, value FROM {}", table_name),
+                error: RefCell::new(None),
+            }
+        )
+    }
+    /// Returns the database connection, which can be used to run other queries.
+    #[inline(always)]
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+    /// Returns the stored value of the entry, or `None` if it hasn't been stored yet.
+    pub fn get<E>(&self) -> Result<Option<E::Data>, Error>
+    where
+        E: Entry,
+        E::Data: DeserializeOwned {
+        let sql = format!("{} WHERE name = ?1", self.select_all);
+        let value = self.connection
+            .query_row(&sql, [E::NAME], |row| row.get::<_, String>(1))
+            .optional()?;
+        value.map(|value| {
+            serde_json::from_str(&value).map_err(|error| Error::Value {entry: E::NAME, inner: error})
+        }).transpose()
+    }
+    /// Stores the value of the entry, replacing the previously stored one.
+    pub fn set<E>(&self, value: &E::Data) -> Result<(), Error>
+    where
+        E: Entry,
+        E::Data: Serialize {
+        let value = serde_json::to_string(value)
+            .map_err(|error| Error::Value {entry: E::NAME, inner: error})?;
+        self.connection.execute(&self.upsert, (E::NAME, value))?;
+        Ok(())
+    }
+    /// Writes the stored values into the corresponding entries of the config table, notifying the receivers of the entries which changed, and returns the number of entries which changed.
+    ///
+    /// All rows are read up front, so the table is never left waiting on the database halfway through. Rows which don't correspond to any entry are ignored, and so are entries which haven't been stored. A row whose JSON doesn't fit its entry fails the load with [`Error::Value`]. The table is left unchanged if an error is returned, including one produced by the [table validator].
+    ///
+    /// [`Error::Value`]: enum.Error.html#variant.Value " "
+    /// [table validator]: ../trait.ValidateTable.html " "
+    #[inline]
+    pub fn load_into<T>(&self, table: &mut T) -> Result<usize, LoadError<Error, T::Error>>
+    where T: VisitEntries<SqliteLoader> + ValidateTable {
+        self.loader().map_err(LoadError::Source)?.load_into(table)
+    }
+    /// Reads all stored values to create a loader for them.
+    pub fn loader(&self) -> Result<SqliteLoader, Error> {
+        let mut statement = self.connection.prepare(&self.select_all)?;
+        let rows = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        Ok(
+            Loader::new(SqliteRows {rows})
+        )
+    }
+    /// Stores the values of all entries of the config table in a single transaction.
+    pub fn save<T>(&self, table: &T) -> Result<(), Error>
+    where T: for<'a> VisitEntryTypes<SqliteSaver<'a, T>> {
+        let transaction = self.connection.unchecked_transaction()?;
+        let mut saver = SqliteSaver {store: self, table, result: Ok(())};
+        T::visit_entry_types(&mut saver);
+        saver.result?;
+        transaction.commit()?;
+        Ok(())
+    }
+    /// Returns the error which occurred while storing a value received as a receiver, if any, and clears it. Only the last error is kept.
+    #[inline]
+    pub fn take_error(&self) -> Option<Error> {
+        self.error.borrow_mut().take()
+    }
+}
+impl<E> Receiver<E> for &SqliteStore
+where
+    E: Entry,
+    E::Data: Serialize {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        if let Err(error) = self.set::<E>(new_value) {
+            *self.error.borrow_mut() = Some(error);
+        }
+    }
+}
+impl<E> TryReceiver<E> for &SqliteStore
+where
+    E: Entry,
+    E::Data: Serialize {
+    type Error = Error;
+    #[inline(always)]
+    fn try_receive(&mut self, new_value: &E::Data) -> Result<(), Self::Error> {
+        self.set::<E>(new_value)
+    }
+}
+impl Debug for SqliteStore {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteStore")
+            .field("connection", &self.connection)
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The [loader] used by [`SqliteStore::load_into`], which writes the values from [`SqliteRows`] into a config table.
+///
+/// [loader]: ../struct.Loader.html " "
+/// [`SqliteStore::load_into`]: struct.SqliteStore.html#method.load_into " "
+/// [`SqliteRows`]: struct.SqliteRows.html " "
+pub type SqliteLoader = Loader<SqliteRows>;
+
+/// The rows read from a database table by [`SqliteStore::loader`], which [look up] the values of entries by deserializing the JSON stored in the rows named after them.
+///
+/// [`SqliteStore::loader`]: struct.SqliteStore.html#method.loader " "
+/// [look up]: ../trait.LookupEntry.html " "
+#[derive(Clone, Debug)]
+pub struct SqliteRows {
+    rows: HashMap<String, String>,
+}
+impl Lookup for SqliteRows {
+    type Error = Error;
+}
+impl<E> LookupEntry<E> for SqliteRows
+where
+    E: Entry,
+    E::Data: DeserializeOwned {
+    #[inline]
+    fn lookup(&mut self) -> Option<Result<E::Data, Error>> {
+        let value = self.rows.get(E::NAME)?;
+        Some(
+            serde_json::from_str(value).map_err(|error| Error::Value {entry: E::NAME, inner: error})
+        )
+    }
+}
+
+/// A [type visitor] which stores the current values of the entries of a config table, used by [`SqliteStore::save`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`SqliteStore::save`]: struct.SqliteStore.html#method.save " "
+pub struct SqliteSaver<'a, T> {
+    store: &'a SqliteStore,
+    table: &'a T,
+    result: Result<(), Error>,
+}
+impl<'a, T, E> EntryTypeVisitor<E> for SqliteSaver<'a, T>
+where
+    T: Get<E>,
+    E: Entry,
+    E::Data: Serialize {
+    #[inline]
+    fn visit_type(&mut self) {
+        if self.result.is_ok() {
+            self.result = self.store.set::<E>(self.table.get_ref());
+        }
+    }
+}
+impl<T> Debug for SqliteSaver<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqliteSaver")
+            .field("result", &self.result)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An error which occurred while loading or storing entries.
+#[derive(Debug)]
+pub enum Error {
+    /// The database query failed.
+    Sqlite(rusqlite::Error),
+    /// The value of an entry could not be serialized, or the stored value could not be converted to the type of the entry.
+    Value {
+        /// The name of the entry.
+        entry: &'static str,
+        /// The error produced by the JSON serializer or deserializer.
+        inner: serde_json::Error,
+    },
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sqlite(error) => write!(f, "database query failed: {}", error),
+            Self::Value {entry, inner} => write!(f, "invalid value for entry `{}`: {}", entry, inner),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Sqlite(error) => Some(error),
+            Self::Value {inner, ..} => Some(inner),
+        }
+    }
+}
+impl From<rusqlite::Error> for Error {
+    #[inline(always)]
+    fn from(op: rusqlite::Error) -> Self {
+        Self::Sqlite(op)
+    }
+}
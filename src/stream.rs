@@ -0,0 +1,254 @@
+//! Consuming changes of entries as asynchronous streams.
+//!
+//! [`ChangeStream`] implements the `Stream` trait of the [`futures`] ecosystem, yielding clones of the values of an entry as it changes, so that changes can be processed with the usual stream combinators, such as `map`, `filter` or debouncing. The values are delivered by [`StreamReceiver`]s created by the stream, which are attached to the entry as its receiver, and are buffered until the stream is polled. The [`BufferPolicy`] decides what happens when the consumer falls behind: the buffer can grow without bounds, keep only a limited number of the oldest or newest values, or conflate the values, keeping only the latest one. Changes are never blocked by a slow consumer.
+//!
+//! The stream never ends on its own, since receivers are usually created anew for every handle and thus can't signal that no more changes will be made. The stream is meant for a single consumer, and only the task which last polled it is woken up.
+//!
+//! Neither the stream nor the receivers depend on a specific runtime, so the stream can be consumed by a task of any executor, such as smol, async-std or Tokio.
+//!
+//! # Examples
+//! ```
+//! use snec::{ConfigTable, GetExt as _, stream::{BufferPolicy, ChangeStream, StreamReceiver}};
+//! use futures_util::StreamExt as _;
+//! #[derive(ConfigTable)]
+//! struct Config {
+//!     #[snec(entry, receiver({self.zoom_changes.clone()}: StreamReceiver<entries::Zoom>))]
+//!     zoom: f32,
+//!     zoom_changes: StreamReceiver<entries::Zoom>,
+//! }
+//! let stream = ChangeStream::<entries::Zoom>::new(BufferPolicy::Unbounded);
+//! let mut config = Config {zoom: 1.0, zoom_changes: stream.receiver()};
+//! config.get_handle_to::<entries::Zoom>().set(1.5);
+//! config.get_handle_to::<entries::Zoom>().set(2.0);
+//! let percentages = stream.map(|zoom| (zoom * 100.0) as u32).take(2).collect::<Vec<_>>();
+//! assert_eq!(futures_executor::block_on(percentages), [150, 200]);
+//! ```
+//! Consuming the changes in a task spawned on async-std:
+//! ```
+//! # use snec::{Entry, Handle, stream::{BufferPolicy, ChangeStream}};
+//! use futures_util::StreamExt as _;
+//! enum Volume {}
+//! impl Entry for Volume {
+//!     type Data = u8;
+//!     const NAME: &'static str = "volume";
+//! }
+//! let mut stream = ChangeStream::<Volume>::new(BufferPolicy::DropOldest(16));
+//! let receiver = stream.receiver();
+//! async_std::task::block_on(async {
+//!     let mixer = async_std::task::spawn(async move {
+//!         let mut applied = Vec::new();
+//!         while let Some(volume) = stream.next().await {
+//!             applied.push(volume);
+//!             if volume == 0 {
+//!                 break;
+//!             }
+//!         }
+//!         applied
+//!     });
+//!     let mut volume = 50;
+//!     let mut handle = Handle::<Volume, _>::new(&mut volume, receiver);
+//!     handle.set(80);
+//!     handle.set(0);
+//!     assert_eq!(mixer.await, [80, 0]);
+//! });
+//! ```
+//!
+//! [`ChangeStream`]: struct.ChangeStream.html " "
+//! [`futures`]: https://docs.rs/futures " "
+//! [`StreamReceiver`]: struct.StreamReceiver.html " "
+//! [`BufferPolicy`]: enum.BufferPolicy.html " "
+
+use core::{
+    fmt::{self, Formatter, Debug},
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use futures_core::Stream;
+use super::{Entry, Receiver, lock_ignoring_poison};
+
+/// What a [`ChangeStream`] does with new values when the consumer falls behind.
+///
+/// [`ChangeStream`]: struct.ChangeStream.html " "
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BufferPolicy {
+    /// Buffers all values, so that the consumer sees every change, at the cost of unbounded memory usage if it never catches up.
+    Unbounded,
+    /// Buffers at most the specified number of values, dropping the oldest buffered value to make room for a new one, so that the consumer always sees the most recent changes.
+    DropOldest(usize),
+    /// Buffers at most the specified number of values, dropping new values while the buffer is full, so that the consumer sees the changes in the order they were made, without gaps until the first dropped one.
+    DropNewest(usize),
+    /// Buffers at most one value, which is replaced by every new value, so that the consumer never lags behind by more than one value and always sees the latest one. Meant for consumers which only care about the current configuration, such as ones reapplying all of it whenever it changes.
+    Conflate,
+}
+impl Default for BufferPolicy {
+    /// Returns `Unbounded`.
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Unbounded
+    }
+}
+
+/// A stream of the values of an entry as it changes. See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+pub struct ChangeStream<E: Entry> {
+    shared: Arc<Mutex<Shared<E::Data>>>,
+}
+struct Shared<D> {
+    buffer: VecDeque<D>,
+    policy: BufferPolicy,
+    dropped: usize,
+    waker: Option<Waker>,
+}
+impl<E: Entry> ChangeStream<E> {
+    /// Creates a stream with an empty buffer which handles overflow according to the specified policy.
+    #[inline]
+    pub fn new(policy: BufferPolicy) -> Self {
+        let shared = Shared {buffer: VecDeque::new(), policy, dropped: 0, waker: None};
+        Self {shared: Arc::new(Mutex::new(shared))}
+    }
+    /// Creates a stream which only keeps the latest value, the same as `new(BufferPolicy::Conflate)`.
+    ///
+    /// # Example
+    /// ```
+    /// # use snec::{Entry, Handle, stream::ChangeStream};
+    /// use futures_util::StreamExt as _;
+    /// enum Gain {}
+    /// impl Entry for Gain {
+    ///     type Data = f32;
+    ///     const NAME: &'static str = "gain";
+    /// }
+    /// let mut stream = ChangeStream::<Gain>::conflated();
+    /// let mut gain = 0.0;
+    /// let mut handle = Handle::<Gain, _>::new(&mut gain, stream.receiver());
+    /// for step in 1..=10 {
+    ///     handle.set(step as f32 / 10.0);
+    /// }
+    /// // The consumer was busy, so it only gets the last value.
+    /// assert_eq!(stream.pending(), 1);
+    /// assert_eq!(futures_executor::block_on(stream.next()), Some(1.0));
+    /// assert_eq!(stream.dropped(), 9);
+    /// ```
+    #[inline]
+    pub fn conflated() -> Self {
+        Self::new(BufferPolicy::Conflate)
+    }
+    /// Creates a receiver which delivers the values of the entry to the stream.
+    #[inline]
+    pub fn receiver(&self) -> StreamReceiver<E> {
+        StreamReceiver {shared: Arc::clone(&self.shared)}
+    }
+    /// Returns the number of values waiting to be yielded.
+    #[inline]
+    pub fn pending(&self) -> usize {
+        self.lock().buffer.len()
+    }
+    /// Returns the number of values dropped so far because the buffer was full, or replaced by newer values in a conflated stream.
+    #[inline]
+    pub fn dropped(&self) -> usize {
+        self.lock().dropped
+    }
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Shared<E::Data>> {
+        lock_ignoring_poison(&self.shared)
+    }
+}
+impl<E: Entry> Stream for ChangeStream<E> {
+    type Item = E::Data;
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.lock();
+        match shared.buffer.pop_front() {
+            Some(value) => Poll::Ready(Some(value)),
+            None => {
+                match &mut shared.waker {
+                    Some(waker) if waker.will_wake(cx.waker()) => {},
+                    waker => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            },
+        }
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.pending(), None)
+    }
+}
+impl<E: Entry> Debug for ChangeStream<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let shared = self.lock();
+        f.debug_struct("ChangeStream")
+            .field("pending", &shared.buffer.len())
+            .field("policy", &shared.policy)
+            .field("dropped", &shared.dropped)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [receiver] which delivers the values of an entry to a [`ChangeStream`], created by [`ChangeStream::receiver`]. Clones deliver to the same stream.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [`ChangeStream`]: struct.ChangeStream.html " "
+/// [`ChangeStream::receiver`]: struct.ChangeStream.html#method.receiver " "
+pub struct StreamReceiver<E: Entry> {
+    shared: Arc<Mutex<Shared<E::Data>>>,
+}
+impl<E> Receiver<E> for StreamReceiver<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    fn receive(&mut self, new_value: &E::Data) {
+        let waker = {
+            let mut shared = lock_ignoring_poison(&self.shared);
+            let shared = &mut *shared;
+            match shared.policy {
+                BufferPolicy::Unbounded => {},
+                BufferPolicy::Conflate => {
+                    if let Some(value) = shared.buffer.back_mut() {
+                        // The consumer was already woken up when the replaced value was buffered.
+                        value.clone_from(new_value);
+                        shared.dropped += 1;
+                        return;
+                    }
+                },
+                BufferPolicy::DropOldest(capacity) => {
+                    while shared.buffer.len() >= capacity && shared.buffer.pop_front().is_some() {
+                        shared.dropped += 1;
+                    }
+                    if capacity == 0 {
+                        shared.dropped += 1;
+                        return;
+                    }
+                },
+                BufferPolicy::DropNewest(capacity) => {
+                    if shared.buffer.len() >= capacity {
+                        shared.dropped += 1;
+                        return;
+                    }
+                },
+            }
+            shared.buffer.push_back(new_value.clone());
+            shared.waker.take()
+        };
+        // Woken with the buffer unlocked, so that executors polling inline don't deadlock.
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+impl<E: Entry> Clone for StreamReceiver<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {shared: Arc::clone(&self.shared)}
+    }
+}
+impl<E: Entry> Debug for StreamReceiver<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamReceiver").finish_non_exhaustive()
+    }
+}
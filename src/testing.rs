@@ -0,0 +1,448 @@
+//! Receivers for testing code which uses config tables.
+//!
+//! The receivers in this module share their state between clones, which allows one clone to be used as the receiver of a config table (usually through a receiver expression cloning it from a field of the table or from a shared location) while another one is kept by the test to inspect what happened.
+//!
+//! The values of [secret] entries are shown as [`Redacted`] in the messages of failed assertions and in debug output, so that they don't end up in test logs.
+//!
+//! [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [`Redacted`]: ../struct.Redacted.html " "
+
+use core::{fmt::{self, Formatter, Debug}, mem};
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+};
+use super::{Entry, Receiver, Redacted, lock_ignoring_poison};
+
+/// A [receiver] which records clones of all values it's notified about, in order, and provides assertions about them.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, testing::RecordingReceiver};
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = u8;
+///     const NAME: &'static str = "volume";
+/// }
+/// let recorder = RecordingReceiver::<Volume>::new();
+/// let mut volume = 100;
+/// let mut handle = Handle::<Volume, _>::new(&mut volume, recorder.clone());
+/// handle.set(50);
+/// handle.set(75);
+/// recorder.assert_received([50, 75]);
+/// ```
+///
+/// [receiver]: ../trait.Receiver.html " "
+pub struct RecordingReceiver<E: Entry> {
+    values: Arc<Mutex<Vec<E::Data>>>,
+}
+impl<E: Entry> RecordingReceiver<E> {
+    /// Creates a receiver which hasn't recorded anything yet.
+    #[inline]
+    pub fn new() -> Self {
+        Self {values: Arc::new(Mutex::new(Vec::new()))}
+    }
+    /// Returns clones of the recorded values, in the order in which they were received.
+    #[inline]
+    pub fn received(&self) -> Vec<E::Data>
+    where E::Data: Clone {
+        self.lock().clone()
+    }
+    /// Returns the number of recorded notifications.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+    /// Returns `true` if no notifications were recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+    /// Forgets all recorded notifications.
+    #[inline]
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+    /// Asserts that exactly the specified values were received, in the specified order.
+    ///
+    /// # Panics
+    /// If the recorded values differ from the expected ones.
+    #[track_caller]
+    pub fn assert_received<I>(&self, expected: I)
+    where
+        I: IntoIterator<Item = E::Data>,
+        E::Data: PartialEq + Debug {
+        let expected = expected.into_iter().collect::<Vec<_>>();
+        let received = self.lock();
+        assert!(
+            *received == expected,
+            "entry `{}` received {:?}, expected {:?}",
+            E::NAME, MaybeRedactedList::<E>(&received), MaybeRedactedList::<E>(&expected),
+        );
+    }
+    /// Asserts that no notifications were recorded.
+    ///
+    /// # Panics
+    /// If any notifications were recorded.
+    #[track_caller]
+    pub fn assert_nothing_received(&self)
+    where E::Data: Debug {
+        let received = self.lock();
+        assert!(
+            received.is_empty(),
+            "entry `{}` was expected not to change, but received {:?}", E::NAME, MaybeRedactedList::<E>(&received),
+        );
+    }
+    fn lock(&self) -> MutexGuard<'_, Vec<E::Data>> {
+        lock_ignoring_poison(&self.values)
+    }
+}
+impl<E> Receiver<E> for RecordingReceiver<E>
+where
+    E: Entry,
+    E::Data: Clone {
+    #[inline]
+    fn receive(&mut self, new_value: &E::Data) {
+        self.lock().push(new_value.clone());
+    }
+}
+impl<E: Entry> Clone for RecordingReceiver<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {values: Arc::clone(&self.values)}
+    }
+}
+impl<E: Entry> Default for RecordingReceiver<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E> Debug for RecordingReceiver<E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecordingReceiver")
+            .field("entry", &E::NAME)
+            .field("values", &MaybeRedactedList::<E>(&self.lock()))
+            .finish()
+    }
+}
+
+/// A mock [receiver] which checks the notifications it receives against declared [expectations], panicking if they are not met.
+///
+/// Every notification is matched against the expectations in the order in which they were declared, and counts towards the first one which matches the value and hasn't been saturated yet. A notification which doesn't match any expectation, or whose first match is an expectation of [no notifications], causes a panic right away. The numbers of notifications are checked by [`verify`], or automatically when the last clone of the receiver is dropped.
+///
+/// # Example
+/// ```
+/// # use snec::{Entry, Handle, testing::{MockReceiver, Expectation}};
+/// enum Volume {}
+/// impl Entry for Volume {
+///     type Data = u8;
+///     const NAME: &'static str = "volume";
+/// }
+/// let mock = MockReceiver::<Volume>::new();
+/// mock.expect(Expectation::new().matching(|value| *value <= 100).times(2));
+/// let mut volume = 100;
+/// let mut handle = Handle::<Volume, _>::new(&mut volume, mock.clone());
+/// handle.set(50);
+/// handle.set(75);
+/// mock.verify();
+/// ```
+/// Unmet expectations are reported when the last clone is dropped, even without calling `verify`:
+/// ```should_panic
+/// # use snec::{Entry, testing::{MockReceiver, Expectation}};
+/// # enum Volume {}
+/// # impl Entry for Volume {
+/// #     type Data = u8;
+/// #     const NAME: &'static str = "volume";
+/// # }
+/// let mock = MockReceiver::<Volume>::new();
+/// mock.expect(Expectation::new().with_value(0).times(1));
+/// // Panics: the volume was never set to 0.
+/// ```
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [expectations]: struct.Expectation.html " "
+/// [`verify`]: #method.verify " "
+/// [no notifications]: struct.Expectation.html#method.never " "
+pub struct MockReceiver<E: Entry> {
+    expectations: Arc<Mutex<Expectations<E>>>,
+}
+impl<E: Entry> MockReceiver<E> {
+    /// Creates a mock receiver without any expectations, which panics on any notification.
+    #[inline]
+    pub fn new() -> Self {
+        Self {expectations: Arc::new(Mutex::new(Expectations(Vec::new())))}
+    }
+    /// Adds an expectation.
+    #[inline]
+    pub fn expect(&self, expectation: Expectation<E>) -> &Self {
+        self.lock().0.push(expectation);
+        self
+    }
+    /// Checks that the numbers of notifications matched by all expectations are within the expected bounds.
+    ///
+    /// # Panics
+    /// If any expectation is not met.
+    #[track_caller]
+    pub fn verify(&self) {
+        self.lock().verify();
+    }
+    /// Checks that all expectations are met, like [`verify`], and then removes them along with the notifications they matched, so that new expectations can be declared for the next phase of the test.
+    ///
+    /// # Panics
+    /// If any expectation is not met, listing the expected and received numbers of notifications for every unmet one. The expectations are removed even in that case.
+    ///
+    /// # Example
+    /// ```should_panic
+    /// # use snec::{Entry, Handle, testing::{MockReceiver, Expectation}};
+    /// # enum Volume {}
+    /// # impl Entry for Volume {
+    /// #     type Data = u8;
+    /// #     const NAME: &'static str = "volume";
+    /// # }
+    /// let mock = MockReceiver::<Volume>::new();
+    /// mock.expect(Expectation::new().with_value(50).times(2));
+    /// let mut volume = 100;
+    /// let mut handle = Handle::<Volume, _>::new(&mut volume, mock.clone());
+    /// handle.set(50);
+    /// // Panics: only one of the two expected notifications was received.
+    /// mock.checkpoint();
+    /// ```
+    ///
+    /// [`verify`]: #method.verify " "
+    #[track_caller]
+    pub fn checkpoint(&self) {
+        let expectations = mem::take(&mut self.lock().0);
+        if let Some(report) = Expectations::<E>::report(&expectations) {
+            panic!("unmet expectations for entry `{}` at checkpoint:\n{}", E::NAME, report);
+        }
+    }
+    fn lock(&self) -> MutexGuard<'_, Expectations<E>> {
+        lock_ignoring_poison(&self.expectations)
+    }
+}
+impl<E: Entry> Receiver<E> for MockReceiver<E>
+where E::Data: Debug {
+    #[track_caller]
+    fn receive(&mut self, new_value: &E::Data) {
+        let mut expectations = self.lock();
+        // Expectations of no notifications are never saturated, so that they catch the values they match even if a later expectation would also match them.
+        let matching = expectations.0.iter_mut().find(|expectation| {
+            (expectation.max_calls == 0 || expectation.calls < expectation.max_calls)
+                && expectation.matches(new_value)
+        });
+        match matching {
+            Some(expectation) if expectation.max_calls == 0 => {
+                expectation.calls += 1;
+                drop(expectations);
+                panic!("forbidden notification for entry `{}` with value {:?}", E::NAME, MaybeRedacted::<E>(new_value));
+            },
+            Some(expectation) => expectation.calls += 1,
+            None => {
+                drop(expectations);
+                panic!("unexpected notification for entry `{}` with value {:?}", E::NAME, MaybeRedacted::<E>(new_value));
+            },
+        }
+    }
+}
+impl<E: Entry> Clone for MockReceiver<E> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        Self {expectations: Arc::clone(&self.expectations)}
+    }
+}
+impl<E: Entry> Default for MockReceiver<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E: Entry> Debug for MockReceiver<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockReceiver")
+            .field("entry", &E::NAME)
+            .field("expectations", &self.lock().0)
+            .finish()
+    }
+}
+
+/// The shared state of a `MockReceiver`, verified when the last clone is dropped.
+struct Expectations<E: Entry>(Vec<Expectation<E>>);
+impl<E: Entry> Expectations<E> {
+    #[track_caller]
+    fn verify(&self) {
+        if let Some(report) = Self::report(&self.0) {
+            panic!("unmet expectations for entry `{}`:\n{}", E::NAME, report);
+        }
+    }
+    /// Lists the expected and received numbers of notifications of every unmet expectation, or returns `None` if all of them are met.
+    fn report(expectations: &[Expectation<E>]) -> Option<String> {
+        let mut report = String::new();
+        for expectation in expectations.iter().filter(|expectation| !expectation.is_satisfied()) {
+            report.push_str(&format!(
+                "- expected {} notification(s) matching {}\n+ received {}\n",
+                expectation.bounds(), expectation.description(), expectation.calls,
+            ));
+        }
+        if report.is_empty() {None} else {Some(report)}
+    }
+}
+impl<E: Entry> Drop for Expectations<E> {
+    fn drop(&mut self) {
+        // Panicking while unwinding would abort the test instead of reporting the original failure.
+        if !thread::panicking() {
+            self.verify();
+        }
+    }
+}
+
+type Predicate<T> = Box<dyn Fn(&T) -> bool + Send>;
+
+/// Formats a value of the entry, or [`Redacted`] in its place if the entry is [secret].
+///
+/// [`Redacted`]: ../struct.Redacted.html " "
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+struct MaybeRedacted<'a, E: Entry>(&'a E::Data);
+impl<E> Debug for MaybeRedacted<'_, E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if E::SECRET {
+            Debug::fmt(&Redacted, f)
+        } else {
+            Debug::fmt(self.0, f)
+        }
+    }
+}
+/// Formats a list of values of the entry like [`MaybeRedacted`] formats one value.
+///
+/// [`MaybeRedacted`]: struct.MaybeRedacted.html " "
+struct MaybeRedactedList<'a, E: Entry>(&'a [E::Data]);
+impl<E> Debug for MaybeRedactedList<'_, E>
+where
+    E: Entry,
+    E::Data: Debug {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(MaybeRedacted::<E>))
+            .finish()
+    }
+}
+
+/// An expected notification for a [`MockReceiver`].
+///
+/// By default, matches any value any number of times.
+///
+/// [`MockReceiver`]: struct.MockReceiver.html " "
+pub struct Expectation<E: Entry> {
+    predicate: Option<Predicate<E::Data>>,
+    min_calls: usize,
+    max_calls: usize,
+    calls: usize,
+}
+impl<E: Entry> Expectation<E> {
+    /// Creates an expectation which matches any value any number of times.
+    #[inline]
+    pub fn new() -> Self {
+        Self {predicate: None, min_calls: 0, max_calls: usize::MAX, calls: 0}
+    }
+    /// Only matches the values for which the predicate returns `true`.
+    #[inline]
+    pub fn matching<F>(mut self, predicate: F) -> Self
+    where F: Fn(&E::Data) -> bool + Send + 'static {
+        self.predicate = Some(Box::new(predicate));
+        self
+    }
+    /// Only matches values equal to the specified one.
+    #[inline]
+    pub fn with_value(self, value: E::Data) -> Self
+    where E::Data: PartialEq + Send + 'static {
+        self.matching(move |new_value| *new_value == value)
+    }
+    /// Expects exactly the specified number of matching notifications.
+    #[inline]
+    pub fn times(mut self, times: usize) -> Self {
+        self.min_calls = times;
+        self.max_calls = times;
+        self
+    }
+    /// Expects at least the specified number of matching notifications.
+    #[inline]
+    pub fn at_least(mut self, times: usize) -> Self {
+        self.min_calls = times;
+        self
+    }
+    /// Expects at most the specified number of matching notifications.
+    #[inline]
+    pub fn at_most(mut self, times: usize) -> Self {
+        self.max_calls = times;
+        self
+    }
+    /// Expects no matching notifications at all, which makes any matching notification panic right away, even if an expectation declared after this one matches it as well.
+    ///
+    /// # Example
+    /// ```should_panic
+    /// # use snec::{Entry, Handle, testing::{MockReceiver, Expectation}};
+    /// # enum Volume {}
+    /// # impl Entry for Volume {
+    /// #     type Data = u8;
+    /// #     const NAME: &'static str = "volume";
+    /// # }
+    /// let mock = MockReceiver::<Volume>::new();
+    /// mock.expect(Expectation::new().matching(|value| *value > 100).never());
+    /// mock.expect(Expectation::new());
+    /// let mut volume = 100;
+    /// let mut handle = Handle::<Volume, _>::new(&mut volume, mock.clone());
+    /// handle.set(50);
+    /// // Panics: values above 100 are forbidden, even though the catch-all expectation matches them.
+    /// handle.set(150);
+    /// ```
+    #[inline(always)]
+    pub fn never(self) -> Self {
+        self.times(0)
+    }
+    fn matches(&self, value: &E::Data) -> bool {
+        match &self.predicate {
+            Some(predicate) => predicate(value),
+            None => true,
+        }
+    }
+    fn is_satisfied(&self) -> bool {
+        (self.min_calls..=self.max_calls).contains(&self.calls)
+    }
+    fn description(&self) -> &'static str {
+        if self.predicate.is_some() {"the predicate"} else {"any value"}
+    }
+    fn bounds(&self) -> String {
+        match (self.min_calls, self.max_calls) {
+            (min, max) if min == max => format!("exactly {}", min),
+            (min, usize::MAX) => format!("at least {}", min),
+            (0, max) => format!("at most {}", max),
+            (min, max) => format!("between {} and {}", min, max),
+        }
+    }
+}
+impl<E: Entry> Default for Expectation<E> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E: Entry> Debug for Expectation<E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Expectation")
+            .field("matches", &self.description())
+            .field("expected", &self.bounds())
+            .field("calls", &self.calls)
+            .finish()
+    }
+}
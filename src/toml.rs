@@ -0,0 +1,508 @@
+//! Loading config tables from TOML documents.
+//!
+//! Unlike deserializing a whole new config table and replacing the old one with it, loading a document using this module writes the values into an existing table through handles, and only for the entries whose values actually changed, so that the receivers are notified about exactly the changes which happened.
+//!
+//! The top-level keys of the document are matched against the [names] of the entries. Keys which don't correspond to any entry are ignored, and so are entries which don't appear in the document. The table is [validated] after the values are written, and left unchanged if it's no longer valid.
+//!
+//! # Example
+//! ```
+//! use snec::ConfigTable;
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     which_year: i64,
+//!     #[snec]
+//!     who: String,
+//! }
+//! let mut table = MyConfigTable {which_year: 1987, who: "Jeremy".to_string()};
+//! let changed = snec::toml::load_into(&mut table, r#"
+//!     which_year = 1983
+//!     who = "Jeremy"
+//! "#).unwrap();
+//! assert_eq!(changed, 1);
+//! assert_eq!(table.which_year, 1983);
+//! ```
+//!
+//! [`sample`] goes the other way, rendering an annotated example document for a config table, with the [description], type and [default value] of every entry as comments and assignments, which can be shipped as the canonical example of the config file.
+//!
+//! [`save`] renders the current values of all entries as a document which can be loaded back. To keep [secret] entries out of an otherwise human-editable file, [`save_encrypted`] encrypts their values with a caller-provided [`Cipher`], storing them as tables with a single `encrypted` key holding the hex-encoded ciphertext, which are rendered as sections such as `[password]` but can also be written inline, as in `password = {encrypted = "<hex>"}`, while the rest of the document stays plaintext. [`load_into_decrypted`] transparently decrypts them when loading. Secret entries whose values are not encrypted, e.g. because they were edited by hand, are loaded as is, even if they are strings which look like ciphertext.
+//!
+//! ```
+//! use snec::{ConfigTable, toml::Cipher};
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     user: String,
+//!     #[snec(entry, secret)]
+//!     password: String,
+//! }
+//! // Not an actual cipher, use one from a cryptography library instead.
+//! struct Xor(u8);
+//! impl Cipher for Xor {
+//!     fn encrypt(&self, _entry_name: &str, plaintext: &[u8]) -> Vec<u8> {
+//!         plaintext.iter().map(|byte| byte ^ self.0).collect()
+//!     }
+//!     fn decrypt(&self, _entry_name: &str, ciphertext: &[u8]) -> Option<Vec<u8>> {
+//!         Some(self.encrypt("", ciphertext))
+//!     }
+//! }
+//! let table = MyConfigTable {user: "jeremy".to_string(), password: "hunter2".to_string()};
+//! let document = snec::toml::save_encrypted(&table, &Xor(42)).unwrap();
+//! assert!(document.contains("user = \"jeremy\""));
+//! assert!(!document.contains("hunter2"));
+//!
+//! let mut loaded = MyConfigTable {user: String::new(), password: String::new()};
+//! snec::toml::load_into_decrypted(&mut loaded, &document, &Xor(42)).unwrap();
+//! assert_eq!(loaded.password, "hunter2");
+//!
+//! // A password typed in by hand is never mistaken for an encrypted one.
+//! snec::toml::load_into_decrypted(&mut loaded, r#"password = "encrypted:hunter2""#, &Xor(42)).unwrap();
+//! assert_eq!(loaded.password, "encrypted:hunter2");
+//! ```
+//!
+//! [names]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [validated]: ../trait.ValidateTable.html " "
+//! [`sample`]: fn.sample.html " "
+//! [`save`]: fn.save.html " "
+//! [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+//! [`save_encrypted`]: fn.save_encrypted.html " "
+//! [`Cipher`]: trait.Cipher.html " "
+//! [`load_into_decrypted`]: fn.load_into_decrypted.html " "
+//! [description]: ../trait.Entry.html#associatedconstant.DESCRIPTION " "
+//! [default value]: ../trait.Entry.html#method.default_value " "
+
+use core::{
+    any,
+    fmt::{self, Formatter, Display, Write as _},
+};
+use std::error;
+use serde::{Serialize, Deserialize, de::{DeserializeOwned, Error as _}};
+use super::{
+    Entry,
+    Get,
+    ConfigTable,
+    Loader,
+    LoadError,
+    Lookup,
+    LookupEntry,
+    VisitEntries,
+    ValidateTable,
+    EntryTypeVisitor,
+    VisitEntryTypes,
+};
+
+/// The only key of the tables which store the encrypted values of secret entries.
+const ENCRYPTED_KEY: &str = "encrypted";
+
+/// Parses a TOML document and writes the values from it into the corresponding entries of the config table, notifying the receivers of the entries which changed. Returns the number of entries which changed.
+///
+/// Keys which don't correspond to any entry are ignored. If a value has the wrong type for its entry, the error names that entry. The table is left unchanged if an error is returned, including one produced by the [table validator]. See the [module-level documentation] for more.
+///
+/// [table validator]: ../trait.ValidateTable.html " "
+/// [module-level documentation]: index.html " "
+#[inline]
+pub fn load_into<T>(table: &mut T, document: &str) -> Result<usize, LoadError<Error, T::Error>>
+where T: VisitEntries<TomlLoader> + ValidateTable {
+    Loader::new(TomlDocument::new(document).map_err(LoadError::Source)?).load_into(table)
+}
+
+/// Same as [`load_into`], but decrypts the values of [secret] entries which were encrypted by [`save_encrypted`] using the specified cipher. See the [module-level documentation] for an example.
+///
+/// [`load_into`]: fn.load_into.html " "
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+/// [`save_encrypted`]: fn.save_encrypted.html " "
+/// [module-level documentation]: index.html " "
+#[inline]
+pub fn load_into_decrypted<T, C>(table: &mut T, document: &str, cipher: &C) -> Result<usize, LoadError<Error, T::Error>>
+where
+    T: ConfigTable + VisitEntries<TomlLoader> + ValidateTable,
+    C: Cipher + ?Sized {
+    let mut document = TomlDocument::new(document).map_err(LoadError::Source)?;
+    document.decrypt_secrets::<T, C>(cipher).map_err(LoadError::Source)?;
+    Loader::new(document).load_into(table)
+}
+
+/// The [loader] used by [`load_into`], which writes the values from a [`TomlDocument`] into a config table.
+///
+/// [loader]: ../struct.Loader.html " "
+/// [`load_into`]: fn.load_into.html " "
+/// [`TomlDocument`]: struct.TomlDocument.html " "
+pub type TomlLoader = Loader<TomlDocument>;
+
+/// A parsed TOML document which [looks up] the values of entries under the keys equal to their names.
+///
+/// [looks up]: ../trait.LookupEntry.html " "
+#[derive(Clone, Debug)]
+pub struct TomlDocument {
+    document: ::toml::Table,
+}
+impl TomlDocument {
+    /// Parses the specified document.
+    #[inline]
+    pub fn new(document: &str) -> Result<Self, Error> {
+        let document = document.parse()
+            .map_err(|error| Error {entry: None, inner: error})?;
+        Ok(
+            Self::from_table(document)
+        )
+    }
+    /// Wraps an already parsed document.
+    #[inline(always)]
+    pub fn from_table(document: ::toml::Table) -> Self {
+        Self {document}
+    }
+    /// Decrypts the values of the [secret] entries of the config table which were encrypted by [`save_encrypted`], leaving the ones which are not encrypted as they are. Only tables with a single `encrypted` key holding a string are treated as encrypted values.
+    ///
+    /// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+    /// [`save_encrypted`]: fn.save_encrypted.html " "
+    pub fn decrypt_secrets<T, C>(&mut self, cipher: &C) -> Result<(), Error>
+    where
+        T: ConfigTable,
+        C: Cipher + ?Sized {
+        for info in T::ENTRIES.iter().filter(|info| info.secret) {
+            let value = match self.document.get_mut(info.name) {
+                Some(value) => value,
+                None => continue,
+            };
+            let encoded = match value.as_table() {
+                Some(table) if table.len() == 1 => match table.get(ENCRYPTED_KEY) {
+                    Some(encoded) => encoded.as_str(),
+                    None => continue,
+                },
+                _ => continue,
+            };
+            *value = encoded.and_then(|encoded| decrypt_value(cipher, info.name, encoded)).ok_or_else(|| Error {
+                entry: Some(info.name),
+                inner: ::toml::de::Error::custom("could not decrypt the value"),
+            })?;
+        }
+        Ok(())
+    }
+}
+impl Lookup for TomlDocument {
+    type Error = Error;
+}
+impl<E> LookupEntry<E> for TomlDocument
+where
+    E: Entry,
+    E::Data: DeserializeOwned {
+    #[inline]
+    fn lookup(&mut self) -> Option<Result<E::Data, Error>> {
+        let value = self.document.get(E::NAME)?.clone();
+        Some(
+            value.try_into().map_err(|error| Error {entry: Some(E::NAME), inner: error})
+        )
+    }
+}
+
+/// Renders the current values of all entries of the config table as a TOML document, which can be loaded back with [`load_into`]. [Secret] entries are written in plaintext; use [`save_encrypted`] to encrypt them.
+///
+/// # Example
+/// ```
+/// use snec::ConfigTable;
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec]
+///     which_year: i64,
+///     #[snec]
+///     who: String,
+/// }
+/// let table = MyConfigTable {which_year: 1987, who: "Jeremy".to_string()};
+/// assert_eq!(snec::toml::save(&table).unwrap(), "\
+/// which_year = 1987
+/// who = \"Jeremy\"
+/// ");
+/// ```
+///
+/// [`load_into`]: fn.load_into.html " "
+/// [Secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+/// [`save_encrypted`]: fn.save_encrypted.html " "
+#[inline]
+pub fn save<T>(table: &T) -> Result<String, SaveError>
+where T: for<'a> VisitEntryTypes<TomlSaver<'a, T>> {
+    let mut saver = TomlSaver::new(table);
+    T::visit_entry_types(&mut saver);
+    saver.finish()
+}
+/// Same as [`save`], but encrypts the values of [secret] entries using the specified cipher, as described in the [module-level documentation].
+///
+/// [`save`]: fn.save.html " "
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+/// [module-level documentation]: index.html " "
+#[inline]
+pub fn save_encrypted<T>(table: &T, cipher: &dyn Cipher) -> Result<String, SaveError>
+where T: for<'a> VisitEntryTypes<TomlSaver<'a, T>> {
+    let mut saver = TomlSaver::encrypting(table, cipher);
+    T::visit_entry_types(&mut saver);
+    saver.finish()
+}
+
+/// A [type visitor] which renders the current values of the entries of a config table, used by [`save`] and [`save_encrypted`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`save`]: fn.save.html " "
+/// [`save_encrypted`]: fn.save_encrypted.html " "
+pub struct TomlSaver<'a, T> {
+    table: &'a T,
+    cipher: Option<&'a dyn Cipher>,
+    document: ::toml::Table,
+    error: Option<SaveError>,
+}
+impl<'a, T> TomlSaver<'a, T> {
+    /// Creates a saver which writes all values in plaintext.
+    #[inline(always)]
+    pub fn new(table: &'a T) -> Self {
+        Self {table, cipher: None, document: ::toml::Table::new(), error: None}
+    }
+    /// Creates a saver which encrypts the values of secret entries using the specified cipher.
+    #[inline(always)]
+    pub fn encrypting(table: &'a T, cipher: &'a dyn Cipher) -> Self {
+        Self {table, cipher: Some(cipher), document: ::toml::Table::new(), error: None}
+    }
+    /// Returns the rendered document, or the first error which occurred.
+    #[inline]
+    pub fn finish(self) -> Result<String, SaveError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        ::toml::to_string(&self.document).map_err(|error| SaveError {entry: None, inner: error})
+    }
+}
+impl<'a, T, E> EntryTypeVisitor<E> for TomlSaver<'a, T>
+where
+    T: Get<E>,
+    E: Entry,
+    E::Data: Serialize {
+    fn visit_type(&mut self) {
+        if self.error.is_some() {
+            return;
+        }
+        let value = match ::toml::Value::try_from(self.table.get_ref()) {
+            Ok(value) => value,
+            Err(error) => {
+                self.error = Some(
+                    SaveError {entry: Some(E::NAME), inner: error}
+                );
+                return;
+            },
+        };
+        let value = match self.cipher {
+            Some(cipher) if E::SECRET => encrypt_value(cipher, E::NAME, &value),
+            _ => value,
+        };
+        self.document.insert(E::NAME.to_string(), value);
+    }
+}
+impl<T> fmt::Debug for TomlSaver<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TomlSaver")
+            .field("encrypting", &self.cipher.is_some())
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A cipher used to encrypt the values of [secret] entries when saving a config table with [`save_encrypted`], and to decrypt them when loading it with [`load_into_decrypted`].
+///
+/// Snec doesn't implement any ciphers itself: implement this trait for a key of an authenticated cipher from a cryptography library, such as AES-GCM or ChaCha20-Poly1305, generating a new nonce for every value and storing it along with the ciphertext. The plaintext is the value serialized as TOML.
+///
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+/// [`save_encrypted`]: fn.save_encrypted.html " "
+/// [`load_into_decrypted`]: fn.load_into_decrypted.html " "
+pub trait Cipher {
+    /// Encrypts the value of the entry with the specified name. The name can be used as associated data, which prevents encrypted values from being moved between entries.
+    fn encrypt(&self, entry_name: &str, plaintext: &[u8]) -> Vec<u8>;
+    /// Decrypts the value of the entry with the specified name, returning `None` if the ciphertext is invalid or was produced with a different key.
+    fn decrypt(&self, entry_name: &str, ciphertext: &[u8]) -> Option<Vec<u8>>;
+}
+impl<C: Cipher + ?Sized> Cipher for &C {
+    #[inline(always)]
+    fn encrypt(&self, entry_name: &str, plaintext: &[u8]) -> Vec<u8> {
+        (**self).encrypt(entry_name, plaintext)
+    }
+    #[inline(always)]
+    fn decrypt(&self, entry_name: &str, ciphertext: &[u8]) -> Option<Vec<u8>> {
+        (**self).decrypt(entry_name, ciphertext)
+    }
+}
+
+/// Encrypts the TOML representation of a value, storing the hex-encoded ciphertext in a table under `ENCRYPTED_KEY`.
+fn encrypt_value(cipher: &dyn Cipher, entry_name: &str, value: &::toml::Value) -> ::toml::Value {
+    let ciphertext = cipher.encrypt(entry_name, value.to_string().as_bytes());
+    let mut encoded = String::with_capacity(ciphertext.len() * 2);
+    for byte in ciphertext {
+        let _ = write!(encoded, "{:02x}", byte);
+    }
+    let mut table = ::toml::Table::new();
+    table.insert(ENCRYPTED_KEY.to_string(), ::toml::Value::String(encoded));
+    ::toml::Value::Table(table)
+}
+/// The reverse of `encrypt_value`, returning `None` if any of the steps fails.
+fn decrypt_value<C>(cipher: &C, entry_name: &str, encoded: &str) -> Option<::toml::Value>
+where C: Cipher + ?Sized {
+    let ciphertext = encoded.as_bytes().chunks(2)
+        .map(|byte| match byte {
+            [high, low] => Some((hex_digit(*high)? << 4) | hex_digit(*low)?),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let plaintext = String::from_utf8(cipher.decrypt(entry_name, &ciphertext)?).ok()?;
+    ::toml::Value::deserialize(::toml::de::ValueDeserializer::new(&plaintext)).ok()
+}
+#[inline]
+fn hex_digit(digit: u8) -> Option<u8> {
+    (digit as char).to_digit(16).map(|digit| digit as u8)
+}
+
+/// Renders an annotated sample TOML document for the config table, which loads into a table with the default values of all entries.
+///
+/// Every entry gets a block of comments with its [description] and [type name], followed by an assignment of its [default value]. Entries without a default value, with one which can't be represented in TOML, such as `None`, or which are [secret] get a commented out assignment instead, so that the document stays valid.
+///
+/// # Example
+/// ```
+/// use snec::ConfigTable;
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     /// The port to listen on.
+///     #[snec(entry, default = 8080)]
+///     port: u16,
+///     #[snec]
+///     host: String,
+/// }
+/// assert_eq!(snec::toml::sample::<MyConfigTable>(), "\
+/// ## The port to listen on.
+/// ## Type: `u16`
+/// port = 8080
+///
+/// ## Type: `String`
+/// ## No default value.
+/// ## host =
+/// ");
+/// ```
+///
+/// [description]: ../trait.Entry.html#associatedconstant.DESCRIPTION " "
+/// [type name]: ../trait.Entry.html#associatedconstant.TYPE_NAME " "
+/// [default value]: ../trait.Entry.html#method.default_value " "
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+#[inline]
+pub fn sample<T>() -> String
+where T: VisitEntryTypes<SampleWriter> {
+    let mut writer = SampleWriter::new();
+    T::visit_entry_types(&mut writer);
+    writer.finish()
+}
+
+/// A [type visitor] which renders the sample document produced by [`sample`].
+///
+/// [type visitor]: ../trait.EntryTypeVisitor.html " "
+/// [`sample`]: fn.sample.html " "
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SampleWriter {
+    document: String,
+}
+impl SampleWriter {
+    /// Creates a writer with an empty document.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Returns the document rendered so far.
+    #[inline(always)]
+    pub fn finish(self) -> String {
+        self.document
+    }
+}
+impl<E> EntryTypeVisitor<E> for SampleWriter
+where
+    E: Entry,
+    E::Data: Serialize {
+    fn visit_type(&mut self) {
+        if !self.document.is_empty() {
+            self.document.push('\n');
+        }
+        for line in E::DESCRIPTION.lines() {
+            if line.is_empty() {
+                self.document.push_str("#\n");
+            } else {
+                let _ = writeln!(self.document, "# {}", line);
+            }
+        }
+        // Entries implemented by hand may not specify the type as written in the source code.
+        let type_name = if E::TYPE_NAME.is_empty() { any::type_name::<E::Data>() } else { E::TYPE_NAME };
+        let _ = writeln!(self.document, "# Type: `{}`", type_name);
+        if !E::UNIT.is_empty() {
+            let _ = writeln!(self.document, "# Unit: {}", E::UNIT);
+        }
+        let key = key(E::NAME);
+        let default_value = E::default_value().map(::toml::Value::try_from);
+        let _ = match default_value {
+            Some(..) if E::SECRET => writeln!(self.document, "# The default value is secret.\n# {} =", key),
+            Some(Ok(value)) => writeln!(self.document, "{} = {}", key, value),
+            Some(Err(..)) => writeln!(
+                self.document,
+                "# The default value can't be represented in TOML.\n# {} =",
+                key,
+            ),
+            None => writeln!(self.document, "# No default value.\n# {} =", key),
+        };
+    }
+}
+
+/// Formats an entry name as a TOML key, which is quoted unless it's a valid bare key. Dotted names are quoted as well, since the loader looks them up as top-level keys.
+fn key(name: &str) -> String {
+    let bare = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        name.to_string()
+    } else {
+        ::toml::Value::String(name.to_string()).to_string()
+    }
+}
+
+/// An error which occurred while loading a TOML document into a config table.
+#[derive(Clone, Debug)]
+pub struct Error {
+    /// The name of the entry whose value was invalid, or `None` if the document itself could not be parsed.
+    pub entry: Option<&'static str>,
+    /// The error produced by the TOML parser.
+    pub inner: ::toml::de::Error,
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.entry {
+            Some(entry) => write!(f, "invalid value for entry `{}`: {}", entry, self.inner),
+            None => write!(f, "could not parse TOML document: {}", self.inner),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.inner)
+    }
+}
+
+/// An error which occurred while saving a config table as a TOML document.
+#[derive(Clone, Debug)]
+pub struct SaveError {
+    /// The name of the entry whose value could not be represented in TOML, or `None` if the document as a whole could not be rendered.
+    pub entry: Option<&'static str>,
+    /// The error produced by the TOML serializer.
+    pub inner: ::toml::ser::Error,
+}
+impl Display for SaveError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.entry {
+            Some(entry) => write!(f, "could not represent the value of entry `{}`: {}", entry, self.inner),
+            None => write!(f, "could not render TOML document: {}", self.inner),
+        }
+    }
+}
+impl error::Error for SaveError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.inner)
+    }
+}
@@ -0,0 +1,122 @@
+//! Notifying JavaScript about changes of config tables when compiled to WebAssembly.
+//!
+//! [`JsReceiver`] is a [receiver] which calls a JavaScript function with the [name] of the entry and its new value whenever an entry changes, which lets web frontends embedding a Rust core react to configuration changes without a custom bridge. Values are converted to plain JavaScript values with [`serde-wasm-bindgen`] in its JSON-compatible mode, so that structs and maps become plain objects, sequences become arrays and `None` becomes `null`.
+//!
+//! # Example
+//! ```no_run
+//! use snec::{ConfigTable, GetExt as _, wasm::JsReceiver};
+//! use wasm_bindgen::prelude::*;
+//! #[derive(ConfigTable)]
+//! #[snec(receiver({self.on_change.clone()}: JsReceiver))]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     theme: String,
+//!     #[snec]
+//!     font_size: u32,
+//!     on_change: JsReceiver,
+//! }
+//! #[wasm_bindgen]
+//! pub struct Config(MyConfigTable);
+//! #[wasm_bindgen]
+//! impl Config {
+//!     // In JavaScript: new Config((name, value) => console.log(`${name} = ${value}`))
+//!     #[wasm_bindgen(constructor)]
+//!     pub fn new(on_change: js_sys::Function) -> Self {
+//!         Self(MyConfigTable {
+//!             theme: "light".to_string(),
+//!             font_size: 14,
+//!             on_change: JsReceiver::new(on_change),
+//!         })
+//!     }
+//!     pub fn set_font_size(&mut self, font_size: u32) -> Result<(), JsValue> {
+//!         self.0.get_handle_to::<entries::FontSize>().set(font_size);
+//!         // Calls on_change with "font_size" and the new size.
+//!         match self.0.on_change.take_error() {
+//!             Some(error) => Err(error),
+//!             None => Ok(()),
+//!         }
+//!     }
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! [`JsReceiver`]: struct.JsReceiver.html " "
+//! [receiver]: ../trait.Receiver.html " "
+//! [name]: ../trait.Entry.html#associatedconstant.NAME " "
+//! [`serde-wasm-bindgen`]: https://docs.rs/serde-wasm-bindgen " "
+
+use core::{
+    cell::RefCell,
+    fmt::{self, Formatter, Debug},
+};
+use std::rc::Rc;
+use js_sys::Function;
+use serde::Serialize;
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::JsValue;
+use super::{Entry, Receiver};
+
+/// A [receiver] which calls a JavaScript function with the [name] of the entry and its new value. See the [module-level documentation] for more.
+///
+/// The values of [secret] entries are withheld from the function, which gets `undefined` instead, unless the receiver was created with [`revealing_secrets`]. Since receivers can't fail, the errors which occur while converting values or thrown by the function are kept by the receiver and can be retrieved with [`take_error`]; clones of the receiver share them.
+///
+/// [receiver]: ../trait.Receiver.html " "
+/// [name]: ../trait.Entry.html#associatedconstant.NAME " "
+/// [module-level documentation]: index.html " "
+/// [secret]: ../trait.Entry.html#associatedconstant.SECRET " "
+/// [`revealing_secrets`]: #method.revealing_secrets " "
+/// [`take_error`]: #method.take_error " "
+#[derive(Clone)]
+pub struct JsReceiver {
+    callback: Function,
+    reveal_secrets: bool,
+    error: Rc<RefCell<Option<JsValue>>>,
+}
+impl JsReceiver {
+    /// Creates a receiver calling the specified function, withholding the values of secret entries.
+    #[inline]
+    pub fn new(callback: Function) -> Self {
+        Self {callback, reveal_secrets: false, error: Rc::default()}
+    }
+    /// Creates a receiver calling the specified function, including the values of secret entries.
+    #[inline]
+    pub fn revealing_secrets(callback: Function) -> Self {
+        Self {callback, reveal_secrets: true, error: Rc::default()}
+    }
+    /// Returns the function which is called.
+    #[inline(always)]
+    pub fn callback(&self) -> &Function {
+        &self.callback
+    }
+    /// Returns the error which occurred while converting a value or was thrown by the function, if any, and clears it. Only the last error is kept.
+    #[inline]
+    pub fn take_error(&self) -> Option<JsValue> {
+        self.error.borrow_mut().take()
+    }
+}
+impl<E> Receiver<E> for JsReceiver
+where
+    E: Entry,
+    E::Data: Serialize {
+    fn receive(&mut self, new_value: &E::Data) {
+        let value = if E::SECRET && !self.reveal_secrets {
+            Ok(JsValue::UNDEFINED)
+        } else {
+            new_value.serialize(&Serializer::json_compatible()).map_err(JsValue::from)
+        };
+        let result = value.and_then(|value| {
+            self.callback.call2(&JsValue::NULL, &JsValue::from_str(E::NAME), &value)
+        });
+        if let Err(error) = result {
+            *self.error.borrow_mut() = Some(error);
+        }
+    }
+}
+impl Debug for JsReceiver {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsReceiver")
+            .field("reveal_secrets", &self.reveal_secrets)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,262 @@
+//! Hot-reloading config tables from TOML files.
+//!
+//! A [`Watcher`] watches a config file for changes and, when asked to, re-reads it and applies the differences to a config table using [`toml::load_into`], which means that only the receivers of the entries which actually changed are notified. Since config tables are usually not shared between threads, the changes are applied on a thread chosen by the caller, by calling [`try_apply`] periodically (in an event loop, for example) or [`wait_apply`] in a loop on a dedicated thread. If the table is shared, a [`CallbackWatcher`] can be used instead, which invokes a callback with the new contents of the file on the thread of the file system watcher.
+//!
+//! The directory containing the file is watched rather than the file itself, which allows the file to be replaced instead of modified in place, as is done by many text editors.
+//!
+//! # Example
+//! ```no_run
+//! use snec::ConfigTable;
+//! #[derive(ConfigTable)]
+//! struct MyConfigTable {
+//!     #[snec]
+//!     which_year: i64,
+//! }
+//! let mut table = MyConfigTable {which_year: 1987};
+//! let watcher = snec::watch::Watcher::new("config.toml")?;
+//! watcher.reload_into(&mut table)?;
+//! loop {
+//!     // Blocks until the file changes.
+//!     let changed = watcher.wait_apply(&mut table)?;
+//!     println!("{} entries changed", changed);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`Watcher`]: struct.Watcher.html " "
+//! [`CallbackWatcher`]: struct.CallbackWatcher.html " "
+//! [`toml::load_into`]: ../toml/fn.load_into.html " "
+//! [`try_apply`]: struct.Watcher.html#method.try_apply " "
+//! [`wait_apply`]: struct.Watcher.html#method.wait_apply " "
+
+use core::fmt::{self, Formatter, Display, Debug};
+use std::{
+    error,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use super::{
+    LoadError,
+    VisitEntries,
+    ValidateTable,
+    toml::{self, TomlLoader},
+};
+
+/// Watches a config file, allowing its changes to be applied to a config table on the thread of the caller.
+///
+/// See the [module-level documentation] for more.
+///
+/// [module-level documentation]: index.html " "
+pub struct Watcher {
+    path: PathBuf,
+    events: mpsc::Receiver<()>,
+    _watcher: RecommendedWatcher,
+}
+impl Watcher {
+    /// Starts watching the file at the specified path. The file doesn't have to exist yet.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        let (sender, events) = mpsc::channel();
+        let watcher = watch(&path, move || {
+            let _ = sender.send(());
+        })?;
+        Ok(
+            Self {path, events, _watcher: watcher}
+        )
+    }
+    /// Returns the path to the watched file.
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+    /// Reads the file and applies it to the table, regardless of whether it changed. Returns the number of entries which changed. The table is left unchanged if the file can't be loaded or the [table validator] rejects its contents.
+    ///
+    /// [table validator]: ../trait.ValidateTable.html " "
+    #[inline]
+    pub fn reload_into<T>(&self, table: &mut T) -> Result<usize, LoadError<Error, T::Error>>
+    where T: VisitEntries<TomlLoader> + ValidateTable {
+        let document = fs::read_to_string(&self.path).map_err(|error| LoadError::Source(error.into()))?;
+        toml::load_into(table, &document).map_err(|error| error.map_source(Error::Toml))
+    }
+    /// Applies the file to the table if it changed since the last time it was applied, without blocking. Returns `None` if the file didn't change, or the number of entries which changed otherwise.
+    #[inline]
+    pub fn try_apply<T>(&self, table: &mut T) -> Result<Option<usize>, LoadError<Error, T::Error>>
+    where T: VisitEntries<TomlLoader> + ValidateTable {
+        if self.drain_events().map_err(LoadError::Source)? {
+            self.reload_into(table).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+    /// Blocks until the file changes and applies it to the table. Returns the number of entries which changed.
+    #[inline]
+    pub fn wait_apply<T>(&self, table: &mut T) -> Result<usize, LoadError<Error, T::Error>>
+    where T: VisitEntries<TomlLoader> + ValidateTable {
+        self.events.recv().map_err(|_| LoadError::Source(Error::Disconnected))?;
+        // A single save usually produces several events, all of which are handled by one reload.
+        self.drain_events().map_err(LoadError::Source)?;
+        self.reload_into(table)
+    }
+    /// Removes all pending events, returning whether there were any.
+    fn drain_events(&self) -> Result<bool, Error> {
+        let mut any = false;
+        loop {
+            match self.events.try_recv() {
+                Ok(()) => any = true,
+                Err(mpsc::TryRecvError::Empty) => return Ok(any),
+                Err(mpsc::TryRecvError::Disconnected) => return Err(Error::Disconnected),
+            }
+        }
+    }
+}
+impl Debug for Watcher {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+/// Watches a config file, invoking a callback with its new contents whenever it changes.
+///
+/// The callback is invoked on the thread of the file system watcher, and thus must be `Send`. Typically, it locks a shared config table and [loads] the new contents into it.
+///
+/// # Example
+/// ```no_run
+/// use snec::ConfigTable;
+/// use std::sync::{Arc, Mutex};
+/// #[derive(ConfigTable)]
+/// struct MyConfigTable {
+///     #[snec]
+///     which_year: i64,
+/// }
+/// let table = Arc::new(Mutex::new(MyConfigTable {which_year: 1987}));
+/// let shared_table = Arc::clone(&table);
+/// let _watcher = snec::watch::CallbackWatcher::new("config.toml", move |document| {
+///     match document {
+///         Ok(document) => {
+///             let mut table = shared_table.lock().unwrap();
+///             if let Err(error) = snec::toml::load_into(&mut *table, &document) {
+///                 eprintln!("{}", error);
+///             }
+///         },
+///         Err(error) => eprintln!("{}", error),
+///     }
+/// })?;
+/// # Ok::<(), snec::watch::Error>(())
+/// ```
+///
+/// [loads]: ../toml/fn.load_into.html " "
+pub struct CallbackWatcher {
+    path: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+impl CallbackWatcher {
+    /// Starts watching the file at the specified path, invoking the callback with the result of reading the file whenever it changes. The file doesn't have to exist yet.
+    pub fn new<F>(path: impl Into<PathBuf>, mut callback: F) -> Result<Self, Error>
+    where F: FnMut(io::Result<String>) + Send + 'static {
+        let path = path.into();
+        let path_to_read = path.clone();
+        let watcher = watch(&path, move || {
+            callback(fs::read_to_string(&path_to_read))
+        })?;
+        Ok(
+            Self {path, _watcher: watcher}
+        )
+    }
+    /// Returns the path to the watched file.
+    #[inline(always)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+impl Debug for CallbackWatcher {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackWatcher")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+/// Starts watching the directory containing the file, calling `on_change` whenever the file is created or modified.
+fn watch<F>(path: &Path, mut on_change: F) -> Result<RecommendedWatcher, Error>
+where F: FnMut() + Send + 'static {
+    let file_name = path.file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the path does not point to a file"))?
+        .to_owned();
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_owned(),
+        _ => PathBuf::from("."),
+    };
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let event = match event {
+            Ok(event) => event,
+            Err(..) => return,
+        };
+        let relevant_kind = matches!(event.kind, EventKind::Create(..) | EventKind::Modify(..));
+        let concerns_file = event.paths.iter().any(|path| path.file_name() == Some(&file_name));
+        if relevant_kind && concerns_file {
+            on_change();
+        }
+    })?;
+    watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// An error which occurred while watching or reloading a config file.
+#[derive(Debug)]
+pub enum Error {
+    /// The file system watcher could not be set up.
+    Notify(notify::Error),
+    /// The file could not be read.
+    Io(io::Error),
+    /// The contents of the file could not be loaded into the config table.
+    Toml(toml::Error),
+    /// The file system watcher stopped unexpectedly.
+    Disconnected,
+}
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Notify(error) => write!(f, "could not watch config file: {}", error),
+            Self::Io(error) => write!(f, "could not read config file: {}", error),
+            Self::Toml(error) => Display::fmt(error, f),
+            Self::Disconnected => f.write_str("the file system watcher stopped unexpectedly"),
+        }
+    }
+}
+impl error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Notify(error) => Some(error),
+            Self::Io(error) => Some(error),
+            Self::Toml(error) => Some(error),
+            Self::Disconnected => None,
+        }
+    }
+}
+impl From<notify::Error> for Error {
+    #[inline(always)]
+    fn from(op: notify::Error) -> Self {
+        Self::Notify(op)
+    }
+}
+impl From<io::Error> for Error {
+    #[inline(always)]
+    fn from(op: io::Error) -> Self {
+        Self::Io(op)
+    }
+}
+impl From<toml::Error> for Error {
+    #[inline(always)]
+    fn from(op: toml::Error) -> Self {
+        Self::Toml(op)
+    }
+}